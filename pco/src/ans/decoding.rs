@@ -19,9 +19,11 @@ pub struct Decoder {
 }
 
 impl Decoder {
-  pub fn new(spec: &Spec) -> Self {
+  // builds the table in `nodes`, reusing its allocation
+  pub fn new(spec: &Spec, mut nodes: Vec<Node>) -> Self {
     let table_size = spec.table_size();
-    let mut nodes = Vec::with_capacity(table_size);
+    nodes.clear();
+    nodes.reserve(table_size);
     // x_s from Jarek Duda's paper
     let mut symbol_x_s = spec.symbol_weights.clone();
     for &symbol in &spec.state_symbols {
@@ -44,6 +46,13 @@ impl Decoder {
 
   pub fn from_chunk_latent_var_meta<L: Latent>(
     latent_meta: &ChunkLatentVarMeta<L>,
+  ) -> PcoResult<Self> {
+    Self::from_chunk_latent_var_meta_reusing(latent_meta, Vec::new())
+  }
+
+  pub fn from_chunk_latent_var_meta_reusing<L: Latent>(
+    latent_meta: &ChunkLatentVarMeta<L>,
+    nodes: Vec<Node>,
   ) -> PcoResult<Self> {
     let weights = latent_meta
       .bins
@@ -51,6 +60,6 @@ impl Decoder {
       .map(|bin| bin.weight)
       .collect::<Vec<_>>();
     let spec = Spec::from_weights(latent_meta.ans_size_log, weights)?;
-    Ok(Self::new(&spec))
+    Ok(Self::new(&spec, nodes))
   }
 }
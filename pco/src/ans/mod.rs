@@ -1,4 +1,4 @@
-pub use decoding::Decoder;
+pub use decoding::{Decoder, Node};
 pub use encoding::quantize_weights;
 pub use encoding::Encoder;
 
@@ -49,7 +49,7 @@ mod tests {
     // DECODE
     compressed.extend(&vec![0; 100]);
     let mut reader = BitReader::new(&compressed, expected_byte_len, 0);
-    let decoder = Decoder::new(spec);
+    let decoder = Decoder::new(spec, Vec::new());
     let mut decoded = Vec::new();
     let mut state_idx = final_state - table_size;
     for _ in 0..symbols.len() {
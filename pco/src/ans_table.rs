@@ -0,0 +1,46 @@
+use crate::ans::Decoder;
+use crate::constants::Bitlen;
+
+/// One state of an [`AnsTable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AnsTableNode {
+  /// The index of the bin decoded at this state, into the latent variable's
+  /// `bins`.
+  pub bin_idx: usize,
+  /// The lowest state index that can follow this one.
+  pub next_state_idx_base: u32,
+  /// The number of bits read to determine the next state index, which is
+  /// `next_state_idx_base` plus the value of those bits.
+  pub bits_to_read: Bitlen,
+}
+
+/// The tANS decoding table of a latent variable, as built from its
+/// [`ChunkLatentVarMeta`][crate::ChunkLatentVarMeta].
+///
+/// This is mainly useful for inspecting how entropy coding was done.
+/// See <https://en.wikipedia.org/wiki/Asymmetric_numeral_systems>.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AnsTable {
+  /// The log2 of the number of states in the table.
+  pub size_log: Bitlen,
+  /// The nodes of the table, indexed by state index in
+  /// `[0, 2^size_log)`.
+  pub nodes: Vec<AnsTableNode>,
+}
+
+impl AnsTable {
+  pub(crate) fn from_decoder(size_log: Bitlen, decoder: &Decoder) -> Self {
+    let nodes = decoder
+      .nodes
+      .iter()
+      .map(|node| AnsTableNode {
+        bin_idx: node.symbol as usize,
+        next_state_idx_base: node.next_state_idx_base,
+        bits_to_read: node.bits_to_read,
+      })
+      .collect();
+    Self { size_log, nodes }
+  }
+}
@@ -0,0 +1,164 @@
+//! Compression and decompression of Arrow primitive arrays.
+//!
+//! Compression reads the array's value buffer in place and writes a
+//! standalone .pco file, so the result can also be read with
+//! [`standalone::simple_decompress`][crate::standalone::simple_decompress].
+//! Decompression infers the Arrow data type from the file.
+//!
+//! Pco has no notion of nulls, so arrays containing any nulls are rejected.
+
+use arrow_array::types::{
+  Float16Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, UInt16Type, UInt32Type,
+  UInt64Type,
+};
+use arrow_array::{Array, ArrayRef, PrimitiveArray};
+use arrow_buffer::ScalarBuffer;
+use arrow_schema::DataType;
+use std::sync::Arc;
+
+use crate::data_types::CoreDataType;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::{
+  simple_compress, simple_decompress, DataTypeOrTermination, FileDecompressor,
+};
+use crate::ChunkConfig;
+
+macro_rules! impl_arrow {
+  {$($name:ident => $arrow:ty,)+} => {
+    /// Compresses the values of an Arrow primitive array into a standalone
+    /// .pco file, without copying them.
+    ///
+    /// Supported data types are the 16-, 32-, and 64-bit integers and floats.
+    ///
+    /// Will return an InvalidArgument error if the array contains nulls, has
+    /// an unsupported data type, or if the config is invalid.
+    pub fn compress_arrow(array: &dyn Array, config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+      if array.null_count() > 0 {
+        return Err(PcoError::invalid_argument(format!(
+          "cannot compress Arrow array with {} nulls",
+          array.null_count(),
+        )));
+      }
+
+      match array.data_type() {
+        $(
+          dtype if dtype == &<$arrow as arrow_array::ArrowPrimitiveType>::DATA_TYPE => {
+            let array = array
+              .as_any()
+              .downcast_ref::<PrimitiveArray<$arrow>>()
+              .expect("Arrow array did not match its data type");
+            simple_compress(array.values(), config)
+          }
+        )+
+        other => Err(unsupported_dtype(other)),
+      }
+    }
+
+    /// Decompresses a standalone .pco file into an Arrow primitive array of
+    /// the corresponding data type, without nulls.
+    ///
+    /// Will return an InvalidArgument error if the file has no chunks to
+    /// infer the data type from, a Compatibility error if its data type is
+    /// not a core Pco data type, or an error if the file is corrupt.
+    pub fn decompress_arrow(src: &[u8]) -> PcoResult<ArrayRef> {
+      let (file_decompressor, rest) = FileDecompressor::new(src)?;
+      match file_decompressor.peek_dtype_or_termination(rest)? {
+        $(
+          DataTypeOrTermination::Known(CoreDataType::$name) => {
+            let nums = simple_decompress(src)?;
+            let array = PrimitiveArray::<$arrow>::new(ScalarBuffer::from(nums), None);
+            Ok(Arc::new(array))
+          }
+        )+
+        DataTypeOrTermination::Termination => Err(PcoError::invalid_argument(
+          "cannot infer an Arrow data type from a file with no chunks",
+        )),
+        DataTypeOrTermination::Unknown(byte) => Err(PcoError::compatibility(format!(
+          "unrecognized data type byte {}",
+          byte,
+        ))),
+      }
+    }
+  }
+}
+
+impl_arrow! {
+  U16 => UInt16Type,
+  U32 => UInt32Type,
+  U64 => UInt64Type,
+  I16 => Int16Type,
+  I32 => Int32Type,
+  I64 => Int64Type,
+  F16 => Float16Type,
+  F32 => Float32Type,
+  F64 => Float64Type,
+}
+
+fn unsupported_dtype(dtype: &DataType) -> PcoError {
+  PcoError::invalid_argument(format!(
+    "unsupported Arrow data type {}",
+    dtype,
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use arrow_array::{Float64Array, Int64Array, StringArray, UInt32Array};
+
+  use crate::errors::ErrorKind;
+  use crate::standalone::simpler_compress;
+
+  use super::*;
+
+  #[test]
+  fn test_arrow_round_trip() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let floats = Float64Array::from_iter_values((0..1000).map(|i| i as f64 * 0.5));
+    let compressed = compress_arrow(&floats, &config)?;
+    assert_eq!(
+      simple_decompress::<f64>(&compressed)?,
+      floats.values().to_vec()
+    );
+    let recovered = decompress_arrow(&compressed)?;
+    assert_eq!(recovered.data_type(), &DataType::Float64);
+    assert_eq!(
+      recovered.as_any().downcast_ref::<Float64Array>().unwrap(),
+      &floats
+    );
+
+    // a slice with an offset should only compress its own values
+    let ints = Int64Array::from_iter_values(-50..50).slice(10, 20);
+    let recovered = decompress_arrow(&compress_arrow(&ints, &config)?)?;
+    assert_eq!(
+      recovered.as_any().downcast_ref::<Int64Array>().unwrap(),
+      &Int64Array::from_iter_values(-40..-20)
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_arrow_errors() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let with_nulls = UInt32Array::from(vec![Some(1), None, Some(3)]);
+    let err = compress_arrow(&with_nulls, &config).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let strings = StringArray::from(vec!["a", "b"]);
+    let err = compress_arrow(&strings, &config).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let empty = simpler_compress::<u32>(&[], 0)?;
+    let err = decompress_arrow(&empty).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+}
@@ -0,0 +1,170 @@
+use crate::data_types::{split_latents_classic, NumberLike};
+use crate::errors::PcoResult;
+use crate::wrapped::chunk_compressor;
+use crate::ChunkConfig;
+
+/// Automatically makes an educated guess for the best delta encoding order,
+/// based on `nums` and `compression_level`.
+///
+/// This has some compute cost by trying different configurations on a subset
+/// of the numbers to determine the most likely one to do well.
+/// By default, each chunk does this automatically, but if you are compressing
+/// many chunks of similar data, you can call this once and set the result as
+/// [`delta_encoding_order`][ChunkConfig::delta_encoding_order] on your config
+/// to skip the work for every chunk.
+///
+/// Will return an InvalidArgument error if `nums` is empty or the compression
+/// level is invalid.
+pub fn auto_delta_encoding_order<T: NumberLike>(
+  nums: &[T],
+  compression_level: usize,
+) -> PcoResult<usize> {
+  let config = ChunkConfig::default().with_compression_level(compression_level);
+  config.validate()?;
+  chunk_compressor::validate_chunk_size(nums.len())?;
+
+  let latents = split_latents_classic(nums);
+  let unoptimized_bins_log =
+    chunk_compressor::choose_unoptimized_bins_log(compression_level, nums.len());
+  chunk_compressor::choose_delta_encoding_order(
+    &latents[0],
+    unoptimized_bins_log,
+    config.max_ans_size_log,
+  )
+}
+
+/// Estimates the compressed size in bytes of `nums` as a single chunk with
+/// the given config, without actually compressing them.
+///
+/// This chooses the mode, delta encoding order, and bins just as compression
+/// would, but instead of encoding every number, it infers the cost of each
+/// page from the bin weights.
+/// The estimate covers the chunk metadata and all its pages, as written by
+/// [`wrapped::ChunkCompressor`][crate::wrapped::ChunkCompressor], and is
+/// usually within a few percent of the real size.
+/// It excludes file headers and footers, as well as the few bytes of
+/// standalone chunk overhead.
+/// This can be used to quickly compare candidate configs, such as different
+/// delta encoding orders.
+///
+/// Will return an InvalidArgument error if `nums` is empty or too long for a
+/// chunk, or if the config is invalid.
+pub fn estimate_compressed_size<T: NumberLike>(
+  nums: &[T],
+  config: &ChunkConfig,
+) -> PcoResult<usize> {
+  let chunk_compressor = chunk_compressor::new(nums, config)?;
+  Ok(chunk_compressor.size_estimate())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::wrapped::FileCompressor;
+  use crate::{PagingSpec, DEFAULT_COMPRESSION_LEVEL};
+
+  use super::*;
+
+  #[test]
+  fn test_auto_delta_encoding_order() -> PcoResult<()> {
+    let level = DEFAULT_COMPRESSION_LEVEL;
+
+    let mut random = Vec::new();
+    let mut state = 1_u64;
+    for _ in 0..2000 {
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      random.push((state >> 40) as u32);
+    }
+    assert_eq!(auto_delta_encoding_order(&random, level)?, 0);
+
+    let mut walk = Vec::new();
+    let mut x = 0_i64;
+    for &r in &random {
+      x += (r % 21) as i64 - 10;
+      walk.push(x);
+    }
+    assert_eq!(auto_delta_encoding_order(&walk, level)?, 1);
+
+    let mut quadratic = Vec::new();
+    for (i, &r) in random.iter().enumerate() {
+      let i = i as i64;
+      quadratic.push(i * i * 1000 + (r % 3) as i64);
+    }
+    assert!(auto_delta_encoding_order(&quadratic, level)? >= 2);
+    Ok(())
+  }
+
+  #[test]
+  fn test_auto_delta_encoding_order_errors() {
+    let empty: &[u32] = &[];
+    let err = auto_delta_encoding_order(empty, 0).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    let err = auto_delta_encoding_order(&[1_u32, 2, 3], 13).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+  }
+
+  fn assert_estimate_close<T: NumberLike>(nums: &[T], config: &ChunkConfig) -> PcoResult<()> {
+    let cc = FileCompressor::default().chunk_compressor(nums, config)?;
+    let mut dst = cc.write_chunk_meta(Vec::new())?;
+    for page_idx in 0..cc.n_per_page().len() {
+      dst = cc.write_page(page_idx, dst)?;
+    }
+
+    let estimate = estimate_compressed_size(nums, config)?;
+    let ratio = estimate as f64 / dst.len() as f64;
+    assert!(
+      (0.95..1.05).contains(&ratio),
+      "estimate={} real={}",
+      estimate,
+      dst.len()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_estimate_compressed_size() -> PcoResult<()> {
+    let mut state = 1_u64;
+    let mut random = Vec::new();
+    for _ in 0..20000 {
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      random.push((state >> 40) as u32 % 1000);
+    }
+    let mut walk = Vec::new();
+    let mut x = 0_i64;
+    for &r in &random {
+      x += (r % 21) as i64 - 10;
+      walk.push(x);
+    }
+    let floats = random.iter().map(|&r| r as f64 * 0.1).collect::<Vec<_>>();
+
+    let configs = [
+      ChunkConfig::default(),
+      ChunkConfig::default().with_delta_encoding_order(Some(0)),
+      ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(3000)),
+    ];
+    for config in &configs {
+      assert_estimate_close(&random, config)?;
+      assert_estimate_close(&walk, config)?;
+      assert_estimate_close(&floats, config)?;
+    }
+
+    let empty: &[u32] = &[];
+    assert!(matches!(
+      estimate_compressed_size(empty, &ChunkConfig::default())
+        .unwrap_err()
+        .kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+}
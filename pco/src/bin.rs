@@ -5,6 +5,7 @@ use crate::data_types::Latent;
 /// Part of [`ChunkLatentVarMeta`][`crate::ChunkLatentVarMeta`] representing
 /// a numerical range.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct Bin<L: Latent> {
   /// The number of occurrences of this bin in the asymmetric numeral system
@@ -60,12 +60,25 @@ pub unsafe fn read_uint_at<U: ReadWriteUint, const MAX_U64S: usize>(
   bits::lowest_bits(res, n)
 }
 
+// For full-width reads starting on a byte boundary, none of the shifting or
+// overlapping u64's of read_uint_at are needed; we just load whole words.
+#[inline]
+pub unsafe fn read_full_width_at<U: ReadWriteUint>(src: &[u8], byte_idx: usize) -> U {
+  let mut res = U::from_u64(u64_at(src, byte_idx));
+  for word_idx in 1..U::BITS.div_ceil(64) {
+    res |= U::from_u64(u64_at(src, byte_idx + 8 * word_idx as usize)) << (64 * word_idx);
+  }
+  res
+}
+
 pub struct BitReader<'a> {
   pub src: &'a [u8],
   unpadded_bit_size: usize,
 
   pub stale_byte_idx: usize,  // in current stream
   pub bits_past_byte: Bitlen, // in current stream
+  // bits consumed by the builder before this reader's stream began
+  bit_offset: usize,
 }
 
 impl<'a> BitReader<'a> {
@@ -75,6 +88,7 @@ impl<'a> BitReader<'a> {
       unpadded_bit_size: unpadded_byte_size * 8,
       stale_byte_idx: 0,
       bits_past_byte,
+      bit_offset: 0,
     }
   }
 
@@ -83,6 +97,12 @@ impl<'a> BitReader<'a> {
     self.stale_byte_idx * 8 + self.bits_past_byte as usize
   }
 
+  // The bit index relative to the start of everything read by the builder,
+  // for reporting where corruptions occur.
+  pub fn absolute_bit_idx(&self) -> usize {
+    self.bit_offset + self.bit_idx()
+  }
+
   fn byte_idx(&self) -> usize {
     self.bit_idx() / 8
   }
@@ -121,6 +141,12 @@ impl<'a> BitReader<'a> {
 
   pub unsafe fn read_uint<U: ReadWriteUint>(&mut self, n: Bitlen) -> U {
     self.refill();
+    if n == U::BITS && self.bits_past_byte == 0 {
+      let res = read_full_width_at::<U>(self.src, self.stale_byte_idx);
+      self.consume(n);
+      return res;
+    }
+
     let res = match U::MAX_U64S {
       1 => read_uint_at::<U, 1>(
         self.src,
@@ -178,12 +204,16 @@ impl<'a> BitReader<'a> {
   // Seek to the end of the byte, asserting it's all 0.
   // Used to terminate each section of the file, since they
   // always start and end byte-aligned.
-  pub fn drain_empty_byte(&mut self, message: &str) -> PcoResult<()> {
+  pub fn drain_empty_byte(&mut self, message: &str, section: &str) -> PcoResult<()> {
     self.check_in_bounds()?;
     self.refill();
     if self.bits_past_byte != 0 {
       if (self.src[self.stale_byte_idx] >> self.bits_past_byte) > 0 {
-        return Err(PcoError::corruption(message));
+        return Err(PcoError::corruption_at(
+          message,
+          section,
+          self.absolute_bit_idx(),
+        ));
       }
       self.consume(8 - self.bits_past_byte);
     }
@@ -197,6 +227,7 @@ pub struct BitReaderBuilder<R: BetterBufRead> {
   eof_buffer: Vec<u8>,
   reached_eof: bool,
   bytes_into_eof_buffer: usize,
+  bytes_consumed: usize,
   bits_past_byte: Bitlen,
 }
 
@@ -208,6 +239,7 @@ impl<R: BetterBufRead> BitReaderBuilder<R> {
       eof_buffer: vec![],
       reached_eof: false,
       bytes_into_eof_buffer: 0,
+      bytes_consumed: 0,
       bits_past_byte,
     }
   }
@@ -239,11 +271,14 @@ impl<R: BetterBufRead> BitReaderBuilder<R> {
       src.len()
     };
     let bits_past_byte = self.bits_past_byte;
-    Ok(BitReader::new(
-      src,
-      unpadded_bytes,
-      bits_past_byte,
-    ))
+    let mut reader = BitReader::new(src, unpadded_bytes, bits_past_byte);
+    reader.bit_offset = self.bytes_consumed * 8;
+    Ok(reader)
+  }
+
+  // The bit index relative to the start of everything read by this builder.
+  pub fn bit_idx(&self) -> usize {
+    self.bytes_consumed * 8 + self.bits_past_byte as usize
   }
 
   pub fn into_inner(self) -> R {
@@ -253,6 +288,7 @@ impl<R: BetterBufRead> BitReaderBuilder<R> {
   fn update(&mut self, final_bit_idx: usize) {
     let bytes_consumed = final_bit_idx / 8;
     self.inner.consume(bytes_consumed);
+    self.bytes_consumed += bytes_consumed;
     if self.reached_eof {
       self.bytes_into_eof_buffer += bytes_consumed;
     }
@@ -281,6 +317,9 @@ pub fn ensure_buf_read_capacity<R: BetterBufRead>(src: &mut R, required: usize)
 
 #[cfg(test)]
 mod tests {
+  use rand::{Rng, SeedableRng};
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
   use crate::constants::OVERSHOOT_PADDING;
   use crate::errors::{ErrorKind, PcoResult};
 
@@ -303,12 +342,95 @@ mod tests {
       assert_eq!(reader.read_bitlen(4), 8);
       assert_eq!(reader.read_aligned_bytes(1)?, vec![38]);
       assert_eq!(reader.read_usize(15), 255 + 65 * 256);
-      reader.drain_empty_byte("should be empty")?;
+      reader.drain_empty_byte("should be empty", "test data")?;
       assert_eq!(reader.aligned_byte_idx()?, 4);
     }
     Ok(())
   }
 
+  #[test]
+  fn test_u64_at_is_little_endian() {
+    // words are always decoded little-endian, whatever the host's byte order
+    let src = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09];
+    unsafe {
+      assert_eq!(u64_at(&src, 0), 0x0807060504030201);
+      assert_eq!(u64_at(&src, 1), 0x0908070605040302);
+    }
+  }
+
+  // the straightforward bit-at-a-time reader the word-based one must match
+  fn read_uint_slow(src: &[u8], bit_idx: usize, n: Bitlen) -> u128 {
+    let mut res = 0;
+    for i in 0..n as usize {
+      let bit = (src[(bit_idx + i) / 8] >> ((bit_idx + i) % 8)) & 1;
+      res |= (bit as u128) << i;
+    }
+    res
+  }
+
+  fn random_bitlen<R: Rng>(rng: &mut R, bits: Bitlen) -> Bitlen {
+    // full-width reads take a separate path, so we make sure to hit them
+    if rng.gen_bool(0.3) {
+      bits
+    } else {
+      rng.gen_range(0..=bits)
+    }
+  }
+
+  #[test]
+  fn test_reads_match_slow_reader() {
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    for _ in 0..100 {
+      let mut src = vec![0_u8; 600];
+      rng.fill(&mut src[..]);
+      let initial_bits_past_byte = rng.gen_range(0..8);
+      let mut bit_idx = initial_bits_past_byte as usize;
+      let mut reader = BitReader::new(&src, src.len(), initial_bits_past_byte);
+      for _ in 0..20 {
+        // aligning now and then so full-width reads can use the fast path
+        if rng.gen_bool(0.3) {
+          let pad = (8 - bit_idx % 8) % 8;
+          let pad_value = unsafe { reader.read_uint::<u16>(pad as Bitlen) };
+          assert_eq!(
+            pad_value as u128,
+            read_uint_slow(&src, bit_idx, pad as Bitlen)
+          );
+          bit_idx += pad;
+        }
+
+        let (n, value) = unsafe {
+          match rng.gen_range(0..4) {
+            0 => {
+              let n = random_bitlen(&mut rng, 16);
+              (n, reader.read_uint::<u16>(n) as u128)
+            }
+            1 => {
+              let n = random_bitlen(&mut rng, 32);
+              (n, reader.read_uint::<u32>(n) as u128)
+            }
+            2 => {
+              let n = random_bitlen(&mut rng, 64);
+              (n, reader.read_uint::<u64>(n) as u128)
+            }
+            _ => {
+              let n = random_bitlen(&mut rng, 128);
+              (n, reader.read_uint::<u128>(n))
+            }
+          }
+        };
+        assert_eq!(
+          value,
+          read_uint_slow(&src, bit_idx, n),
+          "bit_idx={} n={}",
+          bit_idx,
+          n
+        );
+        bit_idx += n as usize;
+        assert_eq!(reader.bit_idx(), bit_idx);
+      }
+    }
+  }
+
   #[test]
   fn test_bit_reader_builder() -> PcoResult<()> {
     let src = (0..7).collect::<Vec<_>>();
@@ -325,8 +447,10 @@ mod tests {
       assert_eq!(reader.read_usize(7), 1);
       assert_eq!(reader.bit_idx(), 8);
       assert_eq!(reader.read_aligned_bytes(3)?, &vec![3, 4, 5]);
+      assert_eq!(reader.absolute_bit_idx(), 48);
       Ok(())
     })?;
+    assert_eq!(reader_builder.bit_idx(), 48);
     let err = reader_builder
       .with_reader(|reader| unsafe {
         assert!(reader.src.len() >= 4); // because of padding
@@ -41,6 +41,20 @@ pub unsafe fn write_uint_to<U: ReadWriteUint, const MAX_U64S: usize>(
   }
 }
 
+// The full-width counterpart of bit_reader::read_full_width_at. Since
+// everything past the current byte is still 0, we can write whole words
+// without combining them with what's already there.
+#[inline]
+pub unsafe fn write_full_width_to<U: ReadWriteUint>(val: U, byte_idx: usize, dst: &mut [u8]) {
+  for word_idx in 0..U::BITS.div_ceil(64) {
+    write_u64_to(
+      (val >> (64 * word_idx)).to_u64(),
+      byte_idx + 8 * word_idx as usize,
+      dst,
+    );
+  }
+}
+
 pub struct BitWriter<W: Write> {
   pub buf: Vec<u8>,
   pub stale_byte_idx: usize,
@@ -93,6 +107,12 @@ impl<W: Write> BitWriter<W> {
 
   pub unsafe fn write_uint<U: ReadWriteUint>(&mut self, x: U, n: Bitlen) {
     self.refill();
+    if n == U::BITS && self.bits_past_byte == 0 {
+      write_full_width_to(x, self.stale_byte_idx, &mut self.buf);
+      self.consume(n);
+      return;
+    }
+
     match U::MAX_U64S {
       1 => write_uint_to::<U, 1>(
         x,
@@ -157,6 +177,9 @@ impl<W: Write> BitWriter<W> {
 
 #[cfg(test)]
 mod tests {
+  use rand::{Rng, SeedableRng};
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
   use super::*;
 
   // I find little endian confusing, hence all the comments.
@@ -192,4 +215,68 @@ mod tests {
     );
     Ok(())
   }
+
+  #[test]
+  fn test_write_u64_to_is_little_endian() {
+    // words are always encoded little-endian, whatever the host's byte order
+    let mut dst = [0; 9];
+    unsafe {
+      write_u64_to(0x0807060504030201, 1, &mut dst);
+    }
+    assert_eq!(dst, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+  }
+
+  // the straightforward bit-at-a-time writer the word-based one must match
+  fn write_uint_slow(x: u128, n: Bitlen, bit_idx: usize, dst: &mut Vec<u8>) {
+    for i in 0..n as usize {
+      let byte_idx = (bit_idx + i) / 8;
+      if byte_idx >= dst.len() {
+        dst.push(0);
+      }
+      dst[byte_idx] |= (((x >> i) & 1) as u8) << ((bit_idx + i) % 8);
+    }
+  }
+
+  #[test]
+  fn test_writes_match_slow_writer() -> PcoResult<()> {
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    for _ in 0..100 {
+      let mut dst = Vec::new();
+      let mut expected = Vec::new();
+      let mut bit_idx = 0;
+      let mut writer = BitWriter::new(&mut dst, 2000);
+      for _ in 0..20 {
+        // aligning now and then so full-width writes can use the fast path
+        if rng.gen_bool(0.3) {
+          let pad = ((8 - bit_idx % 8) % 8) as Bitlen;
+          unsafe { writer.write_uint::<u16>(0, pad) };
+          write_uint_slow(0, pad, bit_idx, &mut expected);
+          bit_idx += pad as usize;
+        }
+
+        let bits = [16, 32, 64, 128][rng.gen_range(0..4)];
+        // full-width writes take a separate path, so we make sure to hit them
+        let n = if rng.gen_bool(0.3) {
+          bits
+        } else {
+          rng.gen_range(0..=bits)
+        };
+        let x = crate::bits::lowest_bits(rng.gen::<u128>(), n);
+        unsafe {
+          match bits {
+            16 => writer.write_uint(x as u16, n),
+            32 => writer.write_uint(x as u32, n),
+            64 => writer.write_uint(x as u64, n),
+            _ => writer.write_uint(x, n),
+          }
+        }
+        write_uint_slow(x, n, bit_idx, &mut expected);
+        bit_idx += n as usize;
+      }
+      writer.finish_byte();
+      writer.flush()?;
+      assert_eq!(dst, expected);
+    }
+    Ok(())
+  }
 }
@@ -1,5 +1,14 @@
-use crate::constants::{Bitlen, DEFAULT_MAX_PAGE_N};
+use std::borrow::Cow;
+use std::cmp::min;
+use std::mem;
+
+use crate::constants::{
+  Bitlen, COMPATIBLE_FORMAT_VERSION, DEFAULT_MAX_PAGE_N, MAX_AUTO_DELTA_ENCODING_ORDER,
+  MAX_COMPRESSION_LEVEL, MAX_COMPRESSOR_ANS_SIZE_LOG, MAX_DELTA_ENCODING_ORDER,
+};
+use crate::data_types::Latent;
 use crate::errors::{PcoError, PcoResult};
+use crate::mode::ModeKind;
 use crate::DEFAULT_COMPRESSION_LEVEL;
 
 /// Configures whether integer multiplier detection is enabled.
@@ -30,7 +39,10 @@ pub enum IntMultSpec {
 /// * approximate multiples of 0.01
 /// * approximate multiples of pi
 ///
-/// Float mults can work even when there are NaNs and infinities.
+/// Float mults can work even when there are NaNs and infinities, or numbers
+/// spanning many orders of magnitude.
+/// When a base is detected automatically, the chunk is also compressed in
+/// classic mode, and whichever is smaller is kept.
 /// When this is helpful, compression and decompression speeds can be
 /// substantially reduced. In rare cases, this configuration
 /// may reduce compression speed somewhat even when it isn't helpful.
@@ -43,7 +55,18 @@ pub enum FloatMultSpec {
   /// If you know all your floats are roughly multiples of `base`, you can
   /// provide `base` here to ensure it gets used and save compression time.
   Provided(f64),
-  // TODO support a LossyEnabled mode that always drops the ULPs latent var
+  /// Lossily compresses floats to within the given absolute tolerance, which
+  /// must be positive and finite.
+  ///
+  /// Each float is rounded to the nearest multiple of `2 * tolerance`,
+  /// dropping its ULP adjustment whenever the result is within tolerance.
+  /// Floats that would not be (such as NaNs and infinities) keep their exact
+  /// adjustment, so every decompressed float is guaranteed to be within
+  /// tolerance.
+  /// When no adjustments are kept, the adjustment latent variable costs
+  /// nothing in the compressed data, and older versions of pco can still
+  /// decompress it.
+  Lossy(f64),
 }
 
 /// Configures whether quantized-float detection is enabled.
@@ -62,12 +85,129 @@ pub enum FloatQuantSpec {
   // for `k`
 }
 
+/// Configures whether float XOR mode is considered.
+///
+/// Float XOR mode encodes the XOR of each float's bits with the previous
+/// float's, similar to Facebook's Gorilla compression.
+///
+/// Examples where this helps:
+/// * time series where many consecutive values are bit-identical, such as
+///   prices or sensor readings sampled faster than they change
+/// * time series whose consecutive values agree in their leading bits but not
+///   in any arithmetic pattern that delta encoding can exploit
+///
+/// When enabled, pco compresses each chunk both with and without float XOR
+/// mode and keeps whichever is estimated to be smaller, so this can roughly
+/// double compression time.
+/// Enabling it makes compressors write format version 3, which older
+/// versions of pco cannot decompress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FloatXorSpec {
+  #[default]
+  Disabled,
+  Enabled,
+}
+
+/// Configures whether run length mode is considered.
+///
+/// Run length mode encodes each run of consecutive identical numbers as a
+/// pair of latents: the repeated value and the run's length.
+///
+/// Examples where this helps:
+/// * step functions, such as a setting that changes only occasionally
+/// * sparse data with long stretches of a default value
+///
+/// When enabled, pco only tries run length mode on chunks whose average run
+/// length is long, and keeps it if it is estimated to be smaller than the
+/// otherwise chosen mode.
+/// Counting runs is cheap, so this rarely affects compression time.
+/// Enabling it makes compressors write format version 3, which older
+/// versions of pco cannot decompress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RunLengthSpec {
+  #[default]
+  Disabled,
+  Enabled,
+}
+
+/// Configures whether dict mode is considered.
+///
+/// Dict mode stores the chunk's distinct numbers once in its metadata and
+/// encodes each number as its index into them.
+///
+/// Examples where this helps:
+/// * a column of a few hash values or IDs, each repeated many times
+///
+/// When enabled, pco tries dict mode on chunks with at most 4096 distinct
+/// numbers, and keeps it if it is estimated to be smaller than the otherwise
+/// chosen mode.
+/// This can roughly double compression time for such chunks, but has little
+/// cost for chunks with more distinct numbers.
+/// Enabling it makes compressors write format version 3, which older
+/// versions of pco cannot decompress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DictSpec {
+  #[default]
+  Disabled,
+  Enabled,
+}
+
+/// Configures whether sparse mode is considered.
+///
+/// Sparse mode encodes each nonzero number along with the count of zeros
+/// after it, where zero means the number type's default value.
+///
+/// Examples where this helps:
+/// * a column that is zero except for occasional events, such as error
+///   counts or trade volumes
+///
+/// When enabled, pco only tries sparse mode on chunks where at least 3/4 of
+/// the numbers are zeros following another number, and keeps it if it is
+/// estimated to be smaller than the otherwise chosen mode.
+/// A chunk of all zeros compresses to a small, constant size.
+/// Enabling it makes compressors write format version 3, which older
+/// versions of pco cannot decompress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SparseSpec {
+  #[default]
+  Disabled,
+  Enabled,
+}
+
+/// Configures whether each chunk tunes its own bin count.
+///
+/// Normally, `compression_level` alone determines how many bins each chunk
+/// starts with before optimizing them.
+/// When auto level is enabled, pco also retrains each chunk's bins as if at
+/// a somewhat lower and a somewhat higher compression level, and keeps
+/// whichever is estimated to be smallest.
+///
+/// Examples where this helps:
+/// * files whose chunks have very different distributions or counts, for
+///   which no single compression level is best
+///
+/// This reuses each chunk's latents and delta encoding, so it costs only two
+/// extra rounds of bin training per chunk.
+/// Each chunk records its own bins and ANS size, so decompression is
+/// unaffected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AutoLevelSpec {
+  #[default]
+  Disabled,
+  Enabled,
+}
+
 /// All configurations available for a compressor.
 ///
 /// Some, like `delta_encoding_order`, are explicitly stored in the
 /// compressed bytes.
 /// Others, like `compression_level`, affect compression but are not explicitly
 /// stored.
+///
+/// Compression is deterministic: the same numbers and config always produce
+/// the same bytes, so outputs can be used in reproducible builds and golden
+/// tests.
+/// Any sampling done to choose modes or bases uses a fixed seed.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct ChunkConfig {
@@ -81,8 +221,14 @@ pub struct ChunkConfig {
   /// The meaning of the compression levels is subject to change with
   /// new releases.
   pub compression_level: usize,
-  /// `delta_encoding_order` ranges from 0 to 7 inclusive (default:
-  /// `None`, automatically detecting on each chunk).
+  /// `auto_level_spec` lets each chunk deviate from `compression_level` when
+  /// it is estimated to compress better
+  /// (default: `Disabled`).
+  ///
+  /// See [`AutoLevelSpec`][crate::AutoLevelSpec] for more detail.
+  pub auto_level_spec: AutoLevelSpec,
+  /// `delta_encoding_order` ranges from 0 to 15 inclusive (default:
+  /// `None`, automatically detecting an order from 0 to 7 on each chunk).
   ///
   /// It is the number of times to apply delta encoding
   /// before compressing. For instance, say we have the numbers
@@ -97,11 +243,34 @@ pub struct ChunkConfig {
   /// somewhat quadratic data.
   /// * Even higher-order is best for time series that are very
   /// smooth, like temperature or light sensor readings.
+  ///   Orders above 7 are mainly useful for smooth curves such as
+  ///   interpolations of high-degree polynomials, and produce data that
+  ///   versions of pco before format version 3 cannot decompress.
+  ///
+  /// Each page stores its own first `delta_encoding_order` values (its delta
+  /// moments) at full width, and its deltas start from those.
+  /// So splitting one monotonic series into consecutive chunks costs only
+  /// these few values per page, rather than a large first delta, and any
+  /// chunk can be decompressed without the ones before it.
   ///
   /// If you would like to automatically choose this once and reuse it for all
   /// chunks,
   /// [`auto_delta_encoding_order`][crate::auto_delta_encoding_order] can help.
   pub delta_encoding_order: Option<usize>,
+  /// `secondary_delta_encoding_order` ranges from 0 to 15 inclusive
+  /// (default: 0).
+  ///
+  /// It is the number of times to apply delta encoding to the secondary
+  /// latent variable, for modes that have one.
+  /// For instance, in int mult mode the secondary latent variable is the
+  /// adjustment `num % base`, and nonzero orders help when the adjustments
+  /// trend smoothly.
+  /// Usually these are uncorrelated remainders, for which 0 is best, so
+  /// this is never chosen automatically.
+  /// Nonzero orders need format version 3, which older versions of pco
+  /// cannot decompress.
+  /// It is ignored in classic mode and float XOR mode.
+  pub secondary_delta_encoding_order: usize,
   /// Integer multiplier mode improves compression ratio in cases where many
   /// numbers are congruent modulo an integer `base`
   /// (default: `Enabled`).
@@ -123,22 +292,149 @@ pub struct ChunkConfig {
   ///
   /// See [`FloatQuantSpec`][crate::FloatQuantSpec] for more detail.
   pub float_quant_spec: FloatQuantSpec,
+  /// Float XOR mode improves compression ratio in cases where the data type
+  /// is a float and consecutive numbers are often bit-identical or share
+  /// their leading bits
+  /// (default: `Disabled`).
+  ///
+  /// When it is used, delta encoding is not.
+  /// See [`FloatXorSpec`][crate::FloatXorSpec] for more detail.
+  pub float_xor_spec: FloatXorSpec,
+  /// Run length mode improves compression ratio in cases where numbers
+  /// repeat in long runs
+  /// (default: `Disabled`).
+  ///
+  /// The secondary delta encoding order applies to the run lengths.
+  /// See [`RunLengthSpec`][crate::RunLengthSpec] for more detail.
+  pub run_length_spec: RunLengthSpec,
+  /// Dict mode improves compression ratio in cases where there are few
+  /// distinct numbers, but they are spread far apart
+  /// (default: `Disabled`).
+  ///
+  /// See [`DictSpec`][crate::DictSpec] for more detail.
+  pub dict_spec: DictSpec,
+  /// Sparse mode improves compression ratio in cases where most numbers are
+  /// zero
+  /// (default: `Disabled`).
+  ///
+  /// The secondary delta encoding order applies to the counts of zeros.
+  /// See [`SparseSpec`][crate::SparseSpec] for more detail.
+  pub sparse_spec: SparseSpec,
+  /// `force_mode` makes every chunk use the given kind of mode instead of
+  /// whichever one is estimated to compress best
+  /// (default: `None`).
+  ///
+  /// This is mainly useful for benchmarking modes against each other.
+  /// The chunk is compressed in the forced mode even if it compresses worse
+  /// than others, and even if it exceeds pco's
+  /// [size guarantee][crate::standalone::guarantee].
+  /// Modes that need a base still take it from the corresponding spec:
+  /// `IntMult` and `FloatMult` use the detected or provided base from
+  /// `int_mult_spec` or `float_mult_spec`, and `FloatQuant` requires
+  /// `float_quant_spec` to provide `k`.
+  ///
+  /// Will return an InvalidArgument error during compression if the mode is
+  /// not valid for the data type (e.g. `FloatMult` for integers), if no base
+  /// is available, or if there are too many distinct numbers for `Dict`.
+  pub force_mode: Option<ModeKind>,
   /// `paging_spec` specifies how the chunk should be split into pages
   /// (default: equal pages up to 2^18 numbers each).
   ///
+  /// In the standalone format, each chunk has exactly one page, so this also
+  /// decides how many numbers go into each chunk.
+  /// Smaller chunks allow finer seeking, since decompression can skip whole
+  /// chunks, but each one carries its own metadata and trains its own bins,
+  /// so very small chunks compress worse.
+  /// In the wrapped format, the caller chooses chunk boundaries by how it
+  /// splits the numbers among chunk compressors, and this only divides each
+  /// chunk into pages.
+  ///
   /// See [`PagingSpec`][crate::PagingSpec] for more information.
   pub paging_spec: PagingSpec,
+  /// `max_ans_size_log` caps the log2 of the number of states in each tANS
+  /// table, ranging from 0 to 12 inclusive (default: 12).
+  ///
+  /// Decompression builds a table of `2^ans_size_log` states for each latent
+  /// variable, so a lower cap bounds decompression memory, which can matter
+  /// on memory-constrained targets.
+  /// Since each bin needs at least one state, this also limits each latent
+  /// variable to `2^max_ans_size_log` bins.
+  ///
+  /// The compression ratio impact depends on the data's distribution.
+  /// Caps of 8 or more rarely cost more than 0.1%, and a cap of 6 typically
+  /// costs 0-2%.
+  /// Smaller caps force coarse bins and weights; at 4 or below, highly skewed
+  /// distributions can compress 10% worse or more, and a cap of 0 (a single
+  /// bin) can make them several times larger.
+  pub max_ans_size_log: Bitlen,
+  /// `max_n_bins` caps the number of bins built for each latent variable
+  /// (default: `None`, letting the compression level decide).
+  ///
+  /// Each bin costs some chunk metadata, so fewer bins mean smaller metadata
+  /// but coarser modeling of the distribution.
+  /// Bins are built by splitting a histogram of `2^k` buckets, so the cap is
+  /// effectively rounded down to a power of 2; for instance, a cap of 100
+  /// allows at most 64 bins.
+  ///
+  /// Since each bin needs at least one state in the tANS table, the table
+  /// size log is always at least the log2 of the number of bins.
+  /// Pco usually picks a table 4x larger than the number of bins, subject to
+  /// [`max_ans_size_log`][ChunkConfig::max_ans_size_log], so capping bins
+  /// also tends to shrink the tables.
+  /// Conversely, `max_ans_size_log` already caps the number of bins at
+  /// `2^max_ans_size_log`, so only smaller caps have any effect.
+  ///
+  /// Will return an InvalidArgument error during compression if the cap is
+  /// 0.
+  pub max_n_bins: Option<usize>,
+  /// `explicit_bins` dictates the boundaries of the bins instead of letting
+  /// pco optimize them
+  /// (default: `None`).
+  ///
+  /// This is useful for matching an existing histogram schema.
+  /// The boundaries `[b_0, b_1, ..., b_k]` define `k` bins, where bin `i`
+  /// covers `b_i` up to but excluding `b_{i+1}`, except that the last bin
+  /// also includes `b_k`.
+  /// Boundaries are in terms of the numbers' ordered latents, as given by
+  /// [`NumberLike::to_latent_ordered`][crate::data_types::NumberLike::to_latent_ordered];
+  /// for unsigned integers, these are just the numbers themselves.
+  /// The bins' weights are still learned from the data, and bins that no
+  /// numbers fall into are omitted.
+  ///
+  /// Since these boundaries only make sense for the numbers as-is, the chunk
+  /// is always compressed in classic mode without delta encoding, ignoring
+  /// the mode specs and auto level.
+  /// And as usual, if the chosen bins would compress worse than pco's
+  /// [size guarantee][crate::standalone::guarantee], the chunk falls back to
+  /// a single trivial bin.
+  ///
+  /// Will return an InvalidArgument error during compression if there are
+  /// fewer than 2 boundaries, they are not strictly increasing, the delta
+  /// encoding order is nonzero, any boundary exceeds the latent type's
+  /// range, any number falls outside `[b_0, b_k]`, or more bins are occupied
+  /// than fit in an ANS table of size `2^max_ans_size_log`.
+  pub explicit_bins: Option<Vec<u64>>,
 }
 
 impl Default for ChunkConfig {
   fn default() -> Self {
     Self {
       compression_level: DEFAULT_COMPRESSION_LEVEL,
+      auto_level_spec: AutoLevelSpec::default(),
       delta_encoding_order: None,
+      secondary_delta_encoding_order: 0,
       int_mult_spec: IntMultSpec::default(),
       float_mult_spec: FloatMultSpec::default(),
       float_quant_spec: FloatQuantSpec::default(),
+      float_xor_spec: FloatXorSpec::default(),
+      run_length_spec: RunLengthSpec::default(),
+      dict_spec: DictSpec::default(),
+      sparse_spec: SparseSpec::default(),
+      force_mode: None,
       paging_spec: PagingSpec::EqualPagesUpTo(DEFAULT_MAX_PAGE_N),
+      max_ans_size_log: MAX_COMPRESSOR_ANS_SIZE_LOG,
+      max_n_bins: None,
+      explicit_bins: None,
     }
   }
 }
@@ -150,12 +446,25 @@ impl ChunkConfig {
     self
   }
 
+  /// Sets [`auto_level_spec`][ChunkConfig::auto_level_spec].
+  pub fn with_auto_level_spec(mut self, auto_level_spec: AutoLevelSpec) -> Self {
+    self.auto_level_spec = auto_level_spec;
+    self
+  }
+
   /// Sets [`delta_encoding_order`][ChunkConfig::delta_encoding_order].
   pub fn with_delta_encoding_order(mut self, order: Option<usize>) -> Self {
     self.delta_encoding_order = order;
     self
   }
 
+  /// Sets
+  /// [`secondary_delta_encoding_order`][ChunkConfig::secondary_delta_encoding_order].
+  pub fn with_secondary_delta_encoding_order(mut self, order: usize) -> Self {
+    self.secondary_delta_encoding_order = order;
+    self
+  }
+
   /// Sets [`int_mult_spec`][ChunkConfig::int_mult_spec].
   pub fn with_int_mult_spec(mut self, int_mult_spec: IntMultSpec) -> Self {
     self.int_mult_spec = int_mult_spec;
@@ -174,11 +483,215 @@ impl ChunkConfig {
     self
   }
 
+  /// Sets [`float_xor_spec`][ChunkConfig::float_xor_spec].
+  pub fn with_float_xor_spec(mut self, float_xor_spec: FloatXorSpec) -> Self {
+    self.float_xor_spec = float_xor_spec;
+    self
+  }
+
+  /// Sets [`run_length_spec`][ChunkConfig::run_length_spec].
+  pub fn with_run_length_spec(mut self, run_length_spec: RunLengthSpec) -> Self {
+    self.run_length_spec = run_length_spec;
+    self
+  }
+
+  /// Sets [`dict_spec`][ChunkConfig::dict_spec].
+  pub fn with_dict_spec(mut self, dict_spec: DictSpec) -> Self {
+    self.dict_spec = dict_spec;
+    self
+  }
+
+  /// Sets [`sparse_spec`][ChunkConfig::sparse_spec].
+  pub fn with_sparse_spec(mut self, sparse_spec: SparseSpec) -> Self {
+    self.sparse_spec = sparse_spec;
+    self
+  }
+
+  /// Sets [`force_mode`][ChunkConfig::force_mode].
+  pub fn with_force_mode(mut self, force_mode: Option<ModeKind>) -> Self {
+    self.force_mode = force_mode;
+    self
+  }
+
   /// Sets [`paging_spec`][ChunkConfig::paging_spec].
   pub fn with_paging_spec(mut self, paging_spec: PagingSpec) -> Self {
     self.paging_spec = paging_spec;
     self
   }
+
+  /// Sets [`max_ans_size_log`][ChunkConfig::max_ans_size_log].
+  pub fn with_max_ans_size_log(mut self, max_ans_size_log: Bitlen) -> Self {
+    self.max_ans_size_log = max_ans_size_log;
+    self
+  }
+
+  /// Sets [`max_n_bins`][ChunkConfig::max_n_bins].
+  pub fn with_max_n_bins(mut self, max_n_bins: Option<usize>) -> Self {
+    self.max_n_bins = max_n_bins;
+    self
+  }
+
+  /// Sets [`explicit_bins`][ChunkConfig::explicit_bins].
+  pub fn with_explicit_bins(mut self, explicit_bins: Option<Vec<u64>>) -> Self {
+    self.explicit_bins = explicit_bins;
+    self
+  }
+
+  /// Returns the earliest format version able to represent every chunk this
+  /// config might produce.
+  ///
+  /// This is 3 if float XOR, run length, dict, or sparse mode is enabled or
+  /// forced, or if the config sets a delta encoding order above 7 or a
+  /// nonzero secondary delta encoding order.
+  /// Otherwise it is 2, which versions of pco from 0.3 on can decompress.
+  /// Compressors that know the config up front, like
+  /// [`simple_compress`][crate::standalone::simple_compress], write this
+  /// version into the file's header.
+  pub fn min_format_version(&self) -> u8 {
+    let uses_new_modes = self.float_xor_spec == FloatXorSpec::Enabled
+      || self.run_length_spec == RunLengthSpec::Enabled
+      || self.dict_spec == DictSpec::Enabled
+      || self.sparse_spec == SparseSpec::Enabled;
+    let uses_new_delta_orders = self
+      .delta_encoding_order
+      .is_some_and(|order| order > MAX_AUTO_DELTA_ENCODING_ORDER)
+      || self.secondary_delta_encoding_order > 0;
+    let version = if uses_new_modes || uses_new_delta_orders {
+      3
+    } else {
+      COMPATIBLE_FORMAT_VERSION
+    };
+    let forced_mode_version = self.force_mode.map_or(0, ModeKind::min_format_version);
+    version.max(forced_mode_version)
+  }
+
+  /// Checks that every setting is within its documented range.
+  ///
+  /// Compression does this automatically, but calling it up front surfaces
+  /// a misconfigured `ChunkConfig` before any data is processed.
+  /// Some settings can only be checked against the data, such as the paging
+  /// spec against the chunk's count, so passing this doesn't guarantee
+  /// compression will succeed.
+  ///
+  /// Will return an InvalidArgument error if the compression level, either
+  /// delta encoding order, or the max ANS size log is too large, if the
+  /// max number of bins is 0, or if the explicit bins are invalid on their
+  /// own.
+  pub fn validate(&self) -> PcoResult<()> {
+    if self.compression_level > MAX_COMPRESSION_LEVEL {
+      return Err(PcoError::invalid_argument(format!(
+        "compression level may not exceed {} (was {})",
+        MAX_COMPRESSION_LEVEL, self.compression_level,
+      )));
+    }
+
+    if let Some(order) = self.delta_encoding_order {
+      if order > MAX_DELTA_ENCODING_ORDER {
+        return Err(PcoError::invalid_argument(format!(
+          "delta encoding order may not exceed {} (was {})",
+          MAX_DELTA_ENCODING_ORDER, order,
+        )));
+      }
+    }
+
+    if self.secondary_delta_encoding_order > MAX_DELTA_ENCODING_ORDER {
+      return Err(PcoError::invalid_argument(format!(
+        "secondary delta encoding order may not exceed {} (was {})",
+        MAX_DELTA_ENCODING_ORDER, self.secondary_delta_encoding_order,
+      )));
+    }
+
+    if self.max_ans_size_log > MAX_COMPRESSOR_ANS_SIZE_LOG {
+      return Err(PcoError::invalid_argument(format!(
+        "max ANS size log may not exceed {} (was {})",
+        MAX_COMPRESSOR_ANS_SIZE_LOG, self.max_ans_size_log,
+      )));
+    }
+
+    if self.max_n_bins == Some(0) {
+      return Err(PcoError::invalid_argument(
+        "max number of bins must be at least 1",
+      ));
+    }
+
+    if let Some(boundaries) = &self.explicit_bins {
+      if boundaries.len() < 2 {
+        return Err(PcoError::invalid_argument(format!(
+          "explicit bins need at least 2 boundaries (had {})",
+          boundaries.len(),
+        )));
+      }
+      if boundaries.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(PcoError::invalid_argument(
+          "explicit bin boundaries must be strictly increasing",
+        ));
+      }
+      if self.delta_encoding_order.is_some_and(|order| order != 0) {
+        return Err(PcoError::invalid_argument(
+          "explicit bins require a delta encoding order of 0",
+        ));
+      }
+      if self
+        .force_mode
+        .is_some_and(|kind| kind != ModeKind::Classic)
+      {
+        return Err(PcoError::invalid_argument(
+          "explicit bins require classic mode",
+        ));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// `ChunkConfigOverrides` replaces some fields of a shared `ChunkConfig` for
+/// a single call, without cloning and mutating it by hand.
+///
+/// Each field left as `None` keeps the config's value.
+/// See [`compress_with_overrides`][crate::standalone::compress_with_overrides].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChunkConfigOverrides {
+  /// If set, replaces [`compression_level`][ChunkConfig::compression_level].
+  pub compression_level: Option<usize>,
+  /// If set, replaces
+  /// [`delta_encoding_order`][ChunkConfig::delta_encoding_order].
+  /// For instance, `Some(Some(0))` disables delta encoding for data that is
+  /// known to be random, and `Some(None)` restores automatic detection.
+  pub delta_encoding_order: Option<Option<usize>>,
+}
+
+impl ChunkConfigOverrides {
+  /// Sets [`compression_level`][ChunkConfigOverrides::compression_level].
+  pub fn with_compression_level(mut self, level: usize) -> Self {
+    self.compression_level = Some(level);
+    self
+  }
+
+  /// Sets
+  /// [`delta_encoding_order`][ChunkConfigOverrides::delta_encoding_order].
+  pub fn with_delta_encoding_order(mut self, order: Option<usize>) -> Self {
+    self.delta_encoding_order = Some(order);
+    self
+  }
+
+  /// Returns the config with these overrides layered on top, only cloning
+  /// it if some field is overridden.
+  pub(crate) fn apply<'a>(&self, config: &'a ChunkConfig) -> Cow<'a, ChunkConfig> {
+    if *self == Self::default() {
+      return Cow::Borrowed(config);
+    }
+
+    let mut config = config.clone();
+    if let Some(level) = self.compression_level {
+      config.compression_level = level;
+    }
+    if let Some(order) = self.delta_encoding_order {
+      config.delta_encoding_order = order;
+    }
+    Cow::Owned(config)
+  }
 }
 
 /// `PagingSpec` specifies how a chunk is split into pages.
@@ -195,6 +708,31 @@ pub enum PagingSpec {
   /// Will return an InvalidArgument error during compression if
   /// any of the counts are 0 or the sum does not equal the chunk count.
   Exact(Vec<usize>),
+  /// Divide the chunk into equal pages of up to this many uncompressed bytes.
+  ///
+  /// This is converted to a count of numbers using the size of the data
+  /// type's latent, which is what gets written (e.g. 8 bytes for
+  /// `DateTime<Utc>`), and then behaves like `EqualPagesUpTo`.
+  /// For example, with a target page size of 256KiB, a chunk of 100,000
+  /// `f64`s (800,000 bytes) would be divided into 4 pages, each of 25,000
+  /// numbers.
+  ///
+  /// Will return an InvalidArgument error during compression if the target
+  /// is smaller than a single number.
+  TargetPageSize(usize),
+  /// Divide the chunk into exactly this many pages, as evenly as possible.
+  ///
+  /// When the chunk count isn't divisible by the page count, the first few
+  /// pages each receive one extra number.
+  /// This is useful for decompressing pages in parallel across a known
+  /// number of workers.
+  ///
+  /// If the chunk has fewer numbers than the page count, each number gets
+  /// its own page, and an empty chunk gets no pages.
+  ///
+  /// Will return an InvalidArgument error during compression if the page
+  /// count is 0 but the chunk is nonempty.
+  EqualPages(usize),
 }
 
 impl Default for PagingSpec {
@@ -203,8 +741,24 @@ impl Default for PagingSpec {
   }
 }
 
+fn equal_pages_up_to(n: usize, max_page_n: usize) -> Vec<usize> {
+  let n_pages = n.div_ceil(max_page_n);
+  let mut res = Vec::new();
+  let mut start = 0;
+  for i in 0..n_pages {
+    let end = ((i + 1) * n) / n_pages;
+    res.push(end - start);
+    start = end;
+  }
+  res
+}
+
 impl PagingSpec {
-  pub(crate) fn n_per_page(&self, n: usize) -> PcoResult<Vec<usize>> {
+  // Page sizes are always in terms of the latent type, since that is what
+  // gets written; e.g. `DateTime<Utc>` is 12 bytes in memory but 8 as a
+  // latent.
+  pub(crate) fn n_per_page<L: Latent>(&self, n: usize) -> PcoResult<Vec<usize>> {
+    let dtype_size = mem::size_of::<L>();
     let n_per_page = match self {
       // You might think it would be beneficial to do either of these:
       // * greedily fill pages since compressed chunk size seems like a concave
@@ -215,18 +769,35 @@ impl PagingSpec {
       // at some point, so the first idea doesn't work.
       // And the 2nd idea has only shown mixed/negative results, so I'm leaving
       // this as-is.
-      PagingSpec::EqualPagesUpTo(max_page_n) => {
-        let n_pages = n.div_ceil(*max_page_n);
-        let mut res = Vec::new();
-        let mut start = 0;
-        for i in 0..n_pages {
-          let end = ((i + 1) * n) / n_pages;
-          res.push(end - start);
-          start = end;
+      PagingSpec::EqualPagesUpTo(max_page_n) => equal_pages_up_to(n, *max_page_n),
+      PagingSpec::Exact(n_per_page) => n_per_page.to_vec(),
+      PagingSpec::TargetPageSize(max_page_bytes) => {
+        let max_page_n = max_page_bytes / dtype_size;
+        if max_page_n == 0 {
+          return Err(PcoError::invalid_argument(format!(
+            "target page size of {} bytes is smaller than a single {}-byte number",
+            max_page_bytes, dtype_size,
+          )));
         }
-        res
+        equal_pages_up_to(n, max_page_n)
+      }
+      PagingSpec::EqualPages(n_pages) => {
+        if *n_pages == 0 && n > 0 {
+          return Err(PcoError::invalid_argument(format!(
+            "cannot divide {} numbers into 0 pages",
+            n,
+          )));
+        }
+        let n_pages = min(*n_pages, n);
+        if n_pages == 0 {
+          return Ok(Vec::new());
+        }
+        let base = n / n_pages;
+        let n_extra = n % n_pages;
+        (0..n_pages)
+          .map(|i| if i < n_extra { base + 1 } else { base })
+          .collect()
       }
-      PagingSpec::Exact(n_per_page) => n_per_page.to_vec(),
     };
 
     let summed_n: usize = n_per_page.iter().sum();
@@ -247,4 +818,159 @@ impl PagingSpec {
 
     Ok(n_per_page)
   }
+
+  // the most numbers a page can have when the total count isn't known ahead
+  // of time
+  pub(crate) fn max_page_n_for_unknown_n<L: Latent>(&self) -> PcoResult<usize> {
+    let dtype_size = mem::size_of::<L>();
+    match self {
+      PagingSpec::EqualPagesUpTo(max_page_n) if *max_page_n > 0 => Ok(*max_page_n),
+      PagingSpec::TargetPageSize(max_page_bytes) if *max_page_bytes >= dtype_size => {
+        Ok(max_page_bytes / dtype_size)
+      }
+      PagingSpec::EqualPagesUpTo(_) | PagingSpec::TargetPageSize(_) => Err(
+        PcoError::invalid_argument("paging spec must allow at least one number per page"),
+      ),
+      PagingSpec::Exact(_) | PagingSpec::EqualPages(_) => Err(PcoError::invalid_argument(format!(
+        "paging spec {:?} requires knowing the total count of numbers",
+        self
+      ))),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::errors::ErrorKind;
+
+  #[test]
+  fn test_target_page_size() -> PcoResult<()> {
+    let spec = PagingSpec::TargetPageSize(1 << 18);
+    assert_eq!(
+      spec.n_per_page::<u64>(100_000)?,
+      vec![25_000; 4],
+    );
+    assert_eq!(spec.n_per_page::<u32>(65_536)?, vec![65_536]);
+    assert_eq!(
+      spec.n_per_page::<u32>(65_537)?,
+      vec![32_768, 32_769],
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_equal_pages() -> PcoResult<()> {
+    let spec = PagingSpec::EqualPages(4);
+    assert_eq!(spec.n_per_page::<u32>(100)?, vec![25; 4]);
+    assert_eq!(spec.n_per_page::<u32>(10)?, vec![3, 3, 2, 2]);
+    assert_eq!(spec.n_per_page::<u32>(4)?, vec![1; 4]);
+    assert_eq!(spec.n_per_page::<u32>(3)?, vec![1; 3]);
+    assert_eq!(
+      spec.n_per_page::<u32>(0)?,
+      Vec::<usize>::new()
+    );
+    assert_eq!(
+      PagingSpec::EqualPages(0).n_per_page::<u32>(0)?,
+      Vec::<usize>::new()
+    );
+
+    let err = PagingSpec::EqualPages(0).n_per_page::<u32>(10).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_overrides() {
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(2));
+    assert!(matches!(
+      ChunkConfigOverrides::default().apply(&config),
+      Cow::Borrowed(_)
+    ));
+
+    let overridden = ChunkConfigOverrides::default()
+      .with_delta_encoding_order(Some(0))
+      .apply(&config);
+    assert_eq!(overridden.delta_encoding_order, Some(0));
+    assert_eq!(
+      overridden.compression_level,
+      config.compression_level
+    );
+
+    let overridden = ChunkConfigOverrides::default()
+      .with_compression_level(2)
+      .with_delta_encoding_order(None)
+      .apply(&config);
+    assert_eq!(overridden.compression_level, 2);
+    assert_eq!(overridden.delta_encoding_order, None);
+  }
+
+  #[test]
+  fn test_validate() -> PcoResult<()> {
+    ChunkConfig::default().validate()?;
+    ChunkConfig::default()
+      .with_compression_level(MAX_COMPRESSION_LEVEL)
+      .with_delta_encoding_order(Some(MAX_DELTA_ENCODING_ORDER))
+      .validate()?;
+
+    for config in [
+      ChunkConfig::default().with_compression_level(MAX_COMPRESSION_LEVEL + 1),
+      ChunkConfig::default().with_delta_encoding_order(Some(MAX_DELTA_ENCODING_ORDER + 1)),
+      ChunkConfig::default().with_secondary_delta_encoding_order(MAX_DELTA_ENCODING_ORDER + 1),
+      ChunkConfig::default().with_max_ans_size_log(MAX_COMPRESSOR_ANS_SIZE_LOG + 1),
+      ChunkConfig::default().with_max_n_bins(Some(0)),
+    ] {
+      let err = config.validate().unwrap_err();
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_min_format_version() {
+    assert_eq!(
+      ChunkConfig::default().min_format_version(),
+      2
+    );
+    assert_eq!(
+      ChunkConfig::default()
+        .with_delta_encoding_order(Some(7))
+        .with_float_mult_spec(FloatMultSpec::Lossy(0.5))
+        .min_format_version(),
+      2
+    );
+    for config in [
+      ChunkConfig::default().with_float_xor_spec(FloatXorSpec::Enabled),
+      ChunkConfig::default().with_run_length_spec(RunLengthSpec::Enabled),
+      ChunkConfig::default().with_dict_spec(DictSpec::Enabled),
+      ChunkConfig::default().with_sparse_spec(SparseSpec::Enabled),
+      ChunkConfig::default().with_force_mode(Some(ModeKind::Sparse)),
+      ChunkConfig::default().with_delta_encoding_order(Some(8)),
+      ChunkConfig::default().with_secondary_delta_encoding_order(1),
+    ] {
+      assert_eq!(
+        config.min_format_version(),
+        3,
+        "{:?}",
+        config
+      );
+    }
+  }
+
+  #[test]
+  fn test_target_page_size_too_small() {
+    let err = PagingSpec::TargetPageSize(7)
+      .n_per_page::<u64>(100)
+      .unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+  }
 }
@@ -1,22 +1,43 @@
 use std::cmp::min;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::io::Write;
+use std::marker::PhantomData;
 
 use better_io::BetterBufRead;
 
+use crate::ans::Decoder;
+use crate::ans_table::AnsTable;
 use crate::bin::Bin;
 use crate::bit_reader::BitReaderBuilder;
 use crate::bit_writer::BitWriter;
 use crate::bits::bits_to_encode_offset_bits;
 use crate::constants::*;
-use crate::data_types::Latent;
+use crate::data_types::{Latent, NumberLike};
 use crate::errors::{PcoError, PcoResult};
 use crate::format_version::FormatVersion;
+use crate::histogram_accumulator::HistogramAccumulator;
+use crate::wrapped::guarantee;
 use crate::Mode;
 
 pub(crate) fn bin_exact_bit_size<L: Latent>(ans_size_log: Bitlen) -> Bitlen {
   ans_size_log + L::BITS + bits_to_encode_offset_bits::<L>()
 }
 
+/// A summary of the offset bits across a latent variable's bins, as returned
+/// by [`ChunkLatentVarMeta::offset_bits_summary`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct OffsetBitsSummary {
+  /// The fewest offset bits of any bin.
+  pub min: Bitlen,
+  /// The most offset bits of any bin.
+  pub max: Bitlen,
+  /// The average offset bits per latent, weighting each bin by its
+  /// approximate fraction of latents, `weight / 2^ans_size_log`.
+  pub weighted_mean: f64,
+}
+
 /// Part of [`ChunkMeta`][crate::ChunkMeta] that describes a latent
 /// variable interleaved into the compressed data.
 ///
@@ -26,6 +47,7 @@ pub(crate) fn bin_exact_bit_size<L: Latent>(ans_size_log: Bitlen) -> Bitlen {
 ///
 /// This is mainly useful for inspecting how compression was done.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChunkLatentVarMeta<L: Latent> {
   /// The log2 of the number of the number of states in this chunk's tANS
   /// table.
@@ -38,6 +60,102 @@ pub struct ChunkLatentVarMeta<L: Latent> {
 }
 
 impl<L: Latent> ChunkLatentVarMeta<L> {
+  /// Returns a rough histogram of this latent variable, with one
+  /// `(lower, upper, fraction)` bucket per bin, sorted by `lower`.
+  ///
+  /// Each bucket covers the inclusive latent range `[lower, upper]`, where
+  /// `upper = lower + 2^offset_bits - 1`.
+  /// A bin's weight is its number of states in the tANS table, so the
+  /// approximate fraction of latents falling in its range is
+  /// `weight / 2^ans_size_log`, and the fractions sum to 1.
+  /// A trivial latent variable with a single bin yields a single bucket with
+  /// fraction 1.0, and one with no bins (an empty chunk) yields no buckets.
+  ///
+  /// The ranges are in terms of latents, not numbers.
+  /// For the primary latent variable in classic mode with delta encoding
+  /// order 0, they can be converted back to numbers via
+  /// [`NumberLike::from_latent_ordered`][crate::data_types::NumberLike::from_latent_ordered].
+  pub fn histogram(&self) -> Vec<(L, L, f64)> {
+    let total_weight = (1_u64 << self.ans_size_log) as f64;
+    let mut res = self
+      .bins
+      .iter()
+      .map(|bin| {
+        let upper = if bin.offset_bits == 0 {
+          bin.lower
+        } else {
+          let max_offset = L::MAX >> (L::BITS - bin.offset_bits);
+          bin.lower.wrapping_add(max_offset)
+        };
+        (
+          bin.lower,
+          upper,
+          bin.weight as f64 / total_weight,
+        )
+      })
+      .collect::<Vec<_>>();
+    res.sort_unstable_by_key(|&(lower, _, _)| lower);
+    res
+  }
+
+  /// Returns the bin that `latent` would be encoded with, or `None` if no bin
+  /// covers it.
+  ///
+  /// Like the compressor, this picks the bin with the greatest `lower` that
+  /// is at most `latent`, then checks that the offset `latent - lower` fits
+  /// in its `offset_bits`.
+  /// This is useful for debugging unexpected offset widths.
+  /// A latent of the chunk that no bin covers indicates corruption or a bug.
+  pub fn bin_for_latent(&self, latent: L) -> Option<&Bin<L>> {
+    let bin = self
+      .bins
+      .iter()
+      .filter(|bin| bin.lower <= latent)
+      .max_by_key(|bin| bin.lower)?;
+    let offset = latent - bin.lower;
+    if bin.offset_bits >= L::BITS || offset >> bin.offset_bits == L::ZERO {
+      Some(bin)
+    } else {
+      None
+    }
+  }
+
+  /// Returns the min, max, and weighted mean of the bins' `offset_bits`, or
+  /// `None` if there are no bins.
+  ///
+  /// Offsets are the bits each latent spends on its position within its bin,
+  /// on top of its tANS-coded bin index.
+  /// A weighted mean close to the max suggests most latents fall in the
+  /// widest bins, in which case a different mode or delta encoding order
+  /// might pack them more tightly.
+  pub fn offset_bits_summary(&self) -> Option<OffsetBitsSummary> {
+    let total_weight = (1_u64 << self.ans_size_log) as f64;
+    let min = self.bins.iter().map(|bin| bin.offset_bits).min()?;
+    let weighted_mean = self
+      .bins
+      .iter()
+      .map(|bin| bin.offset_bits as f64 * bin.weight as f64 / total_weight)
+      .sum();
+    Some(OffsetBitsSummary {
+      min,
+      max: self.max_bits_per_offset(),
+      weighted_mean,
+    })
+  }
+
+  /// Builds the tANS decoding table for this latent variable from its
+  /// `ans_size_log` and bin weights.
+  ///
+  /// Will return an error if the bin weights do not sum to
+  /// `2^ans_size_log`.
+  pub fn build_ans_table(&self) -> PcoResult<AnsTable> {
+    let decoder = Decoder::from_chunk_latent_var_meta(self)?;
+    Ok(AnsTable::from_decoder(
+      self.ans_size_log,
+      &decoder,
+    ))
+  }
+
   pub(crate) fn max_bits_per_offset(&self) -> Bitlen {
     self
       .bins
@@ -72,14 +190,19 @@ unsafe fn parse_bin_batch<L: Latent, R: BetterBufRead>(
       let weight = reader.read_uint::<Weight>(ans_size_log) + 1;
       let lower = reader.read_uint::<L>(L::BITS);
 
+      let offset_bits_idx = reader.absolute_bit_idx();
       let offset_bits = reader.read_bitlen(offset_bits_bits);
       if offset_bits > L::BITS {
         reader.check_in_bounds()?;
-        return Err(PcoError::corruption(format!(
-          "offset bits of {} exceeds data type of {} bits",
-          offset_bits,
-          L::BITS,
-        )));
+        return Err(PcoError::corruption_at(
+          format!(
+            "offset bits of {} exceeds data type of {} bits",
+            offset_bits,
+            L::BITS,
+          ),
+          "chunk metadata",
+          offset_bits_idx,
+        ));
       }
 
       dst.push(Bin {
@@ -98,6 +221,7 @@ impl<L: Latent> ChunkLatentVarMeta<L> {
   unsafe fn parse_from<R: BetterBufRead>(
     reader_builder: &mut BitReaderBuilder<R>,
   ) -> PcoResult<Self> {
+    let start_bit_idx = reader_builder.bit_idx();
     let (ans_size_log, n_bins) = reader_builder.with_reader(|reader| {
       let ans_size_log = reader.read_bitlen(BITS_TO_ENCODE_ANS_SIZE_LOG);
       let n_bins = reader.read_usize(BITS_TO_ENCODE_N_BINS);
@@ -105,22 +229,34 @@ impl<L: Latent> ChunkLatentVarMeta<L> {
     })?;
 
     if 1 << ans_size_log < n_bins {
-      return Err(PcoError::corruption(format!(
-        "ANS size log ({}) is too small for number of bins ({})",
-        ans_size_log, n_bins,
-      )));
+      return Err(PcoError::corruption_at(
+        format!(
+          "ANS size log ({}) is too small for number of bins ({})",
+          ans_size_log, n_bins,
+        ),
+        "chunk metadata",
+        start_bit_idx,
+      ));
     }
     if n_bins == 1 && ans_size_log > 0 {
-      return Err(PcoError::corruption(format!(
-        "Only 1 bin but ANS size log is {} (should be 0)",
-        ans_size_log,
-      )));
+      return Err(PcoError::corruption_at(
+        format!(
+          "Only 1 bin but ANS size log is {} (should be 0)",
+          ans_size_log,
+        ),
+        "chunk metadata",
+        start_bit_idx,
+      ));
     }
     if ans_size_log > MAX_ANS_BITS {
-      return Err(PcoError::corruption(format!(
-        "ANS size log ({}) should not be greater than {}",
-        ans_size_log, MAX_ANS_BITS,
-      )));
+      return Err(PcoError::corruption_at(
+        format!(
+          "ANS size log ({}) should not be greater than {}",
+          ans_size_log, MAX_ANS_BITS,
+        ),
+        "chunk metadata",
+        start_bit_idx,
+      ));
     }
 
     let mut bins = Vec::with_capacity(n_bins);
@@ -159,19 +295,124 @@ impl<L: Latent> ChunkLatentVarMeta<L> {
 
 /// The metadata of a pco chunk.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct ChunkMeta<L: Latent> {
   /// The formula `pco` used to compress each number at a low level.
   pub mode: Mode<L>,
   /// How many times delta encoding was applied during compression.
-  /// This is between 0 and 7, inclusive.
+  /// This is between 0 and 15, inclusive, or up to 7 for data compressed by
+  /// format versions before 3.
   ///
   /// See [`ChunkConfig`][crate::ChunkConfig] for more details.
   pub delta_encoding_order: usize,
+  /// How many times delta encoding was applied to the secondary latent
+  /// variable during compression, if the mode has one.
+  /// This is between 0 and 15, inclusive, and is always 0 for data
+  /// compressed by format versions before 3.
+  ///
+  /// See [`ChunkConfig`][crate::ChunkConfig] for more details.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub secondary_delta_encoding_order: usize,
   /// Metadata about the interleaved streams needed by `pco` to
   /// compress/decompress the inputs
   /// according to the formula used by `mode`.
   pub per_latent_var: Vec<ChunkLatentVarMeta<L>>,
+  /// The sorted distinct latents of the chunk's numbers in
+  /// [dict mode][crate::Mode::Dict], which the primary latent variable
+  /// indexes into.
+  /// This is empty in all other modes.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub dict: Vec<L>,
+}
+
+/// Approximates the `q`th quantile of the numbers in the given chunks from
+/// their bins alone, without decoding any pages.
+///
+/// This is a shorthand for adding every chunk to a [`HistogramAccumulator`]
+/// via [`add_with_count`][HistogramAccumulator::add_with_count] and taking its
+/// [`quantile`][HistogramAccumulator::quantile], so the error is bounded by
+/// the width of the bin containing the target cumulative weight.
+/// Chunk metadata does not record the count of numbers, so each chunk is
+/// paired with its count `n`, e.g. from
+/// [`ChunkDecompressor::n`][crate::standalone::ChunkDecompressor::n], and
+/// weighted by it.
+/// The result is a latent, which can be converted back to a number via
+/// [`NumberLike::from_latent_ordered`].
+///
+/// Will return an InvalidArgument error if `q` is not in `[0, 1]`, if no
+/// chunk with a nonzero count has any bins, or if any chunk is not in
+/// classic mode with delta encoding order 0, since otherwise its latents are
+/// not ordered like its numbers.
+pub fn approximate_quantile<L: Latent>(chunks: &[(ChunkMeta<L>, usize)], q: f64) -> PcoResult<L> {
+  let mut acc = HistogramAccumulator::new();
+  for (meta, n) in chunks {
+    acc.add_with_count(meta, *n)?;
+  }
+  acc.quantile(q)
+}
+
+/// Displays a concise summary of the mode, delta encoding orders, and count
+/// of bins for each latent variable, like
+/// `mode=Classic, delta order=1, bins per latent var=[12]`.
+///
+/// Use [`ChunkMeta::display`] to show float mult bases as floats.
+impl<L: Latent> Display for ChunkMeta<L> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    self.fmt_with_mode(f, self.mode)
+  }
+}
+
+struct ChunkMetaDisplay<'a, T: NumberLike> {
+  meta: &'a ChunkMeta<T::L>,
+  phantom: PhantomData<T>,
+}
+
+impl<T: NumberLike> Display for ChunkMetaDisplay<'_, T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    self.meta.fmt_with_mode(f, self.meta.mode.display::<T>())
+  }
+}
+
+unsafe fn parse_dict<L: Latent, R: BetterBufRead>(
+  reader_builder: &mut BitReaderBuilder<R>,
+) -> PcoResult<Vec<L>> {
+  let start_bit_idx = reader_builder.bit_idx();
+  let dict_size =
+    reader_builder.with_reader(|reader| Ok(reader.read_usize(BITS_TO_ENCODE_DICT_SIZE)))?;
+  if dict_size > MAX_DICT_SIZE {
+    return Err(PcoError::corruption_at(
+      format!(
+        "dict size ({}) should not be greater than {}",
+        dict_size, MAX_DICT_SIZE,
+      ),
+      "chunk metadata",
+      start_bit_idx,
+    ));
+  }
+
+  let mut dict = Vec::with_capacity(dict_size);
+  while dict.len() < dict_size {
+    let batch_size = min(dict_size - dict.len(), FULL_BIN_BATCH_SIZE);
+    reader_builder.with_reader(|reader| {
+      for _ in 0..batch_size {
+        dict.push(reader.read_uint::<L>(L::BITS));
+      }
+      Ok(())
+    })?;
+  }
+  Ok(dict)
+}
+
+unsafe fn write_dict<L: Latent, W: Write>(dict: &[L], writer: &mut BitWriter<W>) -> PcoResult<()> {
+  writer.write_usize(dict.len(), BITS_TO_ENCODE_DICT_SIZE);
+  for dict_batch in dict.chunks(FULL_BIN_BATCH_SIZE) {
+    for &latent in dict_batch {
+      writer.write_uint(latent, L::BITS);
+    }
+    writer.flush()?;
+  }
+  Ok(())
 }
 
 unsafe fn write_bins<L: Latent, W: Write>(
@@ -193,49 +434,157 @@ unsafe fn write_bins<L: Latent, W: Write>(
 }
 
 impl<L: Latent> ChunkMeta<L> {
+  /// Returns the earliest format version able to represent this chunk
+  /// metadata, i.e. the minimum version a reader must support to decompress
+  /// the chunk.
+  ///
+  /// This accounts for the mode (see
+  /// [`ModeKind::min_format_version`][crate::ModeKind::min_format_version]) and
+  /// for secondary delta orders and delta orders above 7, which need format
+  /// version 3.
+  /// Compressors write the format version their config calls for (see
+  /// [`ChunkConfig::min_format_version`][crate::ChunkConfig::min_format_version]),
+  /// which may be higher.
+  pub fn min_format_version(&self) -> u8 {
+    let delta_version = if self.delta_encoding_order > 7 || self.secondary_delta_encoding_order > 0
+    {
+      3
+    } else {
+      0
+    };
+    self.mode.kind().min_format_version().max(delta_version)
+  }
+
+  /// Returns whether the chunk was stored raw, i.e. as uncompressed latents.
+  ///
+  /// Compressors store a chunk raw whenever its planned encoding could
+  /// exceed the raw size of its numbers, e.g. for random data, so that `pco`
+  /// never expands data by more than a few bytes of metadata per chunk (see
+  /// [`guarantee`][crate::wrapped::guarantee]).
+  /// Raw chunks use classic mode without delta encoding and have a single
+  /// full-width bin, which needs no ANS, so decompressing them just reads the
+  /// latents directly.
+  pub fn is_raw(&self) -> bool {
+    *self == guarantee::baseline_chunk_meta::<L>()
+  }
+
+  /// Returns a concise, human-readable summary of the chunk metadata, like
+  /// `Display`, but with the mode displayed via [`Mode::display`] so any
+  /// float base is decoded as a `T`.
+  pub fn display<T: NumberLike<L = L>>(&self) -> impl Display + '_ {
+    ChunkMetaDisplay::<T> {
+      meta: self,
+      phantom: PhantomData,
+    }
+  }
+
+  fn fmt_with_mode(&self, f: &mut Formatter<'_>, mode: impl Display) -> fmt::Result {
+    write!(
+      f,
+      "mode={}, delta order={}",
+      mode, self.delta_encoding_order
+    )?;
+    if self.per_latent_var.len() >= 2 {
+      write!(
+        f,
+        ", secondary delta order={}",
+        self.secondary_delta_encoding_order
+      )?;
+    }
+    if matches!(self.mode, Mode::Dict) {
+      write!(f, ", dict size={}", self.dict.len())?;
+    }
+    let n_bins = self
+      .per_latent_var
+      .iter()
+      .map(|latent_var| latent_var.bins.len().to_string())
+      .collect::<Vec<_>>();
+    write!(
+      f,
+      ", bins per latent var=[{}]",
+      n_bins.join(", ")
+    )
+  }
+
+  /// Returns a rough histogram of the primary latent variable.
+  ///
+  /// See [`ChunkLatentVarMeta::histogram`] for details.
+  pub fn histogram(&self) -> Vec<(L, L, f64)> {
+    self
+      .per_latent_var
+      .first()
+      .map(ChunkLatentVarMeta::histogram)
+      .unwrap_or_default()
+  }
+
   pub(crate) fn new(
     mode: Mode<L>,
     delta_encoding_order: usize,
+    secondary_delta_encoding_order: usize,
     per_latent_var: Vec<ChunkLatentVarMeta<L>>,
   ) -> Self {
     ChunkMeta {
       mode,
       delta_encoding_order,
+      secondary_delta_encoding_order,
       per_latent_var,
+      dict: Vec::new(),
     }
   }
 
-  pub(crate) fn exact_size(&self) -> usize {
+  pub(crate) fn exact_size(&self, version: &FormatVersion) -> usize {
     let extra_bits_for_mode = match self.mode {
       Mode::Classic => 0,
       Mode::IntMult(_) => L::BITS,
       Mode::FloatQuant(_) => BITS_TO_ENCODE_QUANTIZE_K,
       Mode::FloatMult(_) => L::BITS,
+      Mode::FloatXor => 0,
+      Mode::RunLength => 0,
+      Mode::Dict => 0,
+      Mode::Sparse => 0,
     };
     let bits_for_latent_vars: usize = self
       .per_latent_var
       .iter()
       .map(ChunkLatentVarMeta::exact_bit_size)
       .sum();
+    let bits_to_encode_delta_order = version.bits_to_encode_delta_encoding_order();
+    let bits_for_secondary_delta_order =
+      if self.mode.n_latent_vars() >= 2 && version.has_secondary_delta_order() {
+        bits_to_encode_delta_order
+      } else {
+        0
+      };
+    let bits_for_dict = if matches!(self.mode, Mode::Dict) {
+      BITS_TO_ENCODE_DICT_SIZE as usize + self.dict.len() * L::BITS as usize
+    } else {
+      0
+    };
     let n_bits = BITS_TO_ENCODE_MODE as usize
       + extra_bits_for_mode as usize
-      + BITS_TO_ENCODE_DELTA_ENCODING_ORDER as usize
-      + bits_for_latent_vars;
+      + bits_to_encode_delta_order as usize
+      + bits_for_secondary_delta_order as usize
+      + bits_for_latent_vars
+      + bits_for_dict;
     n_bits.div_ceil(8)
   }
 
   pub(crate) fn exact_page_meta_size(&self) -> usize {
-    let bit_size: usize = self
-      .per_latent_var
-      .iter()
-      .enumerate()
-      .map(|(latent_var_idx, latent_var)| {
-        let delta_order = self
-          .mode
-          .delta_order_for_latent_var(latent_var_idx, self.delta_encoding_order);
-        latent_var.ans_size_log as usize * ANS_INTERLEAVING + L::BITS as usize * delta_order
-      })
-      .sum();
+    let bits_for_n_runs = if self.mode.has_runs() {
+      BITS_TO_ENCODE_N_RUNS as usize
+    } else {
+      0
+    };
+    let bit_size: usize = bits_for_n_runs
+      + self
+        .per_latent_var
+        .iter()
+        .enumerate()
+        .map(|(latent_var_idx, latent_var)| {
+          let delta_order = self.delta_order_for_latent_var(latent_var_idx);
+          latent_var.ans_size_log as usize * ANS_INTERLEAVING + L::BITS as usize * delta_order
+        })
+        .sum::<usize>();
     bit_size.div_ceil(8)
   }
 
@@ -243,37 +592,86 @@ impl<L: Latent> ChunkMeta<L> {
     reader_builder: &mut BitReaderBuilder<R>,
     version: &FormatVersion,
   ) -> PcoResult<Self> {
-    let (mode, delta_encoding_order) = reader_builder.with_reader(|reader| {
-      let mode = match reader.read_usize(BITS_TO_ENCODE_MODE) {
-        0 => Ok(Mode::Classic),
-        1 => {
-          if version.used_old_gcds() {
-            return Err(PcoError::compatibility(
-              "unable to decompress data from v0.0.0 of pco with different GCD encoding",
-            ));
-          }
+    let (mode, delta_encoding_order, secondary_delta_encoding_order) =
+      reader_builder.with_reader(|reader| {
+        let mode_bit_idx = reader.absolute_bit_idx();
+        let mode = match reader.read_usize(BITS_TO_ENCODE_MODE) {
+          0 => Ok(Mode::Classic),
+          1 => {
+            if version.used_old_gcds() {
+              return Err(PcoError::compatibility(
+                "unable to decompress data from v0.0.0 of pco with different GCD encoding",
+              ));
+            }
 
-          let base = reader.read_uint::<L>(L::BITS);
-          Ok(Mode::IntMult(base))
-        }
-        2 => {
-          let base_latent = reader.read_uint::<L>(L::BITS);
-          Ok(Mode::FloatMult(base_latent))
-        }
-        3 => {
-          let k = reader.read_bitlen(BITS_TO_ENCODE_QUANTIZE_K);
-          Ok(Mode::FloatQuant(k))
+            let base = reader.read_uint::<L>(L::BITS);
+            Ok(Mode::IntMult(base))
+          }
+          2 => {
+            let base_latent = reader.read_uint::<L>(L::BITS);
+            Ok(Mode::FloatMult(base_latent))
+          }
+          3 => {
+            let k = reader.read_bitlen(BITS_TO_ENCODE_QUANTIZE_K);
+            Ok(Mode::FloatQuant(k))
+          }
+          4 => Ok(Mode::FloatXor),
+          5 => Ok(Mode::RunLength),
+          6 => Ok(Mode::Dict),
+          7 => Ok(Mode::Sparse),
+          // Older format versions only used modes we know, so an unknown mode
+          // there means the file is corrupt. In the current format version, it
+          // may be a mode added by a newer version of pco.
+          value if version.0 >= CURRENT_FORMAT_VERSION => {
+            Err(PcoError::compatibility(format!(
+              "unknown mode value {} in format version {}; it may come from a newer version of pco, so consider upgrading",
+              value, version.0,
+            )))
+          }
+          value => Err(PcoError::corruption_at(
+            format!("unknown mode value {}", value),
+            "chunk metadata",
+            mode_bit_idx,
+          )),
+        }?;
+        let min_version = mode.kind().min_format_version();
+        if version.0 < min_version {
+          return Err(PcoError::compatibility(format!(
+            "{:?} mode requires format version {} but file has format version {}",
+            mode.kind(),
+            min_version,
+            version.0,
+          )));
         }
-        value => Err(PcoError::corruption(format!(
-          "unknown mode value {}",
-          value
-        ))),
-      }?;
 
-      let delta_encoding_order = reader.read_usize(BITS_TO_ENCODE_DELTA_ENCODING_ORDER);
+        let bits_to_encode_delta_order = version.bits_to_encode_delta_encoding_order();
+        let delta_order_bit_idx = reader.absolute_bit_idx();
+        let delta_encoding_order = reader.read_usize(bits_to_encode_delta_order);
+        let secondary_delta_encoding_order =
+          if mode.n_latent_vars() >= 2 && version.has_secondary_delta_order() {
+            reader.read_usize(bits_to_encode_delta_order)
+          } else {
+            0
+          };
+        if matches!(mode, Mode::FloatXor)
+          && (delta_encoding_order != 0 || secondary_delta_encoding_order != 0)
+        {
+          return Err(PcoError::corruption_at(
+            format!(
+              "float xor mode does not support delta encoding (orders were {} and {})",
+              delta_encoding_order, secondary_delta_encoding_order,
+            ),
+            "chunk metadata",
+            delta_order_bit_idx,
+          ));
+        }
 
-      Ok((mode, delta_encoding_order))
-    })?;
+        Ok((
+          mode,
+          delta_encoding_order,
+          secondary_delta_encoding_order,
+        ))
+      })?;
 
     let n_latent_vars = mode.n_latent_vars();
 
@@ -285,23 +683,44 @@ impl<L: Latent> ChunkMeta<L> {
       )?)
     }
 
+    let dict = if matches!(mode, Mode::Dict) {
+      parse_dict(reader_builder)?
+    } else {
+      Vec::new()
+    };
+
     reader_builder.with_reader(|reader| {
-      reader.drain_empty_byte("nonzero bits in end of final byte of chunk metadata")
+      reader.drain_empty_byte(
+        "nonzero bits in end of final byte of chunk metadata",
+        "chunk metadata",
+      )
     })?;
 
     Ok(Self {
       mode,
       delta_encoding_order,
+      secondary_delta_encoding_order,
       per_latent_var,
+      dict,
     })
   }
 
-  pub(crate) unsafe fn write_to<W: Write>(&self, writer: &mut BitWriter<W>) -> PcoResult<()> {
+  // The version must be at least min_format_version, since older versions store
+  // the delta orders in fewer bits and have no secondary delta order.
+  pub(crate) unsafe fn write_to<W: Write>(
+    &self,
+    version: &FormatVersion,
+    writer: &mut BitWriter<W>,
+  ) -> PcoResult<()> {
     let mode_value = match self.mode {
       Mode::Classic => 0,
       Mode::IntMult(_) => 1,
       Mode::FloatMult { .. } => 2,
       Mode::FloatQuant { .. } => 3,
+      Mode::FloatXor => 4,
+      Mode::RunLength => 5,
+      Mode::Dict => 6,
+      Mode::Sparse => 7,
     };
     writer.write_usize(mode_value, BITS_TO_ENCODE_MODE);
     match self.mode {
@@ -315,17 +734,31 @@ impl<L: Latent> ChunkMeta<L> {
       Mode::FloatQuant(k) => {
         writer.write_uint(k, BITS_TO_ENCODE_QUANTIZE_K);
       }
+      Mode::FloatXor => (),
+      Mode::RunLength => (),
+      Mode::Dict => (),
+      Mode::Sparse => (),
     };
 
+    let bits_to_encode_delta_order = version.bits_to_encode_delta_encoding_order();
     writer.write_usize(
       self.delta_encoding_order,
-      BITS_TO_ENCODE_DELTA_ENCODING_ORDER,
+      bits_to_encode_delta_order,
     );
+    if self.mode.n_latent_vars() >= 2 && version.has_secondary_delta_order() {
+      writer.write_usize(
+        self.secondary_delta_encoding_order,
+        bits_to_encode_delta_order,
+      );
+    }
     writer.flush()?;
 
     for latents in &self.per_latent_var {
       latents.write_to(writer)?;
     }
+    if matches!(self.mode, Mode::Dict) {
+      write_dict(&self.dict, writer)?;
+    }
 
     writer.finish_byte();
     writer.flush()?;
@@ -333,36 +766,45 @@ impl<L: Latent> ChunkMeta<L> {
   }
 
   pub(crate) fn delta_order_for_latent_var(&self, latent_idx: usize) -> usize {
-    self
-      .mode
-      .delta_order_for_latent_var(latent_idx, self.delta_encoding_order)
+    self.mode.delta_order_for_latent_var(
+      latent_idx,
+      self.delta_encoding_order,
+      self.secondary_delta_encoding_order,
+    )
   }
 }
 
 #[cfg(test)]
 mod tests {
   use crate::delta::DeltaMoments;
+  use crate::errors::ErrorKind;
   use crate::page_meta::{PageLatentVarMeta, PageMeta};
 
   use super::*;
 
   fn check_exact_sizes<L: Latent>(meta: &ChunkMeta<L>) -> PcoResult<()> {
     let buffer_size = 8192;
-    let mut dst = Vec::new();
-    let mut writer = BitWriter::new(&mut dst, buffer_size);
-    unsafe { meta.write_to(&mut writer)? };
-    writer.flush()?;
-    assert_eq!(meta.exact_size(), dst.len());
+    for version in [COMPATIBLE_FORMAT_VERSION, CURRENT_FORMAT_VERSION] {
+      if meta.min_format_version() > version {
+        continue;
+      }
+      let version = FormatVersion(version);
+      let mut dst = Vec::new();
+      let mut writer = BitWriter::new(&mut dst, buffer_size);
+      unsafe { meta.write_to(&version, &mut writer)? };
+      writer.flush()?;
+      drop(writer);
+      assert_eq!(meta.exact_size(&version), dst.len());
+    }
 
     // page meta size
     let mut dst = Vec::new();
     let mut writer = BitWriter::new(&mut dst, buffer_size);
     let page_meta = PageMeta {
+      n_runs: meta.mode.has_runs().then_some(1),
       per_var: (0..meta.per_latent_var.len())
         .map(|latent_var_idx| {
-          let delta_order = meta
-            .mode
-            .delta_order_for_latent_var(latent_var_idx, meta.delta_encoding_order);
+          let delta_order = meta.delta_order_for_latent_var(latent_var_idx);
           PageLatentVarMeta {
             delta_moments: DeltaMoments {
               moments: vec![L::ZERO; delta_order],
@@ -386,15 +828,294 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn histogram_buckets() {
+    let latent_var = ChunkLatentVarMeta::<u32> {
+      ans_size_log: 3,
+      bins: vec![
+        Bin {
+          weight: 6,
+          lower: 100,
+          offset_bits: 4,
+        },
+        Bin {
+          weight: 2,
+          lower: 0,
+          offset_bits: 32,
+        },
+      ],
+    };
+    assert_eq!(
+      latent_var.histogram(),
+      vec![(0, u32::MAX, 0.25), (100, 115, 0.75)],
+    );
+
+    let trivial = ChunkLatentVarMeta::<u64> {
+      ans_size_log: 0,
+      bins: vec![Bin {
+        weight: 1,
+        lower: 77,
+        offset_bits: 0,
+      }],
+    };
+    assert_eq!(trivial.histogram(), vec![(77, 77, 1.0)]);
+
+    let binless = ChunkLatentVarMeta::<u16> {
+      ans_size_log: 0,
+      bins: vec![],
+    };
+    assert!(binless.histogram().is_empty());
+  }
+
+  #[test]
+  fn bin_for_latent() {
+    let latent_var = ChunkLatentVarMeta::<u32> {
+      ans_size_log: 3,
+      bins: vec![
+        Bin {
+          weight: 6,
+          lower: 100,
+          offset_bits: 4,
+        },
+        Bin {
+          weight: 2,
+          lower: 0,
+          offset_bits: 32,
+        },
+      ],
+    };
+    assert_eq!(
+      latent_var.bin_for_latent(7),
+      Some(&latent_var.bins[1])
+    );
+    assert_eq!(
+      latent_var.bin_for_latent(100),
+      Some(&latent_var.bins[0])
+    );
+    assert_eq!(
+      latent_var.bin_for_latent(115),
+      Some(&latent_var.bins[0])
+    );
+    // the encoder would pick the bin at 100, whose offsets are too narrow
+    assert_eq!(latent_var.bin_for_latent(116), None);
+
+    let trivial = ChunkLatentVarMeta::<u64> {
+      ans_size_log: 0,
+      bins: vec![Bin {
+        weight: 1,
+        lower: 77,
+        offset_bits: 0,
+      }],
+    };
+    assert_eq!(
+      trivial.bin_for_latent(77),
+      Some(&trivial.bins[0])
+    );
+    assert_eq!(trivial.bin_for_latent(76), None);
+    assert_eq!(trivial.bin_for_latent(78), None);
+  }
+
+  #[test]
+  fn display() {
+    let bin = |weight| Bin {
+      weight,
+      lower: 0_u64,
+      offset_bits: 3,
+    };
+    let meta = ChunkMeta::new(
+      Mode::float_mult(0.5_f64),
+      1,
+      0,
+      vec![
+        ChunkLatentVarMeta {
+          ans_size_log: 1,
+          bins: vec![bin(1), bin(1)],
+        },
+        ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins: vec![bin(1)],
+        },
+      ],
+    );
+    assert_eq!(
+      meta.display::<f64>().to_string(),
+      "mode=FloatMult(base=0.5), delta order=1, secondary delta order=0, bins per latent var=[2, 1]",
+    );
+    assert!(meta.to_string().starts_with("mode=FloatMult(base=bits 0x"));
+
+    let dict_meta = ChunkMeta {
+      dict: vec![3_u32, 5, 8],
+      ..ChunkMeta::new(
+        Mode::Dict,
+        0,
+        0,
+        vec![ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins: vec![],
+        }],
+      )
+    };
+    assert_eq!(
+      dict_meta.to_string(),
+      "mode=Dict, delta order=0, dict size=3, bins per latent var=[0]",
+    );
+  }
+
+  #[test]
+  fn quantiles_from_bins() -> PcoResult<()> {
+    let classic = |bins| {
+      ChunkMeta::new(
+        Mode::Classic,
+        0,
+        0,
+        vec![ChunkLatentVarMeta {
+          ans_size_log: 2,
+          bins,
+        }],
+      )
+    };
+    // [0, 99] is 3/4 of the first chunk, [100, 103] is 1/4
+    let first = classic(vec![
+      Bin {
+        weight: 1,
+        lower: 100_u32,
+        offset_bits: 2,
+      },
+      Bin {
+        weight: 3,
+        lower: 0,
+        offset_bits: 0,
+      },
+    ]);
+    let second = classic(vec![Bin {
+      weight: 4,
+      lower: 1000,
+      offset_bits: 10,
+    }]);
+
+    let first = (first, 100);
+    let one = std::slice::from_ref(&first);
+    assert_eq!(approximate_quantile(one, 0.0)?, 0);
+    assert_eq!(approximate_quantile(one, 0.5)?, 0);
+    assert_eq!(approximate_quantile(one, 0.875)?, 101);
+    assert_eq!(approximate_quantile(one, 1.0)?, 103);
+
+    // the second chunk has 3x as many numbers, so it holds 3/4 of the weight
+    let both = [first, (second, 300)];
+    assert_eq!(approximate_quantile(&both, 0.1)?, 0);
+    assert_eq!(approximate_quantile(&both, 0.25)?, 103);
+    assert_eq!(
+      approximate_quantile(&both, 0.625)?,
+      1000 + 511
+    );
+    assert_eq!(
+      approximate_quantile(&both, 1.0)?,
+      1000 + 1023
+    );
+    // chunks without numbers don't count
+    let with_empty = [both[0].clone(), (both[1].0.clone(), 0)];
+    assert_eq!(
+      approximate_quantile(&with_empty, 0.875)?,
+      101
+    );
+
+    for q in [-0.1, 1.5, f64::NAN] {
+      assert!(approximate_quantile(&both, q).is_err());
+    }
+    assert!(approximate_quantile::<u32>(&[], 0.5).is_err());
+    assert!(approximate_quantile(&with_empty[1..], 0.5).is_err());
+    let deltas = ChunkMeta {
+      delta_encoding_order: 1,
+      ..both[0].0.clone()
+    };
+    assert!(approximate_quantile(&[(deltas, 100)], 0.5).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn offset_bits_summary() {
+    let latent_var = ChunkLatentVarMeta::<u32> {
+      ans_size_log: 3,
+      bins: vec![
+        Bin {
+          weight: 6,
+          lower: 100,
+          offset_bits: 4,
+        },
+        Bin {
+          weight: 2,
+          lower: 0,
+          offset_bits: 32,
+        },
+      ],
+    };
+    assert_eq!(
+      latent_var.offset_bits_summary(),
+      Some(OffsetBitsSummary {
+        min: 4,
+        max: 32,
+        weighted_mean: 11.0,
+      })
+    );
+
+    let binless = ChunkLatentVarMeta::<u16> {
+      ans_size_log: 0,
+      bins: vec![],
+    };
+    assert_eq!(binless.offset_bits_summary(), None);
+  }
+
+  #[test]
+  fn build_ans_table() -> PcoResult<()> {
+    let bin = |weight| Bin {
+      weight,
+      lower: 0_u32,
+      offset_bits: 0,
+    };
+    let latent_var = ChunkLatentVarMeta {
+      ans_size_log: 2,
+      bins: vec![bin(3), bin(1)],
+    };
+    let table = latent_var.build_ans_table()?;
+    assert_eq!(table.size_log, 2);
+    let summary = table
+      .nodes
+      .iter()
+      .map(|node| {
+        (
+          node.bin_idx,
+          node.next_state_idx_base,
+          node.bits_to_read,
+        )
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      summary,
+      vec![(0, 2, 1), (1, 0, 2), (0, 0, 0), (0, 1, 0)],
+    );
+
+    let invalid = ChunkLatentVarMeta {
+      ans_size_log: 3,
+      bins: vec![bin(3), bin(1)],
+    };
+    assert!(matches!(
+      invalid.build_ans_table().unwrap_err().kind,
+      ErrorKind::Corruption
+    ));
+    Ok(())
+  }
+
   #[test]
   fn exact_size_binless() -> PcoResult<()> {
     let meta = ChunkMeta::<u32> {
       mode: Mode::Classic,
       delta_encoding_order: 5,
+      secondary_delta_encoding_order: 0,
       per_latent_var: vec![ChunkLatentVarMeta {
         ans_size_log: 0,
         bins: vec![],
       }],
+      dict: vec![],
     };
 
     check_exact_sizes(&meta)
@@ -405,6 +1126,7 @@ mod tests {
     let meta = ChunkMeta::<u64> {
       mode: Mode::Classic,
       delta_encoding_order: 0,
+      secondary_delta_encoding_order: 0,
       per_latent_var: vec![ChunkLatentVarMeta {
         ans_size_log: 0,
         bins: vec![Bin {
@@ -413,6 +1135,7 @@ mod tests {
           offset_bits: 0,
         }],
       }],
+      dict: vec![],
     };
 
     check_exact_sizes(&meta)
@@ -423,6 +1146,7 @@ mod tests {
     let meta = ChunkMeta::<u32> {
       mode: Mode::FloatMult(777_u32),
       delta_encoding_order: 3,
+      secondary_delta_encoding_order: 2,
       per_latent_var: vec![
         ChunkLatentVarMeta {
           ans_size_log: 7,
@@ -455,8 +1179,321 @@ mod tests {
           ],
         },
       ],
+      dict: vec![],
     };
 
     check_exact_sizes(&meta)
   }
+
+  #[test]
+  fn float_xor_round_trip() -> PcoResult<()> {
+    let trivial_var = |lower| ChunkLatentVarMeta {
+      ans_size_log: 0,
+      bins: vec![Bin {
+        weight: 1,
+        lower,
+        offset_bits: 0,
+      }],
+    };
+    let write = |meta: &ChunkMeta<u32>| -> PcoResult<Vec<u8>> {
+      let mut dst = Vec::new();
+      let mut writer = BitWriter::new(&mut dst, 8192);
+      unsafe { meta.write_to(&FormatVersion::default(), &mut writer)? };
+      writer.flush()?;
+      drop(writer);
+      Ok(dst)
+    };
+    let parse = |bytes: &[u8]| unsafe {
+      let mut reader_builder = BitReaderBuilder::new(bytes, CHUNK_META_PADDING, 0);
+      ChunkMeta::<u32>::parse_from(
+        &mut reader_builder,
+        &FormatVersion::default(),
+      )
+    };
+
+    let mut meta = ChunkMeta::<u32> {
+      mode: Mode::FloatXor,
+      delta_encoding_order: 0,
+      secondary_delta_encoding_order: 0,
+      per_latent_var: vec![trivial_var(0), trivial_var(32)],
+      dict: vec![],
+    };
+    check_exact_sizes(&meta)?;
+    assert_eq!(parse(&write(&meta)?)?, meta);
+
+    meta.delta_encoding_order = 1;
+    let err = parse(&write(&meta)?).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    // the delta order comes right after the 4-bit mode
+    assert!(err
+      .message
+      .ends_with("at bit 4 (byte 0) of the chunk metadata"));
+    Ok(())
+  }
+
+  #[test]
+  fn dict_round_trip() -> PcoResult<()> {
+    let meta = ChunkMeta::<u64> {
+      mode: Mode::Dict,
+      delta_encoding_order: 0,
+      secondary_delta_encoding_order: 0,
+      per_latent_var: vec![ChunkLatentVarMeta {
+        ans_size_log: 1,
+        bins: vec![
+          Bin {
+            weight: 1,
+            lower: 0,
+            offset_bits: 1,
+          },
+          Bin {
+            weight: 1,
+            lower: 2,
+            offset_bits: 0,
+          },
+        ],
+      }],
+      dict: (0..300).map(|i| i * 1_000_000_007).collect(),
+    };
+    check_exact_sizes(&meta)?;
+
+    let mut dst = Vec::new();
+    let mut writer = BitWriter::new(&mut dst, 8192);
+    unsafe { meta.write_to(&FormatVersion::default(), &mut writer)? };
+    writer.flush()?;
+    drop(writer);
+    let mut reader_builder = BitReaderBuilder::new(dst.as_slice(), CHUNK_META_PADDING, 0);
+    let parsed = unsafe {
+      ChunkMeta::<u64>::parse_from(
+        &mut reader_builder,
+        &FormatVersion::default(),
+      )?
+    };
+    assert_eq!(parsed, meta);
+    Ok(())
+  }
+
+  #[test]
+  fn corruption_reports_bit_idx() -> PcoResult<()> {
+    let meta = ChunkMeta::<u32> {
+      mode: Mode::IntMult(7),
+      delta_encoding_order: 0,
+      secondary_delta_encoding_order: 0,
+      per_latent_var: vec![
+        ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins: vec![Bin {
+            weight: 1,
+            lower: 0,
+            offset_bits: 3,
+          }],
+        };
+        2
+      ],
+      dict: vec![],
+    };
+    let mut dst = Vec::new();
+    let mut writer = BitWriter::new(&mut dst, 8192);
+    unsafe { meta.write_to(&FormatVersion::default(), &mut writer)? };
+    writer.flush()?;
+    drop(writer);
+    let parse = |bytes: &[u8]| unsafe {
+      let mut reader_builder = BitReaderBuilder::new(bytes, CHUNK_META_PADDING, 0);
+      ChunkMeta::<u32>::parse_from(
+        &mut reader_builder,
+        &FormatVersion::default(),
+      )
+    };
+    assert_eq!(parse(&dst)?, meta);
+
+    let mut bad_mode = dst.clone();
+    bad_mode[0] |= 15;
+    let err = parse(&bad_mode).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Compatibility));
+    assert!(
+      err
+        .message
+        .starts_with("unknown mode value 15 in format version"),
+      "{}",
+      err.message
+    );
+    let err = unsafe {
+      let mut reader_builder = BitReaderBuilder::new(bad_mode.as_slice(), CHUNK_META_PADDING, 0);
+      ChunkMeta::<u32>::parse_from(&mut reader_builder, &FormatVersion(2))
+    }
+    .unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    assert_eq!(
+      err.message,
+      "unknown mode value 15 at bit 0 (byte 0) of the chunk metadata"
+    );
+
+    // The mode, base, and two delta orders take 44 bits, and each var takes
+    // 4 + 15 + 32 + 6 = 57 bits, with its offset bits last, so the second
+    // var's offset bits start at bit 44 + 57 + 4 + 15 + 32 = 152.
+    let mut bad_offset_bits = dst.clone();
+    bad_offset_bits[19] |= 63;
+    let err = parse(&bad_offset_bits).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    assert!(
+      err
+        .message
+        .ends_with("at bit 152 (byte 19) of the chunk metadata"),
+      "{}",
+      err.message
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn parse_secondary_delta_order_by_version() -> PcoResult<()> {
+    let trivial_var = ChunkLatentVarMeta {
+      ans_size_log: 0,
+      bins: vec![Bin {
+        weight: 1,
+        lower: 0_u32,
+        offset_bits: 0,
+      }],
+    };
+    let meta = ChunkMeta::<u32> {
+      mode: Mode::IntMult(7),
+      delta_encoding_order: 2,
+      secondary_delta_encoding_order: 1,
+      per_latent_var: vec![trivial_var.clone(), trivial_var.clone()],
+      dict: vec![],
+    };
+    let parse = |bytes: &[u8], version: u8| unsafe {
+      let mut reader_builder = BitReaderBuilder::new(bytes, CHUNK_META_PADDING, 0);
+      ChunkMeta::<u32>::parse_from(&mut reader_builder, &FormatVersion(version))
+    };
+
+    let mut dst = Vec::new();
+    let mut writer = BitWriter::new(&mut dst, 8192);
+    unsafe { meta.write_to(&FormatVersion::default(), &mut writer)? };
+    writer.flush()?;
+    drop(writer);
+    assert_eq!(parse(&dst, CURRENT_FORMAT_VERSION)?, meta);
+
+    // older versions had no secondary delta order
+    let mut dst = Vec::new();
+    let mut writer = BitWriter::new(&mut dst, 8192);
+    unsafe {
+      writer.write_usize(1, BITS_TO_ENCODE_MODE);
+      writer.write_uint(7_u32, 32);
+      writer.write_usize(2, BITS_TO_ENCODE_OLD_DELTA_ENCODING_ORDER);
+      writer.flush()?;
+      trivial_var.write_to(&mut writer)?;
+      trivial_var.write_to(&mut writer)?;
+      writer.finish_byte();
+    }
+    writer.flush()?;
+    drop(writer);
+    let parsed = parse(&dst, 2)?;
+    assert_eq!(parsed.delta_encoding_order, 2);
+    assert_eq!(parsed.secondary_delta_encoding_order, 0);
+    assert_eq!(parsed.per_latent_var, meta.per_latent_var);
+    assert_eq!(parsed.min_format_version(), 1);
+    assert_eq!(meta.min_format_version(), 3);
+
+    // and writing in the older version gives the same layout
+    let mut old_dst = Vec::new();
+    let mut writer = BitWriter::new(&mut old_dst, 8192);
+    unsafe { parsed.write_to(&FormatVersion(2), &mut writer)? };
+    writer.flush()?;
+    drop(writer);
+    assert_eq!(old_dst, dst);
+    Ok(())
+  }
+
+  #[test]
+  fn parse_mode_by_version() -> PcoResult<()> {
+    let trivial_var = ChunkLatentVarMeta {
+      ans_size_log: 0,
+      bins: vec![Bin {
+        weight: 1,
+        lower: 0_u32,
+        offset_bits: 0,
+      }],
+    };
+    let meta = ChunkMeta::<u32> {
+      mode: Mode::FloatXor,
+      delta_encoding_order: 0,
+      secondary_delta_encoding_order: 0,
+      per_latent_var: vec![trivial_var.clone(), trivial_var],
+      dict: vec![],
+    };
+    assert_eq!(meta.min_format_version(), 3);
+    let mut dst = Vec::new();
+    let mut writer = BitWriter::new(&mut dst, 8192);
+    unsafe { meta.write_to(&FormatVersion::default(), &mut writer)? };
+    writer.flush()?;
+    drop(writer);
+
+    let parse = |version: u8| unsafe {
+      let mut reader_builder = BitReaderBuilder::new(dst.as_slice(), CHUNK_META_PADDING, 0);
+      ChunkMeta::<u32>::parse_from(&mut reader_builder, &FormatVersion(version))
+    };
+    assert_eq!(parse(3)?, meta);
+    let err = parse(2).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Compatibility));
+    assert_eq!(
+      err.message,
+      "FloatXor mode requires format version 3 but file has format version 2"
+    );
+    Ok(())
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_round_trip() -> PcoResult<()> {
+    let meta = ChunkMeta::<u64> {
+      mode: Mode::float_mult(1.5_f64),
+      delta_encoding_order: 1,
+      secondary_delta_encoding_order: 1,
+      per_latent_var: vec![
+        ChunkLatentVarMeta {
+          ans_size_log: 2,
+          bins: vec![
+            Bin {
+              weight: 3,
+              lower: 0,
+              offset_bits: 7,
+            },
+            Bin {
+              weight: 1,
+              lower: u64::MAX - 1,
+              offset_bits: 1,
+            },
+          ],
+        },
+        ChunkLatentVarMeta {
+          ans_size_log: 0,
+          bins: vec![Bin {
+            weight: 1,
+            lower: 1 << 63,
+            offset_bits: 0,
+          }],
+        },
+      ],
+      dict: vec![],
+    };
+
+    let json = serde_json::to_string(&meta).unwrap();
+    let from_json: ChunkMeta<u64> = serde_json::from_str(&json).unwrap();
+    assert_eq!(from_json, meta);
+
+    let mut dst = Vec::new();
+    let mut writer = BitWriter::new(&mut dst, 8192);
+    unsafe { meta.write_to(&FormatVersion::default(), &mut writer)? };
+    writer.flush()?;
+    drop(writer);
+    let mut reader_builder = BitReaderBuilder::new(dst.as_slice(), CHUNK_META_PADDING, 0);
+    let from_binary = unsafe {
+      ChunkMeta::<u64>::parse_from(
+        &mut reader_builder,
+        &FormatVersion::default(),
+      )?
+    };
+    assert_eq!(from_binary, from_json);
+    Ok(())
+  }
 }
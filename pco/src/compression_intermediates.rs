@@ -5,6 +5,9 @@ use crate::data_types::Latent;
 #[derive(Clone, Debug)]
 pub struct PageInfo {
   pub page_n: usize,
+  // the count of latents per var before delta encoding, which is page_n in
+  // every mode except run length, where it is the count of runs
+  pub n_latents: usize,
   pub start_idx: usize,
   pub end_idx_per_var: Vec<usize>,
 }
@@ -22,6 +25,6 @@ pub struct DissectedPageVar<L: Latent> {
 
 #[derive(Clone, Debug)]
 pub struct DissectedPage<L: Latent> {
-  pub page_n: usize,
+  pub n_latents: usize,
   pub per_var: Vec<DissectedPageVar<L>>, // one per latent variable
 }
@@ -0,0 +1,75 @@
+use crate::errors::{PcoError, PcoResult};
+
+/// A breakdown of where the bits of a compressed file go, useful for seeing
+/// whether metadata or the numbers themselves dominate.
+///
+/// See [`compress_with_report`][crate::standalone::compress_with_report].
+/// Every bit of the file is counted in exactly one category, so the counts
+/// sum to 8 times the file's size.
+/// The report also counts how many latent variables were trivial, which
+/// can reveal data that was constant when it was expected to vary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CompressionReport {
+  /// The count of bits in the file's header and footer, plus each chunk's
+  /// data type byte and count of numbers.
+  pub framing_bits: usize,
+  /// The count of bits in chunk metadata, which mostly consists of bins.
+  pub chunk_meta_bits: usize,
+  /// The count of bits in the delta moments stored in page metadata.
+  pub delta_moment_bits: usize,
+  /// The count of bits in the final ANS states stored in page metadata.
+  pub ans_state_bits: usize,
+  /// The count of bits of ANS-encoded bins in page bodies.
+  pub ans_bits: usize,
+  /// The count of bits of offsets within bins in page bodies.
+  pub offset_bits: usize,
+  /// The count of all other bits: run counts in page metadata and padding
+  /// to byte boundaries.
+  pub other_bits: usize,
+  /// The count of latent variables across all chunks.
+  pub n_latent_vars: usize,
+  /// The count of latent variables across all chunks that are trivial,
+  /// i.e. have at most one bin with no offset bits, so their latents take no
+  /// bits in page bodies.
+  pub n_trivial_latent_vars: usize,
+}
+
+impl CompressionReport {
+  /// Returns the total count of bits in all categories.
+  pub fn total_bits(&self) -> usize {
+    self.framing_bits
+      + self.chunk_meta_bits
+      + self.delta_moment_bits
+      + self.ans_state_bits
+      + self.ans_bits
+      + self.offset_bits
+      + self.other_bits
+  }
+
+  /// Returns whether there was at least one latent variable and all of them
+  /// were trivial, meaning every number was determined by chunk metadata
+  /// alone.
+  ///
+  /// This usually means the data was constant, or at least constant after
+  /// delta encoding.
+  pub fn is_all_trivial(&self) -> bool {
+    self.n_latent_vars > 0 && self.n_trivial_latent_vars == self.n_latent_vars
+  }
+
+  /// Returns an InvalidArgument error if [`is_all_trivial`][Self::is_all_trivial]
+  /// holds.
+  ///
+  /// This is meant for pipelines that expect a column to be compressible,
+  /// to catch data that was accidentally constant or a misconfigured column.
+  pub fn ensure_nontrivial(&self) -> PcoResult<()> {
+    if self.is_all_trivial() {
+      Err(PcoError::invalid_argument(format!(
+        "all {} latent variables were trivial; the data is likely constant",
+        self.n_latent_vars,
+      )))
+    } else {
+      Ok(())
+    }
+  }
+}
@@ -8,13 +8,34 @@ pub(crate) type Bitlen = u32;
 pub(crate) type Weight = u32;
 
 // compatibility
-pub const CURRENT_FORMAT_VERSION: u8 = 2;
+/// The newest format version `pco` can write.
+///
+/// Readers can decompress any file with a format version from
+/// [`MIN_FORMAT_VERSION`] to this, inclusive; files with a higher version
+/// come from a newer `pco` and give a compatibility error.
+/// Files are only written with this version when their
+/// [`ChunkConfig`][crate::ChunkConfig] can produce chunks that need it (see
+/// [`ChunkConfig::min_format_version`][crate::ChunkConfig::min_format_version]);
+/// otherwise they get an older version that older readers also support.
+/// Use `format_version()` on a `FileDecompressor` to check a file's version.
+pub const FORMAT_VERSION: u8 = 3;
+/// The earliest format version `pco` can still decompress.
+pub const MIN_FORMAT_VERSION: u8 = 0;
+pub const CURRENT_FORMAT_VERSION: u8 = FORMAT_VERSION;
+// the format version of pco 0.3, written for files that need nothing newer
+pub const COMPATIBLE_FORMAT_VERSION: u8 = 2;
 
 // bit lengths
 pub const BITS_TO_ENCODE_ANS_SIZE_LOG: Bitlen = 4;
-pub const BITS_TO_ENCODE_DELTA_ENCODING_ORDER: Bitlen = 3;
+pub const BITS_TO_ENCODE_DELTA_ENCODING_ORDER: Bitlen = 4;
+// before format version 3, delta encoding orders only went up to 7
+pub const BITS_TO_ENCODE_OLD_DELTA_ENCODING_ORDER: Bitlen = 3;
 pub const BITS_TO_ENCODE_MODE: Bitlen = 4;
 pub const BITS_TO_ENCODE_N_BINS: Bitlen = 15;
+// enough for any dictionary size from 0 to MAX_DICT_SIZE inclusive
+pub const BITS_TO_ENCODE_DICT_SIZE: Bitlen = 13;
+// enough for any count of runs in a page, from 0 to MAX_ENTRIES inclusive
+pub const BITS_TO_ENCODE_N_RUNS: Bitlen = 25;
 // conservative: wide enough to support quantizing float datasets with 255 unused bits of precision
 pub const BITS_TO_ENCODE_QUANTIZE_K: Bitlen = 8;
 
@@ -35,12 +56,26 @@ pub const PAGE_PADDING: usize =
 pub const MAX_ANS_BITS: Bitlen = 14;
 pub const MAX_ANS_BYTES: usize = MAX_ANS_BITS.div_ceil(8) as usize;
 pub const LIMITED_UNOPTIMIZED_BINS_LOG: Bitlen = 6;
+// We cap the ANS table size during compression so that it fits into L1 (or at
+// least L2) cache and has predictably small bitlengths for fast
+// decompression, even though decompression supports up to MAX_ANS_BITS.
+pub const MAX_COMPRESSOR_ANS_SIZE_LOG: Bitlen = 12;
 pub const MAX_COMPRESSION_LEVEL: usize = 12;
-pub const MAX_DELTA_ENCODING_ORDER: usize = 7;
+pub const MAX_DELTA_ENCODING_ORDER: usize = 15;
+// higher orders need format version 3, so they are only used when requested
+pub const MAX_AUTO_DELTA_ENCODING_ORDER: usize = 7;
+pub const MAX_DICT_SIZE: usize = 1 << 12;
 pub const MAX_ENTRIES: usize = 1 << 24;
 pub const MAX_SUPPORTED_PRECISION: Bitlen = 128;
 pub const MAX_SUPPORTED_PRECISION_BYTES: usize = (MAX_SUPPORTED_PRECISION / 8) as usize;
 pub const MULT_REQUIRED_BITS_SAVED_PER_NUM: f64 = 0.5;
+// shorter runs are rarely worth the compute of trying run length mode
+pub const RUN_LENGTH_MIN_AVG_RUN_LENGTH: usize = 8;
+// similarly, sparse mode is rarely worth trying unless at least 3/4 of the
+// numbers are zeros
+pub const SPARSE_MIN_AVG_ENTRY_LENGTH: usize = 4;
+// chunks of up to this many numbers skip mode detection and bin optimization
+pub const TINY_CHUNK_MAX_N: usize = 4;
 pub const CLASSIC_MEMORIZABLE_BINS_LOG: Bitlen = 8;
 
 // defaults
@@ -0,0 +1,156 @@
+use half::{bf16, f16};
+
+use crate::data_types::NumberLike;
+
+/// A number type that
+/// [`decompress_as`][crate::standalone::decompress_as] can cast decoded
+/// numbers to.
+///
+/// Each method returns `None` if the value can't be represented exactly.
+/// Integer types implement this with checked conversions, so e.g. an `i64`
+/// of 70000 or a float of 1.5 can't be cast to a `u16`.
+/// Float types always succeed, rounding to the nearest representable value.
+pub trait CastTarget: NumberLike {
+  fn from_u128(x: u128) -> Option<Self>;
+  fn from_i128(x: i128) -> Option<Self>;
+  fn from_f64(x: f64) -> Option<Self>;
+}
+
+// Implemented by every core data type that can be cast from.
+pub(crate) trait CastSource: NumberLike {
+  fn cast_into<T: CastTarget>(self) -> Option<T>;
+}
+
+macro_rules! impl_int {
+  ($t:ty, $wide:ident, $from_wide:ident) => {
+    impl CastTarget for $t {
+      #[inline]
+      fn from_u128(x: u128) -> Option<Self> {
+        Self::try_from(x).ok()
+      }
+
+      #[inline]
+      fn from_i128(x: i128) -> Option<Self> {
+        Self::try_from(x).ok()
+      }
+
+      #[inline]
+      fn from_f64(x: f64) -> Option<Self> {
+        // MAX as f64 + 1.0 is exactly 2^BITS (or 2^(BITS - 1) if signed),
+        // even once MAX itself rounds up to it. NaN and infinities have NaN
+        // fractional parts, so they are rejected too.
+        if x.fract() == 0.0 && x >= Self::MIN as f64 && x < Self::MAX as f64 + 1.0 {
+          Some(x as Self)
+        } else {
+          None
+        }
+      }
+    }
+
+    impl CastSource for $t {
+      #[inline]
+      fn cast_into<T: CastTarget>(self) -> Option<T> {
+        T::$from_wide(self as $wide)
+      }
+    }
+  };
+}
+
+macro_rules! impl_float {
+  ($t:ty, $from_f64:expr, $to_f64:expr) => {
+    impl CastTarget for $t {
+      #[inline]
+      fn from_u128(x: u128) -> Option<Self> {
+        Some($from_f64(x as f64))
+      }
+
+      #[inline]
+      fn from_i128(x: i128) -> Option<Self> {
+        Some($from_f64(x as f64))
+      }
+
+      #[inline]
+      fn from_f64(x: f64) -> Option<Self> {
+        Some($from_f64(x))
+      }
+    }
+
+    impl CastSource for $t {
+      #[inline]
+      fn cast_into<T: CastTarget>(self) -> Option<T> {
+        T::from_f64($to_f64(self))
+      }
+    }
+  };
+}
+
+impl_int!(u16, u128, from_u128);
+impl_int!(u32, u128, from_u128);
+impl_int!(u64, u128, from_u128);
+impl_int!(u128, u128, from_u128);
+impl_int!(i16, i128, from_i128);
+impl_int!(i32, i128, from_i128);
+impl_int!(i64, i128, from_i128);
+impl_int!(i128, i128, from_i128);
+
+impl_float!(f16, f16::from_f64, f16::to_f64);
+impl_float!(bf16, bf16::from_f64, bf16::to_f64);
+impl_float!(f32, |x| x as f32, |x| x as f64);
+impl_float!(f64, |x| x, |x| x);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_int_casts() {
+    assert_eq!(70000_i64.cast_into::<u32>(), Some(70000));
+    assert_eq!(70000_i64.cast_into::<u16>(), None);
+    assert_eq!((-1_i32).cast_into::<u64>(), None);
+    assert_eq!(u128::MAX.cast_into::<i128>(), None);
+    assert_eq!(
+      u64::MAX.cast_into::<i128>(),
+      Some(u64::MAX as i128)
+    );
+    assert_eq!(i16::MIN.cast_into::<i64>(), Some(-32768));
+  }
+
+  #[test]
+  fn test_float_to_int_casts() {
+    assert_eq!(3.0_f64.cast_into::<u16>(), Some(3));
+    assert_eq!(1.5_f32.cast_into::<i32>(), None);
+    assert_eq!(
+      65535.0_f64.cast_into::<u16>(),
+      Some(u16::MAX)
+    );
+    assert_eq!(65536.0_f64.cast_into::<u16>(), None);
+    assert_eq!(
+      (-32768.0_f64).cast_into::<i16>(),
+      Some(i16::MIN)
+    );
+    assert_eq!((-32769.0_f64).cast_into::<i16>(), None);
+    // u64::MAX as f64 rounds up to 2^64, which doesn't fit
+    assert_eq!((u64::MAX as f64).cast_into::<u64>(), None);
+    assert_eq!(f64::NAN.cast_into::<i64>(), None);
+    assert_eq!(f64::INFINITY.cast_into::<u64>(), None);
+    assert_eq!((-0.0_f64).cast_into::<u32>(), Some(0));
+  }
+
+  #[test]
+  fn test_float_casts() {
+    assert_eq!(0.1_f64.cast_into::<f32>(), Some(0.1_f32));
+    assert_eq!(
+      f16::from_f32(2.5).cast_into::<f64>(),
+      Some(2.5)
+    );
+    assert_eq!(
+      u128::MAX.cast_into::<f32>(),
+      Some(u128::MAX as f32)
+    );
+    assert_eq!(
+      (-7_i16).cast_into::<bf16>(),
+      Some(bf16::from_f32(-7.0))
+    );
+    assert!(f64::NAN.cast_into::<f32>().unwrap().is_nan());
+  }
+}
@@ -1,3 +1,5 @@
+use std::mem;
+
 use CoreDataType::*;
 
 use crate::data_types::NumberLike;
@@ -24,6 +26,10 @@ macro_rules! impl_core_dtypes {
 /// A macro to help cross the dynamic<->generic boundary for pco core data
 /// types.
 ///
+/// `u128`, `i128`, and `half::bf16` are implemented by pco but not yet listed
+/// here, since the Arrow and numpy frontends built on this macro have no
+/// corresponding types.
+///
 /// Accepts a macro of a particular format. For example:
 /// ```
 /// use pco::data_types::{CoreDataType, NumberLike, Latent};
@@ -75,3 +81,144 @@ macro_rules! with_core_latents {
 }
 
 with_core_dtypes!(impl_core_dtypes);
+
+// Like with_core_dtypes, but over every type pco implements NumberLike for
+// without any features, all of which can be cast by decompress_as.
+// Accepts a macro over a repeated list of `$($(#[$attr])* $name => $t,)+`,
+// where `$name` is a string literal; any extra entries are passed through
+// after these.
+macro_rules! with_castable_dtypes {
+  ($inner:ident $(, $($extra:tt)*)?) => {
+    $inner!(
+      "u16" => u16,
+      "u32" => u32,
+      "u64" => u64,
+      "u128" => u128,
+      "i16" => i16,
+      "i32" => i32,
+      "i64" => i64,
+      "i128" => i128,
+      "f16" => half::f16,
+      "bf16" => half::bf16,
+      "f32" => f32,
+      "f64" => f64,
+      $($($extra)*)?
+    )
+  };
+}
+
+// Like with_castable_dtypes, plus the types behind enabled features, whose
+// entries are preceded by their #[cfg] attribute.
+macro_rules! with_all_dtypes {
+  ($inner:ident) => {
+    $crate::data_types::with_castable_dtypes!(
+      $inner,
+      #[cfg(feature = "chrono")]
+      "DateTime<Utc>" => chrono::DateTime<chrono::Utc>,
+      #[cfg(feature = "chrono")]
+      "TimeDelta" => chrono::TimeDelta,
+    )
+  };
+}
+
+pub(crate) use {with_all_dtypes, with_castable_dtypes};
+
+/// A description of a data type `pco` can compress, as returned by
+/// [`supported_types`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TypeInfo {
+  /// The type's name, e.g. `"u32"` or `"f16"`.
+  pub name: &'static str,
+  /// The size of one number of this type in memory, in bytes, i.e.
+  /// `size_of::<T>()`.
+  ///
+  /// This is not the width of its latent, which determines compressed
+  /// sizes: `DateTime<Utc>` is 12 bytes in memory, but its latent is a
+  /// `u64`.
+  pub byte_width: usize,
+  /// The type's [`NumberLike::DTYPE_BYTE`], which identifies it in
+  /// compressed data.
+  pub dtype_byte: u8,
+}
+
+impl TypeInfo {
+  /// Returns the supported type with the given name, if any.
+  pub fn from_name(name: &str) -> Option<Self> {
+    supported_types()
+      .iter()
+      .find(|info| info.name == name)
+      .copied()
+  }
+}
+
+macro_rules! type_infos {
+  {$($(#[$attr:meta])* $name:literal => $t:ty,)+} => {
+    &[$(
+      $(#[$attr])*
+      TypeInfo {
+        name: $name,
+        byte_width: mem::size_of::<$t>(),
+        dtype_byte: <$t as NumberLike>::DTYPE_BYTE,
+      },
+    )+]
+  };
+}
+
+static SUPPORTED_TYPES: &[TypeInfo] = with_all_dtypes!(type_infos);
+
+/// Returns every data type this build of `pco` implements
+/// [`NumberLike`] for, including those behind enabled features.
+///
+/// This is useful for validating a user-provided type name, e.g. in a CLI,
+/// before dispatching to the matching generic instantiation.
+/// Unlike [`with_core_dtypes`][crate::with_core_dtypes], this includes every
+/// implemented type, not just the core ones.
+pub fn supported_types() -> &'static [TypeInfo] {
+  SUPPORTED_TYPES
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use super::*;
+
+  #[test]
+  fn test_supported_types() {
+    let types = supported_types();
+    let dtype_bytes = types
+      .iter()
+      .map(|info| info.dtype_byte)
+      .collect::<HashSet<_>>();
+    assert_eq!(dtype_bytes.len(), types.len());
+
+    macro_rules! check_core_dtypes {
+      {$($name:ident($lname:ident) => $t:ty,)+} => {
+        $(
+          let info = types
+            .iter()
+            .find(|info| info.dtype_byte == CoreDataType::$name as u8)
+            .unwrap();
+          assert_eq!(info.byte_width, mem::size_of::<$t>());
+        )+
+      }
+    }
+    with_core_dtypes!(check_core_dtypes);
+
+    assert_eq!(
+      TypeInfo::from_name("bf16"),
+      Some(TypeInfo {
+        name: "bf16",
+        byte_width: 2,
+        dtype_byte: half::bf16::DTYPE_BYTE,
+      })
+    );
+    assert_eq!(TypeInfo::from_name("u8"), None);
+    #[cfg(feature = "chrono")]
+    assert_eq!(
+      TypeInfo::from_name("DateTime<Utc>").unwrap().byte_width,
+      12
+    );
+  }
+}
@@ -1,20 +1,20 @@
 use std::mem;
 
-use half::f16;
+use half::{bf16, f16};
 
 use crate::constants::Bitlen;
-use crate::data_types::{split_latents_classic, FloatLike, Latent, NumberLike};
+use crate::data_types::{split_latents_classic, FloatLike, Latent, ModeAndLatents, NumberLike};
 use crate::describers::LatentDescriber;
 use crate::errors::{PcoError, PcoResult};
 use crate::{
-  describers, float_mult_utils, float_quant_utils, ChunkConfig, ChunkMeta, FloatMultSpec,
-  FloatQuantSpec, Mode,
+  describers, float_mult_utils, float_quant_utils, float_xor_utils, ChunkConfig, ChunkMeta,
+  FloatMultSpec, FloatQuantSpec, Mode,
 };
 
 fn choose_mode_and_split_latents<F: FloatLike>(
   nums: &[F],
   chunk_config: &ChunkConfig,
-) -> PcoResult<(Mode<F::L>, Vec<Vec<F::L>>)> {
+) -> PcoResult<ModeAndLatents<F::L>> {
   if chunk_config.float_mult_spec != FloatMultSpec::Disabled
     && chunk_config.float_quant_spec != FloatQuantSpec::Disabled
   {
@@ -42,10 +42,33 @@ fn choose_mode_and_split_latents<F: FloatLike>(
         let latents = float_mult_utils::split_latents(nums, base, base.inv());
         (mode, latents)
       }
-      (FloatMultSpec::Disabled, FloatQuantSpec::Provided(k)) => (
-        Mode::FloatQuant(k),
-        float_quant_utils::split_latents(nums, k),
-      ),
+      (FloatMultSpec::Lossy(tolerance_f64), _) => {
+        let tolerance = F::from_f64(tolerance_f64);
+        let base = F::from_f64(2.0 * tolerance_f64);
+        if !(tolerance_f64 > 0.0 && tolerance.is_finite_and_normal() && base.is_finite_and_normal())
+        {
+          return Err(PcoError::invalid_argument(format!(
+            "lossy FloatMult tolerance must be positive and normal for the data type (was {})",
+            tolerance_f64,
+          )));
+        }
+        let mode = Mode::float_mult(base);
+        let latents = float_mult_utils::split_latents_lossy(nums, base, base.inv(), tolerance);
+        (mode, latents)
+      }
+      (FloatMultSpec::Disabled, FloatQuantSpec::Provided(k)) => {
+        if k > F::PRECISION_BITS {
+          return Err(PcoError::invalid_argument(format!(
+            "FloatQuant k may not exceed the {} precision bits of the data type (was {})",
+            F::PRECISION_BITS,
+            k,
+          )));
+        }
+        (
+          Mode::FloatQuant(k),
+          float_quant_utils::split_latents(nums, k),
+        )
+      }
       (FloatMultSpec::Disabled, FloatQuantSpec::Disabled) => {
         (Mode::Classic, split_latents_classic(nums))
       } // TODO(https://github.com/mwlon/pcodec/issues/194): Add a case for FloatQuantSpec::Enabled
@@ -54,6 +77,17 @@ fn choose_mode_and_split_latents<F: FloatLike>(
   )
 }
 
+fn split_latents_for_mode<F: FloatLike>(nums: &[F], mode: Mode<F::L>) -> Vec<Vec<F::L>> {
+  match mode {
+    Mode::FloatMult(base_latent) => {
+      let base = F::from_latent_ordered(base_latent);
+      float_mult_utils::split_latents(nums, base, base.inv())
+    }
+    Mode::FloatQuant(k) => float_quant_utils::split_latents(nums, k),
+    _ => split_latents_classic(nums),
+  }
+}
+
 macro_rules! impl_float_like {
   ($t: ty, $latent: ty, $bits: expr, $exp_offset: expr) => {
     impl FloatLike for $t {
@@ -93,6 +127,16 @@ macro_rules! impl_float_like {
         self as f64
       }
 
+      #[inline]
+      fn is_finite_(&self) -> bool {
+        self.is_finite()
+      }
+
+      #[inline]
+      fn is_nan_(&self) -> bool {
+        self.is_nan()
+      }
+
       #[inline]
       fn is_finite_and_normal(&self) -> bool {
         self.is_finite() && !self.is_subnormal()
@@ -175,119 +219,134 @@ macro_rules! impl_float_like {
   };
 }
 
-impl FloatLike for f16 {
-  const BITS: Bitlen = 16;
-  const PRECISION_BITS: Bitlen = Self::MANTISSA_DIGITS as Bitlen - 1;
-  const ZERO: Self = f16::ZERO;
-  const MAX_FOR_SAMPLING: Self = f16::from_bits(30719); // Half of MAX size.
+macro_rules! impl_half_float_like {
+  ($t: ty, $max_for_sampling_bits: expr, $exp_offset: expr) => {
+    impl FloatLike for $t {
+      const BITS: Bitlen = 16;
+      const PRECISION_BITS: Bitlen = Self::MANTISSA_DIGITS as Bitlen - 1;
+      const ZERO: Self = <$t>::ZERO;
+      // half of MAX size
+      const MAX_FOR_SAMPLING: Self = <$t>::from_bits($max_for_sampling_bits);
 
-  #[inline]
-  fn abs(self) -> Self {
-    Self::from_bits(self.to_bits() & 0x7FFF)
-  }
+      #[inline]
+      fn abs(self) -> Self {
+        Self::from_bits(self.to_bits() & 0x7FFF)
+      }
 
-  fn inv(self) -> Self {
-    Self::ONE / self
-  }
+      fn inv(self) -> Self {
+        Self::ONE / self
+      }
 
-  #[inline]
-  fn round(self) -> Self {
-    Self::from_f32(self.to_f32().round())
-  }
+      #[inline]
+      fn round(self) -> Self {
+        Self::from_f32(self.to_f32().round())
+      }
 
-  #[inline]
-  fn exp2(power: i32) -> Self {
-    Self::from_f32(f32::exp2(power as f32))
-  }
+      #[inline]
+      fn exp2(power: i32) -> Self {
+        Self::from_f32(f32::exp2(power as f32))
+      }
 
-  #[inline]
-  fn from_f64(x: f64) -> Self {
-    Self::from_f64(x)
-  }
+      #[inline]
+      fn from_f64(x: f64) -> Self {
+        Self::from_f64(x)
+      }
 
-  #[inline]
-  fn to_f64(self) -> f64 {
-    self.to_f64()
-  }
+      #[inline]
+      fn to_f64(self) -> f64 {
+        self.to_f64()
+      }
 
-  #[inline]
-  fn is_finite_and_normal(&self) -> bool {
-    self.is_finite() && self.is_normal()
-  }
+      #[inline]
+      fn is_finite_(&self) -> bool {
+        self.is_finite()
+      }
 
-  #[inline]
-  fn is_sign_positive_(&self) -> bool {
-    self.is_sign_positive()
-  }
+      #[inline]
+      fn is_nan_(&self) -> bool {
+        self.is_nan()
+      }
 
-  #[inline]
-  fn exponent(&self) -> i32 {
-    (self.abs().to_bits() >> Self::PRECISION_BITS) as i32 - 15
-  }
+      #[inline]
+      fn is_finite_and_normal(&self) -> bool {
+        self.is_finite() && self.is_normal()
+      }
 
-  #[inline]
-  fn trailing_zeros(&self) -> u32 {
-    self.to_bits().trailing_zeros()
-  }
+      #[inline]
+      fn is_sign_positive_(&self) -> bool {
+        self.is_sign_positive()
+      }
 
-  #[inline]
-  fn max(a: Self, b: Self) -> Self {
-    Self::max(a, b)
-  }
+      #[inline]
+      fn exponent(&self) -> i32 {
+        (self.abs().to_bits() >> Self::PRECISION_BITS) as i32 + $exp_offset
+      }
 
-  #[inline]
-  fn min(a: Self, b: Self) -> Self {
-    Self::min(a, b)
-  }
+      #[inline]
+      fn trailing_zeros(&self) -> u32 {
+        self.to_bits().trailing_zeros()
+      }
 
-  #[inline]
-  fn to_latent_bits(self) -> Self::L {
-    self.to_bits()
-  }
+      #[inline]
+      fn max(a: Self, b: Self) -> Self {
+        Self::max(a, b)
+      }
 
-  #[inline]
-  fn int_float_from_latent(l: Self::L) -> Self {
-    let mid = Self::L::MID;
-    let (negative, abs_int) = if l >= mid {
-      (false, l - mid)
-    } else {
-      (true, mid - 1 - l)
-    };
-    let gpi = 1 << Self::MANTISSA_DIGITS;
-    let abs_float = if abs_int < gpi {
-      Self::from_f32(abs_int as f32)
-    } else {
-      Self::from_bits(Self::from_f32(gpi as f32).to_bits() + (abs_int - gpi))
-    };
-    if negative {
-      -abs_float
-    } else {
-      abs_float
-    }
-  }
+      #[inline]
+      fn min(a: Self, b: Self) -> Self {
+        Self::min(a, b)
+      }
 
-  #[inline]
-  fn int_float_to_latent(self) -> Self::L {
-    let abs = self.abs();
-    let gpi = 1 << Self::MANTISSA_DIGITS;
-    let gpi_float = Self::from_f32(gpi as f32);
-    let abs_int = if abs < gpi_float {
-      abs.to_f32() as Self::L
-    } else {
-      gpi + (abs.to_bits() - gpi_float.to_bits())
-    };
-    if self.is_sign_positive() {
-      Self::L::MID + abs_int
-    } else {
-      // -1 because we need to distinguish -0.0 from +0.0
-      Self::L::MID - 1 - abs_int
-    }
-  }
+      #[inline]
+      fn to_latent_bits(self) -> Self::L {
+        self.to_bits()
+      }
 
-  #[inline]
-  fn from_latent_numerical(l: Self::L) -> Self {
-    Self::from_f32(l as f32)
-  }
+      #[inline]
+      fn int_float_from_latent(l: Self::L) -> Self {
+        let mid = Self::L::MID;
+        let (negative, abs_int) = if l >= mid {
+          (false, l - mid)
+        } else {
+          (true, mid - 1 - l)
+        };
+        let gpi = 1 << Self::MANTISSA_DIGITS;
+        let abs_float = if abs_int < gpi {
+          Self::from_f32(abs_int as f32)
+        } else {
+          Self::from_bits(Self::from_f32(gpi as f32).to_bits() + (abs_int - gpi))
+        };
+        if negative {
+          -abs_float
+        } else {
+          abs_float
+        }
+      }
+
+      #[inline]
+      fn int_float_to_latent(self) -> Self::L {
+        let abs = self.abs();
+        let gpi = 1 << Self::MANTISSA_DIGITS;
+        let gpi_float = Self::from_f32(gpi as f32);
+        let abs_int = if abs < gpi_float {
+          abs.to_f32() as Self::L
+        } else {
+          gpi + (abs.to_bits() - gpi_float.to_bits())
+        };
+        if self.is_sign_positive() {
+          Self::L::MID + abs_int
+        } else {
+          // -1 because we need to distinguish -0.0 from +0.0
+          Self::L::MID - 1 - abs_int
+        }
+      }
+
+      #[inline]
+      fn from_latent_numerical(l: Self::L) -> Self {
+        Self::from_f32(l as f32)
+      }
+    }
+  };
 }
 
 macro_rules! impl_float_number_like {
@@ -311,14 +370,24 @@ macro_rules! impl_float_number_like {
             Self::from_latent_ordered(base_latent).is_finite_and_normal()
           }
           Mode::FloatQuant(k) => k <= Self::PRECISION_BITS,
+          Mode::FloatXor => true,
+          Mode::RunLength => true,
+          Mode::Dict => true,
+          Mode::Sparse => true,
           _ => false,
         }
       }
       fn choose_mode_and_split_latents(
         nums: &[Self],
         config: &ChunkConfig,
-      ) -> (Mode<Self::L>, Vec<Vec<Self::L>>) {
-        choose_mode_and_split_latents(nums, config).unwrap()
+      ) -> PcoResult<ModeAndLatents<Self::L>> {
+        choose_mode_and_split_latents(nums, config)
+      }
+      fn split_latents_for_mode(
+        nums: &[Self],
+        mode: Mode<Self::L>,
+      ) -> PcoResult<Vec<Vec<Self::L>>> {
+        Ok(split_latents_for_mode(nums, mode))
       }
 
       #[inline]
@@ -350,6 +419,7 @@ macro_rules! impl_float_number_like {
             float_mult_utils::join_latents(base, primary, secondary)
           }
           Mode::FloatQuant(k) => float_quant_utils::join_latents::<Self>(k, primary, secondary),
+          Mode::FloatXor => float_xor_utils::join_latents(primary, secondary),
           _ => unreachable!("impossible mode for floats"),
         }
       }
@@ -368,10 +438,13 @@ macro_rules! impl_float_number_like {
 
 impl_float_like!(f32, u32, 32, -127);
 impl_float_like!(f64, u64, 64, -1023);
-// f16 FloatLike is implemented separately because it's non-native.
+// f16 and bf16 FloatLikes are implemented separately because they're non-native.
+impl_half_float_like!(f16, 0x77FF, -15);
+impl_half_float_like!(bf16, 0x7EFF, -127);
 impl_float_number_like!(f32, u32, 1_u32 << 31, 5);
 impl_float_number_like!(f64, u64, 1_u64 << 63, 6);
 impl_float_number_like!(f16, u16, 1_u16 << 15, 9);
+impl_float_number_like!(bf16, u16, 1_u16 << 15, 12);
 
 #[cfg(test)]
 mod tests {
@@ -384,6 +457,23 @@ mod tests {
     assert!((0.0_f32).to_latent_ordered() < f32::INFINITY.to_latent_ordered());
   }
 
+  #[test]
+  fn test_latents_from_bits() {
+    // latents come from the float's bits, never its in-memory bytes, so they
+    // are the same on hosts of either byte order
+    assert_eq!(1.0_f32.to_latent_ordered(), 0xbf800000);
+    assert_eq!((-1.0_f32).to_latent_ordered(), 0x407fffff);
+    assert_eq!(
+      1.0_f64.to_latent_ordered(),
+      0xbff0000000000000
+    );
+    let mut nums = [1.0_f32, -1.0];
+    assert_eq!(
+      f32::transmute_to_latents(&mut nums),
+      &[0x3f800000, 0xbf800000]
+    );
+  }
+
   #[test]
   fn test_exp() {
     assert_eq!(1.0_f32.exponent(), 0);
@@ -1,21 +1,29 @@
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::ops::{
-  Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, Div, DivAssign, Mul, MulAssign, Neg,
-  Rem, RemAssign, Shl, Shr, Sub, SubAssign,
+  Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+  Mul, MulAssign, Neg, Rem, RemAssign, Shl, Shr, Sub, SubAssign,
 };
 
-pub use dynamic::CoreDataType;
+pub use cast::CastTarget;
+pub use dynamic::{supported_types, CoreDataType, TypeInfo};
+pub(crate) use dynamic::{with_all_dtypes, with_castable_dtypes};
 
 use crate::constants::Bitlen;
 use crate::describers::LatentDescriber;
+use crate::errors::PcoResult;
 use crate::{ChunkConfig, ChunkMeta, Mode};
 
+pub(crate) mod cast;
 mod dynamic;
 mod floats;
 mod signeds;
+#[cfg(feature = "chrono")]
+mod timestamps;
 mod unsigneds;
 
+pub(crate) type ModeAndLatents<L> = (Mode<L>, Vec<Vec<L>>);
+
 /// This is used internally for compressing and decompressing with
 /// [`FloatMultMode`][`crate::Mode::FloatMult`].
 pub(crate) trait FloatLike:
@@ -48,6 +56,8 @@ pub(crate) trait FloatLike:
   fn exp2(power: i32) -> Self;
   fn from_f64(x: f64) -> Self;
   fn to_f64(self) -> f64;
+  fn is_finite_(&self) -> bool;
+  fn is_nan_(&self) -> bool;
   fn is_finite_and_normal(&self) -> bool;
   fn is_sign_positive_(&self) -> bool;
   /// Returns the float's exponent. For instance, for f32 this should be
@@ -84,6 +94,8 @@ pub trait Latent:
   + BitOr<Output = Self>
   + BitAndAssign
   + BitOrAssign
+  + BitXor<Output = Self>
+  + BitXorAssign
   + Display
   + Div<Output = Self>
   + DivAssign
@@ -112,6 +124,7 @@ pub trait Latent:
   fn from_u64(x: u64) -> Self;
 
   fn leading_zeros(self) -> Bitlen;
+  fn trailing_zeros(self) -> Bitlen;
 
   /// Converts the latent to a usize, truncating higher bits if necessary.
   fn to_u64(self) -> u64;
@@ -145,7 +158,7 @@ pub trait NumberLike: Copy + Debug + Display + Default + PartialEq + Send + Sync
   ///
   /// To choose a header byte for a new data type, review all header bytes in
   /// the library and pick an unused one. For instance, as of writing, bytes
-  /// 1 through 6 are used, so 7 would be a good choice for another
+  /// 1 through 14 are used, so 15 would be a good choice for another
   /// `pco` data type implementation.
   const DTYPE_BYTE: u8;
   /// If true, decompressors write the primary latent stream to `dst` directly
@@ -167,7 +180,10 @@ pub trait NumberLike: Copy + Debug + Display + Default + PartialEq + Send + Sync
   fn choose_mode_and_split_latents(
     nums: &[Self],
     config: &ChunkConfig,
-  ) -> (Mode<Self::L>, Vec<Vec<Self::L>>);
+  ) -> PcoResult<ModeAndLatents<Self::L>>;
+  /// Splits the numbers into latents for an already chosen classic, int
+  /// mult, float mult, or float quant mode that is valid for this type.
+  fn split_latents_for_mode(nums: &[Self], mode: Mode<Self::L>) -> PcoResult<Vec<Vec<Self::L>>>;
 
   fn from_latent_ordered(l: Self::L) -> Self;
   fn to_latent_ordered(self) -> Self::L;
@@ -1,7 +1,8 @@
 use std::mem;
 
-use crate::data_types::{unsigneds, NumberLike};
+use crate::data_types::{unsigneds, ModeAndLatents, NumberLike};
 use crate::describers::LatentDescriber;
+use crate::errors::PcoResult;
 use crate::{describers, int_mult_utils, ChunkMeta};
 use crate::{ChunkConfig, Mode};
 
@@ -23,14 +24,25 @@ macro_rules! impl_signed {
         match mode {
           Mode::Classic => true,
           Mode::IntMult(_) => true,
+          Mode::RunLength => true,
+          Mode::Dict => true,
+          Mode::Sparse => true,
           _ => false,
         }
       }
       fn choose_mode_and_split_latents(
         nums: &[Self],
         config: &ChunkConfig,
-      ) -> (Mode<Self::L>, Vec<Vec<Self::L>>) {
-        unsigneds::choose_mode_and_split_latents(&nums, config)
+      ) -> PcoResult<ModeAndLatents<Self::L>> {
+        Ok(unsigneds::choose_mode_and_split_latents(
+          &nums, config,
+        ))
+      }
+      fn split_latents_for_mode(
+        nums: &[Self],
+        mode: Mode<Self::L>,
+      ) -> PcoResult<Vec<Vec<Self::L>>> {
+        Ok(unsigneds::split_latents_for_mode(nums, mode))
       }
 
       #[inline]
@@ -54,7 +66,7 @@ macro_rules! impl_signed {
       }
       #[inline]
       fn transmute_to_latent(self) -> Self::L {
-        unsafe { mem::transmute(self) }
+        self as $latent
       }
     }
   };
@@ -63,6 +75,7 @@ macro_rules! impl_signed {
 impl_signed!(i32, u32, 3);
 impl_signed!(i64, u64, 4);
 impl_signed!(i16, u16, 8);
+impl_signed!(i128, u128, 11);
 
 #[cfg(test)]
 mod tests {
@@ -75,4 +88,16 @@ mod tests {
     assert_eq!(0_i32.to_latent_ordered(), u32::MID);
     assert_eq!(i32::MAX.to_latent_ordered(), u32::MAX);
   }
+
+  #[test]
+  fn test_transmute_to_latents() {
+    // reinterpreting a signed integer as unsigned preserves its two's
+    // complement value, regardless of the host's byte order
+    let mut nums = [-2_i16, 0x0102];
+    assert_eq!(
+      i16::transmute_to_latents(&mut nums),
+      &[0xfffe, 0x0102]
+    );
+    assert_eq!((-2_i64).transmute_to_latent(), u64::MAX - 1);
+  }
 }
@@ -0,0 +1,201 @@
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+use crate::data_types::{unsigneds, ModeAndLatents, NumberLike};
+use crate::describers::LatentDescriber;
+use crate::errors::{PcoError, PcoResult};
+use crate::{describers, int_mult_utils, ChunkConfig, ChunkMeta, Mode};
+
+// Both types are compressed exactly like an i64 count of nanoseconds, so
+// they get all the same modes and delta encoding as i64.
+// Only values representable in i64 nanoseconds can be compressed, which we
+// check up front so the infallible latent conversions are safe to use
+// afterward.
+fn validate_nanos<T: NumberLike>(
+  nums: &[T],
+  to_nanos: impl Fn(&T) -> Option<i64>,
+) -> PcoResult<()> {
+  match nums.iter().find(|num| to_nanos(num).is_none()) {
+    Some(num) => Err(PcoError::invalid_argument(format!(
+      "{} cannot be represented as i64 nanoseconds",
+      num,
+    ))),
+    None => Ok(()),
+  }
+}
+
+macro_rules! impl_nanos_number_like {
+  ($t: ty, $header_byte: expr, $to_nanos: expr, $from_nanos: expr) => {
+    impl NumberLike for $t {
+      const DTYPE_BYTE: u8 = $header_byte;
+      const TRANSMUTABLE_TO_LATENT: bool = false;
+
+      type L = u64;
+
+      fn get_latent_describers(meta: &ChunkMeta<Self::L>) -> Vec<LatentDescriber<Self::L>> {
+        describers::match_classic_mode::<Self>(meta, " ns")
+          .or_else(|| describers::match_int_modes(meta, true))
+          .expect("invalid mode for nanosecond type")
+      }
+
+      fn mode_is_valid(mode: Mode<Self::L>) -> bool {
+        matches!(
+          mode,
+          Mode::Classic | Mode::IntMult(_) | Mode::RunLength | Mode::Dict | Mode::Sparse
+        )
+      }
+      fn choose_mode_and_split_latents(
+        nums: &[Self],
+        config: &ChunkConfig,
+      ) -> PcoResult<ModeAndLatents<Self::L>> {
+        validate_nanos(nums, $to_nanos)?;
+        Ok(unsigneds::choose_mode_and_split_latents(
+          nums, config,
+        ))
+      }
+      fn split_latents_for_mode(
+        nums: &[Self],
+        mode: Mode<Self::L>,
+      ) -> PcoResult<Vec<Vec<Self::L>>> {
+        validate_nanos(nums, $to_nanos)?;
+        Ok(unsigneds::split_latents_for_mode(nums, mode))
+      }
+
+      #[inline]
+      fn from_latent_ordered(l: Self::L) -> Self {
+        $from_nanos(i64::from_latent_ordered(l))
+      }
+      #[inline]
+      fn to_latent_ordered(self) -> Self::L {
+        $to_nanos(&self)
+          .expect("nanoseconds were validated before compressing")
+          .to_latent_ordered()
+      }
+      fn join_latents(mode: Mode<Self::L>, primary: &mut [Self::L], secondary: &[Self::L]) {
+        match mode {
+          Mode::Classic => (),
+          Mode::IntMult(base) => int_mult_utils::join_latents(base, primary, secondary),
+          _ => unreachable!("impossible mode for nanosecond types"),
+        }
+      }
+    }
+  };
+}
+
+impl_nanos_number_like!(
+  DateTime<Utc>,
+  13,
+  |t: &DateTime<Utc>| t.timestamp_nanos_opt(),
+  |nanos| Utc.timestamp_nanos(nanos)
+);
+impl_nanos_number_like!(
+  TimeDelta,
+  14,
+  |d: &TimeDelta| d.num_nanoseconds(),
+  TimeDelta::nanoseconds
+);
+
+#[cfg(test)]
+mod tests {
+  use chrono::NaiveDate;
+
+  use std::mem;
+
+  use crate::errors::ErrorKind;
+  use crate::standalone::{decompress_pages, guarantee, simple_compress, simple_decompress};
+  use crate::PagingSpec;
+
+  use super::*;
+
+  fn utc(year: i32, month: u32, day: u32, nanos: u32) -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(year, month, day)
+      .unwrap()
+      .and_hms_nano_opt(12, 30, 15, nanos)
+      .unwrap()
+      .and_utc()
+  }
+
+  #[test]
+  fn test_ordering() {
+    let times = [
+      utc(1677, 9, 22, 0),
+      utc(1969, 12, 31, 999_999_999),
+      DateTime::<Utc>::default(),
+      utc(1970, 1, 1, 1),
+      utc(2262, 4, 11, 0),
+    ];
+    for pair in times.windows(2) {
+      assert!(pair[0].to_latent_ordered() < pair[1].to_latent_ordered());
+    }
+    assert_eq!(
+      DateTime::<Utc>::default().to_latent_ordered(),
+      0_i64.to_latent_ordered()
+    );
+  }
+
+  #[test]
+  fn test_round_trip_timestamps() -> PcoResult<()> {
+    // regularly spaced, crossing the epoch, with some sub-second jitter
+    let start = utc(1969, 12, 25, 0);
+    let times = (0..3000)
+      .map(|i| start + TimeDelta::nanoseconds(i * 7 * 60 * 1_000_000_000 + i % 13))
+      .collect::<Vec<_>>();
+    assert!(times[0].timestamp() < 0 && times[2999].timestamp() > 0);
+    let compressed = simple_compress(&times, &ChunkConfig::default())?;
+    assert!(compressed.len() < times.len() * 2);
+    assert_eq!(
+      simple_decompress::<DateTime<Utc>>(&compressed)?,
+      times
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_round_trip_durations() -> PcoResult<()> {
+    let durations = (-1000..1000)
+      .map(|i| TimeDelta::nanoseconds(i * 250_000_001))
+      .collect::<Vec<_>>();
+    let compressed = simple_compress(&durations, &ChunkConfig::default())?;
+    assert_eq!(
+      simple_decompress::<TimeDelta>(&compressed)?,
+      durations
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_target_page_size_uses_latent_size() -> PcoResult<()> {
+    // timestamps take more bytes in memory than as latents, which is what
+    // gets written
+    assert!(mem::size_of::<DateTime<Utc>>() > mem::size_of::<u64>());
+    let start = utc(2000, 1, 1, 0);
+    let times = (0..3000)
+      .map(|i| start + TimeDelta::seconds(i))
+      .collect::<Vec<_>>();
+    let paging_spec = PagingSpec::TargetPageSize(1000 * mem::size_of::<u64>());
+    let config = ChunkConfig::default().with_paging_spec(paging_spec.clone());
+    let compressed = simple_compress(&times, &config)?;
+    let chunk_ns = decompress_pages::<DateTime<Utc>>(&compressed)
+      .map(|page| page.map(|page| page.len()))
+      .collect::<PcoResult<Vec<_>>>()?;
+    assert_eq!(chunk_ns, vec![1000; 3]);
+    assert!(compressed.len() <= guarantee::file_size::<u64>(times.len(), &paging_spec)?);
+    Ok(())
+  }
+
+  #[test]
+  fn test_out_of_range() {
+    let times = vec![utc(2000, 1, 1, 0), utc(3000, 1, 1, 0)];
+    let err = simple_compress(&times, &ChunkConfig::default()).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let durations = vec![TimeDelta::max_value()];
+    let err = simple_compress(&durations, &ChunkConfig::default()).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+  }
+}
@@ -1,13 +1,14 @@
 use crate::constants::Bitlen;
-use crate::data_types::{split_latents_classic, Latent, NumberLike};
+use crate::data_types::{split_latents_classic, Latent, ModeAndLatents, NumberLike};
 use crate::describers::LatentDescriber;
+use crate::errors::PcoResult;
 use crate::Mode::Classic;
 use crate::{describers, int_mult_utils, ChunkConfig, ChunkMeta, IntMultSpec, Mode};
 
 pub fn choose_mode_and_split_latents<T: NumberLike>(
   nums: &[T],
   config: &ChunkConfig,
-) -> (Mode<T::L>, Vec<Vec<T::L>>) {
+) -> ModeAndLatents<T::L> {
   use IntMultSpec::*;
 
   match config.int_mult_spec {
@@ -30,6 +31,16 @@ pub fn choose_mode_and_split_latents<T: NumberLike>(
   }
 }
 
+pub(crate) fn split_latents_for_mode<T: NumberLike>(
+  nums: &[T],
+  mode: Mode<T::L>,
+) -> Vec<Vec<T::L>> {
+  match mode {
+    Mode::IntMult(base) => int_mult_utils::split_latents(nums, base),
+    _ => split_latents_classic(nums),
+  }
+}
+
 macro_rules! impl_latent {
   ($t: ty) => {
     impl Latent for $t {
@@ -49,6 +60,11 @@ macro_rules! impl_latent {
         self.leading_zeros() as Bitlen
       }
 
+      #[inline]
+      fn trailing_zeros(self) -> Bitlen {
+        self.trailing_zeros() as Bitlen
+      }
+
       #[inline]
       fn to_u64(self) -> u64 {
         self as u64
@@ -70,6 +86,7 @@ macro_rules! impl_latent {
 impl_latent!(u16);
 impl_latent!(u32);
 impl_latent!(u64);
+impl_latent!(u128);
 
 macro_rules! impl_unsigned_number {
   ($t: ty, $header_byte: expr) => {
@@ -89,14 +106,23 @@ macro_rules! impl_unsigned_number {
         match mode {
           Mode::Classic => true,
           Mode::IntMult(_) => true,
+          Mode::RunLength => true,
+          Mode::Dict => true,
+          Mode::Sparse => true,
           _ => false,
         }
       }
       fn choose_mode_and_split_latents(
         nums: &[Self],
         config: &ChunkConfig,
-      ) -> (Mode<Self::L>, Vec<Vec<Self::L>>) {
-        choose_mode_and_split_latents(nums, config)
+      ) -> PcoResult<ModeAndLatents<Self::L>> {
+        Ok(choose_mode_and_split_latents(nums, config))
+      }
+      fn split_latents_for_mode(
+        nums: &[Self],
+        mode: Mode<Self::L>,
+      ) -> PcoResult<Vec<Vec<Self::L>>> {
+        Ok(split_latents_for_mode(nums, mode))
       }
 
       #[inline]
@@ -129,3 +155,4 @@ macro_rules! impl_unsigned_number {
 impl_unsigned_number!(u32, 1);
 impl_unsigned_number!(u64, 2);
 impl_unsigned_number!(u16, 7);
+impl_unsigned_number!(u128, 10);
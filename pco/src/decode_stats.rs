@@ -0,0 +1,18 @@
+/// Statistics gathered while decompressing, useful for checking how well a
+/// file's bins fit its data.
+///
+/// See [`simple_decompress_with_stats`][crate::standalone::simple_decompress_with_stats].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodeStats {
+  /// For each chunk, the count of latents decoded from each bin of its
+  /// primary latent variable, indexed the same as
+  /// [`per_latent_var[0].bins`][crate::ChunkLatentVarMeta::bins].
+  ///
+  /// This counts deltas, so after delta encoding of order `k` each page has
+  /// `k` fewer of them than numbers.
+  pub bin_counts_per_chunk: Vec<Vec<usize>>,
+  /// The total count of offset bits read, across all chunks and latent
+  /// variables.
+  pub offset_bits: usize,
+}
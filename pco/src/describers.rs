@@ -34,6 +34,71 @@ pub(crate) fn match_classic_mode<T: NumberLike>(
       let describer = centered_delta_describer("delta".to_string(), delta_units.to_string());
       Some(vec![describer])
     }
+    (Mode::Dict, 0) => {
+      let describer = Box::new(IntDescriber {
+        description: "dict index".to_string(),
+        units: "".to_string(),
+        center: T::L::ZERO,
+        is_signed: false,
+      });
+      Some(vec![describer])
+    }
+    (Mode::Dict, _) => {
+      let describer = centered_delta_describer(
+        "dict index delta".to_string(),
+        "".to_string(),
+      );
+      Some(vec![describer])
+    }
+    (Mode::RunLength, _) => {
+      let primary: LatentDescriber<T::L> = if meta.delta_encoding_order == 0 {
+        Box::new(RunValueDescriber::<T>::default())
+      } else {
+        centered_delta_describer(
+          "run value delta".to_string(),
+          delta_units.to_string(),
+        )
+      };
+      let secondary = if meta.secondary_delta_encoding_order == 0 {
+        // run lengths are stored minus 1, so we "center" at -1
+        Box::new(IntDescriber {
+          description: "run length".to_string(),
+          units: "".to_string(),
+          center: T::L::MAX,
+          is_signed: false,
+        })
+      } else {
+        centered_delta_describer(
+          "run length delta".to_string(),
+          "".to_string(),
+        )
+      };
+      Some(vec![primary, secondary])
+    }
+    (Mode::Sparse, _) => {
+      let primary: LatentDescriber<T::L> = if meta.delta_encoding_order == 0 {
+        Box::new(SparseValueDescriber::<T>::default())
+      } else {
+        centered_delta_describer(
+          "sparse value delta".to_string(),
+          delta_units.to_string(),
+        )
+      };
+      let secondary = if meta.secondary_delta_encoding_order == 0 {
+        Box::new(IntDescriber {
+          description: "zeros after".to_string(),
+          units: "".to_string(),
+          center: T::L::ZERO,
+          is_signed: false,
+        })
+      } else {
+        centered_delta_describer(
+          "zeros after delta".to_string(),
+          "".to_string(),
+        )
+      };
+      Some(vec![primary, secondary])
+    }
     _ => None,
   }
 }
@@ -61,12 +126,19 @@ pub(crate) fn match_int_modes<L: Latent>(
           "x".to_string(),
         )
       };
-      let secondary = Box::new(IntDescriber {
-        description: "adjustment".to_string(),
-        units: "".to_string(),
-        center: adj_center,
-        is_signed: false,
-      });
+      let secondary = if meta.secondary_delta_encoding_order == 0 {
+        Box::new(IntDescriber {
+          description: "adjustment".to_string(),
+          units: "".to_string(),
+          center: adj_center,
+          is_signed: false,
+        })
+      } else {
+        centered_delta_describer(
+          "adjustment delta".to_string(),
+          "".to_string(),
+        )
+      };
       Some(vec![primary, secondary])
     }
     _ => None,
@@ -92,12 +164,19 @@ pub(crate) fn match_float_modes<F: FloatLike>(
           is_signed: true,
         })
       };
-      let secondary = Box::new(IntDescriber {
-        description: "adjustment".to_string(),
-        units: " ULPs".to_string(),
-        center: F::L::MID,
-        is_signed: true,
-      });
+      let secondary = if meta.secondary_delta_encoding_order == 0 {
+        Box::new(IntDescriber {
+          description: "adjustment".to_string(),
+          units: " ULPs".to_string(),
+          center: F::L::MID,
+          is_signed: true,
+        })
+      } else {
+        centered_delta_describer(
+          "adjustment delta".to_string(),
+          " ULPs".to_string(),
+        )
+      };
       Some(vec![primary, secondary])
     }
     Mode::FloatQuant(k) => {
@@ -112,13 +191,35 @@ pub(crate) fn match_float_modes<F: FloatLike>(
           "q".to_string(),
         )
       };
+      let secondary = if meta.secondary_delta_encoding_order == 0 {
+        Box::new(IntDescriber {
+          description: "magnitude adjustment".to_string(),
+          units: " ULPs".to_string(),
+          center: F::L::ZERO,
+          is_signed: false,
+        })
+      } else {
+        centered_delta_describer(
+          "magnitude adjustment delta".to_string(),
+          " ULPs".to_string(),
+        )
+      };
+
+      Some(vec![primary, secondary])
+    }
+    Mode::FloatXor => {
+      let primary = Box::new(IntDescriber {
+        description: "xor bits".to_string(),
+        units: "".to_string(),
+        center: F::L::ZERO,
+        is_signed: false,
+      });
       let secondary = Box::new(IntDescriber {
-        description: "magnitude adjustment".to_string(),
-        units: " ULPs".to_string(),
+        description: "xor trailing zeros".to_string(),
+        units: " bits".to_string(),
         center: F::L::ZERO,
         is_signed: false,
       });
-
       Some(vec![primary, secondary])
     }
     _ => None,
@@ -142,6 +243,40 @@ impl<T: NumberLike> DescribeLatent<T::L> for ClassicDescriber<T> {
   }
 }
 
+#[derive(Default)]
+struct RunValueDescriber<T: NumberLike>(PhantomData<T>);
+
+impl<T: NumberLike> DescribeLatent<T::L> for RunValueDescriber<T> {
+  fn latent_var(&self) -> String {
+    "run value".to_string()
+  }
+
+  fn latent_units(&self) -> String {
+    "".to_string()
+  }
+
+  fn latent(&self, latent: T::L) -> String {
+    T::from_latent_ordered(latent).to_string()
+  }
+}
+
+#[derive(Default)]
+struct SparseValueDescriber<T: NumberLike>(PhantomData<T>);
+
+impl<T: NumberLike> DescribeLatent<T::L> for SparseValueDescriber<T> {
+  fn latent_var(&self) -> String {
+    "sparse value".to_string()
+  }
+
+  fn latent_units(&self) -> String {
+    "".to_string()
+  }
+
+  fn latent(&self, latent: T::L) -> String {
+    T::from_latent_ordered(latent).to_string()
+  }
+}
+
 struct IntDescriber<L: Latent> {
   description: String,
   units: String,
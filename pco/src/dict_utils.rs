@@ -0,0 +1,85 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::constants::MAX_DICT_SIZE;
+use crate::data_types::{Latent, NumberLike};
+use crate::errors::{PcoError, PcoResult};
+
+// Each number becomes the index of its latent in a sorted dictionary of the
+// chunk's distinct latents, which is stored in the chunk metadata.
+
+// Returns the sorted distinct latents, or None if there are more than
+// MAX_DICT_SIZE of them.
+pub(crate) fn build_dict<T: NumberLike>(nums: &[T]) -> Option<Vec<T::L>> {
+  let mut distinct = HashSet::new();
+  for &num in nums {
+    distinct.insert(num.to_latent_ordered());
+    if distinct.len() > MAX_DICT_SIZE {
+      return None;
+    }
+  }
+  let mut dict = distinct.into_iter().collect::<Vec<_>>();
+  dict.sort_unstable();
+  Some(dict)
+}
+
+pub(crate) fn split_latents<T: NumberLike>(nums: &[T], dict: &[T::L]) -> Vec<Vec<T::L>> {
+  let idx_by_latent = dict
+    .iter()
+    .enumerate()
+    .map(|(idx, &latent)| (latent, T::L::from_u64(idx as u64)))
+    .collect::<HashMap<_, _>>();
+  let indices = nums
+    .iter()
+    .map(|num| idx_by_latent[&num.to_latent_ordered()])
+    .collect();
+  vec![indices]
+}
+
+// Replaces each index with its dictionary entry in place.
+// Corrupt data can decode to any index, so they must be checked.
+#[inline(never)]
+pub(crate) fn join_latents<L: Latent>(dict: &[L], primary: &mut [L]) -> PcoResult<()> {
+  let dict_size = L::from_u64(dict.len() as u64);
+  if let Some(&idx) = primary.iter().find(|&&idx| idx >= dict_size) {
+    return Err(PcoError::corruption(format!(
+      "dict index {} is out of range for dict of size {}",
+      idx,
+      dict.len(),
+    )));
+  }
+  for idx_and_dst in primary {
+    *idx_and_dst = dict[idx_and_dst.to_u64() as usize];
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_split_join_latents() -> PcoResult<()> {
+    let nums = vec![-5_i32, 1 << 30, -5, 7, 7, 7];
+    let dict = build_dict(&nums).unwrap();
+    assert_eq!(dict.len(), 3);
+    let latents = split_latents(&nums, &dict);
+    assert_eq!(latents[0], vec![0, 2, 0, 1, 1, 1]);
+
+    let mut recovered = latents[0].clone();
+    join_latents(&dict, &mut recovered)?;
+    for (&num, &latent) in nums.iter().zip(recovered.iter()) {
+      assert_eq!(i32::from_latent_ordered(latent), num);
+    }
+
+    let mut out_of_range = vec![0, 3];
+    assert!(join_latents(&dict, &mut out_of_range).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_build_dict_too_big() {
+    let nums = (0..MAX_DICT_SIZE as u32 + 1).collect::<Vec<_>>();
+    assert!(build_dict(&nums).is_none());
+    assert!(build_dict(&nums[1..]).is_some());
+  }
+}
@@ -6,6 +6,10 @@ use std::{fmt, io};
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ErrorKind {
+  /// `ChecksumMismatch` errors occur during decompression of standalone files
+  /// written with a checksum, indicating the data was structurally valid but
+  /// its bytes do not match the checksum in its trailer.
+  ChecksumMismatch,
   /// `Compatibility` errors occur during decompression, indicating the library
   /// version is not up-to-date enough for the provided data.
   Compatibility,
@@ -40,6 +44,10 @@ impl PcoError {
     }
   }
 
+  pub(crate) fn checksum_mismatch<S: AsRef<str>>(message: S) -> Self {
+    Self::new(ErrorKind::ChecksumMismatch, message)
+  }
+
   pub(crate) fn compatibility<S: AsRef<str>>(message: S) -> Self {
     Self::new(ErrorKind::Compatibility, message)
   }
@@ -48,6 +56,32 @@ impl PcoError {
     Self::new(ErrorKind::Corruption, message)
   }
 
+  // for corruptions found at a known position in the data being parsed,
+  // counted from the start of the named section, e.g. the chunk metadata or
+  // page
+  pub(crate) fn corruption_at<S: AsRef<str>>(message: S, section: &str, bit_idx: usize) -> Self {
+    Self::corruption(format!(
+      "{} at bit {} (byte {}) of the {}",
+      message.as_ref(),
+      bit_idx,
+      bit_idx / 8,
+      section,
+    ))
+  }
+
+  // Positions in corruption messages are relative to their section, so
+  // standalone decompression adds which chunk of the file it was in and
+  // where that chunk starts.
+  pub(crate) fn in_chunk(mut self, chunk_idx: usize, chunk_byte_idx: usize) -> Self {
+    if matches!(self.kind, ErrorKind::Corruption) {
+      self.message = format!(
+        "{} (in chunk {} starting at byte {} of the file)",
+        self.message, chunk_idx, chunk_byte_idx,
+      );
+    }
+    self
+  }
+
   pub(crate) fn insufficient_data<S: AsRef<str>>(message: S) -> Self {
     Self::new(ErrorKind::InsufficientData, message)
   }
@@ -29,7 +29,15 @@ pub(crate) fn split_latents<F: FloatLike>(page_nums: &[F], base: F, inv_base: F)
     .iter()
     .zip(primary.iter_mut().zip(adjustments.iter_mut()))
   {
-    let mult = (num * inv_base).round();
+    // NaNs aren't multiples of anything, so we store them entirely in the
+    // adjustment, relative to 0. This also avoids NaN arithmetic, whose
+    // resulting bits can vary by platform. Infinities are fine as they are,
+    // since inf * base is exactly inf again.
+    let mult = if num.is_nan_() {
+      F::ZERO
+    } else {
+      (num * inv_base).round()
+    };
     *primary_dst = F::int_float_to_latent(mult);
     *adj_dst = num
       .to_latent_ordered()
@@ -41,6 +49,31 @@ pub(crate) fn split_latents<F: FloatLike>(page_nums: &[F], base: F, inv_base: F)
   vec![primary, adjustments]
 }
 
+// Like split_latents, but drops adjustments whenever the resulting float is
+// within tolerance of the original.
+pub(crate) fn split_latents_lossy<F: FloatLike>(
+  page_nums: &[F],
+  base: F,
+  inv_base: F,
+  tolerance: F,
+) -> Vec<Vec<F::L>> {
+  let mut latents = split_latents(page_nums, base, inv_base);
+  let (primary, adjustments) = latents.split_at_mut(1);
+  let zero_adj = F::L::ZERO.toggle_center();
+  for ((&num, &mult), adj) in page_nums
+    .iter()
+    .zip(primary[0].iter())
+    .zip(adjustments[0].iter_mut())
+  {
+    // this must match how join_latents reconstructs the float
+    let approx = F::int_float_from_latent(mult) * base;
+    if (num - approx).abs() <= tolerance {
+      *adj = zero_adj;
+    }
+  }
+  latents
+}
+
 // The rest of this file concerns automatically detecting the float `base`
 // such that `x = mult * base + adj * ULP` usefully splits a delta `x` into
 // latent variables `mult` and `adj` (if such a `base` exists).
@@ -56,6 +89,32 @@ pub(crate) fn split_latents<F: FloatLike>(page_nums: &[F], base: F, inv_base: F)
 // off computationally efficiently when the frequency of interest could be in
 // such a large range and must be determined so precisely.
 // So instead we use an approximate Euclidean algorithm on pairs of floats.
+//
+// Overall, the base search goes:
+// 1. Sample the finite, normal numbers by absolute value.
+// 2. Look for a candidate base, first from the power of 2 that divides most
+//    of the sample exactly (trailing zeros), then from the approximate GCDs
+//    of pairs of sampled numbers (Euclidean), centered to minimize errors and
+//    snapped to an integer or decimal reciprocal if close to one.
+// 3. Accept the candidate only if its mults are spread out enough that
+//    classic mode couldn't just memorize them, and if the adjustments take
+//    sufficiently fewer bits than classic would spend between consecutive
+//    multiples of the base.
+// 4. If no candidate passes, retry steps 2 and 3 on only the sampled numbers
+//    within PRECISION_BITS binary orders of magnitude of the largest ones
+//    (see choose_large_magnitude_sample).
+//
+// A base can only help numbers that are a reasonably small multiple of it,
+// i.e. within PRECISION_BITS orders of magnitude. So in data spanning many
+// orders of magnitude, the small numbers can't inform the base at all: pairs
+// of them yield spurious GCDs that crowd out the real one, and they all have
+// a mult of 0, making the mults look memorizable.
+// Retrying without them gives the large numbers a chance to reveal their
+// base. The adjustment check still runs on all the numbers, so small
+// numbers, which float mult must store entirely in their adjustments, still
+// count against the candidate.
+// Finally, since all of this is heuristic, the chunk compressor also
+// compares the resulting chunk against classic mode and keeps the smaller.
 
 const REQUIRED_PRECISION_BITS: Bitlen = 6;
 const SNAP_THRESHOLD_ABSOLUTE: f64 = 0.02;
@@ -269,7 +328,8 @@ fn uses_few_enough_adj_bits<F: FloatLike>(config: FloatMultConfig<F>, nums: &[F]
   let total_uncompressed_size = nums.len() * F::BITS as usize;
   let mut total_bits_saved = 0;
   let mut total_inter_base_bits = 0;
-  for &x in nums {
+  // non-finite numbers are outliers that save nothing
+  for &x in nums.iter().filter(|x| x.is_finite_()) {
     let mult = (x * inv_base).round();
     if mult != F::ZERO {
       let u = x.to_latent_ordered();
@@ -333,6 +393,31 @@ fn choose_config_w_sample<F: FloatLike>(sample: &[F], nums: &[F]) -> Option<Floa
   }
 }
 
+// Returns the sampled numbers within PRECISION_BITS binary orders of
+// magnitude of the largest ones, or None if that excludes nothing or leaves
+// too few. To be robust to a few huge outliers, "largest" means the 95th
+// percentile exponent.
+#[inline(never)]
+fn choose_large_magnitude_sample<F: FloatLike>(sample: &[F]) -> Option<Vec<F>> {
+  let mut exponents = sample.iter().map(|x| x.exponent()).collect::<Vec<_>>();
+  exponents.sort_unstable();
+  let high_exponent = exponents[(exponents.len() - 1) * 19 / 20];
+  let min_exponent = high_exponent - F::PRECISION_BITS as i32;
+  if exponents[0] >= min_exponent {
+    return None;
+  }
+
+  let large_sample = sample
+    .iter()
+    .cloned()
+    .filter(|x| x.exponent() >= min_exponent)
+    .collect::<Vec<_>>();
+  if large_sample.len() < sampling::MIN_SAMPLE {
+    return None;
+  }
+  Some(large_sample)
+}
+
 #[inline(never)]
 pub(crate) fn choose_config<F: FloatLike>(nums: &[F]) -> Option<FloatMultConfig<F>> {
   // We can compress infinities, nans, and baby floats, but we can't learn
@@ -348,13 +433,17 @@ pub(crate) fn choose_config<F: FloatLike>(nums: &[F]) -> Option<FloatMultConfig<
     None
   })?;
 
-  choose_config_w_sample(&sample, nums)
+  choose_config_w_sample(&sample, nums).or_else(|| {
+    let large_sample = choose_large_magnitude_sample(&sample)?;
+    choose_config_w_sample(&large_sample, nums)
+  })
 }
 
 #[cfg(test)]
 mod test {
   use std::f32::consts::{E, TAU};
 
+  use half::{bf16, f16};
   use rand::{Rng, SeedableRng};
 
   use crate::data_types::NumberLike;
@@ -413,6 +502,24 @@ mod test {
     )
   }
 
+  #[test]
+  fn test_trailing_zeros_16_bit() {
+    let f16s = [0.0, 3.0, 6.0, 21.0, 3.0 * 1024.0]
+      .map(f16::from_f32)
+      .repeat(5);
+    assert_eq!(
+      choose_candidate_base_by_trailing_zeros(&f16s).unwrap(),
+      FloatMultConfig::from_base(f16::from_f32(3.0)),
+    );
+    let bf16s = [0.0, 0.75, 1.5, 5.25, 0.75 * 64.0]
+      .map(bf16::from_f32)
+      .repeat(5);
+    assert_eq!(
+      choose_candidate_base_by_trailing_zeros(&bf16s).unwrap(),
+      FloatMultConfig::from_base(bf16::from_f32(0.75)),
+    );
+  }
+
   #[test]
   fn test_approx_pair_gcd() {
     assert_eq!(approx_pair_gcd(0.0, 0.0), None);
@@ -600,6 +707,19 @@ mod test {
     ));
   }
 
+  #[test]
+  fn test_choose_large_magnitude_sample() {
+    // numbers of similar magnitudes are all kept
+    let sample = (1..100).map(|x| x as f32).collect::<Vec<_>>();
+    assert_eq!(choose_large_magnitude_sample(&sample), None);
+
+    let mut sample = (1..100).map(|x| x as f32 * 0.1).collect::<Vec<_>>();
+    sample.extend((1..40).map(|x| f32::exp2(-x as f32 * 3.0)));
+    let large_sample = choose_large_magnitude_sample(&sample).unwrap();
+    assert!(large_sample.len() >= 99);
+    assert!(large_sample.iter().all(|&x| x > 1e-6));
+  }
+
   #[test]
   fn test_choose_config() {
     let mut sevenths = Vec::new();
@@ -637,4 +757,33 @@ mod test {
     big_nums.resize(20, f32::MAX * 0.6);
     choose_config(&big_nums);
   }
+
+  #[test]
+  fn test_split_join_non_finite() {
+    let nums = vec![
+      f64::NAN,
+      -f64::NAN,
+      f64::from_bits(0x7ff0_0000_0000_0001), // signaling
+      f64::from_bits(0xfff8_dead_beef_0000),
+      f64::INFINITY,
+      f64::NEG_INFINITY,
+      -0.0,
+      0.3,
+    ];
+    let base = 0.1;
+    let latents = split_latents(&nums, base, 10.0);
+    let zero_mult = 0.0.int_float_to_latent();
+    for &mult in &latents[0][..4] {
+      assert_eq!(mult, zero_mult);
+    }
+
+    let mut recovered = latents[0].clone();
+    join_latents(base, &mut recovered, &latents[1]);
+    for (&num, &latent) in nums.iter().zip(recovered.iter()) {
+      assert_eq!(
+        f64::from_latent_ordered(latent).to_bits(),
+        num.to_bits()
+      );
+    }
+  }
 }
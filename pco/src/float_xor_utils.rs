@@ -0,0 +1,93 @@
+use crate::constants::Bitlen;
+use crate::data_types::{Latent, NumberLike};
+
+// Each latent is XORed with the previous one in its page (or 0 for the first),
+// and the XOR is split into its bits above the trailing zeros and the count of
+// trailing zeros. Bit-identical consecutive numbers become (0, L::BITS).
+
+#[inline(never)]
+pub(crate) fn join_latents<L: Latent>(primary: &mut [L], secondary: &[L]) {
+  for (xor_and_dst, &trailing_zeros) in primary.iter_mut().zip(secondary.iter()) {
+    let trailing_zeros = trailing_zeros.to_u64();
+    *xor_and_dst = if trailing_zeros < L::BITS as u64 {
+      *xor_and_dst << trailing_zeros as Bitlen
+    } else {
+      L::ZERO
+    };
+  }
+}
+
+// Undoes the XORing of consecutive latents in place. `prev` holds the last
+// latent decoded so far in the page, so this can be applied batch by batch.
+#[inline(never)]
+pub(crate) fn decode_in_place<L: Latent>(prev: &mut L, latents: &mut [L]) {
+  for latent in latents {
+    *prev ^= *latent;
+    *latent = *prev;
+  }
+}
+
+pub(crate) fn split_latents<T: NumberLike>(nums: &[T], n_per_page: &[usize]) -> Vec<Vec<T::L>> {
+  let n = nums.len();
+  let mut primary = Vec::with_capacity(n);
+  let mut secondary = Vec::with_capacity(n);
+  let mut start = 0;
+  for &page_n in n_per_page {
+    // each page must be decodable on its own, so the XOR chain restarts
+    let mut prev = T::L::ZERO;
+    for &num in &nums[start..start + page_n] {
+      let latent = num.to_latent_ordered();
+      let xor = latent ^ prev;
+      let trailing_zeros = xor.trailing_zeros();
+      primary.push(if trailing_zeros < T::L::BITS {
+        xor >> trailing_zeros
+      } else {
+        T::L::ZERO
+      });
+      secondary.push(T::L::from_u64(trailing_zeros as u64));
+      prev = latent;
+    }
+    start += page_n;
+  }
+  vec![primary, secondary]
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_split_join_latents() {
+    let nums = vec![1.5_f32, 1.5, 1.75, -2.0, -2.0, f32::NAN, 0.0];
+    let n_per_page = [5, 2];
+    let latents = split_latents(&nums, &n_per_page);
+    let (primary, secondary) = (&latents[0], &latents[1]);
+
+    // repeats are all zeros
+    assert_eq!(primary[1], 0);
+    assert_eq!(secondary[1], 32);
+    assert_eq!(primary[4], 0);
+    assert_eq!(secondary[4], 32);
+    // 1.5 and 1.75 differ only in one bit of the significand
+    assert_eq!(primary[2], 1);
+    assert_eq!(secondary[2], 21);
+
+    let mut recovered = primary.clone();
+    join_latents(&mut recovered, secondary);
+    let mut start = 0;
+    for page_n in n_per_page {
+      let mut prev = 0;
+      decode_in_place(
+        &mut prev,
+        &mut recovered[start..start + page_n],
+      );
+      start += page_n;
+    }
+    for (&num, &latent) in nums.iter().zip(recovered.iter()) {
+      assert_eq!(
+        f32::from_latent_ordered(latent).to_bits(),
+        num.to_bits()
+      );
+    }
+  }
+}
@@ -2,7 +2,10 @@ use std::io::Write;
 
 use crate::bit_reader::BitReader;
 use crate::bit_writer::BitWriter;
-use crate::constants::CURRENT_FORMAT_VERSION;
+use crate::constants::{
+  Bitlen, BITS_TO_ENCODE_DELTA_ENCODING_ORDER, BITS_TO_ENCODE_OLD_DELTA_ENCODING_ORDER,
+  CURRENT_FORMAT_VERSION, MIN_FORMAT_VERSION,
+};
 use crate::errors::{PcoError, PcoResult};
 
 /// The version of pco used to compress a file.
@@ -29,8 +32,8 @@ impl FormatVersion {
     let version = reader.read_aligned_bytes(1)?[0];
     if version > CURRENT_FORMAT_VERSION {
       return Err(PcoError::compatibility(format!(
-        "file's format version ({}) exceeds max supported ({}); consider upgrading pco",
-        version, CURRENT_FORMAT_VERSION,
+        "file's format version ({}) is outside the supported range ({}..={}); consider upgrading pco",
+        version, MIN_FORMAT_VERSION, CURRENT_FORMAT_VERSION,
       )));
     }
 
@@ -46,4 +49,56 @@ impl FormatVersion {
   pub(crate) fn used_old_gcds(&self) -> bool {
     self.0 == 0
   }
+
+  pub(crate) fn has_secondary_delta_order(&self) -> bool {
+    self.0 >= 3
+  }
+
+  pub(crate) fn bits_to_encode_delta_encoding_order(&self) -> Bitlen {
+    if self.0 >= 3 {
+      BITS_TO_ENCODE_DELTA_ENCODING_ORDER
+    } else {
+      BITS_TO_ENCODE_OLD_DELTA_ENCODING_ORDER
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::constants::COMPATIBLE_FORMAT_VERSION;
+  use crate::errors::ErrorKind;
+  use crate::wrapped::{FileCompressor, FileDecompressor};
+  use crate::{ChunkConfig, FloatXorSpec, FORMAT_VERSION};
+
+  use super::*;
+
+  #[test]
+  fn test_format_version_gate() -> PcoResult<()> {
+    let mut header = Vec::new();
+    FileCompressor::default().write_header(&mut header)?;
+    let (fd, _) = FileDecompressor::new(header.as_slice())?;
+    assert_eq!(
+      fd.format_version(),
+      COMPATIBLE_FORMAT_VERSION
+    );
+
+    let config = ChunkConfig::default().with_float_xor_spec(FloatXorSpec::Enabled);
+    let mut header = Vec::new();
+    FileCompressor::default()
+      .with_format_version_for(&config)
+      .write_header(&mut header)?;
+    let (fd, _) = FileDecompressor::new(header.as_slice())?;
+    assert_eq!(fd.format_version(), FORMAT_VERSION);
+
+    let newer = [FORMAT_VERSION + 1];
+    let err = FileDecompressor::new(newer.as_slice()).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Compatibility));
+    assert!(err.message.contains(&format!(
+      "({}) is outside the supported range ({}..={})",
+      FORMAT_VERSION + 1,
+      MIN_FORMAT_VERSION,
+      FORMAT_VERSION
+    )));
+    Ok(())
+  }
 }
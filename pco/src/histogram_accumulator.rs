@@ -0,0 +1,278 @@
+use crate::data_types::Latent;
+use crate::errors::{PcoError, PcoResult};
+use crate::{ChunkMeta, Mode};
+
+fn latent_to_f64<L: Latent>(x: L) -> f64 {
+  if L::BITS > 64 {
+    (x >> 64).to_u64() as f64 * 2.0_f64.powi(64) + x.to_u64() as f64
+  } else {
+    x.to_u64() as f64
+  }
+}
+
+fn latent_from_f64<L: Latent>(x: f64) -> L {
+  let high = (x / 2.0_f64.powi(64)).floor();
+  if L::BITS > 64 && high >= 1.0 {
+    (L::from_u64(high as u64) << 64) + L::from_u64((x - high * 2.0_f64.powi(64)) as u64)
+  } else {
+    L::from_u64(x as u64)
+  }
+}
+
+// the count of latents in [lower, upper]
+fn width<L: Latent>(lower: L, upper: L) -> f64 {
+  latent_to_f64(upper - lower) + 1.0
+}
+
+/// Merges the histograms of many chunks' primary latent variables, e.g.
+/// across many files, to approximate their overall distribution without
+/// decoding any pages.
+///
+/// Each [`histogram`][ChunkMeta::histogram] bucket is treated as spreading
+/// its weight uniformly over its range.
+/// Since different chunks have different bin boundaries, each added chunk
+/// is resampled onto a common grid made of every boundary seen so far, so
+/// memory usage grows with the count of distinct boundaries rather than the
+/// count of chunks.
+///
+/// The ranges are in terms of latents, which can be converted back to
+/// numbers via
+/// [`NumberLike::from_latent_ordered`][crate::data_types::NumberLike::from_latent_ordered].
+#[derive(Clone, Debug, Default)]
+pub struct HistogramAccumulator<L: Latent> {
+  // disjoint (lower, upper, weight) cells, sorted by lower
+  cells: Vec<(L, L, f64)>,
+  total_weight: f64,
+}
+
+impl<L: Latent> HistogramAccumulator<L> {
+  /// Creates an accumulator with no chunks added.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a chunk's histogram, weighted the same as every other chunk added
+  /// this way.
+  ///
+  /// Chunk metadata does not record the count of numbers, so use
+  /// [`add_with_count`][Self::add_with_count] instead if it is known.
+  ///
+  /// Will return an InvalidArgument error if the chunk is not in classic mode
+  /// with delta encoding order 0, since otherwise its latents are not
+  /// ordered like its numbers.
+  pub fn add(&mut self, meta: &ChunkMeta<L>) -> PcoResult<()> {
+    self.add_weighted(meta, 1.0)
+  }
+
+  /// Adds a chunk's histogram, weighted by its count of numbers `n`, so
+  /// that bucket weights approximate counts of numbers.
+  ///
+  /// Will return an InvalidArgument error if the chunk is not in classic mode
+  /// with delta encoding order 0.
+  pub fn add_with_count(&mut self, meta: &ChunkMeta<L>, n: usize) -> PcoResult<()> {
+    self.add_weighted(meta, n as f64)
+  }
+
+  fn add_weighted(&mut self, meta: &ChunkMeta<L>, chunk_weight: f64) -> PcoResult<()> {
+    if !matches!(meta.mode, Mode::Classic) || meta.delta_encoding_order != 0 {
+      return Err(PcoError::invalid_argument(format!(
+        "histograms can only be accumulated for classic mode with delta \
+         encoding order 0, but found {} with order {}",
+        meta.mode, meta.delta_encoding_order,
+      )));
+    }
+
+    let histogram = meta.histogram();
+    if histogram.is_empty() || chunk_weight == 0.0 {
+      return Ok(());
+    }
+
+    // Each cell or bucket contributes a constant density over its range, so
+    // we sweep over the points where densities start and stop, and every
+    // range between consecutive points becomes a cell of the new grid.
+    let mut events = Vec::with_capacity(2 * (self.cells.len() + histogram.len()));
+    let buckets = histogram
+      .into_iter()
+      .map(|(lower, upper, fraction)| (lower, upper, fraction * chunk_weight));
+    for (lower, upper, weight) in self.cells.drain(..).chain(buckets) {
+      let density = weight / width(lower, upper);
+      events.push((lower, density, 1_i32));
+      if upper != L::MAX {
+        events.push((upper + L::ONE, -density, -1));
+      }
+    }
+    events.sort_unstable_by_key(|&(pos, _, _)| pos);
+
+    let mut density = 0.0;
+    let mut n_active = 0;
+    let mut event_idx = 0;
+    while event_idx < events.len() {
+      let pos = events[event_idx].0;
+      while event_idx < events.len() && events[event_idx].0 == pos {
+        density += events[event_idx].1;
+        n_active += events[event_idx].2;
+        event_idx += 1;
+      }
+      if n_active == 0 {
+        // avoid accumulating floating point error across gaps
+        density = 0.0;
+        continue;
+      }
+
+      let upper = match events.get(event_idx) {
+        Some(&(next_pos, _, _)) => next_pos - L::ONE,
+        None => L::MAX,
+      };
+      let weight = density.max(0.0) * width(pos, upper);
+      match self.cells.last_mut() {
+        // coalesce cells of equal density so shared boundaries don't
+        // fragment the grid
+        Some(last)
+          if last.1.wrapping_add(L::ONE) == pos
+            && (last.2 / width(last.0, last.1) - density).abs() <= f64::EPSILON * density =>
+        {
+          last.1 = upper;
+          last.2 += weight;
+        }
+        _ => self.cells.push((pos, upper, weight)),
+      }
+    }
+    self.total_weight += chunk_weight;
+    Ok(())
+  }
+
+  /// Returns the total weight of all chunks added, i.e. their count for
+  /// [`add`][Self::add] plus their counts of numbers for
+  /// [`add_with_count`][Self::add_with_count].
+  pub fn total_weight(&self) -> f64 {
+    self.total_weight
+  }
+
+  /// Returns the merged histogram on the common grid, as disjoint
+  /// `(lower, upper, weight)` buckets sorted by `lower`.
+  ///
+  /// Each bucket covers the inclusive latent range `[lower, upper]`, and the
+  /// weights sum to [`total_weight`][Self::total_weight].
+  pub fn buckets(&self) -> &[(L, L, f64)] {
+    &self.cells
+  }
+
+  /// Approximates the `q`th quantile of all chunks added, linearly
+  /// interpolating within the bucket containing the target cumulative
+  /// weight.
+  ///
+  /// Will return an InvalidArgument error if `q` is not in `[0, 1]` or if no
+  /// chunk with any bins has been added.
+  pub fn quantile(&self, q: f64) -> PcoResult<L> {
+    if !(0.0..=1.0).contains(&q) {
+      return Err(PcoError::invalid_argument(format!(
+        "quantile must be in [0, 1] but was {}",
+        q
+      )));
+    }
+    let Some(&(_, max_upper, _)) = self.cells.last() else {
+      return Err(PcoError::invalid_argument(
+        "cannot approximate quantiles without any bins",
+      ));
+    };
+
+    let mut remaining = q * self.total_weight;
+    for &(lower, upper, weight) in &self.cells {
+      if remaining <= weight {
+        let max_offset = latent_to_f64(upper - lower);
+        let offset = (remaining / weight * max_offset).min(max_offset);
+        return Ok(lower + latent_from_f64::<L>(offset).min(upper - lower));
+      }
+      remaining -= weight;
+    }
+    // only reachable via floating point error near q = 1
+    Ok(max_upper)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::standalone::{simple_compress, FileDecompressor, MaybeChunkDecompressor};
+  use crate::{ChunkConfig, PagingSpec};
+
+  use super::*;
+
+  fn chunk_metas(nums: &[u32], config: &ChunkConfig) -> PcoResult<Vec<(ChunkMeta<u32>, usize)>> {
+    let compressed = simple_compress(nums, config)?;
+    let (fd, mut src) = FileDecompressor::new(compressed.as_slice())?;
+    let mut res = Vec::new();
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<u32, _>(src)? {
+      res.push((cd.meta().clone(), cd.n()));
+      cd.decompress_remaining_extend(&mut Vec::new())?;
+      src = cd.into_src();
+    }
+    Ok(res)
+  }
+
+  #[test]
+  fn test_accumulate_across_files() -> PcoResult<()> {
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(0))
+      .with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    // two files with different ranges, so their bins don't line up
+    let low = (0..3000).map(|i| (i * 7919) % 1000).collect::<Vec<u32>>();
+    let high = (0..1000)
+      .map(|i| 1000 + (i * 7919) % 3000)
+      .collect::<Vec<u32>>();
+
+    let mut acc = HistogramAccumulator::new();
+    for (meta, n) in chunk_metas(&low, &config)?
+      .into_iter()
+      .chain(chunk_metas(&high, &config)?)
+    {
+      acc.add_with_count(&meta, n)?;
+    }
+    assert_eq!(acc.total_weight(), 4000.0);
+    let bucket_sum = acc.buckets().iter().map(|&(_, _, w)| w).sum::<f64>();
+    assert!((bucket_sum - 4000.0).abs() < 1e-6);
+    for pair in acc.buckets().windows(2) {
+      assert!(pair[0].1 < pair[1].0);
+    }
+
+    // the true median is about 667, and the bins are much narrower than the
+    // ranges
+    let median = acc.quantile(0.5)?;
+    assert!((600..=750).contains(&median), "{}", median);
+    assert!(acc.quantile(0.0)? <= 10);
+    assert!(acc.quantile(1.0)? >= 3990);
+    Ok(())
+  }
+
+  #[test]
+  fn test_accumulate_identical_chunks() -> PcoResult<()> {
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(0));
+    let nums = (0..2000).map(|i| (i * 31) % 500).collect::<Vec<u32>>();
+    let (meta, _) = chunk_metas(&nums, &config)?.remove(0);
+
+    let mut once = HistogramAccumulator::new();
+    once.add(&meta)?;
+    let mut twice = HistogramAccumulator::new();
+    twice.add(&meta)?;
+    twice.add(&meta)?;
+    // identical boundaries don't fragment the grid
+    assert_eq!(once.buckets().len(), twice.buckets().len());
+    for q in [0.1, 0.5, 0.9] {
+      assert_eq!(once.quantile(q)?, twice.quantile(q)?);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_accumulate_errors() -> PcoResult<()> {
+    let acc = HistogramAccumulator::<u32>::new();
+    assert!(acc.quantile(0.5).is_err());
+    assert!(acc.quantile(1.5).is_err());
+
+    let mut acc = HistogramAccumulator::new();
+    let nums = (0..1000).collect::<Vec<u32>>();
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(1));
+    let (meta, _) = chunk_metas(&nums, &config)?.remove(0);
+    assert!(acc.add(&meta).is_err());
+    Ok(())
+  }
+}
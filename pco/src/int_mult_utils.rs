@@ -1,4 +1,4 @@
-use std::cmp::min;
+use std::cmp::{min, Reverse};
 use std::collections::HashMap;
 use std::f64::consts::PI;
 use std::mem;
@@ -182,6 +182,8 @@ fn most_prominent_gcd<L: Latent>(triple_gcds: &[L], total_triples: usize) -> Opt
     *counts.entry(gcd).or_insert(0) += 1;
   }
 
+  // HashMap iteration order varies from run to run, so we break ties toward
+  // the smaller GCD to keep compression deterministic
   let gcd_and_score = counts //counts_accounting_for_small_multiples
     .iter()
     .filter_map(|(&gcd, &count)| {
@@ -189,7 +191,7 @@ fn most_prominent_gcd<L: Latent>(triple_gcds: &[L], total_triples: usize) -> Opt
       let score = filter_score_triple_gcd(gcd_f64, count, total_triples)?;
       Some((gcd, score))
     })
-    .max_by_key(|(_, score)| score.to_latent_ordered())?;
+    .max_by_key(|&(gcd, score)| (score.to_latent_ordered(), Reverse(gcd)))?;
 
   Some(gcd_and_score)
 }
@@ -314,4 +316,26 @@ mod tests {
       2
     );
   }
+
+  #[test]
+  fn test_choose_base() {
+    let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+    // prices in cents, always a multiple of 5
+    let cents = (0..1000)
+      .map(|_| rng.gen_range(0_i64..100_000) * 5)
+      .collect::<Vec<_>>();
+    assert_eq!(choose_base(&cents), Some(5));
+
+    // values divisible by 1000, plus a nearly-constant remainder
+    let thousands = (0..1000)
+      .map(|i| rng.gen_range(0_u32..100_000) * 1000 + if i % 100 == 0 { 7 } else { 0 })
+      .collect::<Vec<_>>();
+    assert_eq!(choose_base(&thousands), Some(1000));
+
+    // no common base
+    let randoms = (0..1000)
+      .map(|_| rng.gen_range(0_u64..1_000_000))
+      .collect::<Vec<_>>();
+    assert_eq!(choose_base(&randoms), None);
+  }
 }
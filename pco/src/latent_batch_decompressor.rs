@@ -7,7 +7,8 @@ use crate::constants::{Bitlen, ANS_INTERLEAVING, FULL_BATCH_N};
 use crate::data_types::Latent;
 use crate::errors::PcoResult;
 use crate::page_meta::PageLatentVarMeta;
-use crate::{ans, bit_reader, read_write_uint, ChunkLatentVarMeta};
+use crate::simd_offsets::SimdLevel;
+use crate::{ans, bit_reader, read_write_uint, simd_offsets, ChunkLatentVarMeta};
 
 #[derive(Clone, Debug)]
 struct State<L: Latent> {
@@ -15,6 +16,7 @@ struct State<L: Latent> {
   offset_bits_csum_scratch: [Bitlen; FULL_BATCH_N],
   offset_bits_scratch: [Bitlen; FULL_BATCH_N],
   lowers_scratch: [L; FULL_BATCH_N],
+  uniform_offsets_scratch: [u64; FULL_BATCH_N],
   state_idxs: [AnsState; ANS_INTERLEAVING],
 }
 
@@ -29,11 +31,31 @@ impl<L: Latent> State<L> {
   }
 }
 
+// counts of what was decoded, gathered only on request
+#[derive(Clone, Debug, Default)]
+pub struct LatentVarStats {
+  pub bin_counts: Vec<usize>,
+  pub offset_bits: usize,
+  // batches whose offsets all had the same width, decoded via SIMD
+  pub uniform_offset_batches: usize,
+}
+
+// heap allocations that can be recycled from one LatentBatchDecompressor to
+// the next, so decompressing many small chunks doesn't reallocate them
+#[derive(Clone, Debug, Default)]
+pub struct LatentBatchBuffers<L: Latent> {
+  infos: Vec<BinDecompressionInfo<L>>,
+  ans_nodes: Vec<ans::Node>,
+}
+
 // LatentBatchDecompressor does the main work of decoding bytes into Latents
 #[derive(Clone, Debug)]
 pub struct LatentBatchDecompressor<L: Latent> {
   // known information about this latent variable
   u64s_per_offset: usize,
+  // Some if every bin has the same offset bits, so no batch needs checking
+  uniform_offset_bits: Option<Bitlen>,
+  simd_level: SimdLevel,
   infos: Vec<BinDecompressionInfo<L>>,
   needs_ans: bool,
   decoder: ans::Decoder,
@@ -41,26 +63,36 @@ pub struct LatentBatchDecompressor<L: Latent> {
 
   // mutable state
   state: State<L>,
+  pub stats: Option<LatentVarStats>,
 }
 
 impl<L: Latent> LatentBatchDecompressor<L> {
   pub fn new(
     chunk_latent_var_meta: &ChunkLatentVarMeta<L>,
     page_latent_var_meta: &PageLatentVarMeta<L>,
+    buffers: LatentBatchBuffers<L>,
   ) -> PcoResult<Self> {
     let u64s_per_offset =
       read_write_uint::calc_max_u64s(chunk_latent_var_meta.max_bits_per_offset());
-    let infos = chunk_latent_var_meta
-      .bins
-      .iter()
-      .map(BinDecompressionInfo::from)
-      .collect::<Vec<_>>();
-    let decoder = ans::Decoder::from_chunk_latent_var_meta(chunk_latent_var_meta)?;
+    let LatentBatchBuffers {
+      mut infos,
+      ans_nodes,
+    } = buffers;
+    infos.clear();
+    infos.extend(
+      chunk_latent_var_meta
+        .bins
+        .iter()
+        .map(BinDecompressionInfo::from),
+    );
+    let decoder =
+      ans::Decoder::from_chunk_latent_var_meta_reusing(chunk_latent_var_meta, ans_nodes)?;
 
     let mut state = State {
       offset_bits_csum_scratch: [0; FULL_BATCH_N],
       offset_bits_scratch: [0; FULL_BATCH_N],
       lowers_scratch: [L::ZERO; FULL_BATCH_N],
+      uniform_offsets_scratch: [0; FULL_BATCH_N],
       state_idxs: page_latent_var_meta.ans_final_state_idxs,
     };
 
@@ -77,6 +109,14 @@ impl<L: Latent> LatentBatchDecompressor<L> {
       }
     }
 
+    let bins = &chunk_latent_var_meta.bins;
+    let uniform_offset_bits = match bins.first() {
+      Some(first) if bins.iter().all(|bin| bin.offset_bits == first.offset_bits) => {
+        Some(first.offset_bits)
+      }
+      _ => None,
+    };
+
     let maybe_constant_value = if chunk_latent_var_meta.is_trivial() {
       chunk_latent_var_meta.bins.first().map(|bin| bin.lower)
     } else {
@@ -85,14 +125,24 @@ impl<L: Latent> LatentBatchDecompressor<L> {
 
     Ok(Self {
       u64s_per_offset,
+      uniform_offset_bits,
+      simd_level: SimdLevel::detect(),
       infos,
       needs_ans,
       decoder,
       maybe_constant_value,
       state,
+      stats: None,
     })
   }
 
+  pub fn into_buffers(self) -> LatentBatchBuffers<L> {
+    LatentBatchBuffers {
+      infos: self.infos,
+      ans_nodes: self.decoder.nodes,
+    }
+  }
+
   // This implementation handles only a full batch, but is faster.
   #[inline(never)]
   unsafe fn decompress_full_ans_symbols(&mut self, reader: &mut BitReader) {
@@ -195,6 +245,31 @@ impl<L: Latent> LatentBatchDecompressor<L> {
     reader.bits_past_byte = final_bit_idx as Bitlen % 8;
   }
 
+  // Only valid when every offset has the same width of at most 57 bits.
+  #[inline(never)]
+  unsafe fn decompress_uniform_offsets(
+    &mut self,
+    reader: &mut BitReader,
+    offset_bits: Bitlen,
+    dst: &mut [L],
+  ) {
+    let base_bit_idx = reader.bit_idx();
+    let scratch = &mut self.state.uniform_offsets_scratch[..dst.len()];
+    simd_offsets::read_uniform_offsets(
+      self.simd_level,
+      reader.src,
+      base_bit_idx,
+      offset_bits,
+      scratch,
+    );
+    for (dst, &offset) in dst.iter_mut().zip(scratch.iter()) {
+      *dst = L::from_u64(offset);
+    }
+    let final_bit_idx = base_bit_idx + dst.len() * offset_bits as usize;
+    reader.stale_byte_idx = final_bit_idx / 8;
+    reader.bits_past_byte = final_bit_idx as Bitlen % 8;
+  }
+
   #[inline(never)]
   fn add_lowers(&self, dst: &mut [L]) {
     for (&lower, dst) in self.state.lowers_scratch[0..dst.len()]
@@ -205,6 +280,55 @@ impl<L: Latent> LatentBatchDecompressor<L> {
     }
   }
 
+  pub fn enable_stats(&mut self) {
+    self.stats = Some(LatentVarStats {
+      bin_counts: vec![0; self.infos.len()],
+      offset_bits: 0,
+      uniform_offset_batches: 0,
+    });
+  }
+
+  // Even when the chunk's bins have different offset bits, a batch often
+  // draws only from bins of one width, e.g. when the data drifts slowly.
+  // Then its offsets can be decoded via SIMD too.
+  #[inline]
+  fn batch_uniform_offset_bits(&self, batch_n: usize) -> Option<Bitlen> {
+    if self.uniform_offset_bits.is_some() {
+      return self.uniform_offset_bits;
+    }
+    let offset_bits = &self.state.offset_bits_scratch[..batch_n];
+    let first = offset_bits[0];
+    if offset_bits.iter().all(|&bits| bits == first) {
+      Some(first)
+    } else {
+      None
+    }
+  }
+
+  // Bins are identified by their lower bounds, so this doesn't slow down the
+  // ANS decoding loops when stats are off.
+  #[inline(never)]
+  fn record_stats(&mut self, batch_n: usize) {
+    let Some(stats) = &mut self.stats else {
+      return;
+    };
+    for (&lower, &offset_bits) in self.state.lowers_scratch[..batch_n]
+      .iter()
+      .zip(&self.state.offset_bits_scratch[..batch_n])
+    {
+      // valid bins are sorted by lower bound, but we don't rely on it
+      let bin_idx = self
+        .infos
+        .binary_search_by(|info| info.lower.cmp(&lower))
+        .ok()
+        .or_else(|| self.infos.iter().position(|info| info.lower == lower));
+      if let Some(bin_idx) = bin_idx {
+        stats.bin_counts[bin_idx] += 1;
+      }
+      stats.offset_bits += offset_bits as usize;
+    }
+  }
+
   // If hits a corruption, it returns an error and leaves reader and self unchanged.
   // May contaminate dst.
   pub unsafe fn decompress_latent_batch(
@@ -231,7 +355,15 @@ impl<L: Latent> LatentBatchDecompressor<L> {
     assert!(self.u64s_per_offset <= read_write_uint::calc_max_u64s(L::BITS));
     match self.u64s_per_offset {
       0 => dst.fill(L::ZERO),
-      1 => self.decompress_offsets::<1>(reader, dst),
+      1 => match self.batch_uniform_offset_bits(dst.len()) {
+        Some(offset_bits) => {
+          self.decompress_uniform_offsets(reader, offset_bits, dst);
+          if let Some(stats) = &mut self.stats {
+            stats.uniform_offset_batches += 1;
+          }
+        }
+        None => self.decompress_offsets::<1>(reader, dst),
+      },
       2 => self.decompress_offsets::<2>(reader, dst),
       3 => self.decompress_offsets::<3>(reader, dst),
       _ => panic!(
@@ -241,6 +373,7 @@ impl<L: Latent> LatentBatchDecompressor<L> {
     }
 
     self.add_lowers(dst);
+    self.record_stats(dst.len());
 
     Ok(())
   }
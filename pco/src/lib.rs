@@ -34,32 +34,78 @@
 //! * Pco will always try to process all numbers, and it will fail if insufficient bytes are
 //! available. For instance, during decompression Pco will try to fill the entire `&mut [T]`
 //! passed in, returning an insufficient data error if the `&[u8]` passed in is not long enough.
+//!
+//! # Optional Features
+//!
+//! * `arrow`: adds the [`arrow`] module for compressing Arrow primitive
+//!   arrays and decompressing into them.
+//! * `bench`: adds `standalone::compress_timed` for timing each compression phase.
+//! * `chrono`: implements [`NumberLike`][data_types::NumberLike] for
+//!   `chrono::DateTime<Utc>` and `chrono::TimeDelta` as i64 nanoseconds,
+//!   returning an InvalidArgument error for values out of that range.
+//! * `mmap`: enables `standalone::decompress_mmap`, which decompresses a
+//!   file by memory-mapping it instead of reading it into memory.
+//! * `ndarray`: adds the [`ndarray`][mod@ndarray] module for 1D `ndarray` arrays.
+//! * `rayon`: enables [`standalone::par_compress`], which compresses chunks
+//!   in parallel, and
+//!   [`wrapped::ChunkDecompressor::par_decompress_pages`], which decompresses
+//!   pages in parallel.
+//! * `serde`: implements `Serialize` and `Deserialize` for chunk metadata
+//!   types like [`ChunkMeta`], [`ChunkLatentVarMeta`], [`Bin`], and [`Mode`].
+//! * `testing`: adds `testing::assert_roundtrip` for checking exact round trips.
+//! * `wasm`: adds the `wasm` module of non-generic `compress_*` and
+//!   `decompress_*` functions exported with `wasm-bindgen`, for use from
+//!   JavaScript.
 
 #![allow(clippy::uninit_vec)]
 #![deny(clippy::unused_unit)]
 #![deny(dead_code)]
 
+pub use ans_table::{AnsTable, AnsTableNode};
+pub use auto::{auto_delta_encoding_order, estimate_compressed_size};
 pub use bin::Bin;
-pub use chunk_config::{ChunkConfig, FloatMultSpec, FloatQuantSpec, IntMultSpec, PagingSpec};
-pub use chunk_meta::{ChunkLatentVarMeta, ChunkMeta};
-pub use constants::{DEFAULT_COMPRESSION_LEVEL, DEFAULT_MAX_PAGE_N, FULL_BATCH_N};
-pub use mode::Mode;
+pub use chunk_config::{
+  AutoLevelSpec, ChunkConfig, ChunkConfigOverrides, DictSpec, FloatMultSpec, FloatQuantSpec,
+  FloatXorSpec, IntMultSpec, PagingSpec, RunLengthSpec, SparseSpec,
+};
+pub use chunk_meta::{approximate_quantile, ChunkLatentVarMeta, ChunkMeta, OffsetBitsSummary};
+pub use compression_report::CompressionReport;
+pub use constants::{
+  DEFAULT_COMPRESSION_LEVEL, DEFAULT_MAX_PAGE_N, FORMAT_VERSION, FULL_BATCH_N, MIN_FORMAT_VERSION,
+};
+pub use decode_stats::DecodeStats;
+pub use histogram_accumulator::HistogramAccumulator;
+pub use mode::{Mode, ModeKind};
+pub use page_meta::{PageLatentVarMeta, PageMeta};
 pub use progress::Progress;
+#[cfg(feature = "bench")]
+pub use timings::Timings;
 
 #[doc = include_str!("../README.md")]
 #[cfg(doctest)]
 struct ReadmeDoctest;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
 pub mod data_types;
 /// for inspecting certain types of Pco metadata
 pub mod describers;
 pub mod errors;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
 /// for compressing/decompressing .pco files
 pub mod standalone;
+/// for testing that numbers round trip through compression
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 /// for compressing/decompressing as part of an outer, wrapping format
 pub mod wrapped;
 
 mod ans;
+mod ans_table;
+mod auto;
 mod bin;
 mod bin_optimization;
 mod bit_reader;
@@ -68,12 +114,17 @@ mod bits;
 mod chunk_config;
 mod chunk_meta;
 mod compression_intermediates;
+mod compression_report;
 mod compression_table;
 mod constants;
+mod decode_stats;
 mod delta;
+mod dict_utils;
 mod float_mult_utils;
 mod float_quant_utils;
+mod float_xor_utils;
 mod format_version;
+mod histogram_accumulator;
 mod histograms;
 mod int_mult_utils;
 mod latent_batch_decompressor;
@@ -82,8 +133,13 @@ mod mode;
 mod page_meta;
 mod progress;
 mod read_write_uint;
+mod run_length_utils;
 mod sampling;
+mod simd_offsets;
 mod sort_utils;
+mod sparse_utils;
+#[cfg(feature = "bench")]
+mod timings;
 
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,9 @@
-use std::fmt::Debug;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
 
 use crate::constants::Bitlen;
-use crate::data_types::{FloatLike, Latent};
+use crate::data_types::{FloatLike, Latent, NumberLike};
 
 // Internally, here's how we should model each mode:
 //
@@ -22,6 +24,19 @@ use crate::data_types::{FloatLike, Latent};
 //   less-significant bits drawn from a second, very low-entropy distribution
 //   (e.g. in the common case, one that always produces zeros).
 //
+// FloatXor: The data is a time series whose consecutive values often share
+//   their sign, exponent, and leading significand bits, or are exactly equal.
+//
+// RunLength: The data consists of long runs of identical values, such as a
+//   step function or a column of flags.
+//
+// Dict: The data is drawn from a small set of arbitrary values, such as hashes
+//   or IDs, in which case the distribution of their indices is smoother than
+//   that of the values themselves.
+//
+// Sparse: The data is mostly zeros, and the positions and values of the
+//   nonzero numbers are each drawn from smooth distributions.
+//
 // Note the differences between int mult and float mult,
 // which have equivalent formulas.
 
@@ -34,6 +49,7 @@ use crate::data_types::{FloatLike, Latent};
 /// complexities.
 /// Slightly more rigorous formulas are in format.md.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mode<L: Latent> {
   /// Represents each number as a single latent: itself.
   ///
@@ -63,16 +79,118 @@ pub enum Mode<L: Latent> {
   /// Formula: `num = from_bits(quantums << k + adjustment)`
   /// (warning: this formula is especially simplified)
   FloatQuant(Bitlen),
+  /// Represents each number as two latents, in the style of Gorilla
+  /// compression: the XOR of its bits with the previous number's bits,
+  /// excluding trailing zeros, and the count of those trailing zeros.
+  /// Bit-identical consecutive numbers are represented as zeros.
+  ///
+  /// Only applies to floats.
+  /// This mode replaces delta encoding, so the delta encoding order is
+  /// always 0.
+  ///
+  /// Formula: `num = from_bits(xor << trailing_zeros ^ prev_num_bits)`
+  FloatXor,
+  /// Represents each run of bit-identical consecutive numbers as two
+  /// latents: the run's number and its length.
+  ///
+  /// Unlike other modes, this produces one pair of latents per run rather
+  /// than per number, so delta encoding applies to the sequence of runs.
+  ///
+  /// Formula: `nums = [value; run_length]` for each run
+  RunLength,
+  /// Represents each number as its index in a sorted dictionary of the
+  /// chunk's distinct numbers.
+  ///
+  /// The dictionary is stored in
+  /// [`ChunkMeta::dict`][crate::ChunkMeta::dict].
+  ///
+  /// Formula: `num = dict[index]`
+  Dict,
+  /// Represents each nonzero number and the run of zeros after it as two
+  /// latents: the number itself and the count of zeros.
+  ///
+  /// Like run length mode, this produces one pair of latents per nonzero
+  /// number rather than per number, so delta encoding applies to the
+  /// sequence of nonzero numbers.
+  /// Here zero means the latent of `T::default()`.
+  ///
+  /// Formula: `nums = [value] + [0; n_zeros]` for each nonzero number
+  Sparse,
+}
+
+/// The kind of a [`Mode`], without any parameters like its base.
+///
+/// See [`ChunkConfig::force_mode`][crate::ChunkConfig::force_mode].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModeKind {
+  Classic,
+  IntMult,
+  FloatMult,
+  FloatQuant,
+  FloatXor,
+  RunLength,
+  Dict,
+  Sparse,
+}
+
+impl ModeKind {
+  /// Returns the earliest format version able to represent this mode.
+  ///
+  /// Readers that only support older format versions cannot decompress
+  /// chunks in this mode.
+  pub fn min_format_version(self) -> u8 {
+    match self {
+      ModeKind::Classic => 0,
+      ModeKind::IntMult => 1,
+      ModeKind::FloatMult => 0,
+      ModeKind::FloatQuant => 2,
+      ModeKind::FloatXor | ModeKind::RunLength | ModeKind::Dict | ModeKind::Sparse => 3,
+    }
+  }
+
+  // Returns an example mode of this kind, for checking validity against a
+  // data type.
+  pub(crate) fn example_mode<L: Latent>(self) -> Mode<L> {
+    match self {
+      ModeKind::Classic => Mode::Classic,
+      ModeKind::IntMult => Mode::IntMult(L::ONE),
+      // the ordered latent of 2.0 for every float type
+      ModeKind::FloatMult => Mode::FloatMult(L::MID | (L::MID >> 1)),
+      ModeKind::FloatQuant => Mode::FloatQuant(0),
+      ModeKind::FloatXor => Mode::FloatXor,
+      ModeKind::RunLength => Mode::RunLength,
+      ModeKind::Dict => Mode::Dict,
+      ModeKind::Sparse => Mode::Sparse,
+    }
+  }
 }
 
 impl<L: Latent> Mode<L> {
+  /// Returns the kind of this mode, dropping any parameters.
+  pub fn kind(&self) -> ModeKind {
+    match self {
+      Mode::Classic => ModeKind::Classic,
+      Mode::IntMult(_) => ModeKind::IntMult,
+      Mode::FloatMult(_) => ModeKind::FloatMult,
+      Mode::FloatQuant(_) => ModeKind::FloatQuant,
+      Mode::FloatXor => ModeKind::FloatXor,
+      Mode::RunLength => ModeKind::RunLength,
+      Mode::Dict => ModeKind::Dict,
+      Mode::Sparse => ModeKind::Sparse,
+    }
+  }
+
   pub(crate) fn n_latent_vars(&self) -> usize {
     use Mode::*;
 
     match self {
-      Classic => 1,
+      Classic | Dict => 1,
       FloatMult(_) | IntMult(_) => 2, // multiplier, adjustment
       FloatQuant(_) => 2,             // quantums, adjustment
+      FloatXor => 2,                  // XOR bits, trailing zeros
+      RunLength => 2,                 // run values, run lengths
+      Sparse => 2,                    // nonzero values, zero counts
     }
   }
 
@@ -80,17 +198,30 @@ impl<L: Latent> Mode<L> {
     &self,
     latent_var_idx: usize,
     delta_order: usize,
+    secondary_delta_order: usize,
   ) -> usize {
     use Mode::*;
 
     match (self, latent_var_idx) {
-      // In all currently-available modes, the overall `delta_order` is really the delta-order of
-      // the first latent.
-      (Classic, 0) | (FloatMult(_), 0) | (FloatQuant(_), 0) | (IntMult(_), 0) => delta_order,
+      // FloatXor already takes the place of delta encoding.
+      (FloatXor, 0) | (FloatXor, 1) => 0,
+      // In all other currently-available modes, the overall `delta_order` is really the
+      // delta-order of the first latent.
+      (Classic, 0)
+      | (FloatMult(_), 0)
+      | (FloatQuant(_), 0)
+      | (IntMult(_), 0)
+      | (RunLength, 0)
+      | (Dict, 0)
+      | (Sparse, 0) => delta_order,
       // In FloatMult, IntMult, and FloatQuant, the second latent is essentially a remainder or
-      // adjustment; there isn't any a priori reason that deltas should be useful for that kind of
-      // term and we do not attempt them.
-      (FloatMult(_), 1) | (IntMult(_), 1) | (FloatQuant(_), 1) => 0,
+      // adjustment; there usually isn't any a priori reason that deltas should be useful for
+      // that kind of term, so the secondary delta order defaults to 0.
+      // In RunLength and Sparse, the second latent is a run length, for which
+      // the same reasoning applies.
+      (FloatMult(_), 1) | (IntMult(_), 1) | (FloatQuant(_), 1) | (RunLength, 1) | (Sparse, 1) => {
+        secondary_delta_order
+      }
       _ => unreachable!(
         "unknown latent {:?}/{}",
         self, latent_var_idx
@@ -98,7 +229,109 @@ impl<L: Latent> Mode<L> {
     }
   }
 
+  // whether each page has a count of runs, and each pair of latents
+  // expands to a run of numbers
+  pub(crate) fn has_runs(&self) -> bool {
+    matches!(self, Mode::RunLength | Mode::Sparse)
+  }
+
   pub(crate) fn float_mult<F: FloatLike<L = L>>(base: F) -> Self {
     Self::FloatMult(base.to_latent_ordered())
   }
+
+  /// Returns a human-readable form of the mode, like `Display`, but with any
+  /// float base decoded as a `T`.
+  ///
+  /// For instance, float mult mode with a base of 0.01 displays as
+  /// `FloatMult(base=0.01)`.
+  pub fn display<T: NumberLike<L = L>>(&self) -> impl Display {
+    ModeDisplay::<T> {
+      mode: *self,
+      phantom: PhantomData,
+    }
+  }
+
+  fn fmt_with_float_base(
+    &self,
+    f: &mut Formatter<'_>,
+    fmt_float_base: impl FnOnce(L, &mut Formatter<'_>) -> fmt::Result,
+  ) -> fmt::Result {
+    use Mode::*;
+
+    match self {
+      Classic => write!(f, "Classic"),
+      IntMult(base) => write!(f, "IntMult(base={})", base),
+      FloatMult(base) => {
+        write!(f, "FloatMult(base=")?;
+        fmt_float_base(*base, f)?;
+        write!(f, ")")
+      }
+      FloatQuant(k) => write!(f, "FloatQuant(k={})", k),
+      FloatXor => write!(f, "FloatXor"),
+      RunLength => write!(f, "RunLength"),
+      Dict => write!(f, "Dict"),
+      Sparse => write!(f, "Sparse"),
+    }
+  }
+}
+
+/// Displays the mode in a human-readable form, like `IntMult(base=7)`.
+///
+/// Since the mode doesn't know its number type, a float mult base is shown
+/// as the hex bits of its latent.
+/// Use [`Mode::display`] to show the base as a float instead.
+impl<L: Latent> Display for Mode<L> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    self.fmt_with_float_base(f, |base, f| {
+      write!(f, "bits 0x{:x}", base.to_u64())
+    })
+  }
+}
+
+struct ModeDisplay<T: NumberLike> {
+  mode: Mode<T::L>,
+  phantom: PhantomData<T>,
+}
+
+impl<T: NumberLike> Display for ModeDisplay<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    self.mode.fmt_with_float_base(f, |base, f| {
+      write!(f, "{}", T::from_latent_ordered(base))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_display() {
+    assert_eq!(Mode::<u32>::Classic.to_string(), "Classic");
+    assert_eq!(
+      Mode::<u64>::IntMult(7).to_string(),
+      "IntMult(base=7)"
+    );
+    assert_eq!(
+      Mode::<u32>::FloatQuant(3).to_string(),
+      "FloatQuant(k=3)"
+    );
+
+    let float_mult = Mode::float_mult(0.01_f64);
+    assert_eq!(
+      float_mult.display::<f64>().to_string(),
+      "FloatMult(base=0.01)"
+    );
+    assert_eq!(
+      float_mult.to_string(),
+      format!(
+        "FloatMult(base=bits 0x{:x})",
+        0.01_f64.to_latent_ordered()
+      )
+    );
+    assert_eq!(
+      Mode::<u16>::Dict.display::<i16>().to_string(),
+      "Dict"
+    );
+  }
 }
@@ -0,0 +1,116 @@
+//! Compression and decompression of 1D `ndarray` arrays.
+//!
+//! Compression writes a standalone .pco file, so the result can also be read
+//! with [`standalone::simple_decompress`][crate::standalone::simple_decompress],
+//! and files written any other way can be decompressed into an array.
+
+use ::ndarray::{Array1, ArrayView1};
+
+use crate::data_types::NumberLike;
+use crate::errors::PcoResult;
+use crate::standalone::{compress_gathered, simple_compress, simple_decompress};
+use crate::ChunkConfig;
+
+/// Compresses a 1D array view into a standalone .pco file.
+///
+/// Contiguous views are read in place.
+/// Strided views, e.g. every other element or a reversed view, are gathered
+/// in logical order into a buffer one chunk at a time, like
+/// [`compress_strided`][crate::standalone::compress_strided], so they are
+/// never copied all at once.
+///
+/// Will return an InvalidArgument error if the config is invalid.
+pub fn compress_ndarray<T: NumberLike>(
+  arr: &ArrayView1<T>,
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  if let Some(nums) = arr.as_slice() {
+    return simple_compress(nums, config);
+  }
+
+  let mut nums = arr.iter();
+  compress_gathered(arr.len(), config, |range, gathered| {
+    gathered.extend(nums.by_ref().take(range.len()).copied())
+  })
+}
+
+/// Decompresses a standalone .pco file into an owned 1D array.
+///
+/// The array takes ownership of the decompressed buffer, so there is no
+/// copy beyond decompression itself.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match.
+pub fn decompress_ndarray<T: NumberLike>(src: &[u8]) -> PcoResult<Array1<T>> {
+  simple_decompress(src).map(Array1::from_vec)
+}
+
+#[cfg(test)]
+mod tests {
+  use ::ndarray::{s, Array1};
+
+  use crate::errors::ErrorKind;
+  use crate::PagingSpec;
+
+  use super::*;
+
+  #[test]
+  fn test_ndarray_round_trip() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let arr = Array1::from_iter((0..1000).map(|i| (i * i) as i64 - 500));
+    let compressed = compress_ndarray(&arr.view(), &config)?;
+    assert_eq!(
+      compressed,
+      simple_compress(arr.as_slice().unwrap(), &config)?
+    );
+    assert_eq!(decompress_ndarray::<i64>(&compressed)?, arr);
+
+    let empty = Array1::<f32>::zeros(0);
+    let compressed = compress_ndarray(&empty.view(), &config)?;
+    assert_eq!(
+      decompress_ndarray::<f32>(&compressed)?,
+      empty
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_ndarray_strided() -> PcoResult<()> {
+    // small chunks, so views are gathered across several of them
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(64));
+    let arr = Array1::from_iter((0..1000).map(|i| i as f64 * 0.25));
+    for view in [
+      arr.slice(s![1..;3]),
+      arr.slice(s![..;-1]),
+      arr.slice(s![10..20]),
+      arr.slice(s![2..997;7]),
+      arr.slice(s![5..6;4]),
+    ] {
+      let compressed = compress_ndarray(&view, &config)?;
+      assert_eq!(
+        compressed,
+        simple_compress(&view.to_vec(), &config)?
+      );
+      assert_eq!(
+        decompress_ndarray::<f64>(&compressed)?,
+        view.to_owned()
+      );
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_ndarray_errors() -> PcoResult<()> {
+    let arr = Array1::from_vec(vec![1_u32, 2, 3]);
+    let compressed = compress_ndarray(&arr.view(), &ChunkConfig::default())?;
+    let err = decompress_ndarray::<u32>(&compressed[..compressed.len() - 1]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    let err = decompress_ndarray::<i32>(&compressed).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+}
@@ -3,20 +3,33 @@ use std::io::Write;
 use crate::ans::AnsState;
 use crate::bit_reader::BitReader;
 use crate::bit_writer::BitWriter;
-use crate::constants::{Bitlen, ANS_INTERLEAVING};
+use crate::constants::{Bitlen, ANS_INTERLEAVING, BITS_TO_ENCODE_N_RUNS};
 use crate::data_types::Latent;
 use crate::delta::DeltaMoments;
 use crate::errors::PcoResult;
 use crate::ChunkMeta;
 
+/// The metadata of a data page for one latent variable.
 #[derive(Clone, Debug)]
 pub struct PageLatentVarMeta<L: Latent> {
-  pub delta_moments: DeltaMoments<L>,
-  pub ans_final_state_idxs: [AnsState; ANS_INTERLEAVING],
+  pub(crate) delta_moments: DeltaMoments<L>,
+  pub(crate) ans_final_state_idxs: [AnsState; ANS_INTERLEAVING],
 }
 
 impl<L: Latent> PageLatentVarMeta<L> {
-  pub unsafe fn write_to<W: Write>(&self, ans_size_log: Bitlen, writer: &mut BitWriter<W>) {
+  /// Returns the latents that delta decoding starts from, one per delta
+  /// encoding order applied to this latent variable.
+  pub fn delta_moments(&self) -> &[L] {
+    &self.delta_moments.moments
+  }
+
+  /// Returns the index of each interleaved ANS decoder's starting state, in
+  /// the range `[0, 2^ans_size_log)`.
+  pub fn ans_final_state_idxs(&self) -> &[u32] {
+    &self.ans_final_state_idxs
+  }
+
+  pub(crate) unsafe fn write_to<W: Write>(&self, ans_size_log: Bitlen, writer: &mut BitWriter<W>) {
     self.delta_moments.write_to(writer);
 
     // write the final ANS state, moving it down the range [0, table_size)
@@ -25,7 +38,7 @@ impl<L: Latent> PageLatentVarMeta<L> {
     }
   }
 
-  pub unsafe fn parse_from(
+  pub(crate) unsafe fn parse_from(
     reader: &mut BitReader,
     delta_order: usize,
     ans_size_log: Bitlen,
@@ -47,24 +60,55 @@ impl<L: Latent> PageLatentVarMeta<L> {
 // Importantly, `n` and `compressed_body_size` might come from either the
 // chunk metadata parsing step (standalone mode) OR from the wrapping format
 // (wrapped mode).
+/// The metadata of a data page, which determines the initial decoding
+/// state for each latent variable.
+///
+/// This can be parsed without decoding the rest of the page via
+/// [`wrapped::ChunkDecompressor::parse_page_meta`][crate::wrapped::ChunkDecompressor::parse_page_meta].
 #[derive(Clone, Debug)]
 pub struct PageMeta<L: Latent> {
-  pub per_var: Vec<PageLatentVarMeta<L>>,
+  // only present in run length mode, where each latent var has one latent
+  // per run instead of per number
+  pub(crate) n_runs: Option<usize>,
+  pub(crate) per_var: Vec<PageLatentVarMeta<L>>,
 }
 
 impl<L: Latent> PageMeta<L> {
-  pub unsafe fn write_to<I: Iterator<Item = Bitlen>, W: Write>(
+  /// Returns the count of runs in the page in run length mode, or `None` in
+  /// other modes.
+  pub fn n_runs(&self) -> Option<usize> {
+    self.n_runs
+  }
+
+  /// Returns the metadata for each latent variable, in the same order as
+  /// [`ChunkMeta::per_latent_var`].
+  pub fn per_latent_var(&self) -> &[PageLatentVarMeta<L>] {
+    &self.per_var
+  }
+
+  pub(crate) unsafe fn write_to<I: Iterator<Item = Bitlen>, W: Write>(
     &self,
     ans_size_logs: I,
     writer: &mut BitWriter<W>,
   ) {
+    if let Some(n_runs) = self.n_runs {
+      writer.write_usize(n_runs, BITS_TO_ENCODE_N_RUNS);
+    }
     for (latent_idx, ans_size_log) in ans_size_logs.enumerate() {
       self.per_var[latent_idx].write_to(ans_size_log, writer);
     }
     writer.finish_byte();
   }
 
-  pub unsafe fn parse_from(reader: &mut BitReader, chunk_meta: &ChunkMeta<L>) -> PcoResult<Self> {
+  pub(crate) unsafe fn parse_from(
+    reader: &mut BitReader,
+    chunk_meta: &ChunkMeta<L>,
+  ) -> PcoResult<Self> {
+    let n_runs = if chunk_meta.mode.has_runs() {
+      Some(reader.read_usize(BITS_TO_ENCODE_N_RUNS))
+    } else {
+      None
+    };
     let mut per_var = Vec::with_capacity(chunk_meta.per_latent_var.len());
     for (latent_idx, chunk_latent_var_meta) in chunk_meta.per_latent_var.iter().enumerate() {
       per_var.push(PageLatentVarMeta::parse_from(
@@ -73,8 +117,11 @@ impl<L: Latent> PageMeta<L> {
         chunk_latent_var_meta.ans_size_log,
       )?);
     }
-    reader.drain_empty_byte("non-zero bits at end of data page metadata")?;
+    reader.drain_empty_byte(
+      "non-zero bits at end of data page metadata",
+      "page",
+    )?;
 
-    Ok(Self { per_var })
+    Ok(Self { n_runs, per_var })
   }
 }
@@ -0,0 +1,94 @@
+use crate::data_types::{Latent, NumberLike};
+
+// Each run of bit-identical consecutive numbers becomes two latents: the
+// number itself and the run's length minus 1. Runs never cross page
+// boundaries, so each page can be decoded on its own, and a run is split if
+// its length minus 1 would not fit in a latent (only possible for 16-bit
+// types).
+
+fn max_run_length<L: Latent>() -> u64 {
+  L::MAX.to_u64().saturating_add(1)
+}
+
+// Counts the runs the numbers would be split into if they were all in one
+// page.
+pub(crate) fn count_runs<T: NumberLike>(nums: &[T]) -> usize {
+  let max_run_length = max_run_length::<T::L>();
+  let mut n_runs = 0;
+  let mut prev = None;
+  let mut run_length = 0;
+  for &num in nums {
+    let latent = num.to_latent_ordered();
+    if prev == Some(latent) && run_length < max_run_length {
+      run_length += 1;
+    } else {
+      n_runs += 1;
+      prev = Some(latent);
+      run_length = 1;
+    }
+  }
+  n_runs
+}
+
+// Returns the latents and the count of runs in each page.
+pub(crate) fn split_latents<T: NumberLike>(
+  nums: &[T],
+  n_per_page: &[usize],
+) -> (Vec<Vec<T::L>>, Vec<usize>) {
+  let max_run_length = max_run_length::<T::L>();
+  let mut values = Vec::new();
+  let mut lengths = Vec::new();
+  let mut n_runs_per_page = Vec::with_capacity(n_per_page.len());
+  let mut start = 0;
+  for &page_n in n_per_page {
+    let page_start_run = values.len();
+    let mut run_length = 0;
+    for &num in &nums[start..start + page_n] {
+      let latent = num.to_latent_ordered();
+      if run_length > 0 && values.last() == Some(&latent) && run_length < max_run_length {
+        run_length += 1;
+      } else {
+        if run_length > 0 {
+          lengths.push(T::L::from_u64(run_length - 1));
+        }
+        values.push(latent);
+        run_length = 1;
+      }
+    }
+    if run_length > 0 {
+      lengths.push(T::L::from_u64(run_length - 1));
+    }
+    n_runs_per_page.push(values.len() - page_start_run);
+    start += page_n;
+  }
+  (vec![values, lengths], n_runs_per_page)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_split_latents() {
+    let nums = vec![3_u32, 3, 3, 5, 5, 3, 3, 3];
+    assert_eq!(count_runs(&nums), 3);
+    let (latents, n_runs_per_page) = split_latents(&nums, &[4, 4]);
+    // the page boundary splits the run of 5s
+    assert_eq!(n_runs_per_page, vec![2, 2]);
+    assert_eq!(latents[0], vec![3, 5, 5, 3]);
+    assert_eq!(latents[1], vec![2, 0, 0, 2]);
+  }
+
+  #[test]
+  fn test_split_latents_long_run() {
+    let nums = vec![7_u16; 70000];
+    assert_eq!(count_runs(&nums), 2);
+    let (latents, n_runs_per_page) = split_latents(&nums, &[70000]);
+    assert_eq!(n_runs_per_page, vec![2]);
+    assert_eq!(latents[0], vec![7, 7]);
+    assert_eq!(
+      latents[1],
+      vec![u16::MAX, (70000 - 65536 - 1) as u16]
+    );
+  }
+}
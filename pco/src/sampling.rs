@@ -37,6 +37,7 @@ pub fn choose_sample<T, S: Copy + Debug, Filter: Fn(&T) -> Option<S>>(
   // Maybe this is a bad idea, but it works for now.
   let target_sample_size = calc_sample_n(nums.len())?;
 
+  // a fixed seed keeps compression deterministic
   let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
   let mut visited = vec![0_u8; nums.len().div_ceil(8)];
   let mut res = Vec::with_capacity(target_sample_size);
@@ -0,0 +1,208 @@
+use crate::bit_reader;
+use crate::constants::Bitlen;
+
+// Decoding offsets is mostly shifting and masking u64's, which vectorizes
+// well as long as every offset has the same width. In that case offset i
+// starts at bit base_bit_idx + i * offset_bits, so we can compute all the
+// load positions up front instead of from a cumulative sum.
+//
+// All implementations here require offset_bits <= 57, i.e. that each offset
+// can be read from a single u64 (see bit_reader::read_uint_at).
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimdLevel {
+  Scalar,
+  #[cfg(target_arch = "x86_64")]
+  Avx2,
+  #[cfg(target_arch = "aarch64")]
+  Neon,
+}
+
+impl SimdLevel {
+  pub fn detect() -> Self {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+      return SimdLevel::Avx2;
+    }
+    #[cfg(target_arch = "aarch64")]
+    if std::arch::is_aarch64_feature_detected!("neon") {
+      return SimdLevel::Neon;
+    }
+    SimdLevel::Scalar
+  }
+}
+
+#[inline]
+fn offset_mask(offset_bits: Bitlen) -> u64 {
+  (1_u64 << offset_bits) - 1
+}
+
+pub unsafe fn read_uniform_offsets_scalar(
+  src: &[u8],
+  base_bit_idx: usize,
+  offset_bits: Bitlen,
+  dst: &mut [u64],
+) {
+  let mask = offset_mask(offset_bits);
+  for (i, dst) in dst.iter_mut().enumerate() {
+    let bit_idx = base_bit_idx + i * offset_bits as usize;
+    *dst = (bit_reader::u64_at(src, bit_idx / 8) >> (bit_idx % 8)) & mask;
+  }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn read_uniform_offsets_avx2(
+  src: &[u8],
+  base_bit_idx: usize,
+  offset_bits: Bitlen,
+  dst: &mut [u64],
+) {
+  use std::arch::x86_64::*;
+
+  let w = offset_bits as i64;
+  let mask = _mm256_set1_epi64x(offset_mask(offset_bits) as i64);
+  let seven = _mm256_set1_epi64x(7);
+  let step = _mm256_set1_epi64x(4 * w);
+  let mut bit_idxs = _mm256_add_epi64(
+    _mm256_set1_epi64x(base_bit_idx as i64),
+    _mm256_set_epi64x(3 * w, 2 * w, w, 0),
+  );
+  let n_vectorized = dst.len() / 4 * 4;
+  for base_i in (0..n_vectorized).step_by(4) {
+    let byte_idxs = _mm256_srli_epi64::<3>(bit_idxs);
+    let shifts = _mm256_and_si256(bit_idxs, seven);
+    let words = _mm256_i64gather_epi64::<1>(src.as_ptr() as *const i64, byte_idxs);
+    let offsets = _mm256_and_si256(_mm256_srlv_epi64(words, shifts), mask);
+    _mm256_storeu_si256(
+      dst.as_mut_ptr().add(base_i) as *mut __m256i,
+      offsets,
+    );
+    bit_idxs = _mm256_add_epi64(bit_idxs, step);
+  }
+
+  read_uniform_offsets_scalar(
+    src,
+    base_bit_idx + n_vectorized * offset_bits as usize,
+    offset_bits,
+    &mut dst[n_vectorized..],
+  );
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn read_uniform_offsets_neon(
+  src: &[u8],
+  base_bit_idx: usize,
+  offset_bits: Bitlen,
+  dst: &mut [u64],
+) {
+  use std::arch::aarch64::*;
+
+  // NEON has no gather, so we load the u64's individually and vectorize
+  // only the shifting and masking.
+  let mask = vdupq_n_u64(offset_mask(offset_bits));
+  let n_vectorized = dst.len() / 2 * 2;
+  for base_i in (0..n_vectorized).step_by(2) {
+    let bit_idx_0 = base_bit_idx + base_i * offset_bits as usize;
+    let bit_idx_1 = bit_idx_0 + offset_bits as usize;
+    let words = [
+      bit_reader::u64_at(src, bit_idx_0 / 8),
+      bit_reader::u64_at(src, bit_idx_1 / 8),
+    ];
+    // shifting left by a negative amount shifts right
+    let shifts = [-((bit_idx_0 % 8) as i64), -((bit_idx_1 % 8) as i64)];
+    let offsets = vandq_u64(
+      vshlq_u64(
+        vld1q_u64(words.as_ptr()),
+        vld1q_s64(shifts.as_ptr()),
+      ),
+      mask,
+    );
+    vst1q_u64(dst.as_mut_ptr().add(base_i), offsets);
+  }
+
+  read_uniform_offsets_scalar(
+    src,
+    base_bit_idx + n_vectorized * offset_bits as usize,
+    offset_bits,
+    &mut dst[n_vectorized..],
+  );
+}
+
+// Reads dst.len() offsets of width offset_bits, packed contiguously starting
+// at base_bit_idx. The result is identical for every SimdLevel.
+#[inline]
+pub unsafe fn read_uniform_offsets(
+  simd_level: SimdLevel,
+  src: &[u8],
+  base_bit_idx: usize,
+  offset_bits: Bitlen,
+  dst: &mut [u64],
+) {
+  match simd_level {
+    SimdLevel::Scalar => read_uniform_offsets_scalar(src, base_bit_idx, offset_bits, dst),
+    #[cfg(target_arch = "x86_64")]
+    SimdLevel::Avx2 => read_uniform_offsets_avx2(src, base_bit_idx, offset_bits, dst),
+    #[cfg(target_arch = "aarch64")]
+    SimdLevel::Neon => read_uniform_offsets_neon(src, base_bit_idx, offset_bits, dst),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{Rng, SeedableRng};
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
+  use crate::constants::FULL_BATCH_N;
+
+  use super::*;
+
+  #[test]
+  fn test_simd_matches_scalar() {
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    let simd_level = SimdLevel::detect();
+    let mut src = vec![0_u8; FULL_BATCH_N * 8 + 16];
+    for offset_bits in 0..=57 {
+      rng.fill(&mut src[..]);
+      let base_bit_idx = rng.gen_range(0..16);
+      let n = rng.gen_range(0..=FULL_BATCH_N);
+      let mut expected = vec![0; n];
+      let mut actual = vec![0; n];
+      unsafe {
+        read_uniform_offsets_scalar(
+          &src,
+          base_bit_idx,
+          offset_bits,
+          &mut expected,
+        );
+        read_uniform_offsets(
+          simd_level,
+          &src,
+          base_bit_idx,
+          offset_bits,
+          &mut actual,
+        );
+      }
+      assert_eq!(
+        actual, expected,
+        "offset_bits={}",
+        offset_bits
+      );
+
+      // and the scalar version agrees with the general bit reader
+      for (i, &offset) in expected.iter().enumerate() {
+        let bit_idx = base_bit_idx + i * offset_bits as usize;
+        let from_reader = unsafe {
+          bit_reader::read_uint_at::<u64, 1>(
+            &src,
+            bit_idx / 8,
+            (bit_idx % 8) as Bitlen,
+            offset_bits,
+          )
+        };
+        assert_eq!(offset, from_reader);
+      }
+    }
+  }
+}
@@ -0,0 +1,108 @@
+use crate::data_types::{Latent, NumberLike};
+
+// Each page is split into entries, each starting at a nonzero number (or at
+// the start of the page) and covering the run of zeros after it. An entry
+// becomes two latents: its first number and the count of zeros following it.
+// As in run length mode, entries never cross page boundaries, and a run of
+// zeros is split into another entry starting with a zero if its count would
+// not fit in a latent (only possible for 16-bit types).
+
+pub(crate) fn zero_latent<T: NumberLike>() -> T::L {
+  T::default().to_latent_ordered()
+}
+
+fn max_zeros<L: Latent>() -> u64 {
+  L::MAX.to_u64()
+}
+
+// Counts the entries the numbers would be split into if they were all in one
+// page.
+pub(crate) fn count_entries<T: NumberLike>(nums: &[T]) -> usize {
+  let zero = zero_latent::<T>();
+  let max_zeros = max_zeros::<T::L>();
+  let mut n_entries = 0;
+  let mut n_zeros = 0;
+  for (i, &num) in nums.iter().enumerate() {
+    if i > 0 && num.to_latent_ordered() == zero && n_zeros < max_zeros {
+      n_zeros += 1;
+    } else {
+      n_entries += 1;
+      n_zeros = 0;
+    }
+  }
+  n_entries
+}
+
+// Returns the latents and the count of entries in each page.
+pub(crate) fn split_latents<T: NumberLike>(
+  nums: &[T],
+  n_per_page: &[usize],
+) -> (Vec<Vec<T::L>>, Vec<usize>) {
+  let zero = zero_latent::<T>();
+  let max_zeros = max_zeros::<T::L>();
+  let mut values = Vec::new();
+  let mut zero_counts = Vec::new();
+  let mut n_entries_per_page = Vec::with_capacity(n_per_page.len());
+  let mut start = 0;
+  for &page_n in n_per_page {
+    let page_start_entry = values.len();
+    let mut n_zeros = 0;
+    for (i, &num) in nums[start..start + page_n].iter().enumerate() {
+      let latent = num.to_latent_ordered();
+      if i > 0 && latent == zero && n_zeros < max_zeros {
+        n_zeros += 1;
+      } else {
+        if i > 0 {
+          zero_counts.push(T::L::from_u64(n_zeros));
+        }
+        values.push(latent);
+        n_zeros = 0;
+      }
+    }
+    if page_n > 0 {
+      zero_counts.push(T::L::from_u64(n_zeros));
+    }
+    n_entries_per_page.push(values.len() - page_start_entry);
+    start += page_n;
+  }
+  (vec![values, zero_counts], n_entries_per_page)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_split_latents() {
+    let nums = vec![0_i32, 0, 5, 0, 0, 0, -3, 7, 0, 0];
+    assert_eq!(count_entries(&nums), 4);
+    let (latents, n_entries_per_page) = split_latents(&nums, &[5, 5]);
+    // the page boundary splits the zeros after 5
+    assert_eq!(n_entries_per_page, vec![2, 3]);
+    let zero = 0_i32.to_latent_ordered();
+    assert_eq!(
+      latents[0],
+      vec![
+        zero,
+        5_i32.to_latent_ordered(),
+        zero,
+        (-3_i32).to_latent_ordered(),
+        7_i32.to_latent_ordered(),
+      ]
+    );
+    assert_eq!(latents[1], vec![1, 2, 0, 0, 2]);
+  }
+
+  #[test]
+  fn test_split_latents_all_zeros() {
+    let nums = vec![0_u16; 70000];
+    assert_eq!(count_entries(&nums), 2);
+    let (latents, n_entries_per_page) = split_latents(&nums, &[70000]);
+    assert_eq!(n_entries_per_page, vec![2]);
+    assert_eq!(latents[0], vec![0, 0]);
+    assert_eq!(
+      latents[1],
+      vec![u16::MAX, (70000 - 65536 - 1) as u16]
+    );
+  }
+}
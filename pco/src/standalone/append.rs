@@ -0,0 +1,228 @@
+use crate::constants::COMPATIBLE_FORMAT_VERSION;
+use crate::data_types::{Latent, NumberLike};
+use crate::errors::{PcoError, PcoResult};
+use crate::format_version::FormatVersion;
+use crate::standalone::checksum;
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::{ChunkMeta, FULL_BATCH_N};
+
+// What append_page needs from the existing file: its format version, whether
+// it has a checksum, its last chunk's metadata, and where its footer starts.
+struct Tail<L: Latent> {
+  format_version: u8,
+  checksum: bool,
+  last_meta: Option<ChunkMeta<L>>,
+  footer_start: usize,
+}
+
+fn parse_tail<T: NumberLike>(file: &[u8]) -> PcoResult<Tail<T::L>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+  if file_decompressor.format_version() < COMPATIBLE_FORMAT_VERSION {
+    return Err(PcoError::invalid_argument(format!(
+      "can only append to files of format version {} or newer, not {}",
+      COMPATIBLE_FORMAT_VERSION,
+      file_decompressor.format_version(),
+    )));
+  }
+
+  let mut last_meta = None;
+  let mut batch = vec![T::default(); FULL_BATCH_N];
+  loop {
+    let footer_start = file.len() - src.len();
+    match file_decompressor.chunk_decompressor::<T, _>(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        // pages are not length-prefixed, so we decode them to find their end
+        while !chunk_decompressor.decompress(&mut batch)?.finished {}
+        last_meta = Some(chunk_decompressor.meta().clone());
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        let after_trailer = verify_checksum_if_present(file, rest)?;
+        if !after_trailer.is_empty() {
+          return Err(PcoError::corruption(format!(
+            "found {} unexpected bytes after the footer",
+            after_trailer.len(),
+          )));
+        }
+        return Ok(Tail {
+          format_version: file_decompressor.format_version(),
+          checksum: after_trailer.len() < rest.len(),
+          last_meta,
+          footer_start,
+        });
+      }
+    }
+  }
+}
+
+/// Appends a page of numbers to a complete standalone file, reusing the last
+/// chunk's metadata so earlier chunks are never rewritten.
+///
+/// Since each standalone chunk has exactly one page, the page is written as
+/// a new chunk whose metadata is a copy of the last chunk's: the same mode,
+/// delta encoding orders, and bins, with no re-optimization.
+/// Only the footer (and checksum, if the file has one) is rewritten.
+/// This makes it cheap to keep adding numbers in a live-ingest scenario, as
+/// long as they resemble the last chunk's.
+/// The existing chunks are still decoded once to find where the footer
+/// starts and check their data type.
+///
+/// Will return an InvalidArgument error if `nums` is empty, if the file has
+/// no chunks or was written by a format version older than pco 0.3's, or if
+/// any number falls outside the coverage of the last chunk's bins (or dict),
+/// in which case `file` is left unchanged.
+/// Will otherwise return an error if the file has any compatibility,
+/// corruption, or insufficient data issues, or a mismatched checksum.
+pub fn append_page<T: NumberLike>(file: &mut Vec<u8>, nums: &[T]) -> PcoResult<()> {
+  let tail = parse_tail::<T>(file)?;
+  let meta = tail
+    .last_meta
+    .ok_or_else(|| PcoError::invalid_argument("cannot append a page to a file with no chunks"))?;
+
+  let file_compressor = FileCompressor::default()
+    .with_format_version(FormatVersion(tail.format_version))
+    .with_checksum(tail.checksum);
+  let chunk = file_compressor
+    .chunk_compressor_w_meta(nums, &meta)?
+    .write_chunk(Vec::new())?;
+
+  file.truncate(tail.footer_start);
+  file.extend(chunk);
+  let crc = tail.checksum.then(|| checksum::crc32c(file));
+  file_compressor.write_footer_with_crc(crc, file)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::{simple_compress, simple_decompress, StreamCompressor};
+  use crate::{ChunkConfig, DictSpec, IntMultSpec, Mode, ModeKind};
+
+  use super::*;
+
+  fn chunk_metas<T: NumberLike>(file: &[u8]) -> PcoResult<Vec<ChunkMeta<T::L>>> {
+    let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+    let mut metas = Vec::new();
+    while let MaybeChunkDecompressor::Some(mut chunk_decompressor) =
+      file_decompressor.chunk_decompressor::<T, _>(src)?
+    {
+      metas.push(chunk_decompressor.meta().clone());
+      chunk_decompressor.decompress_remaining_extend(&mut Vec::new())?;
+      src = chunk_decompressor.into_src();
+    }
+    Ok(metas)
+  }
+
+  #[test]
+  fn test_append_page() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| 7 * i + (i % 3)).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_int_mult_spec(IntMultSpec::Provided(7));
+    let mut file = simple_compress(&nums, &config)?;
+    let original = file.clone();
+
+    let appended = (1000..1300).map(|i| 7 * i + (i % 3)).collect::<Vec<i64>>();
+    append_page(&mut file, &appended)?;
+    append_page(&mut file, &appended[..1])?;
+    assert_eq!(
+      &file[..original.len() - 1],
+      &original[..original.len() - 1]
+    );
+    assert_eq!(
+      simple_decompress::<i64>(&file)?,
+      [nums, appended.clone(), appended[..1].to_vec()].concat()
+    );
+
+    let metas = chunk_metas::<i64>(&file)?;
+    assert_eq!(metas.len(), 3);
+    assert!(matches!(metas[0].mode, Mode::IntMult(7)));
+    assert_eq!(metas[1], metas[0]);
+    assert_eq!(metas[2], metas[0]);
+
+    let floats = (0..1000).map(|i| i as f64 * 0.1).collect::<Vec<_>>();
+    let mut file = simple_compress(&floats, &ChunkConfig::default())?;
+    let appended = (2000..2100).map(|i| i as f64 * 0.1).collect::<Vec<_>>();
+    append_page(&mut file, &appended)?;
+    let metas = chunk_metas::<f64>(&file)?;
+    assert!(matches!(metas[1].mode, Mode::FloatMult(_)));
+    assert_eq!(
+      simple_decompress::<f64>(&file)?,
+      [floats, appended].concat()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_append_page_w_checksum_and_dict() -> PcoResult<()> {
+    let nums = (0..1000)
+      .map(|i| [10, 200, 3000][i % 3])
+      .collect::<Vec<u32>>();
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(0))
+      .with_dict_spec(DictSpec::Enabled)
+      .with_force_mode(Some(ModeKind::Dict));
+    let mut compressor = StreamCompressor::new(
+      FileCompressor::default().with_checksum(true),
+      config,
+      Vec::new(),
+    )?;
+    compressor.write_chunk(&nums)?;
+    let mut file = compressor.finish()?;
+
+    append_page(&mut file, &[3000_u32, 10, 10])?;
+    let (file_decompressor, _) = FileDecompressor::new(file.as_slice())?;
+    file_decompressor.verify_checksum(&file)?;
+    assert_eq!(
+      simple_decompress::<u32>(&file)?,
+      [nums, vec![3000, 10, 10]].concat()
+    );
+
+    // numbers outside the dict can't be appended
+    let before = file.clone();
+    let err = append_page(&mut file, &[11_u32]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    assert_eq!(file, before);
+    Ok(())
+  }
+
+  #[test]
+  fn test_append_page_errors() -> PcoResult<()> {
+    let config = ChunkConfig::default()
+      .with_int_mult_spec(IntMultSpec::Disabled)
+      .with_delta_encoding_order(Some(0));
+    let nums = (0..1000).collect::<Vec<u32>>();
+    let mut file = simple_compress(&nums, &config)?;
+    let before = file.clone();
+
+    // outside the bins' coverage
+    let err = append_page(&mut file, &[1_000_000_u32]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    assert_eq!(file, before);
+
+    let err = append_page::<u32>(&mut file, &[]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    // wrong data type
+    let err = append_page(&mut file, &[1_i32]).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+
+    let mut empty = simple_compress::<u32>(&[], &config)?;
+    let err = append_page(&mut empty, &[1_u32]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+}
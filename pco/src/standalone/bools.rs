@@ -0,0 +1,168 @@
+use crate::chunk_config::ChunkConfig;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::{PagingSpec, RunLengthSpec, SparseSpec};
+
+// A bools file is an ordinary standalone file of u16s. The bools are packed
+// 16 to a word, least significant bit first, and the words are split into
+// chunks as usual. A final chunk holds a single u16: the count of bools in
+// the last word, from 1 to 16. An empty input has no chunks at all.
+// Runs of identical bools become runs of identical words, which run length
+// or sparse mode pick up, so all-true or all-false bools cost next to
+// nothing. Since those modes need format version 3, so do bools files.
+const BOOLS_PER_WORD: usize = u16::BITS as usize;
+
+/// Takes in a slice of bools and returns compressed bytes.
+///
+/// The bools are packed as bits before compression, so even without long
+/// runs, each bool costs at most about 1 bit, and long runs of `true` or
+/// `false` cost close to nothing.
+/// Use [`decompress_bools`] to read the result.
+///
+/// Will only return an error in the unlikely event of an internal issue.
+pub fn compress_bools(bools: &[bool]) -> PcoResult<Vec<u8>> {
+  let words = bools
+    .chunks(BOOLS_PER_WORD)
+    .map(|word_bools| {
+      word_bools.iter().enumerate().fold(0_u16, |word, (i, &b)| {
+        word | ((b as u16) << i)
+      })
+    })
+    .collect::<Vec<_>>();
+
+  // deltas of bit patterns are meaningless
+  let mut config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_run_length_spec(RunLengthSpec::Enabled)
+    .with_sparse_spec(SparseSpec::Enabled);
+  let file_compressor = FileCompressor::default()
+    .with_n_hint(words.len())
+    .with_format_version_for(&config);
+  let mut dst = file_compressor.write_header(Vec::new())?;
+  if words.is_empty() {
+    return file_compressor.write_footer(dst);
+  }
+
+  let n_per_chunk = config.paging_spec.n_per_page::<u16>(words.len())?;
+  let mut start = 0;
+  for &chunk_n in &n_per_chunk {
+    let end = start + chunk_n;
+    config.paging_spec = PagingSpec::Exact(vec![chunk_n]);
+    dst = file_compressor
+      .chunk_compressor(&words[start..end], &config)?
+      .write_chunk(dst)?;
+    start = end;
+  }
+
+  let last_word_n = (bools.len() - 1) % BOOLS_PER_WORD + 1;
+  config.paging_spec = PagingSpec::Exact(vec![1]);
+  dst = file_compressor
+    .chunk_compressor(&[last_word_n as u16], &config)?
+    .write_chunk(dst)?;
+  file_compressor.write_footer(dst)
+}
+
+/// Takes in compressed bytes written by [`compress_bools`] and returns a
+/// vector of bools.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match.
+pub fn decompress_bools(file: &[u8]) -> PcoResult<Vec<bool>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  // we don't know which chunk is the last until reaching the footer, so
+  // each chunk's words are held back until the next one is found
+  let mut words = Vec::with_capacity(file_decompressor.n_hint());
+  let mut last_chunk = Vec::new();
+  loop {
+    match file_decompressor.chunk_decompressor::<u16, _>(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        words.append(&mut last_chunk);
+        chunk_decompressor.decompress_remaining_extend(&mut last_chunk)?;
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        break;
+      }
+    }
+  }
+
+  let last_word_n = match last_chunk.as_slice() {
+    [] if words.is_empty() => return Ok(Vec::new()),
+    &[n] if (1..=BOOLS_PER_WORD).contains(&(n as usize)) && !words.is_empty() => n as usize,
+    _ => {
+      return Err(PcoError::corruption(
+        "bools file must end with a chunk holding the count of bools in the last word",
+      ))
+    }
+  };
+
+  let mut res = Vec::with_capacity(words.len() * BOOLS_PER_WORD);
+  for &word in &words {
+    res.extend((0..BOOLS_PER_WORD).map(|i| (word >> i) & 1 == 1));
+  }
+  res.truncate(res.len() - (BOOLS_PER_WORD - last_word_n));
+  Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::simple_compress;
+
+  use super::*;
+
+  #[test]
+  fn test_bools_round_trip() -> PcoResult<()> {
+    for n in [0, 1, 15, 16, 17, 1000] {
+      let bools = (0..n).map(|i| (i * 7919) % 3 == 0).collect::<Vec<_>>();
+      let compressed = compress_bools(&bools)?;
+      assert_eq!(
+        decompress_bools(&compressed)?,
+        bools,
+        "n={}",
+        n
+      );
+    }
+
+    // long runs of each
+    let bools = (0..100_000)
+      .map(|i| (i / 3000) % 2 == 0)
+      .collect::<Vec<_>>();
+    let compressed = compress_bools(&bools)?;
+    assert_eq!(decompress_bools(&compressed)?, bools);
+    assert!(compressed.len() < 300);
+    Ok(())
+  }
+
+  #[test]
+  fn test_bools_constant() -> PcoResult<()> {
+    for b in [false, true] {
+      let bools = vec![b; 1_000_001];
+      let compressed = compress_bools(&bools)?;
+      assert_eq!(decompress_bools(&compressed)?, bools);
+      assert!(
+        compressed.len() < 100,
+        "{}",
+        compressed.len()
+      );
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_bools_corrupt() -> PcoResult<()> {
+    // an ordinary file of u16s whose last chunk isn't a valid count
+    let compressed = simple_compress(&[3_u16, 1, 0], &ChunkConfig::default())?;
+    let err = decompress_bools(&compressed).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    let compressed = simple_compress(&[17_u16], &ChunkConfig::default())?;
+    let err = decompress_bools(&compressed).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+}
@@ -0,0 +1,109 @@
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::constants::{CHECKSUM_BYTES, CHECKSUM_MAGIC, CHECKSUM_TRAILER_BYTES};
+
+// CRC32C (Castagnoli), reflected, as used by iSCSI and many storage systems
+const POLYNOMIAL: u32 = 0x82f63b78;
+
+const fn build_table() -> [u32; 256] {
+  let mut table = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut j = 0;
+    while j < 8 {
+      crc = if crc & 1 == 1 {
+        (crc >> 1) ^ POLYNOMIAL
+      } else {
+        crc >> 1
+      };
+      j += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+}
+
+const TABLE: [u32; 256] = build_table();
+
+// for computing a checksum over bytes that arrive incrementally
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32c(u32);
+
+impl Default for Crc32c {
+  fn default() -> Self {
+    Self(!0)
+  }
+}
+
+impl Crc32c {
+  pub fn update(&mut self, bytes: &[u8]) {
+    let mut crc = self.0;
+    for &byte in bytes {
+      crc = TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    self.0 = crc;
+  }
+
+  pub fn finish(self) -> u32 {
+    !self.0
+  }
+}
+
+pub fn crc32c(bytes: &[u8]) -> u32 {
+  let mut crc = Crc32c::default();
+  crc.update(bytes);
+  crc.finish()
+}
+
+// Returns the CRC from the checksum trailer at the start of `after_footer`,
+// the bytes after a file's termination byte, if it has one.
+pub fn parse_trailer(after_footer: &[u8]) -> PcoResult<Option<u32>> {
+  if !after_footer.starts_with(&CHECKSUM_MAGIC) {
+    return Ok(None);
+  }
+  if after_footer.len() < CHECKSUM_TRAILER_BYTES {
+    return Err(PcoError::insufficient_data(format!(
+      "checksum trailer is cut off after {} of its {} bytes",
+      after_footer.len(),
+      CHECKSUM_TRAILER_BYTES,
+    )));
+  }
+
+  let mut crc_bytes = [0; CHECKSUM_BYTES];
+  crc_bytes.copy_from_slice(&after_footer[CHECKSUM_MAGIC.len()..CHECKSUM_TRAILER_BYTES]);
+  Ok(Some(u32::from_le_bytes(crc_bytes)))
+}
+
+// `contents` is every byte of the file before its termination byte
+pub fn verify(contents: &[u8], expected: u32) -> PcoResult<()> {
+  let actual = crc32c(contents);
+  if actual != expected {
+    return Err(PcoError::checksum_mismatch(format!(
+      "file checksum {:#010x} does not match trailer checksum {:#010x}",
+      actual, expected,
+    )));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_crc32c() {
+    assert_eq!(crc32c(&[]), 0);
+    assert_eq!(crc32c(b"123456789"), 0xe3069283);
+    assert_eq!(crc32c(&[0; 32]), 0x8a9136aa);
+  }
+
+  #[test]
+  fn test_crc32c_incremental() {
+    let mut crc = Crc32c::default();
+    crc.update(b"1234");
+    crc.update(b"");
+    crc.update(b"56789");
+    assert_eq!(crc.finish(), crc32c(b"123456789"));
+  }
+}
@@ -0,0 +1,309 @@
+use crate::chunk_config::ChunkConfig;
+use crate::data_types::NumberLike;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::constants::{
+  COLUMN_INDEX_ENTRY_BYTES, COLUMN_INDEX_MAGIC, RECORD_INDEX_MAGIC,
+};
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+
+// The column index goes after the standalone footer, where decompressors
+// ignore it, so a file of columns is still an ordinary .pco file.
+// It consists of
+// * for each column, its count and the byte offset of its chunk, as u64s
+// * the number of columns, as a u64
+// * the column index magic
+// all little-endian.
+// Files written by compress_records also have a schema of each column's
+// data type byte just before the index, and end with a different magic.
+const TRAILER_BYTES: usize = 8 + COLUMN_INDEX_MAGIC.len();
+
+/// Compresses each column independently into its own chunk of a single .pco
+/// file, followed by an index of each column's count and byte offset.
+///
+/// All columns share `config` (except for its `PagingSpec`, since each
+/// column becomes exactly one chunk) and a single file header.
+/// Use [`decompress_column`] to decompress any one column without touching
+/// the others.
+/// The result is still a valid .pco file, so
+/// [`simple_decompress`][crate::standalone::simple_decompress] returns all
+/// the columns concatenated, and
+/// [`validate`][crate::standalone::validate] accepts the index in place of
+/// the usual end of the file.
+/// Empty columns are recorded in the index but get no chunk.
+///
+/// Will return an InvalidArgument error if the config is invalid or if any
+/// column is too long to fit in a single chunk.
+pub fn compress_columns<T: NumberLike>(
+  columns: &[&[T]],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  config.validate()?;
+  let total_n = columns.iter().map(|column| column.len()).sum();
+  let file_compressor = FileCompressor::default()
+    .with_n_hint(total_n)
+    .with_format_version_for(config);
+  let mut dst = file_compressor.write_header(Vec::new())?;
+
+  let mut index = Vec::with_capacity(columns.len());
+  for column in columns {
+    dst = write_column(
+      &file_compressor,
+      column,
+      config,
+      &mut index,
+      dst,
+    )?;
+  }
+  dst = file_compressor.write_footer(dst)?;
+  Ok(write_index(&index, COLUMN_INDEX_MAGIC, dst))
+}
+
+// Compresses the column into a chunk (or nothing, if it's empty), recording
+// its count and byte offset in the index.
+pub(crate) fn write_column<T: NumberLike>(
+  file_compressor: &FileCompressor,
+  column: &[T],
+  config: &ChunkConfig,
+  index: &mut Vec<(usize, usize)>,
+  mut dst: Vec<u8>,
+) -> PcoResult<Vec<u8>> {
+  index.push((column.len(), dst.len()));
+  if column.is_empty() {
+    return Ok(dst);
+  }
+
+  let chunk_compressor = file_compressor.chunk_compressor(column, config)?;
+  dst.reserve(chunk_compressor.chunk_size_hint());
+  chunk_compressor.write_chunk(dst)
+}
+
+pub(crate) fn write_index(index: &[(usize, usize)], magic: [u8; 4], mut dst: Vec<u8>) -> Vec<u8> {
+  dst.reserve(index.len() * COLUMN_INDEX_ENTRY_BYTES + TRAILER_BYTES);
+  for &(n, offset) in index {
+    dst.extend((n as u64).to_le_bytes());
+    dst.extend((offset as u64).to_le_bytes());
+  }
+  dst.extend((index.len() as u64).to_le_bytes());
+  dst.extend(magic);
+  dst
+}
+
+fn read_u64(bytes: &[u8], idx: usize) -> u64 {
+  u64::from_le_bytes(bytes[idx..idx + 8].try_into().unwrap())
+}
+
+pub(crate) struct ColumnIndex<'a> {
+  // the count and byte offset of each column
+  pub entries: Vec<(usize, usize)>,
+  // where the index begins
+  pub start: usize,
+  // each column's data type byte, if written by compress_records
+  pub schema: Option<&'a [u8]>,
+}
+
+pub(crate) fn parse_column_index(file: &[u8]) -> PcoResult<ColumnIndex<'_>> {
+  if file.len() < TRAILER_BYTES {
+    return Err(PcoError::insufficient_data(format!(
+      "file of {} bytes is too short to contain a column index",
+      file.len(),
+    )));
+  }
+  let trailer_start = file.len() - TRAILER_BYTES;
+  let magic = &file[trailer_start + 8..];
+  let has_schema = magic == RECORD_INDEX_MAGIC;
+  if !has_schema && magic != COLUMN_INDEX_MAGIC {
+    return Err(PcoError::corruption(
+      "file does not end with a column index",
+    ));
+  }
+
+  let n_columns = read_u64(file, trailer_start);
+  let schema_bytes = if has_schema { 1 } else { 0 };
+  let schema_start = usize::try_from(n_columns)
+    .ok()
+    .and_then(|n_columns| n_columns.checked_mul(COLUMN_INDEX_ENTRY_BYTES + schema_bytes))
+    .and_then(|section_bytes| trailer_start.checked_sub(section_bytes))
+    .ok_or_else(|| {
+      PcoError::corruption(format!(
+        "column index of {} columns does not fit in file of {} bytes",
+        n_columns,
+        file.len(),
+      ))
+    })?;
+  let start = schema_start + n_columns as usize * schema_bytes;
+
+  let entries = file[start..trailer_start]
+    .chunks_exact(COLUMN_INDEX_ENTRY_BYTES)
+    .map(|entry| {
+      (
+        read_u64(entry, 0) as usize,
+        read_u64(entry, 8) as usize,
+      )
+    })
+    .collect();
+  Ok(ColumnIndex {
+    entries,
+    start,
+    schema: has_schema.then(|| &file[schema_start..start]),
+  })
+}
+
+// Returns whether `rest`, the bytes after a file's footer, consist of exactly
+// a column index whose counts add up to the file's `n`.
+pub(crate) fn is_trailing_column_index(rest: &[u8], n: usize) -> bool {
+  let Ok(index) = parse_column_index(rest) else {
+    return false;
+  };
+  let schema_len = index.schema.map_or(0, <[u8]>::len);
+  let index_n = index.entries.iter().try_fold(0_usize, |acc, &(col_n, _)| {
+    acc.checked_add(col_n)
+  });
+  index.start == schema_len && index_n == Some(n)
+}
+
+/// Returns the count of numbers in each column of a file written by
+/// [`compress_columns`] or
+/// [`compress_records`][crate::standalone::compress_records].
+///
+/// This only reads the column index at the end of the file.
+/// Will return an error if the file does not end with a valid column index.
+pub fn column_counts(file: &[u8]) -> PcoResult<Vec<usize>> {
+  let index = parse_column_index(file)?;
+  Ok(index.entries.into_iter().map(|(n, _)| n).collect())
+}
+
+/// Decompresses only the column at `col_idx` of a file written by
+/// [`compress_columns`] or
+/// [`compress_records`][crate::standalone::compress_records].
+///
+/// This reads the file header and column index, then jumps straight to the
+/// column's chunk, so its cost does not depend on the other columns.
+///
+/// Will return an InvalidArgument error if `col_idx` is out of range, and
+/// otherwise an error if there are any compatibility, corruption, or
+/// insufficient data issues, including a missing column index.
+pub fn decompress_column<T: NumberLike>(file: &[u8], col_idx: usize) -> PcoResult<Vec<T>> {
+  let index = parse_column_index(file)?;
+  read_column(file, &index, col_idx)
+}
+
+pub(crate) fn read_column<T: NumberLike>(
+  file: &[u8],
+  index: &ColumnIndex,
+  col_idx: usize,
+) -> PcoResult<Vec<T>> {
+  let Some(&(n, offset)) = index.entries.get(col_idx) else {
+    return Err(PcoError::invalid_argument(format!(
+      "column index {} is out of range for file with {} columns",
+      col_idx,
+      index.entries.len(),
+    )));
+  };
+  if n == 0 {
+    return Ok(Vec::new());
+  }
+
+  let (file_decompressor, _) = FileDecompressor::new(file)?;
+  if offset >= index.start {
+    return Err(PcoError::corruption(format!(
+      "column {} has out-of-range byte offset {}",
+      col_idx, offset,
+    )));
+  }
+  match file_decompressor.chunk_decompressor(&file[offset..index.start])? {
+    MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+      if chunk_decompressor.n() != n {
+        return Err(PcoError::corruption(format!(
+          "column {} should have {} numbers but its chunk has {}",
+          col_idx,
+          n,
+          chunk_decompressor.n(),
+        )));
+      }
+      let mut res = Vec::with_capacity(n);
+      chunk_decompressor.decompress_remaining_extend(&mut res)?;
+      Ok(res)
+    }
+    MaybeChunkDecompressor::EndOfData(_) => Err(PcoError::corruption(format!(
+      "column {} should have {} numbers but points past the last chunk",
+      col_idx, n,
+    ))),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::{simple_decompress, validate};
+
+  use super::*;
+
+  #[test]
+  fn test_compress_columns() -> PcoResult<()> {
+    let ints = (0..1000).collect::<Vec<i64>>();
+    let steps = (0..3000).map(|i| (i / 7) * 1000).collect::<Vec<i64>>();
+    let empty: [i64; 0] = [];
+    let single = [-5_i64];
+    let columns = [&ints[..], &empty[..], &steps[..], &single[..]];
+    let file = compress_columns(&columns, &ChunkConfig::default())?;
+
+    assert_eq!(column_counts(&file)?, vec![1000, 0, 3000, 1]);
+    for (col_idx, column) in columns.iter().enumerate() {
+      assert_eq!(
+        decompress_column::<i64>(&file, col_idx)?,
+        *column
+      );
+    }
+    assert_eq!(
+      simple_decompress::<i64>(&file)?,
+      columns.concat()
+    );
+    assert_eq!(
+      validate::<i64>(&file)?.n_per_chunk,
+      vec![1000, 3000, 1]
+    );
+
+    let err = decompress_column::<i64>(&file, 4).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_column_errors() -> PcoResult<()> {
+    let nums = [1_u32, 2, 3];
+    let file = compress_columns(&[&nums[..]], &ChunkConfig::default())?;
+    let err = decompress_column::<u32>(&file[..file.len() - 1], 0).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    let err = decompress_column::<u32>(&file[..3], 0).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+
+    // an ordinary .pco file has no column index
+    let plain = crate::standalone::simpler_compress(&nums, 0)?;
+    let err = decompress_column::<u32>(&plain, 0).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+
+    // an index claiming more columns than fit
+    let mut too_many = file.clone();
+    let trailer_start = too_many.len() - TRAILER_BYTES;
+    too_many[trailer_start..trailer_start + 8].copy_from_slice(&1000_u64.to_le_bytes());
+    let err = decompress_column::<u32>(&too_many, 0).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    let err = validate::<u32>(&too_many).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+
+    // an index whose counts don't match the chunks
+    let mut wrong_count = file.clone();
+    let entry_start = wrong_count.len() - TRAILER_BYTES - COLUMN_INDEX_ENTRY_BYTES;
+    wrong_count[entry_start..entry_start + 8].copy_from_slice(&4_u64.to_le_bytes());
+    let err = validate::<u32>(&wrong_count).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+}
@@ -1,10 +1,17 @@
 use std::io::Write;
+#[cfg(feature = "bench")]
+use std::time::Instant;
 
 use crate::bit_writer::BitWriter;
 use crate::chunk_config::PagingSpec;
+use crate::compression_report::CompressionReport;
 use crate::data_types::{Latent, NumberLike};
-use crate::errors::PcoResult;
+use crate::errors::{PcoError, PcoResult};
+use crate::format_version::FormatVersion;
+use crate::standalone::checksum;
 use crate::standalone::constants::*;
+#[cfg(feature = "bench")]
+use crate::timings::Timings;
 use crate::{bits, wrapped, ChunkConfig, ChunkMeta};
 
 unsafe fn write_varint<W: Write>(n: u64, writer: &mut BitWriter<W>) {
@@ -44,6 +51,7 @@ unsafe fn write_varint<W: Write>(n: u64, writer: &mut BitWriter<W>) {
 pub struct FileCompressor {
   inner: wrapped::FileCompressor,
   n_hint: usize,
+  checksum: bool,
 }
 
 impl FileCompressor {
@@ -52,6 +60,41 @@ impl FileCompressor {
     self
   }
 
+  /// Sets whether to write a CRC32C checksum of the whole file in a trailer
+  /// after its footer.
+  ///
+  /// Defaults to false.
+  /// The trailer comes right after the termination byte, where older
+  /// versions of pco stop reading, so they can still decompress files with
+  /// checksums; they just don't verify them.
+  /// When enabled, the footer must be written with
+  /// [`write_checksummed_footer`][Self::write_checksummed_footer].
+  pub fn with_checksum(mut self, checksum: bool) -> Self {
+    self.checksum = checksum;
+    self
+  }
+
+  /// Raises the file's format version, if necessary, so that it can hold
+  /// chunks compressed with the given config.
+  ///
+  /// By default, files get a format version that older versions of pco can
+  /// also decompress.
+  /// Call this for each distinct config you plan to use, before writing the
+  /// header.
+  pub fn with_format_version_for(mut self, config: &ChunkConfig) -> Self {
+    self.inner = self.inner.with_format_version_for(config);
+    self
+  }
+
+  pub(crate) fn with_format_version(mut self, format_version: FormatVersion) -> Self {
+    self.inner = self.inner.with_format_version(format_version);
+    self
+  }
+
+  pub(crate) fn has_checksum(&self) -> bool {
+    self.checksum
+  }
+
   /// Writes a short header to the destination.
   ///
   /// Will return an error if the provided `Write` errors.
@@ -74,7 +117,8 @@ impl FileCompressor {
   /// Creates a `ChunkCompressor` that can be used to write entire chunks
   /// at a time.
   ///
-  /// Will return an error if any arguments provided are invalid.
+  /// Will return an error if any arguments provided are invalid, including
+  /// configs that need a newer format version than this file's.
   ///
   /// Although this doesn't write anything yet, it does the bulk of
   /// compute necessary for the compression.
@@ -92,12 +136,58 @@ impl FileCompressor {
     })
   }
 
+  // Like chunk_compressor, but reuses the given chunk metadata exactly
+  // instead of choosing a mode and training bins.
+  pub(crate) fn chunk_compressor_w_meta<T: NumberLike>(
+    &self,
+    nums: &[T],
+    meta: &ChunkMeta<T::L>,
+  ) -> PcoResult<ChunkCompressor<T::L>> {
+    Ok(ChunkCompressor {
+      inner: wrapped::chunk_compressor::new_w_meta(nums, meta)?
+        .with_format_version(self.inner.format_version())?,
+      dtype_byte: T::DTYPE_BYTE,
+    })
+  }
+
   /// Writes a short footer to the destination.
   ///
-  /// Will return an error if the provided `Write` errors.
+  /// Will return an error if the provided `Write` errors, or an
+  /// InvalidArgument error if checksums are enabled.
   pub fn write_footer<W: Write>(&self, dst: W) -> PcoResult<W> {
-    let mut writer = BitWriter::new(dst, 1);
+    if self.checksum {
+      return Err(PcoError::invalid_argument(
+        "footers with checksums must be written with write_checksummed_footer",
+      ));
+    }
+
+    self.write_footer_with_crc(None, dst)
+  }
+
+  /// Writes a short footer to the destination, followed by a trailer holding
+  /// a checksum of `compressed`.
+  ///
+  /// `compressed` must be all the bytes of the file written so far: the header
+  /// followed by every chunk.
+  /// Will return an error if the provided `Write` errors, or an
+  /// InvalidArgument error if checksums are not enabled.
+  pub fn write_checksummed_footer<W: Write>(&self, compressed: &[u8], dst: W) -> PcoResult<W> {
+    if !self.checksum {
+      return Err(PcoError::invalid_argument(
+        "checksummed footers require the file compressor to have checksums enabled",
+      ));
+    }
+
+    self.write_footer_with_crc(Some(checksum::crc32c(compressed)), dst)
+  }
+
+  pub(crate) fn write_footer_with_crc<W: Write>(&self, crc: Option<u32>, dst: W) -> PcoResult<W> {
+    let mut writer = BitWriter::new(dst, 1 + CHECKSUM_TRAILER_BYTES);
     writer.write_aligned_bytes(&[MAGIC_TERMINATION_BYTE])?;
+    if let Some(crc) = crc {
+      writer.write_aligned_bytes(&CHECKSUM_MAGIC)?;
+      writer.write_aligned_bytes(&crc.to_le_bytes())?;
+    }
     writer.flush()?;
     Ok(writer.into_inner())
   }
@@ -130,6 +220,43 @@ impl<L: Latent> ChunkCompressor<L> {
   ///
   /// Will return an error if the provided `Write` errors.
   pub fn write_chunk<W: Write>(&self, dst: W) -> PcoResult<W> {
+    let dst = self.write_preamble(dst)?;
+    let dst = self.inner.write_chunk_meta(dst)?;
+    self.inner.write_page(0, dst)
+  }
+
+  // Like write_chunk, but tallies the chunk's bits into the report.
+  pub(crate) fn write_chunk_w_report(
+    &self,
+    mut dst: Vec<u8>,
+    report: &mut CompressionReport,
+  ) -> PcoResult<Vec<u8>> {
+    let start = dst.len();
+    dst = self.write_preamble(dst)?;
+    let meta_start = dst.len();
+    report.framing_bits += 8 * (meta_start - start);
+    dst = self.inner.write_chunk_meta(dst)?;
+    report.chunk_meta_bits += 8 * (dst.len() - meta_start);
+    for latent_var in &self.meta().per_latent_var {
+      report.n_latent_vars += 1;
+      if latent_var.is_trivial() {
+        report.n_trivial_latent_vars += 1;
+      }
+    }
+    self.inner.write_page_w_report(0, dst, report)
+  }
+
+  // Like write_chunk, but adds the time spent in each phase into the timings.
+  #[cfg(feature = "bench")]
+  pub(crate) fn write_chunk_timed<W: Write>(&self, dst: W, timings: &mut Timings) -> PcoResult<W> {
+    let start = Instant::now();
+    let dst = self.write_preamble(dst)?;
+    let dst = self.inner.write_chunk_meta(dst)?;
+    timings.other += start.elapsed();
+    self.inner.write_page_timed(0, dst, timings)
+  }
+
+  fn write_preamble<W: Write>(&self, dst: W) -> PcoResult<W> {
     let mut writer = BitWriter::new(dst, STANDALONE_CHUNK_PREAMBLE_PADDING);
     writer.write_aligned_bytes(&[self.dtype_byte])?;
     let n = self.inner.n_per_page()[0];
@@ -138,8 +265,6 @@ impl<L: Latent> ChunkCompressor<L> {
     }
 
     writer.flush()?;
-    let dst = writer.into_inner();
-    let dst = self.inner.write_chunk_meta(dst)?;
-    self.inner.write_page(0, dst)
+    Ok(writer.into_inner())
   }
 }
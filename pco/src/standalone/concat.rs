@@ -0,0 +1,252 @@
+use crate::errors::{PcoError, PcoResult};
+use crate::format_version::FormatVersion;
+use crate::standalone::checksum;
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::constants::MAGIC_TERMINATION_BYTE;
+use crate::standalone::decompressor::FileDecompressor;
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::standalone::validation::skip_chunk;
+
+// What concat_files needs from each part: its header fields, its chunks'
+// data type byte (if it has any chunks), its count of numbers, and the byte
+// range of its chunks.
+struct PartInfo<'a> {
+  format_version: u8,
+  checksum: bool,
+  dtype_byte: Option<u8>,
+  n: usize,
+  chunks: &'a [u8],
+}
+
+fn parse_part(part_idx: usize, part: &[u8]) -> PcoResult<PartInfo<'_>> {
+  let (file_decompressor, chunks_src) = FileDecompressor::new(part)?;
+
+  let mut dtype_byte = None;
+  let mut n = 0;
+  let mut src = chunks_src;
+  while let Some(&byte) = src.first() {
+    let Some((chunk_n, rest)) = skip_chunk(&file_decompressor, src)? else {
+      break;
+    };
+    if dtype_byte.is_some_and(|dtype_byte| dtype_byte != byte) {
+      return Err(PcoError::invalid_argument(format!(
+        "part {} contains chunks of multiple data types",
+        part_idx,
+      )));
+    }
+    dtype_byte = Some(byte);
+    n += chunk_n;
+    src = rest;
+  }
+
+  let Some((&termination_byte, after_footer)) = src.split_first() else {
+    return Err(PcoError::insufficient_data(format!(
+      "part {} ends before its footer",
+      part_idx,
+    )));
+  };
+  if termination_byte != MAGIC_TERMINATION_BYTE {
+    return Err(PcoError::corruption(format!(
+      "part {} does not end with a termination byte",
+      part_idx,
+    )));
+  }
+  let after_trailer = verify_checksum_if_present(part, after_footer)?;
+  if !after_trailer.is_empty() {
+    return Err(PcoError::corruption(format!(
+      "found {} unexpected bytes after the end of part {}",
+      after_trailer.len(),
+      part_idx,
+    )));
+  }
+
+  let chunks_start = part.len() - chunks_src.len();
+  let chunks_end = part.len() - src.len();
+  Ok(PartInfo {
+    format_version: file_decompressor.format_version(),
+    checksum: after_trailer.len() < after_footer.len(),
+    dtype_byte,
+    n,
+    chunks: &part[chunks_start..chunks_end],
+  })
+}
+
+/// Combines complete standalone files into a single file containing all
+/// their chunks, in order, without recompressing them.
+///
+/// Since chunks are self-describing, this only writes a new header, with a
+/// size hint for the total count of numbers, followed by each part's chunks
+/// copied byte-for-byte and a new footer.
+/// Pages are not length-prefixed though, so each part's chunks are still
+/// decoded (and discarded) to find where they end and check their data
+/// types.
+/// The result decompresses to the concatenation of the parts' numbers.
+///
+/// All parts must share the same format version, have the same flags (e.g.
+/// either all or none have checksums), and contain numbers of the same data
+/// type, though parts with no chunks may be mixed with any others.
+/// If the parts have checksums, each one is verified and the result gets a
+/// new checksum.
+/// If `parts` is empty, the result is an empty file.
+///
+/// Will return an InvalidArgument error if the parts are incompatible with
+/// each other, and otherwise an error if any part has compatibility,
+/// corruption, or insufficient data issues, a mismatched checksum, or bytes
+/// after its footer.
+pub fn concat_files(parts: &[&[u8]]) -> PcoResult<Vec<u8>> {
+  let infos = parts
+    .iter()
+    .enumerate()
+    .map(|(part_idx, part)| parse_part(part_idx, part))
+    .collect::<PcoResult<Vec<_>>>()?;
+
+  let mut file_compressor = FileCompressor::default();
+  if let Some(first) = infos.first() {
+    for (part_idx, info) in infos.iter().enumerate().skip(1) {
+      if info.format_version != first.format_version {
+        return Err(PcoError::invalid_argument(format!(
+          "part {} has format version {}, but part 0 has {}",
+          part_idx, info.format_version, first.format_version,
+        )));
+      }
+      if info.checksum != first.checksum {
+        return Err(PcoError::invalid_argument(format!(
+          "part {} has checksum flag {}, but part 0 has {}",
+          part_idx, info.checksum, first.checksum,
+        )));
+      }
+    }
+
+    let mut dtype_bytes = infos
+      .iter()
+      .enumerate()
+      .filter_map(|(part_idx, info)| info.dtype_byte.map(|byte| (part_idx, byte)));
+    if let Some((first_part_idx, first_byte)) = dtype_bytes.next() {
+      if let Some((part_idx, byte)) = dtype_bytes.find(|&(_, byte)| byte != first_byte) {
+        return Err(PcoError::invalid_argument(format!(
+          "part {} has data type byte {}, but part {} has {}",
+          part_idx, byte, first_part_idx, first_byte,
+        )));
+      }
+    }
+
+    file_compressor = file_compressor
+      .with_format_version(FormatVersion(first.format_version))
+      .with_checksum(first.checksum);
+  }
+
+  let n = infos.iter().map(|info| info.n).sum();
+  file_compressor = file_compressor.with_n_hint(n);
+  let chunks_size = infos.iter().map(|info| info.chunks.len()).sum::<usize>();
+  let mut dst = Vec::with_capacity(chunks_size);
+  dst = file_compressor.write_header(dst)?;
+  for info in &infos {
+    dst.extend_from_slice(info.chunks);
+  }
+  if file_compressor.has_checksum() {
+    let crc = checksum::crc32c(&dst);
+    file_compressor.write_footer_with_crc(Some(crc), dst)
+  } else {
+    file_compressor.write_footer(dst)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::{simple_compress, simple_decompress, StreamCompressor};
+  use crate::{ChunkConfig, PagingSpec};
+
+  use super::*;
+
+  #[test]
+  fn test_concat_files() -> PcoResult<()> {
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    let day0 = (0..1000).collect::<Vec<i64>>();
+    let day1 = (0..500).map(|i| i * i).collect::<Vec<i64>>();
+    let part0 = simple_compress(&day0, &config)?;
+    let part1 = simple_compress(&day1, &config)?;
+    let empty = simple_compress::<i64>(&[], &config)?;
+
+    let concatenated = concat_files(&[&part0, &empty, &part1])?;
+    assert_eq!(
+      simple_decompress::<i64>(&concatenated)?,
+      [day0.clone(), day1].concat(),
+    );
+    let (file_decompressor, _) = FileDecompressor::new(concatenated.as_slice())?;
+    assert_eq!(file_decompressor.n_hint(), 1500);
+
+    // a single part comes back unchanged
+    assert_eq!(concat_files(&[&part0])?, part0);
+
+    assert_eq!(
+      simple_decompress::<i64>(&concat_files(&[])?)?,
+      Vec::<i64>::new(),
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_concat_files_with_checksums() -> PcoResult<()> {
+    let mut parts = Vec::new();
+    for offset in [0.0, 100.0] {
+      let nums = (0..200).map(|i| offset + i as f32).collect::<Vec<_>>();
+      let mut compressor = StreamCompressor::new(
+        FileCompressor::default().with_checksum(true),
+        ChunkConfig::default(),
+        Vec::new(),
+      )?;
+      compressor.write_chunk(&nums)?;
+      parts.push(compressor.finish()?);
+    }
+
+    let concatenated = concat_files(&[&parts[0], &parts[1]])?;
+    let (file_decompressor, _) = FileDecompressor::new(concatenated.as_slice())?;
+    file_decompressor.verify_checksum(&concatenated)?;
+    assert_eq!(
+      simple_decompress::<f32>(&concatenated)?.len(),
+      400
+    );
+
+    let mut corrupt = parts[1].clone();
+    let last = corrupt.len() - 1;
+    corrupt[last] ^= 1;
+    let err = concat_files(&[&parts[0], &corrupt]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::ChecksumMismatch
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_concat_files_incompatible() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let ints = simple_compress(&[1_u32, 2, 3], &config)?;
+    let floats = simple_compress(&[1.0_f32, 2.0, 3.0], &config)?;
+    let err = concat_files(&[&ints, &floats]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let mut compressor = StreamCompressor::new(
+      FileCompressor::default().with_checksum(true),
+      config,
+      Vec::new(),
+    )?;
+    compressor.write_chunk(&[4_u32, 5])?;
+    let checksummed = compressor.finish()?;
+    let err = concat_files(&[&ints, &checksummed]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let mut extended = ints.clone();
+    extended.push(7);
+    let err = concat_files(&[&extended]).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+}
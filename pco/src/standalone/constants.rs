@@ -8,6 +8,21 @@ pub const BITS_TO_ENCODE_STANDALONE_VERSION: Bitlen = 8;
 pub const BITS_TO_ENCODE_VARINT_POWER: Bitlen = 6;
 pub const CURRENT_STANDALONE_VERSION: usize = 2;
 
+// checksum trailer, written right after the termination byte, where older
+// decompressors stop reading
+// ascii for pcoc
+pub const CHECKSUM_MAGIC: [u8; 4] = [112, 99, 111, 99];
+pub const CHECKSUM_BYTES: usize = 4;
+pub const CHECKSUM_TRAILER_BYTES: usize = CHECKSUM_MAGIC.len() + CHECKSUM_BYTES;
+
+// column index, written after the footer by compress_columns
+// ascii for pcoi
+pub const COLUMN_INDEX_MAGIC: [u8; 4] = [112, 99, 111, 105];
+pub const COLUMN_INDEX_ENTRY_BYTES: usize = 16;
+// the same, but preceded by a schema, written by compress_records
+// ascii for pcos
+pub const RECORD_INDEX_MAGIC: [u8; 4] = [112, 99, 111, 115];
+
 // padding
 pub const STANDALONE_CHUNK_PREAMBLE_PADDING: usize =
   1 + BITS_TO_ENCODE_N_ENTRIES as usize + OVERSHOOT_PADDING;
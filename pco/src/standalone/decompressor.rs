@@ -4,15 +4,20 @@ use crate::bit_reader::{BitReader, BitReaderBuilder};
 use crate::constants::Bitlen;
 use crate::data_types::NumberLike;
 use crate::errors::{PcoError, PcoResult};
+use crate::latent_batch_decompressor::LatentVarStats;
 use crate::progress::Progress;
 use crate::standalone::constants::*;
-use crate::standalone::DataTypeOrTermination;
+use crate::standalone::{checksum, DataTypeOrTermination};
+use crate::wrapped::PageBuffers;
 use crate::{bit_reader, wrapped, ChunkMeta};
 
 unsafe fn read_varint(reader: &mut BitReader) -> PcoResult<u64> {
   let power = 1 + reader.read_uint::<Bitlen>(BITS_TO_ENCODE_VARINT_POWER);
   let res = reader.read_uint(power);
-  reader.drain_empty_byte("standalone size hint")?;
+  reader.drain_empty_byte(
+    "nonzero bits after standalone size hint",
+    "file",
+  )?;
   Ok(res)
 }
 
@@ -40,6 +45,12 @@ unsafe fn read_varint(reader: &mut BitReader) -> PcoResult<u64> {
 /// # Ok(())
 /// # }
 /// ```
+///
+/// The source can be any `BetterBufRead`, so to stream a large file without
+/// holding all its compressed bytes in memory, wrap an `impl Read` in a
+/// [`BetterBufReader`][better_io::BetterBufReader].
+/// Bytes are then pulled lazily as chunk metadata and pages are decoded, and
+/// an insufficient data error is returned if the `Read` ends partway through.
 #[derive(Clone, Debug)]
 pub struct FileDecompressor {
   n_hint: usize,
@@ -47,6 +58,7 @@ pub struct FileDecompressor {
 }
 
 /// The outcome of starting a new chunk of a standalone file.
+#[allow(clippy::large_enum_variant)]
 pub enum MaybeChunkDecompressor<T: NumberLike, R: BetterBufRead> {
   /// We get a `ChunkDecompressor` when there is another chunk as evidenced
   /// by the data type byte.
@@ -109,6 +121,44 @@ impl FileDecompressor {
     self.n_hint
   }
 
+  /// Verifies the checksum in the trailer of a complete file.
+  ///
+  /// `file` must contain exactly the bytes of the file, from its header
+  /// through its checksum trailer.
+  /// This is done automatically by
+  /// [`simple_decompress`][crate::standalone::simple_decompress] and
+  /// [`simple_decompress_into`][crate::standalone::simple_decompress_into]
+  /// upon reaching the end of the file.
+  ///
+  /// Will return a ChecksumMismatch error if the file's bytes do not match the
+  /// checksum, an InsufficientData error if `file` is too short to contain a
+  /// footer and trailer, or an InvalidArgument error if the file does not end
+  /// with a checksum trailer.
+  pub fn verify_checksum(&self, file: &[u8]) -> PcoResult<()> {
+    let footer_size = 1 + CHECKSUM_TRAILER_BYTES;
+    if file.len() < footer_size {
+      return Err(PcoError::insufficient_data(format!(
+        "file of {} bytes is too short to contain a checksummed footer",
+        file.len(),
+      )));
+    }
+
+    let (contents, footer) = file.split_at(file.len() - footer_size);
+    let Some(expected) = checksum::parse_trailer(&footer[1..])? else {
+      return Err(PcoError::invalid_argument(
+        "unable to verify checksum of a file without a checksum trailer",
+      ));
+    };
+    if footer[0] != MAGIC_TERMINATION_BYTE {
+      return Err(PcoError::corruption(format!(
+        "expected termination byte before checksum trailer; instead found {:?}",
+        footer[0],
+      )));
+    }
+
+    checksum::verify(contents, expected)
+  }
+
   /// Peeks at what's next in the file, returning whether it's a termination
   /// or chunk with some data type.
   ///
@@ -125,11 +175,22 @@ impl FileDecompressor {
   /// Reads a chunk's metadata and returns either a `ChunkDecompressor` or
   /// the rest of the source if at the end of the pco file.
   ///
+  /// The rest of the source begins right after the termination byte, so for a
+  /// file with a checksum, it begins with the checksum trailer.
+  ///
   /// Will return an error if corruptions or insufficient
   /// data are found.
   pub fn chunk_decompressor<T: NumberLike, R: BetterBufRead>(
+    &self,
+    src: R,
+  ) -> PcoResult<MaybeChunkDecompressor<T, R>> {
+    self.chunk_decompressor_reusing(src, PageBuffers::default())
+  }
+
+  pub(crate) fn chunk_decompressor_reusing<T: NumberLike, R: BetterBufRead>(
     &self,
     mut src: R,
+    buffers: PageBuffers<T::L>,
   ) -> PcoResult<MaybeChunkDecompressor<T, R>> {
     bit_reader::ensure_buf_read_capacity(&mut src, STANDALONE_CHUNK_PREAMBLE_PADDING);
     let mut reader_builder = BitReaderBuilder::new(src, STANDALONE_CHUNK_PREAMBLE_PADDING, 0);
@@ -153,7 +214,7 @@ impl FileDecompressor {
       .with_reader(|reader| unsafe { Ok(reader.read_usize(BITS_TO_ENCODE_N_ENTRIES) + 1) })?;
     let src = reader_builder.into_inner();
     let (inner_cd, src) = self.inner.chunk_decompressor::<T, R>(src)?;
-    let inner_pd = inner_cd.page_decompressor(src, n)?;
+    let inner_pd = inner_cd.page_decompressor_reusing(src, n, buffers)?;
 
     let res = ChunkDecompressor {
       inner_cd,
@@ -199,11 +260,52 @@ impl<T: NumberLike, R: BetterBufRead> ChunkDecompressor<T, R> {
     Ok(progress)
   }
 
+  /// Reads all remaining numbers in the chunk into the start of the
+  /// destination, returning the count of numbers written.
+  ///
+  /// This allows decompressing many chunks into the same reusable buffer.
+  /// Will return an invalid argument error if `dst` is shorter than the count
+  /// of numbers remaining in the chunk, or an error if corruptions or
+  /// insufficient data are found.
+  pub fn decompress_remaining_into(&mut self, dst: &mut [T]) -> PcoResult<usize> {
+    let remaining = self.n - self.n_processed;
+    if dst.len() < remaining {
+      return Err(PcoError::invalid_argument(format!(
+        "destination of length {} is too short for the {} remaining numbers in the chunk",
+        dst.len(),
+        remaining,
+      )));
+    }
+
+    let progress = self.decompress(&mut dst[..remaining])?;
+    assert!(progress.finished);
+    Ok(progress.n_processed)
+  }
+
   /// Returns the rest of the compressed data source.
   pub fn into_src(self) -> R {
     self.inner_pd.into_src()
   }
 
+  pub(crate) fn into_src_and_buffers(self) -> (R, PageBuffers<T::L>) {
+    self.inner_pd.into_src_and_buffers()
+  }
+
+  pub(crate) fn enable_stats(&mut self) {
+    self.inner_pd.enable_stats();
+  }
+
+  pub(crate) fn stats(&self) -> Vec<LatentVarStats> {
+    self.inner_pd.stats()
+  }
+
+  // must be called before any numbers are decompressed
+  pub(crate) fn decompress_raw_latents_extend(&mut self, dst: &mut [Vec<T::L>]) -> PcoResult<()> {
+    self.inner_pd.decompress_raw_latents_extend(dst)?;
+    self.n_processed = self.n;
+    Ok(())
+  }
+
   // a helper for some internal things
   pub(crate) fn decompress_remaining_extend(&mut self, dst: &mut Vec<T>) -> PcoResult<()> {
     let initial_len = dst.len();
@@ -212,8 +314,7 @@ impl<T: NumberLike, R: BetterBufRead> ChunkDecompressor<T, R> {
     unsafe {
       dst.set_len(initial_len + remaining);
     }
-    let progress = self.decompress(&mut dst[initial_len..])?;
-    assert!(progress.finished);
+    self.decompress_remaining_into(&mut dst[initial_len..])?;
     Ok(())
   }
 }
@@ -26,7 +26,7 @@ pub fn chunk_size<L: Latent>(n: usize) -> usize {
 ///
 /// Will return an invalid argument error if the paging spec is invalid.
 pub fn file_size<L: Latent>(n: usize, paging_spec: &PagingSpec) -> PcoResult<usize> {
-  let n_per_chunk = paging_spec.n_per_page(n)?;
+  let n_per_chunk = paging_spec.n_per_page::<L>(n)?;
   let res = header_size()
     + n_per_chunk
       .iter()
@@ -38,13 +38,17 @@ pub fn file_size<L: Latent>(n: usize, paging_spec: &PagingSpec) -> PcoResult<usi
 
 #[cfg(test)]
 mod tests {
+  use std::mem;
+
   use rand::Rng;
   use rand_xoshiro::rand_core::SeedableRng;
   use rand_xoshiro::Xoroshiro128PlusPlus;
 
   use crate::data_types::NumberLike;
   use crate::errors::PcoResult;
-  use crate::standalone::{simple_compress, FileCompressor};
+  use crate::standalone::{
+    simple_compress, FileCompressor, FileDecompressor, MaybeChunkDecompressor,
+  };
   use crate::{ChunkConfig, FloatMultSpec, PagingSpec};
 
   use super::*;
@@ -85,4 +89,36 @@ mod tests {
     };
     check_file_guarantee(&nums, &config)
   }
+
+  #[test]
+  fn test_random_data_is_stored_raw() -> PcoResult<()> {
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    let nums = (0..100_000).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+    let raw_size = nums.len() * mem::size_of::<u64>();
+    for delta_encoding_order in [None, Some(0), Some(3)] {
+      let config = ChunkConfig::default()
+        .with_delta_encoding_order(delta_encoding_order)
+        .with_paging_spec(PagingSpec::EqualPagesUpTo(10_000));
+      let compressed = simple_compress(&nums, &config)?;
+      assert!(
+        compressed.len() <= raw_size + 200,
+        "{:?}: {} > {} + 200",
+        delta_encoding_order,
+        compressed.len(),
+        raw_size,
+      );
+
+      let (fd, mut src) = FileDecompressor::new(compressed.as_slice())?;
+      let mut decompressed = Vec::new();
+      while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<u64, _>(src)? {
+        if delta_encoding_order == Some(3) {
+          assert!(cd.meta().is_raw());
+        }
+        cd.decompress_remaining_extend(&mut decompressed)?;
+        src = cd.into_src();
+      }
+      assert_eq!(decompressed, nums);
+    }
+    Ok(())
+  }
 }
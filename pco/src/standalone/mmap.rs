@@ -0,0 +1,96 @@
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::data_types::NumberLike;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::constants::MAGIC_HEADER;
+use crate::standalone::simple_decompress;
+
+// the magic header followed by the standalone version byte
+const MIN_FILE_SIZE: usize = MAGIC_HEADER.len() + 1;
+
+/// Memory-maps the standalone .pco file at `path` and decompresses it into a
+/// vector of numbers, without first copying the file's bytes into memory.
+///
+/// This is otherwise identical to
+/// [`simple_decompress`][crate::standalone::simple_decompress].
+/// As with any memory map, the result is undefined if the file is modified
+/// by another process while being decompressed.
+///
+/// Will return an Io error if the file cannot be opened or mapped, an
+/// insufficient data error if it is too short to contain a .pco header, and
+/// otherwise any error `simple_decompress` would return.
+pub fn decompress_mmap<T: NumberLike>(path: &Path) -> PcoResult<Vec<T>> {
+  let file = File::open(path)?;
+  let len = usize::try_from(file.metadata()?.len())
+    .map_err(|_| PcoError::invalid_argument("file is too large to memory-map"))?;
+  // a file too short for the header can't be valid, so skip mapping it
+  if len < MIN_FILE_SIZE {
+    return Err(PcoError::insufficient_data(format!(
+      "file of {} bytes is shorter than the minimal .pco header of {} bytes",
+      len, MIN_FILE_SIZE,
+    )));
+  }
+
+  let mmap = unsafe { Mmap::map(&file)? };
+  simple_decompress(&mmap)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::path::PathBuf;
+
+  use crate::errors::ErrorKind;
+  use crate::standalone::simpler_compress;
+  use crate::DEFAULT_COMPRESSION_LEVEL;
+
+  use super::*;
+
+  fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+      "pco_mmap_{}_{}.pco",
+      std::process::id(),
+      name
+    ))
+  }
+
+  #[test]
+  fn test_decompress_mmap() -> PcoResult<()> {
+    let nums = (0..3000).map(|x| x as f64 * 0.25).collect::<Vec<_>>();
+    let path = temp_path("round_trip");
+    fs::write(
+      &path,
+      simpler_compress(&nums, DEFAULT_COMPRESSION_LEVEL)?,
+    )?;
+    let recovered = decompress_mmap::<f64>(&path);
+    fs::remove_file(&path)?;
+    assert_eq!(recovered?, nums);
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_mmap_short_files() -> PcoResult<()> {
+    let full = simpler_compress(&[1_u32, 2, 3], DEFAULT_COMPRESSION_LEVEL)?;
+    for len in [0, 3, MIN_FILE_SIZE, full.len() - 1] {
+      let path = temp_path(&format!("short_{}", len));
+      fs::write(&path, &full[..len])?;
+      let res = decompress_mmap::<u32>(&path);
+      fs::remove_file(&path)?;
+      assert!(
+        matches!(
+          res.unwrap_err().kind,
+          ErrorKind::InsufficientData
+        ),
+        "len={}",
+        len
+      );
+    }
+
+    let err = decompress_mmap::<u32>(&temp_path("missing")).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Io(_)));
+    Ok(())
+  }
+}
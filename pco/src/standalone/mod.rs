@@ -1,11 +1,49 @@
+pub use append::append_page;
+pub use bools::{compress_bools, decompress_bools};
+pub use columns::{column_counts, compress_columns, decompress_column};
 pub use compressor::{ChunkCompressor, FileCompressor};
+pub use concat::concat_files;
 pub use decompressor::{ChunkDecompressor, FileDecompressor, MaybeChunkDecompressor};
 pub use dtype_or_termination::DataTypeOrTermination;
-pub use simple::{simple_compress, simple_decompress, simple_decompress_into, simpler_compress};
+#[cfg(feature = "mmap")]
+pub use mmap::decompress_mmap;
+pub use nullable::{compress_nullable, decompress_nullable};
+pub use pages::{decompress_pages, Checkpoint, PageIter};
+pub use rechunk::rechunk;
+pub use records::{compress_records, decompress_records, ColumnTuple};
+pub use reusable_decompressor::ReusableDecompressor;
+#[cfg(feature = "ndarray")]
+pub(crate) use simple::compress_gathered;
+#[cfg(feature = "bench")]
+pub use simple::compress_timed;
+#[cfg(feature = "rayon")]
+pub use simple::par_compress;
+pub use simple::{
+  compress_from_iter, compress_strided, compress_with_overrides, compress_with_report,
+  decompress_as, decompress_chunk, decompress_for_each, decompress_limited, decompress_raw_latents,
+  decompress_recoverable, simple_compress, simple_compress_with_progress, simple_decompress,
+  simple_decompress_into, simple_decompress_with_stats, simpler_compress,
+};
+pub use stream_compressor::StreamCompressor;
+pub use validation::{count_elements, validate, FileStats};
 
+mod append;
+mod bools;
+mod checksum;
+mod columns;
 mod compressor;
+mod concat;
 mod constants;
 mod decompressor;
 mod dtype_or_termination;
 pub mod guarantee;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod nullable;
+mod pages;
+mod rechunk;
+mod records;
+mod reusable_decompressor;
 mod simple;
+mod stream_compressor;
+mod validation;
@@ -0,0 +1,212 @@
+use crate::chunk_config::ChunkConfig;
+use crate::data_types::NumberLike;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::PagingSpec;
+
+// A nullable file is an ordinary standalone file whose chunks come in
+// segments. Each segment starts with a u16 chunk of validity flags, 1 for
+// each present number and 0 for each null, followed by a chunk of just the
+// present numbers if there are any.
+// Since a whole segment of flags is either constant or only takes 2 values,
+// the flags compress to next to nothing when most numbers are null or most
+// are present.
+const NULL_FLAG: u16 = 0;
+const PRESENT_FLAG: u16 = 1;
+
+/// Takes in a slice of optional numbers and an exact configuration and
+/// returns compressed bytes.
+///
+/// The nulls are stored as a separate stream of validity flags, and the
+/// present numbers are compressed with `config` as usual, so nulls cost
+/// close to nothing when they are rare or when they are common.
+/// This will use the `PagingSpec` in `ChunkConfig` to decide where to split
+/// the optional numbers into segments, each getting a chunk of flags and a
+/// chunk of present numbers.
+/// Use [`decompress_nullable`] to read the result.
+///
+/// Will return an error if the compressor config is invalid.
+pub fn compress_nullable<T: NumberLike>(
+  nums: &[Option<T>],
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  config.validate()?;
+  let file_compressor = FileCompressor::default()
+    .with_n_hint(nums.len())
+    .with_format_version_for(config);
+  let mut dst = file_compressor.write_header(Vec::new())?;
+
+  let n_per_segment = config.paging_spec.n_per_page::<T::L>(nums.len())?;
+  let mut flag_config = ChunkConfig::default()
+    .with_compression_level(config.compression_level)
+    .with_delta_encoding_order(Some(0));
+  let mut value_config = config.clone();
+  let mut start = 0;
+  for &segment_n in &n_per_segment {
+    let segment = &nums[start..start + segment_n];
+    start += segment_n;
+    if segment.is_empty() {
+      continue;
+    }
+
+    let flags = segment
+      .iter()
+      .map(|x| x.map_or(NULL_FLAG, |_| PRESENT_FLAG))
+      .collect::<Vec<_>>();
+    flag_config.paging_spec = PagingSpec::Exact(vec![flags.len()]);
+    dst = file_compressor
+      .chunk_compressor(&flags, &flag_config)?
+      .write_chunk(dst)?;
+
+    let values = segment.iter().flatten().copied().collect::<Vec<_>>();
+    if values.is_empty() {
+      continue;
+    }
+    value_config.paging_spec = PagingSpec::Exact(vec![values.len()]);
+    dst = file_compressor
+      .chunk_compressor(&values, &value_config)?
+      .write_chunk(dst)?;
+  }
+
+  file_compressor.write_footer(dst)
+}
+
+/// Takes in compressed bytes written by [`compress_nullable`] and returns a
+/// vector of optional numbers.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match.
+pub fn decompress_nullable<T: NumberLike>(file: &[u8]) -> PcoResult<Vec<Option<T>>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut res = Vec::with_capacity(file_decompressor.n_hint());
+  let mut flags = Vec::new();
+  let mut values = Vec::new();
+  loop {
+    match file_decompressor.chunk_decompressor::<u16, _>(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        flags.clear();
+        chunk_decompressor.decompress_remaining_extend(&mut flags)?;
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        break;
+      }
+    }
+
+    let mut n_present = 0;
+    for &flag in &flags {
+      match flag {
+        NULL_FLAG => (),
+        PRESENT_FLAG => n_present += 1,
+        _ => {
+          return Err(PcoError::corruption(format!(
+            "invalid validity flag {}",
+            flag
+          )))
+        }
+      }
+    }
+
+    values.clear();
+    if n_present > 0 {
+      let MaybeChunkDecompressor::Some(mut chunk_decompressor) =
+        file_decompressor.chunk_decompressor::<T, _>(src)?
+      else {
+        return Err(PcoError::corruption(format!(
+          "expected a chunk of {} present numbers but reached the end of the file",
+          n_present,
+        )));
+      };
+      if chunk_decompressor.n() != n_present {
+        return Err(PcoError::corruption(format!(
+          "expected a chunk of {} present numbers but found {}",
+          n_present,
+          chunk_decompressor.n(),
+        )));
+      }
+      chunk_decompressor.decompress_remaining_extend(&mut values)?;
+      src = chunk_decompressor.into_src();
+    }
+
+    let mut values = values.iter();
+    res.extend(flags.iter().map(|&flag| {
+      if flag == PRESENT_FLAG {
+        values.next().copied()
+      } else {
+        None
+      }
+    }));
+  }
+  Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::simple_compress;
+
+  use super::*;
+
+  #[test]
+  fn test_nullable_round_trip() -> PcoResult<()> {
+    let nums = (0..5000)
+      .map(|i| {
+        if i % 7 == 3 {
+          None
+        } else {
+          Some(i as f32 * 0.5)
+        }
+      })
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(2000));
+    let compressed = compress_nullable(&nums, &config)?;
+    assert_eq!(
+      decompress_nullable::<f32>(&compressed)?,
+      nums
+    );
+
+    let empty = compress_nullable::<i64>(&[], &config)?;
+    assert_eq!(
+      decompress_nullable::<i64>(&empty)?,
+      Vec::<Option<i64>>::new()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_nullable_all_null_or_present() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let all_null = vec![None; 10000];
+    let compressed = compress_nullable::<u32>(&all_null, &config)?;
+    assert_eq!(
+      decompress_nullable::<u32>(&compressed)?,
+      all_null
+    );
+    assert!(compressed.len() < 40);
+
+    let present = (0..10000_u32).map(|i| i * i).collect::<Vec<_>>();
+    let all_present = present.iter().copied().map(Some).collect::<Vec<_>>();
+    let compressed = compress_nullable(&all_present, &config)?;
+    assert_eq!(
+      decompress_nullable::<u32>(&compressed)?,
+      all_present
+    );
+    let without_flags = simple_compress(&present, &config)?;
+    assert!(compressed.len() < without_flags.len() + 32);
+    Ok(())
+  }
+
+  #[test]
+  fn test_nullable_corrupt() -> PcoResult<()> {
+    // an ordinary file of u16s has no chunks of present numbers
+    let compressed = simple_compress(&[3_u16, 1, 0], &ChunkConfig::default())?;
+    let err = decompress_nullable::<u16>(&compressed).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+}
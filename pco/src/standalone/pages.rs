@@ -0,0 +1,262 @@
+use std::mem;
+
+use crate::data_types::NumberLike;
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::wrapped::PageBuffers;
+
+enum State<'a> {
+  Unstarted,
+  Decoding {
+    file_decompressor: FileDecompressor,
+    src: &'a [u8],
+  },
+  Finished,
+}
+
+/// A saved position between pages of a standalone file, from which a
+/// [`PageIter`] can resume decoding later.
+///
+/// Since each standalone chunk has exactly one page and each page's metadata
+/// holds its own ANS states and delta moments, a page boundary needs no
+/// decoder state beyond a byte offset.
+/// The fields are public so checkpoints can be persisted, e.g. to resume an
+/// interrupted query in another process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+  /// The byte offset in the file of the next page to decode, or 0 if
+  /// decoding hasn't started.
+  pub byte_idx: usize,
+  /// The count of numbers in all pages before the next one.
+  pub n_processed: usize,
+}
+
+/// An iterator over the pages of a standalone file, returned by
+/// [`decompress_pages`].
+pub struct PageIter<'a, T: NumberLike> {
+  file: &'a [u8],
+  state: State<'a>,
+  buffers: PageBuffers<T::L>,
+  n_processed: usize,
+}
+
+/// Takes in compressed bytes and returns an iterator that lazily decodes
+/// one page's numbers per item.
+///
+/// Since each standalone chunk has exactly one page, each item holds one
+/// chunk's numbers.
+/// Nothing is decoded until the first call to `next`, and each call only
+/// decodes the next page, so memory usage is bounded by the largest page
+/// rather than the whole file.
+/// This is useful for pipelines that process the numbers in page-sized
+/// batches.
+///
+/// If there are any compatibility, corruption, or insufficient data issues,
+/// or if the end of a file with a checksum is reached and the checksum does
+/// not match, the iterator yields the error as its last item.
+pub fn decompress_pages<T: NumberLike>(file: &[u8]) -> PageIter<'_, T> {
+  PageIter {
+    file,
+    state: State::Unstarted,
+    buffers: PageBuffers::default(),
+    n_processed: 0,
+  }
+}
+
+impl<'a, T: NumberLike> PageIter<'a, T> {
+  /// Returns a checkpoint at the boundary before the next page, or `None` if
+  /// the iterator has finished.
+  pub fn checkpoint(&self) -> Option<Checkpoint> {
+    let byte_idx = match &self.state {
+      State::Unstarted => 0,
+      State::Decoding { src, .. } => self.file.len() - src.len(),
+      State::Finished => return None,
+    };
+    Some(Checkpoint {
+      byte_idx,
+      n_processed: self.n_processed,
+    })
+  }
+
+  /// Takes in the same compressed bytes a checkpoint was taken from and
+  /// returns an iterator over the pages after it.
+  ///
+  /// Only the header is re-read; the pages before the checkpoint are
+  /// skipped entirely.
+  /// If the file has a checksum, it is still verified against the whole file
+  /// upon reaching the end.
+  ///
+  /// Will return an InvalidArgument error if the checkpoint's byte offset
+  /// falls within the header or beyond the end of the file, or an error if
+  /// the header has any compatibility, corruption, or insufficient data
+  /// issues.
+  /// A checkpoint that isn't at a page boundary of this file will generally
+  /// yield a corruption error once iterated.
+  pub fn resume(file: &'a [u8], checkpoint: &Checkpoint) -> PcoResult<Self> {
+    let mut res = decompress_pages(file);
+    if checkpoint.byte_idx == 0 {
+      return Ok(res);
+    }
+
+    let (file_decompressor, rest) = FileDecompressor::new(file)?;
+    let header_size = file.len() - rest.len();
+    if checkpoint.byte_idx < header_size || checkpoint.byte_idx > file.len() {
+      return Err(PcoError::invalid_argument(format!(
+        "checkpoint byte offset {} must be between the end of the header ({}) and the end of the file ({})",
+        checkpoint.byte_idx,
+        header_size,
+        file.len(),
+      )));
+    }
+    res.state = State::Decoding {
+      file_decompressor,
+      src: &file[checkpoint.byte_idx..],
+    };
+    res.n_processed = checkpoint.n_processed;
+    Ok(res)
+  }
+
+  fn next_page(&mut self) -> PcoResult<Option<Vec<T>>> {
+    let (file_decompressor, src) = match mem::replace(&mut self.state, State::Finished) {
+      State::Unstarted => FileDecompressor::new(self.file)?,
+      State::Decoding {
+        file_decompressor,
+        src,
+      } => (file_decompressor, src),
+      State::Finished => return Ok(None),
+    };
+
+    let buffers = mem::take(&mut self.buffers);
+    match file_decompressor.chunk_decompressor_reusing(src, buffers)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        let mut page = Vec::with_capacity(chunk_decompressor.n());
+        chunk_decompressor.decompress_remaining_extend(&mut page)?;
+        let (src, buffers) = chunk_decompressor.into_src_and_buffers();
+        self.buffers = buffers;
+        self.n_processed += page.len();
+        self.state = State::Decoding {
+          file_decompressor,
+          src,
+        };
+        Ok(Some(page))
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(self.file, rest)?;
+        Ok(None)
+      }
+    }
+  }
+}
+
+impl<T: NumberLike> Iterator for PageIter<'_, T> {
+  type Item = PcoResult<Vec<T>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    // on errors, the state is left finished so the iterator stops
+    self.next_page().transpose()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::{simple_compress, FileCompressor, StreamCompressor};
+  use crate::{ChunkConfig, PagingSpec};
+
+  use super::*;
+
+  #[test]
+  fn test_decompress_pages() -> PcoResult<()> {
+    let nums = (0..2500).map(|x| x * 3).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![1000, 1000, 500]));
+    let src = simple_compress(&nums, &config)?;
+    let pages = decompress_pages::<i64>(&src).collect::<PcoResult<Vec<_>>>()?;
+    assert_eq!(
+      pages,
+      vec![
+        nums[..1000].to_vec(),
+        nums[1000..2000].to_vec(),
+        nums[2000..].to_vec(),
+      ]
+    );
+
+    let empty = simple_compress::<i64>(&[], &ChunkConfig::default())?;
+    assert_eq!(decompress_pages::<i64>(&empty).count(), 0);
+    Ok(())
+  }
+
+  #[test]
+  fn test_checkpoint_resume() -> PcoResult<()> {
+    let nums = (0..2500).map(|x| x * 3).collect::<Vec<i64>>();
+    let mut compressor = StreamCompressor::new(
+      FileCompressor::default().with_checksum(true),
+      ChunkConfig::default(),
+      Vec::new(),
+    )?;
+    for chunk in nums.chunks(1000) {
+      compressor.write_chunk(chunk)?;
+    }
+    let src = compressor.finish()?;
+
+    let mut pages = decompress_pages::<i64>(&src);
+    let start = pages.checkpoint().unwrap();
+    assert_eq!(start.byte_idx, 0);
+    pages.next().unwrap()?;
+    let checkpoint = pages.checkpoint().unwrap();
+    assert_eq!(checkpoint.n_processed, 1000);
+    drop(pages);
+
+    let resumed = PageIter::<i64>::resume(&src, &checkpoint)?.collect::<PcoResult<Vec<_>>>()?;
+    assert_eq!(resumed.concat(), nums[1000..]);
+    let resumed = PageIter::<i64>::resume(&src, &start)?.collect::<PcoResult<Vec<_>>>()?;
+    assert_eq!(resumed.concat(), nums);
+
+    // there is nothing left to checkpoint once every page is decoded
+    let mut pages = decompress_pages::<i64>(&src);
+    while pages.next().transpose()?.is_some() {}
+    assert!(pages.checkpoint().is_none());
+
+    for byte_idx in [1, src.len() + 1] {
+      let bad = Checkpoint {
+        byte_idx,
+        n_processed: 0,
+      };
+      assert!(matches!(
+        PageIter::<i64>::resume(&src, &bad).err().unwrap().kind,
+        ErrorKind::InvalidArgument
+      ));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_pages_errors() -> PcoResult<()> {
+    let nums = (0..2000).map(|i| (i * i) % 10007).collect::<Vec<u32>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let src = simple_compress(&nums, &config)?;
+
+    // the first page is still intact
+    let mut pages = decompress_pages::<u32>(&src[..src.len() - 10]);
+    assert_eq!(pages.next().unwrap()?, nums[..1000]);
+    let err = pages.next().unwrap().unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    assert!(pages.next().is_none());
+
+    // header errors come from the first item
+    let mut pages = decompress_pages::<u32>(&src[..3]);
+    assert!(pages.next().unwrap().is_err());
+    assert!(pages.next().is_none());
+
+    let mut pages = decompress_pages::<f32>(&src);
+    assert!(matches!(
+      pages.next().unwrap().unwrap_err().kind,
+      ErrorKind::Corruption
+    ));
+    Ok(())
+  }
+}
@@ -0,0 +1,159 @@
+use crate::chunk_config::{ChunkConfig, PagingSpec};
+use crate::constants::COMPATIBLE_FORMAT_VERSION;
+use crate::data_types::NumberLike;
+use crate::errors::{ErrorKind, PcoResult};
+use crate::format_version::FormatVersion;
+use crate::standalone::checksum;
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::ChunkMeta;
+
+/// Takes in a complete standalone file and returns an equivalent file whose
+/// chunks are split according to `paging_spec` instead.
+///
+/// For standalone, each chunk has exactly one page, so this is how to get
+/// finer pages for random access (or coarser ones for a better compression
+/// ratio) without decompressing and recompressing through user code.
+/// Each new chunk reuses the metadata of the original chunk containing its
+/// first number, keeping the already-chosen mode, delta encoding orders,
+/// and bins, whenever those bins cover the new chunk's latents.
+/// Otherwise, the new chunk is compressed from scratch with the default
+/// config.
+/// The result decompresses to exactly the same numbers, and it has a
+/// checksum if the original did.
+///
+/// Will return an InvalidArgument error if `paging_spec` is invalid for the
+/// file's count of numbers, and otherwise an error if there are any
+/// compatibility, corruption, or insufficient data issues, or if the file
+/// has a checksum that does not match.
+pub fn rechunk<T: NumberLike>(src: &[u8], paging_spec: &PagingSpec) -> PcoResult<Vec<u8>> {
+  let (file_decompressor, mut rest) = FileDecompressor::new(src)?;
+  let mut nums = Vec::with_capacity(file_decompressor.n_hint());
+  // the index of each original chunk's first number, and its metadata
+  let mut chunks: Vec<(usize, ChunkMeta<T::L>)> = Vec::new();
+  let has_checksum = loop {
+    match file_decompressor.chunk_decompressor::<T, _>(rest)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        chunks.push((nums.len(), chunk_decompressor.meta().clone()));
+        chunk_decompressor.decompress_remaining_extend(&mut nums)?;
+        rest = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        break verify_checksum_if_present(src, rest)?.len() < rest.len();
+      }
+    }
+  };
+
+  // keep the original format version so every chunk's metadata still fits
+  let format_version = file_decompressor
+    .format_version()
+    .max(COMPATIBLE_FORMAT_VERSION);
+  let file_compressor = FileCompressor::default()
+    .with_format_version(FormatVersion(format_version))
+    .with_n_hint(nums.len())
+    .with_checksum(has_checksum);
+  let mut dst = file_compressor.write_header(Vec::new())?;
+  let n_per_chunk = paging_spec.n_per_page::<T::L>(nums.len())?;
+  let mut config = ChunkConfig::default();
+  let mut start = 0;
+  for &chunk_n in &n_per_chunk {
+    let end = start + chunk_n;
+    let chunk_nums = &nums[start..end];
+    let orig_chunk_idx = chunks.partition_point(|(orig_start, _)| *orig_start <= start) - 1;
+    let chunk_compressor =
+      match file_compressor.chunk_compressor_w_meta(chunk_nums, &chunks[orig_chunk_idx].1) {
+        Ok(chunk_compressor) => chunk_compressor,
+        // the original bins don't cover this chunk, e.g. because its runs
+        // were cut short
+        Err(e) if matches!(e.kind, ErrorKind::InvalidArgument) => {
+          config.paging_spec = PagingSpec::Exact(vec![chunk_n]);
+          file_compressor.chunk_compressor(chunk_nums, &config)?
+        }
+        Err(e) => return Err(e),
+      };
+    dst = chunk_compressor.write_chunk(dst)?;
+    start = end;
+  }
+
+  let crc = has_checksum.then(|| checksum::crc32c(&dst));
+  file_compressor.write_footer_with_crc(crc, dst)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::standalone::{decompress_pages, simple_compress, simple_decompress, StreamCompressor};
+  use crate::{IntMultSpec, Mode};
+
+  use super::*;
+
+  fn chunk_ns<T: NumberLike>(file: &[u8]) -> PcoResult<Vec<usize>> {
+    decompress_pages::<T>(file)
+      .map(|page| page.map(|page| page.len()))
+      .collect()
+  }
+
+  #[test]
+  fn test_rechunk() -> PcoResult<()> {
+    let nums = (0..3000)
+      .map(|i| 5 * ((i * i) % 101) + i % 5)
+      .collect::<Vec<i32>>();
+    let config = ChunkConfig::default().with_int_mult_spec(IntMultSpec::Provided(5));
+    let file = simple_compress(&nums, &config)?;
+    assert_eq!(chunk_ns::<i32>(&file)?, vec![3000]);
+
+    let finer = rechunk::<i32>(&file, &PagingSpec::EqualPagesUpTo(1000))?;
+    assert_eq!(chunk_ns::<i32>(&finer)?, vec![1000; 3]);
+    assert_eq!(simple_decompress::<i32>(&finer)?, nums);
+
+    // the original mode and bins were reused
+    let (file_decompressor, src) = FileDecompressor::new(finer.as_slice())?;
+    let MaybeChunkDecompressor::Some(chunk_decompressor) =
+      file_decompressor.chunk_decompressor::<i32, _>(src)?
+    else {
+      panic!("expected a chunk");
+    };
+    assert!(matches!(
+      chunk_decompressor.meta().mode,
+      Mode::IntMult(5)
+    ));
+
+    let coarser = rechunk::<i32>(&finer, &PagingSpec::Exact(vec![1500, 1500]))?;
+    assert_eq!(chunk_ns::<i32>(&coarser)?, vec![1500, 1500]);
+    assert_eq!(simple_decompress::<i32>(&coarser)?, nums);
+    Ok(())
+  }
+
+  #[test]
+  fn test_rechunk_checksum_and_errors() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| (i / 100) as u64).collect::<Vec<_>>();
+    let mut compressor = StreamCompressor::new(
+      FileCompressor::default().with_checksum(true),
+      ChunkConfig::default(),
+      Vec::new(),
+    )?;
+    compressor.write_chunk(&nums)?;
+    let file = compressor.finish()?;
+
+    let rechunked = rechunk::<u64>(&file, &PagingSpec::EqualPagesUpTo(33))?;
+    let (file_decompressor, _) = FileDecompressor::new(rechunked.as_slice())?;
+    file_decompressor.verify_checksum(&rechunked)?;
+    assert_eq!(simple_decompress::<u64>(&rechunked)?, nums);
+
+    let err = rechunk::<u64>(&file, &PagingSpec::Exact(vec![1])).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    let err = rechunk::<u64>(
+      &file[..file.len() - 1],
+      &PagingSpec::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    Ok(())
+  }
+}
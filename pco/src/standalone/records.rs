@@ -0,0 +1,216 @@
+use crate::chunk_config::ChunkConfig;
+use crate::data_types::{CoreDataType, NumberLike};
+use crate::errors::{PcoError, PcoResult};
+use crate::standalone::columns::{self, ColumnIndex};
+use crate::standalone::compressor::FileCompressor;
+use crate::standalone::constants::RECORD_INDEX_MAGIC;
+
+/// A tuple of columns of possibly different data types, such as
+/// `(Vec<i64>, Vec<f64>, Vec<u32>)`, for use with [`compress_records`] and
+/// [`decompress_records`].
+///
+/// This is implemented for tuples of 1 to 8 `Vec`s of any [`NumberLike`]
+/// types, and is sealed, so it cannot be implemented outside of `pco`.
+pub trait ColumnTuple: private::Sealed {}
+
+mod private {
+  use crate::chunk_config::ChunkConfig;
+  use crate::errors::PcoResult;
+  use crate::standalone::compressor::FileCompressor;
+
+  pub trait Sealed: Sized {
+    // the data type byte of each column, in order
+    fn dtype_bytes() -> Vec<u8>;
+
+    fn total_n(&self) -> usize;
+
+    // compresses each column into its own chunk, appending them to `dst` and
+    // recording each one's count and byte offset in `index`
+    fn write_columns(
+      &self,
+      file_compressor: &FileCompressor,
+      config: &ChunkConfig,
+      index: &mut Vec<(usize, usize)>,
+      dst: Vec<u8>,
+    ) -> PcoResult<Vec<u8>>;
+
+    // decompresses each column of a file whose schema is already known to
+    // match
+    fn read_columns(file: &[u8]) -> PcoResult<Self>;
+  }
+}
+
+macro_rules! impl_column_tuple {
+  ($($t:ident: $idx:tt),+) => {
+    impl<$($t: NumberLike),+> ColumnTuple for ($(Vec<$t>,)+) {}
+
+    impl<$($t: NumberLike),+> private::Sealed for ($(Vec<$t>,)+) {
+      fn dtype_bytes() -> Vec<u8> {
+        vec![$($t::DTYPE_BYTE),+]
+      }
+
+      fn total_n(&self) -> usize {
+        0 $(+ self.$idx.len())+
+      }
+
+      fn write_columns(
+        &self,
+        file_compressor: &FileCompressor,
+        config: &ChunkConfig,
+        index: &mut Vec<(usize, usize)>,
+        mut dst: Vec<u8>,
+      ) -> PcoResult<Vec<u8>> {
+        $(dst = columns::write_column(file_compressor, &self.$idx, config, index, dst)?;)+
+        Ok(dst)
+      }
+
+      fn read_columns(file: &[u8]) -> PcoResult<Self> {
+        let index = columns::parse_column_index(file)?;
+        Ok(($(columns::read_column::<$t>(file, &index, $idx)?,)+))
+      }
+    }
+  };
+}
+
+impl_column_tuple!(A: 0);
+impl_column_tuple!(A: 0, B: 1);
+impl_column_tuple!(A: 0, B: 1, C: 2);
+impl_column_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_column_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_column_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_column_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_column_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+fn describe_schema(dtype_bytes: &[u8]) -> String {
+  let names = dtype_bytes
+    .iter()
+    .map(|&byte| match CoreDataType::from_byte(byte) {
+      Some(dtype) => format!("{:?}", dtype),
+      None => format!("dtype byte {}", byte),
+    })
+    .collect::<Vec<_>>();
+  format!("[{}]", names.join(", "))
+}
+
+/// Compresses each column of a tuple of columns, which may have different
+/// data types, into its own chunk of a single .pco file, followed by a
+/// schema of the columns' data types and an index of each column's count
+/// and byte offset.
+///
+/// This is handy for records like `(i64, f64, u32)` stored as one column
+/// per field.
+/// All columns share `config` (except for its `PagingSpec`, since each
+/// column becomes exactly one chunk) and a single file header.
+/// Use [`decompress_records`] to get the columns back, or
+/// [`decompress_column`][crate::standalone::decompress_column] to decompress
+/// any one of them without touching the others.
+/// Unlike the files written by
+/// [`compress_columns`][crate::standalone::compress_columns], the result
+/// can only be decompressed as a whole by
+/// [`simple_decompress`][crate::standalone::simple_decompress] if all
+/// columns share a data type.
+///
+/// Will return an InvalidArgument error if the config is invalid or if any
+/// column is too long to fit in a single chunk.
+pub fn compress_records<C: ColumnTuple>(columns: &C, config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+  config.validate()?;
+  let file_compressor = FileCompressor::default()
+    .with_n_hint(columns.total_n())
+    .with_format_version_for(config);
+  let mut dst = file_compressor.write_header(Vec::new())?;
+
+  let mut index = Vec::new();
+  dst = columns.write_columns(&file_compressor, config, &mut index, dst)?;
+  dst = file_compressor.write_footer(dst)?;
+  dst.extend(C::dtype_bytes());
+  Ok(columns::write_index(
+    &index,
+    RECORD_INDEX_MAGIC,
+    dst,
+  ))
+}
+
+/// Decompresses all columns of a file written by [`compress_records`].
+///
+/// Will return an InvalidArgument error if the file's schema doesn't match
+/// the data types of `C`'s columns, and otherwise an error if there are any
+/// compatibility, corruption, or insufficient data issues, including a
+/// missing schema or column index.
+pub fn decompress_records<C: ColumnTuple>(file: &[u8]) -> PcoResult<C> {
+  let ColumnIndex { schema, .. } = columns::parse_column_index(file)?;
+  let Some(schema) = schema else {
+    return Err(PcoError::corruption(
+      "file has a column index but no schema",
+    ));
+  };
+  let expected = C::dtype_bytes();
+  if schema != expected {
+    return Err(PcoError::invalid_argument(format!(
+      "file has schema {} but expected {}",
+      describe_schema(schema),
+      describe_schema(&expected),
+    )));
+  }
+  C::read_columns(file)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::{column_counts, compress_columns, decompress_column};
+
+  use super::*;
+
+  #[test]
+  fn test_records_round_trip() -> PcoResult<()> {
+    let records = (
+      (0..1000).map(|i| i * 1000 - 7).collect::<Vec<i64>>(),
+      (0..1000).map(|i| i as f64 * 0.25).collect::<Vec<f64>>(),
+      (0..1000).map(|i| i % 17).collect::<Vec<u32>>(),
+    );
+    let file = compress_records(&records, &ChunkConfig::default())?;
+    assert_eq!(
+      decompress_records::<(Vec<i64>, Vec<f64>, Vec<u32>)>(&file)?,
+      records
+    );
+
+    // records files still work with the column functions
+    assert_eq!(column_counts(&file)?, vec![1000; 3]);
+    assert_eq!(
+      decompress_column::<f64>(&file, 1)?,
+      records.1
+    );
+
+    let with_empty = (vec![1_u16, 2], Vec::<f32>::new());
+    let file = compress_records(&with_empty, &ChunkConfig::default())?;
+    assert_eq!(
+      decompress_records::<(Vec<u16>, Vec<f32>)>(&file)?,
+      with_empty
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_records_schema_mismatch() -> PcoResult<()> {
+    let records = (vec![1_i64, 2, 3], vec![0.5_f32]);
+    let file = compress_records(&records, &ChunkConfig::default())?;
+    for err in [
+      decompress_records::<(Vec<i64>, Vec<f64>)>(&file).unwrap_err(),
+      decompress_records::<(Vec<i64>,)>(&file).unwrap_err(),
+      decompress_records::<(Vec<i64>, Vec<f32>, Vec<u32>)>(&file).unwrap_err(),
+    ] {
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+    }
+    let err = decompress_records::<(Vec<i64>, Vec<f64>)>(&file).unwrap_err();
+    assert!(err.message.contains("[I64, F32]"));
+
+    // files of plain columns have no schema
+    let file = compress_columns(&[&[1_u32][..]], &ChunkConfig::default())?;
+    let err = decompress_records::<(Vec<u32>,)>(&file).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+}
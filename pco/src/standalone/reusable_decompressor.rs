@@ -0,0 +1,137 @@
+use std::mem;
+
+use crate::data_types::NumberLike;
+use crate::errors::PcoResult;
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::wrapped::PageBuffers;
+
+/// Decompresses many standalone .pco files one after another, reusing its
+/// allocations between them.
+///
+/// Example:
+/// ```
+/// use pco::standalone::{simpler_compress, ReusableDecompressor};
+/// use pco::DEFAULT_COMPRESSION_LEVEL;
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let files = [
+///   simpler_compress(&[1_i64, 2, 3], DEFAULT_COMPRESSION_LEVEL)?,
+///   simpler_compress(&[4_i64, 5], DEFAULT_COMPRESSION_LEVEL)?,
+/// ];
+/// let mut decompressor = ReusableDecompressor::<i64>::default();
+/// assert_eq!(decompressor.decompress(&files[0])?, &[1, 2, 3]);
+/// assert_eq!(decompressor.decompress(&files[1])?, &[4, 5]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Construction of each chunk's decompressor allocates for its bins and its
+/// tANS decoding table, which has up to `2^ans_size_log` states, and the
+/// decompressed numbers need a vector of their own.
+/// For many small files, these allocations can dominate decompression time,
+/// so this keeps them around and refills them for each chunk and file.
+/// Nothing else carries over: every call starts from a fresh header, and the
+/// recycled buffers are cleared before use, so decompressing a short file
+/// after a long one returns only the short file's numbers.
+#[derive(Clone, Debug, Default)]
+pub struct ReusableDecompressor<T: NumberLike> {
+  nums: Vec<T>,
+  buffers: PageBuffers<T::L>,
+}
+
+impl<T: NumberLike> ReusableDecompressor<T> {
+  /// Takes in compressed bytes and returns the numbers, which stay valid until
+  /// the next call.
+  ///
+  /// This is otherwise equivalent to
+  /// [`simple_decompress`][crate::standalone::simple_decompress].
+  ///
+  /// Will return an error if there are any compatibility, corruption,
+  /// or insufficient data issues, or if the file has a checksum that does not
+  /// match.
+  pub fn decompress(&mut self, file: &[u8]) -> PcoResult<&[T]> {
+    self.nums.clear();
+    let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+    self.nums.reserve(file_decompressor.n_hint());
+    loop {
+      let buffers = mem::take(&mut self.buffers);
+      match file_decompressor.chunk_decompressor_reusing(src, buffers)? {
+        MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+          let extended = chunk_decompressor.decompress_remaining_extend(&mut self.nums);
+          (src, self.buffers) = chunk_decompressor.into_src_and_buffers();
+          if let Err(e) = extended {
+            self.nums.clear();
+            return Err(e);
+          }
+        }
+        MaybeChunkDecompressor::EndOfData(rest) => {
+          verify_checksum_if_present(file, rest)?;
+          break;
+        }
+      }
+    }
+    Ok(&self.nums)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::errors::ErrorKind;
+  use crate::standalone::{simple_compress, simple_decompress, FileCompressor};
+  use crate::{ChunkConfig, PagingSpec};
+
+  use super::*;
+
+  #[test]
+  fn test_matches_simple_decompress() -> PcoResult<()> {
+    let long = (0..5000).map(|x| (x * x) % 1009).collect::<Vec<u32>>();
+    let short = vec![7_u32, 8, 9];
+    let mut files = vec![
+      simple_compress(
+        &long,
+        &ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1200)),
+      )?,
+      simple_compress(&short, &ChunkConfig::default())?,
+      simple_compress::<u32>(&[], &ChunkConfig::default())?,
+    ];
+    let mut checksummed = Vec::new();
+    let file_compressor = FileCompressor::default().with_checksum(true);
+    file_compressor.write_header(&mut checksummed)?;
+    file_compressor
+      .chunk_compressor(&short, &ChunkConfig::default())?
+      .write_chunk(&mut checksummed)?;
+    let written = checksummed.clone();
+    file_compressor.write_checksummed_footer(&written, &mut checksummed)?;
+    files.push(checksummed);
+
+    let mut decompressor = ReusableDecompressor::<u32>::default();
+    for _ in 0..2 {
+      for file in &files {
+        assert_eq!(
+          decompressor.decompress(file)?,
+          simple_decompress::<u32>(file)?
+        );
+      }
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_recovers_after_error() -> PcoResult<()> {
+    let nums = (0..1000).collect::<Vec<u32>>();
+    let file = simple_compress(&nums, &ChunkConfig::default())?;
+    let mut decompressor = ReusableDecompressor::<u32>::default();
+
+    let err = decompressor
+      .decompress(&file[..file.len() / 2])
+      .unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    assert_eq!(decompressor.decompress(&file)?, nums);
+    Ok(())
+  }
+}
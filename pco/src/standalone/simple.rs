@@ -1,11 +1,27 @@
 use std::cmp::min;
+use std::mem;
+use std::ops::Range;
+#[cfg(feature = "bench")]
+use std::time::{Duration, Instant};
 
-use crate::chunk_config::ChunkConfig;
-use crate::data_types::NumberLike;
-use crate::errors::PcoResult;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::chunk_config::{ChunkConfig, ChunkConfigOverrides};
+use crate::compression_report::CompressionReport;
+use crate::data_types::cast::CastSource;
+use crate::data_types::{with_castable_dtypes, CastTarget, NumberLike};
+use crate::decode_stats::DecodeStats;
+use crate::errors::{PcoError, PcoResult};
 use crate::progress::Progress;
+use crate::standalone::checksum;
 use crate::standalone::compressor::FileCompressor;
+use crate::standalone::constants::{CHECKSUM_TRAILER_BYTES, MAGIC_TERMINATION_BYTE};
 use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::stream_compressor::StreamCompressor;
+use crate::standalone::validation;
+#[cfg(feature = "bench")]
+use crate::timings::Timings;
 use crate::{PagingSpec, FULL_BATCH_N};
 
 /// Takes in a slice of numbers and an exact configuration and returns
@@ -17,33 +33,329 @@ use crate::{PagingSpec, FULL_BATCH_N};
 /// For standalone, the concepts of chunk and page are conflated since each
 /// chunk has exactly one page.
 pub fn simple_compress<T: NumberLike>(nums: &[T], config: &ChunkConfig) -> PcoResult<Vec<u8>> {
-  let mut dst = Vec::new();
-  let file_compressor = FileCompressor::default().with_n_hint(nums.len());
-  file_compressor.write_header(&mut dst)?;
+  simple_compress_with_progress(nums, config, |_| {})
+}
+
+/// Like [`simple_compress`], but with some fields of `config` replaced by
+/// `overrides` for this call only.
+///
+/// This is useful when configs are shared, e.g. kept in a registry, but a
+/// particular input needs different settings, such as disabling delta
+/// encoding for data that is known to be random.
+/// The output is byte-identical to that of [`simple_compress`] with the
+/// overridden config.
+///
+/// Will return an error if the overridden compressor config is invalid.
+pub fn compress_with_overrides<T: NumberLike>(
+  nums: &[T],
+  config: &ChunkConfig,
+  overrides: &ChunkConfigOverrides,
+) -> PcoResult<Vec<u8>> {
+  simple_compress(nums, &overrides.apply(config))
+}
+
+/// Like [`simple_compress`], but calls `on_progress` after each chunk is
+/// written with the cumulative count of numbers compressed so far.
+///
+/// This can be used to report progress while compressing large inputs.
+/// The callback runs on the compressing thread, so slow callbacks slow down
+/// compression.
+/// The output is byte-identical to that of [`simple_compress`].
+///
+/// Will return an error if the compressor config is invalid.
+pub fn simple_compress_with_progress<T: NumberLike, F: FnMut(usize)>(
+  nums: &[T],
+  config: &ChunkConfig,
+  on_progress: F,
+) -> PcoResult<Vec<u8>> {
+  compress_slice(nums, config, on_progress, None)
+}
+
+/// Like [`simple_compress`], but also returns a breakdown of how many bits
+/// of the compressed file went to each category, such as metadata, ANS
+/// symbols, and offsets.
+///
+/// This is meant for tuning compression, e.g. seeing whether metadata
+/// overhead dominates for small chunks.
+/// The output is byte-identical to that of [`simple_compress`].
+///
+/// Will return an error if the compressor config is invalid.
+pub fn compress_with_report<T: NumberLike>(
+  nums: &[T],
+  config: &ChunkConfig,
+) -> PcoResult<(Vec<u8>, CompressionReport)> {
+  let mut report = CompressionReport::default();
+  let dst = compress_slice(nums, config, |_| {}, Some(&mut report))?;
+  Ok((dst, report))
+}
+
+/// Like [`simple_compress`], but also returns the time spent in each phase
+/// of compression, such as building bins and ANS encoding.
+///
+/// This is meant for benchmarking and profiling, since timing the whole call
+/// hides where the time goes.
+/// Measuring adds a little overhead, so it is only available with the
+/// `bench` feature.
+/// The output is byte-identical to that of [`simple_compress`].
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "bench")]
+pub fn compress_timed<T: NumberLike>(
+  nums: &[T],
+  config: &ChunkConfig,
+) -> PcoResult<(Vec<u8>, Timings)> {
+  let mut timings = Timings::default();
+  let mut chunk_elapsed = Duration::ZERO;
+  let start = Instant::now();
+  let (dst, _) = compress_chunks::<T>(
+    nums.len(),
+    config,
+    |file_compressor, chunks, dst| {
+      let chunk_start = Instant::now();
+      for chunk in chunks {
+        let bin_start = Instant::now();
+        let chunk_compressor =
+          file_compressor.chunk_compressor(&nums[chunk.range.clone()], &chunk.config)?;
+        timings.bin_building += bin_start.elapsed();
+        chunk_compressor.write_chunk_timed(&mut *dst, &mut timings)?;
+      }
+      chunk_elapsed = chunk_start.elapsed();
+      Ok(())
+    },
+  )?;
+  // the header, paging, and footer
+  timings.other += start.elapsed().saturating_sub(chunk_elapsed);
+  Ok((dst, timings))
+}
+
+// One chunk for compress_chunks to write: the range of numbers it covers,
+// and the config to compress them with, whose paging spec makes the chunk a
+// single page.
+struct ChunkJob {
+  range: Range<usize>,
+  config: ChunkConfig,
+}
 
+// Compresses `n` numbers into a standalone file, with one chunk per page of
+// `config`'s paging spec.
+// `write_chunks` gets each chunk's job in order and must append the
+// compressed chunks to `dst`; this is where the simple_compress family of
+// functions differ, e.g. in where they get the numbers from or whether they
+// compress chunks in parallel.
+// Also returns how many bytes went to the header and footer.
+fn compress_chunks<T: NumberLike>(
+  n: usize,
+  config: &ChunkConfig,
+  write_chunks: impl FnOnce(&FileCompressor, Vec<ChunkJob>, &mut Vec<u8>) -> PcoResult<()>,
+) -> PcoResult<(Vec<u8>, usize)> {
   // here we use the paging spec to determine chunks; each chunk has 1 page
-  let n_per_page = config.paging_spec.n_per_page(nums.len())?;
+  let n_per_page = config.paging_spec.n_per_page::<T::L>(n)?;
+  let mut chunks = Vec::with_capacity(n_per_page.len());
   let mut start = 0;
-  let mut this_chunk_config = config.clone();
-  let mut hinted_size = false;
   for &page_n in &n_per_page {
-    let end = start + page_n;
-    this_chunk_config.paging_spec = PagingSpec::Exact(vec![page_n]);
-    let chunk_compressor =
-      file_compressor.chunk_compressor(&nums[start..end], &this_chunk_config)?;
+    let mut chunk_config = config.clone();
+    chunk_config.paging_spec = PagingSpec::Exact(vec![page_n]);
+    chunks.push(ChunkJob {
+      range: start..start + page_n,
+      config: chunk_config,
+    });
+    start += page_n;
+  }
+
+  let mut dst = Vec::new();
+  let file_compressor = FileCompressor::default()
+    .with_n_hint(n)
+    .with_format_version_for(config);
+  file_compressor.write_header(&mut dst)?;
+  let header_size = dst.len();
+  write_chunks(&file_compressor, chunks, &mut dst)?;
+  let footer_start = dst.len();
+  file_compressor.write_footer(&mut dst)?;
+  let framing_size = header_size + dst.len() - footer_start;
+  Ok((dst, framing_size))
+}
+
+fn compress_slice<T: NumberLike, F: FnMut(usize)>(
+  nums: &[T],
+  config: &ChunkConfig,
+  mut on_progress: F,
+  mut report: Option<&mut CompressionReport>,
+) -> PcoResult<Vec<u8>> {
+  let (dst, framing_size) = compress_chunks::<T>(
+    nums.len(),
+    config,
+    |file_compressor, chunks, dst| {
+      let mut hinted_size = false;
+      for chunk in chunks {
+        let chunk_compressor =
+          file_compressor.chunk_compressor(&nums[chunk.range.clone()], &chunk.config)?;
+
+        if !hinted_size {
+          let file_size_hint = chunk_compressor.chunk_size_hint() as f64 * nums.len() as f64
+            / chunk.range.len() as f64;
+          dst.reserve_exact(file_size_hint as usize + 10);
+          hinted_size = true;
+        }
 
-    if !hinted_size {
-      let file_size_hint =
-        chunk_compressor.chunk_size_hint() as f64 * nums.len() as f64 / page_n as f64;
-      dst.reserve_exact(file_size_hint as usize + 10);
-      hinted_size = true;
+        if let Some(report) = report.as_deref_mut() {
+          *dst = chunk_compressor.write_chunk_w_report(mem::take(dst), report)?;
+        } else {
+          chunk_compressor.write_chunk(&mut *dst)?;
+        }
+        on_progress(chunk.range.end);
+      }
+      Ok(())
+    },
+  )?;
+  if let Some(report) = report {
+    report.framing_bits += 8 * framing_size;
+  }
+  Ok(dst)
+}
+
+/// Like [`simple_compress`], but takes the numbers from an iterator, so they
+/// never need to be held in memory all at once.
+///
+/// Numbers are buffered one chunk at a time, and each chunk is compressed as
+/// soon as it fills up, so memory usage is bounded by the chunk size rather
+/// than the total count.
+/// Since the total count isn't known ahead of time, chunks are filled
+/// greedily up to the size allowed by the `PagingSpec` in `ChunkConfig`, and
+/// any remaining numbers go into a final, smaller chunk.
+/// This means the output may differ from that of [`simple_compress`].
+/// To write the compressed bytes to a `Write` as they are produced, use
+/// [`StreamCompressor`][crate::standalone::StreamCompressor] instead.
+///
+/// Will return an error if the compressor config is invalid, or if its
+/// `PagingSpec` requires knowing the total count, as `Exact` and
+/// `EqualPages` do.
+pub fn compress_from_iter<T: NumberLike, I: IntoIterator<Item = T>>(
+  nums: I,
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  let max_chunk_n = config.paging_spec.max_page_n_for_unknown_n::<T::L>()?;
+  let mut compressor = StreamCompressor::new(
+    FileCompressor::default(),
+    config.clone(),
+    Vec::new(),
+  )?;
+  let mut nums = nums.into_iter();
+  let mut chunk = Vec::new();
+  loop {
+    chunk.clear();
+    chunk.extend(nums.by_ref().take(max_chunk_n));
+    if chunk.is_empty() {
+      break;
     }
+    compressor.write_chunk(&chunk)?;
+  }
+  compressor.finish()
+}
 
-    chunk_compressor.write_chunk(&mut dst)?;
-    start = end;
+/// Like [`simple_compress`], but compresses the `len` numbers at
+/// `base[offset]`, `base[offset + stride]`, `base[offset + 2 * stride]`,
+/// and so on.
+///
+/// This is useful for compressing one field of an interleaved record array
+/// without first copying it into a contiguous slice.
+/// Numbers are gathered into a buffer one chunk at a time, so memory usage
+/// is bounded by the chunk size rather than the total count.
+/// The output is byte-identical to that of [`simple_compress`] on the
+/// gathered numbers.
+///
+/// Will return an InvalidArgument error if `stride` is 0 or any of the
+/// numbers would fall outside `base`, or if the compressor config is invalid.
+pub fn compress_strided<T: NumberLike>(
+  base: &[T],
+  stride: usize,
+  offset: usize,
+  len: usize,
+  config: &ChunkConfig,
+) -> PcoResult<Vec<u8>> {
+  if stride == 0 {
+    return Err(PcoError::invalid_argument(
+      "stride must be positive",
+    ));
+  }
+  let in_bounds = match len.checked_sub(1) {
+    Some(last_idx) => last_idx
+      .checked_mul(stride)
+      .and_then(|last_offset| last_offset.checked_add(offset))
+      .is_some_and(|last_base_idx| last_base_idx < base.len()),
+    None => offset <= base.len(),
+  };
+  if !in_bounds {
+    return Err(PcoError::invalid_argument(format!(
+      "{} numbers at stride {} from offset {} do not fit in {} numbers",
+      len,
+      stride,
+      offset,
+      base.len(),
+    )));
   }
 
-  file_compressor.write_footer(&mut dst)?;
+  compress_gathered(len, config, |range, gathered| {
+    gathered.extend(range.map(|i| base[offset + i * stride]))
+  })
+}
+
+// Compresses `n` numbers that aren't contiguous in memory, having `gather`
+// append each chunk's range of them to a reused buffer just before the chunk
+// is compressed.
+pub(crate) fn compress_gathered<T: NumberLike>(
+  n: usize,
+  config: &ChunkConfig,
+  mut gather: impl FnMut(Range<usize>, &mut Vec<T>),
+) -> PcoResult<Vec<u8>> {
+  let (dst, _) = compress_chunks::<T>(n, config, |file_compressor, chunks, dst| {
+    let mut gathered = Vec::new();
+    for chunk in chunks {
+      gathered.clear();
+      gather(chunk.range, &mut gathered);
+      file_compressor
+        .chunk_compressor(&gathered, &chunk.config)?
+        .write_chunk(&mut *dst)?;
+    }
+    Ok(())
+  })?;
+  Ok(dst)
+}
+
+/// Like [`simple_compress`], but compresses chunks in parallel using rayon.
+///
+/// Since each standalone chunk is independent, every chunk is written to its
+/// own buffer on the rayon thread pool, and the buffers are then concatenated
+/// in order.
+/// The output is byte-identical to that of [`simple_compress`] given the same
+/// numbers and config.
+/// Parallelism only helps when the `PagingSpec` in `ChunkConfig` splits the
+/// numbers into multiple chunks.
+///
+/// Will return an error if the compressor config is invalid.
+#[cfg(feature = "rayon")]
+pub fn par_compress<T: NumberLike>(nums: &[T], config: &ChunkConfig) -> PcoResult<Vec<u8>> {
+  let (dst, _) = compress_chunks::<T>(
+    nums.len(),
+    config,
+    |file_compressor, chunks, dst| {
+      let chunks = chunks
+        .into_par_iter()
+        .map(|chunk| {
+          let chunk_compressor =
+            file_compressor.chunk_compressor(&nums[chunk.range], &chunk.config)?;
+          let mut bytes = Vec::with_capacity(chunk_compressor.chunk_size_hint());
+          chunk_compressor.write_chunk(&mut bytes)?;
+          Ok(bytes)
+        })
+        .collect::<PcoResult<Vec<Vec<u8>>>>()?;
+
+      dst.reserve_exact(chunks.iter().map(Vec::len).sum::<usize>() + 1);
+      for chunk in &chunks {
+        dst.extend_from_slice(chunk);
+      }
+      Ok(())
+    },
+  )?;
   Ok(dst)
 }
 
@@ -51,20 +363,28 @@ pub fn simple_compress<T: NumberLike>(nums: &[T], config: &ChunkConfig) -> PcoRe
 /// progress into the file.
 ///
 /// Will return an error if there are any compatibility, corruption,
-/// or insufficient data issues.
+/// or insufficient data issues, or if the end of a file with a checksum is
+/// reached and the checksum does not match.
 /// Does not error if dst is too short or too long, but that can be inferred
 /// from `Progress`.
-pub fn simple_decompress_into<T: NumberLike>(src: &[u8], mut dst: &mut [T]) -> PcoResult<Progress> {
-  let (file_decompressor, mut src) = FileDecompressor::new(src)?;
+pub fn simple_decompress_into<T: NumberLike>(
+  file: &[u8],
+  mut dst: &mut [T],
+) -> PcoResult<Progress> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
 
   let mut incomplete_batch_buffer = vec![T::default(); FULL_BATCH_N];
   let mut progress = Progress::default();
+  let mut chunk_idx = 0;
   loop {
-    let maybe_cd = file_decompressor.chunk_decompressor(src)?;
+    let chunk_start = file.len() - src.len();
+    let locate = |err: PcoError| err.in_chunk(chunk_idx, chunk_start);
+    let maybe_cd = file_decompressor.chunk_decompressor(src).map_err(locate)?;
     let mut chunk_decompressor;
     match maybe_cd {
       MaybeChunkDecompressor::Some(cd) => chunk_decompressor = cd,
-      MaybeChunkDecompressor::EndOfData(_) => {
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
         progress.finished = true;
         break;
       }
@@ -76,14 +396,18 @@ pub fn simple_decompress_into<T: NumberLike>(src: &[u8], mut dst: &mut [T]) -> P
       (dst.len(), false)
     };
 
-    let new_progress = chunk_decompressor.decompress(&mut dst[..limit])?;
+    let new_progress = chunk_decompressor
+      .decompress(&mut dst[..limit])
+      .map_err(locate)?;
     dst = &mut dst[new_progress.n_processed..];
     progress.n_processed += new_progress.n_processed;
 
     // If we're near the end of dst, we do one possibly incomplete batch
     // of numbers and copy them over.
     if !dst.is_empty() {
-      let new_progress = chunk_decompressor.decompress(&mut incomplete_batch_buffer)?;
+      let new_progress = chunk_decompressor
+        .decompress(&mut incomplete_batch_buffer)
+        .map_err(locate)?;
       let n_processed = min(dst.len(), new_progress.n_processed);
       dst[..n_processed].copy_from_slice(&incomplete_batch_buffer[..n_processed]);
       dst = &mut dst[n_processed..];
@@ -95,6 +419,7 @@ pub fn simple_decompress_into<T: NumberLike>(src: &[u8], mut dst: &mut [T]) -> P
     }
 
     src = chunk_decompressor.into_src();
+    chunk_idx += 1;
   }
   Ok(progress)
 }
@@ -117,22 +442,381 @@ pub fn simpler_compress<T: NumberLike>(nums: &[T], compression_level: usize) ->
 /// Takes in compressed bytes and returns a vector of numbers.
 ///
 /// Will return an error if there are any compatibility, corruption,
-/// or insufficient data issues.
-pub fn simple_decompress<T: NumberLike>(src: &[u8]) -> PcoResult<Vec<T>> {
-  let (file_decompressor, mut src) = FileDecompressor::new(src)?;
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match.
+pub fn simple_decompress<T: NumberLike>(file: &[u8]) -> PcoResult<Vec<T>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
 
   let mut res = Vec::with_capacity(file_decompressor.n_hint());
-  while let MaybeChunkDecompressor::Some(mut chunk_decompressor) =
-    file_decompressor.chunk_decompressor(src)?
-  {
-    chunk_decompressor.decompress_remaining_extend(&mut res)?;
-    src = chunk_decompressor.into_src();
+  let mut chunk_idx = 0;
+  loop {
+    let chunk_start = file.len() - src.len();
+    let locate = |err: PcoError| err.in_chunk(chunk_idx, chunk_start);
+    match file_decompressor.chunk_decompressor(src).map_err(locate)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        chunk_decompressor
+          .decompress_remaining_extend(&mut res)
+          .map_err(locate)?;
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        break;
+      }
+    }
+    chunk_idx += 1;
+  }
+  Ok(res)
+}
+
+/// Takes in compressed bytes and returns the numbers of every chunk that could
+/// be fully decoded, along with the error that stopped decoding, if any.
+///
+/// This is useful for salvaging the intact start of a file that was
+/// truncated or corrupted partway through, e.g. by a crash while writing.
+/// Decoding stops at the first chunk that fails, and none of that chunk's
+/// numbers are returned, so the result is always a prefix of the original
+/// numbers made up of whole chunks.
+/// If the header itself can't be read, no numbers are returned.
+/// If every chunk decodes but the file's checksum does not match, all the
+/// numbers are returned along with the checksum mismatch error, since they
+/// can no longer be trusted.
+/// If there is no error, this is equivalent to [`simple_decompress`].
+pub fn decompress_recoverable<T: NumberLike>(file: &[u8]) -> (Vec<T>, Option<PcoError>) {
+  let mut res = Vec::new();
+  let err = decompress_recoverable_into(file, &mut res).err();
+  (res, err)
+}
+
+fn decompress_recoverable_into<T: NumberLike>(file: &[u8], res: &mut Vec<T>) -> PcoResult<()> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+  res.reserve(file_decompressor.n_hint());
+  loop {
+    match file_decompressor.chunk_decompressor(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        let n_recovered = res.len();
+        if let Err(e) = chunk_decompressor.decompress_remaining_extend(res) {
+          res.truncate(n_recovered);
+          return Err(e);
+        }
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        return Ok(());
+      }
+    }
+  }
+}
+
+/// Takes in compressed bytes and returns a vector of up to `limit` numbers
+/// from the start of the file.
+///
+/// This is useful for previewing large files.
+/// Decoding stops as soon as `limit` numbers are available, so chunks after
+/// that point are never read, and the last chunk read is only decoded up to
+/// the batch containing the limit.
+/// If the file has fewer than `limit` numbers, all of them are returned.
+/// Since a checksum covers the whole file, it is only verified if the end of
+/// the file is reached.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues in the part of the file that was read.
+pub fn decompress_limited<T: NumberLike>(file: &[u8], limit: usize) -> PcoResult<Vec<T>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut res = Vec::with_capacity(min(limit, file_decompressor.n_hint()));
+  while res.len() < limit {
+    match file_decompressor.chunk_decompressor(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        let n_remaining = limit - res.len();
+        if chunk_decompressor.n() <= n_remaining {
+          chunk_decompressor.decompress_remaining_extend(&mut res)?;
+          src = chunk_decompressor.into_src();
+          continue;
+        }
+
+        // decode only the full batches needed to reach the limit
+        let start = res.len();
+        let n_to_decode = n_remaining.div_ceil(FULL_BATCH_N) * FULL_BATCH_N;
+        res.resize(
+          start + min(n_to_decode, chunk_decompressor.n()),
+          T::default(),
+        );
+        chunk_decompressor.decompress(&mut res[start..])?;
+        res.truncate(limit);
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        break;
+      }
+    }
+  }
+  Ok(res)
+}
+
+/// Takes in compressed bytes and calls `f` on each number, in order, as it
+/// is decoded.
+///
+/// This is useful for consuming numbers as a stream, e.g. summing them or
+/// sending them to a channel, without allocating the full output vector.
+/// Numbers are decoded one batch of up to [`FULL_BATCH_N`] at a time into a
+/// fixed buffer, and `f` runs on the calling thread.
+/// Since a checksum covers the whole file, it is only verified upon reaching
+/// the end, after `f` has already been called on every number.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match. In that case, `f` may have been called on some of the numbers.
+pub fn decompress_for_each<T: NumberLike, F: FnMut(T)>(file: &[u8], mut f: F) -> PcoResult<()> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+  let mut batch = vec![T::default(); FULL_BATCH_N];
+  let mut chunk_idx = 0;
+  loop {
+    let chunk_start = file.len() - src.len();
+    let locate = |err: PcoError| err.in_chunk(chunk_idx, chunk_start);
+    match file_decompressor.chunk_decompressor(src).map_err(locate)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        loop {
+          let progress = chunk_decompressor.decompress(&mut batch).map_err(locate)?;
+          batch[..progress.n_processed].iter().for_each(|&x| f(x));
+          if progress.finished {
+            break;
+          }
+        }
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        return Ok(());
+      }
+    }
+    chunk_idx += 1;
+  }
+}
+
+/// Takes in compressed bytes and returns a vector of numbers, along with
+/// statistics about how they were decoded.
+///
+/// This is meant for tuning compression, e.g. seeing whether the numbers are
+/// spread evenly across bins.
+/// Counting adds a pass over each batch, so it is slower than
+/// [`simple_decompress`], which never counts.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match.
+pub fn simple_decompress_with_stats<T: NumberLike>(
+  file: &[u8],
+) -> PcoResult<(Vec<T>, DecodeStats)> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut res = Vec::with_capacity(file_decompressor.n_hint());
+  let mut stats = DecodeStats::default();
+  loop {
+    match file_decompressor.chunk_decompressor(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        chunk_decompressor.enable_stats();
+        chunk_decompressor.decompress_remaining_extend(&mut res)?;
+        let mut per_latent_var = chunk_decompressor.stats().into_iter();
+        let primary = per_latent_var.next().unwrap_or_default();
+        stats.offset_bits += primary.offset_bits
+          + per_latent_var
+            .map(|var_stats| var_stats.offset_bits)
+            .sum::<usize>();
+        stats.bin_counts_per_chunk.push(primary.bin_counts);
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        break;
+      }
+    }
+  }
+  Ok((res, stats))
+}
+
+/// Takes in compressed bytes and returns the latents of each latent variable
+/// as they were encoded, before delta decoding or joining them into numbers.
+///
+/// This is meant for research, e.g. visualizing what latents a mode
+/// produces.
+/// Each latent is decoded from its bin and offset, so it is the
+/// delta-encoded value if the latent variable uses delta encoding.
+/// Each page contributes its count of latents minus its delta order, since
+/// the first few values are stored in the page's delta moments instead.
+/// These counts are per number, except in run length and sparse modes,
+/// where they are per run.
+///
+/// The result has one vector per latent variable, concatenated across
+/// chunks, so a chunk whose mode has fewer latent variables only contributes
+/// to the first ones.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has a checksum that does not
+/// match.
+pub fn decompress_raw_latents<T: NumberLike>(file: &[u8]) -> PcoResult<Vec<Vec<T::L>>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut res: Vec<Vec<T::L>> = Vec::new();
+  loop {
+    match file_decompressor.chunk_decompressor::<T, _>(src)? {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        let n_latent_vars = chunk_decompressor.meta().mode.n_latent_vars();
+        if res.len() < n_latent_vars {
+          res.resize_with(n_latent_vars, Vec::new);
+        }
+        chunk_decompressor.decompress_raw_latents_extend(&mut res)?;
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        verify_checksum_if_present(file, rest)?;
+        break;
+      }
+    }
+  }
+  Ok(res)
+}
+
+/// Takes in compressed bytes and returns the numbers of only the chunk at
+/// `chunk_idx`.
+///
+/// Earlier chunks may be of any data type.
+/// Since standalone pages are not length-prefixed, earlier chunks still have
+/// to be decoded to find where the requested one begins, but their numbers
+/// are discarded batch by batch instead of being collected.
+/// For fast random access to chunks, consider storing their byte offsets
+/// alongside the file, or using the wrapped format.
+///
+/// Will return an error if there are any compatibility, corruption,
+/// or insufficient data issues, or if the file has no chunk at `chunk_idx`.
+/// The checksum, if present, is not verified.
+pub fn decompress_chunk<T: NumberLike>(file: &[u8], chunk_idx: usize) -> PcoResult<Vec<T>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  for n_chunks in 0..chunk_idx {
+    match validation::skip_chunk(&file_decompressor, src)? {
+      Some((_, rest)) => src = rest,
+      None => return Err(chunk_idx_out_of_range(chunk_idx, n_chunks)),
+    }
+  }
+
+  match file_decompressor.chunk_decompressor(src)? {
+    MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+      let mut res = Vec::with_capacity(chunk_decompressor.n());
+      chunk_decompressor.decompress_remaining_extend(&mut res)?;
+      Ok(res)
+    }
+    MaybeChunkDecompressor::EndOfData(_) => Err(chunk_idx_out_of_range(chunk_idx, chunk_idx)),
+  }
+}
+
+/// Takes in compressed bytes and returns a vector of numbers cast to
+/// `Target`, whatever data type they were compressed as.
+///
+/// This is useful e.g. for reading numbers stored as `f64` when only `f32`
+/// precision is needed downstream.
+/// Numbers are decoded in their native type one batch at a time and cast as
+/// they are collected, so the file is never decoded into a full buffer of
+/// the native type.
+/// Casts to integer types are checked, so every number must be exactly
+/// representable in `Target`; casts to float types round to the nearest
+/// representable value.
+/// See [`CastTarget`] for details.
+///
+/// Will return an InvalidArgument error if any number doesn't fit in
+/// `Target` or is of a data type that can't be cast, and otherwise an error
+/// if there are any compatibility, corruption, or insufficient data issues,
+/// or if the file has a checksum that does not match.
+pub fn decompress_as<Target: CastTarget>(file: &[u8]) -> PcoResult<Vec<Target>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut res = Vec::with_capacity(file_decompressor.n_hint());
+  loop {
+    let byte = u8::from(file_decompressor.peek_dtype_or_termination(src)?);
+    macro_rules! decompress_chunk_as_dtypes {
+      {$($name:literal => $t:ty,)+} => {
+        match byte {
+          $(<$t>::DTYPE_BYTE => decompress_chunk_as::<$t, Target>(&file_decompressor, src, &mut res)?,)+
+          MAGIC_TERMINATION_BYTE => break,
+          _ => return Err(PcoError::invalid_argument(format!(
+            "cannot cast numbers with data type byte {} to {}",
+            byte,
+            std::any::type_name::<Target>(),
+          ))),
+        }
+      };
+    }
+    src = with_castable_dtypes!(decompress_chunk_as_dtypes);
+  }
+
+  match file_decompressor.chunk_decompressor::<Target, _>(src)? {
+    MaybeChunkDecompressor::EndOfData(rest) => {
+      verify_checksum_if_present(file, rest)?;
+    }
+    MaybeChunkDecompressor::Some(_) => unreachable!("peeked a termination byte"),
   }
   Ok(res)
 }
 
+fn decompress_chunk_as<'a, Source: CastSource, Target: CastTarget>(
+  file_decompressor: &FileDecompressor,
+  src: &'a [u8],
+  res: &mut Vec<Target>,
+) -> PcoResult<&'a [u8]> {
+  match file_decompressor.chunk_decompressor::<Source, _>(src)? {
+    MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+      res.reserve(chunk_decompressor.n());
+      let mut batch = vec![Source::default(); FULL_BATCH_N];
+      loop {
+        let progress = chunk_decompressor.decompress(&mut batch)?;
+        for &x in &batch[..progress.n_processed] {
+          let Some(cast) = x.cast_into::<Target>() else {
+            return Err(PcoError::invalid_argument(format!(
+              "number {} at index {} does not fit in {}",
+              x,
+              res.len(),
+              std::any::type_name::<Target>(),
+            )));
+          };
+          res.push(cast);
+        }
+        if progress.finished {
+          break;
+        }
+      }
+      Ok(chunk_decompressor.into_src())
+    }
+    MaybeChunkDecompressor::EndOfData(_) => unreachable!("peeked a data type byte"),
+  }
+}
+
+fn chunk_idx_out_of_range(chunk_idx: usize, n_chunks: usize) -> PcoError {
+  PcoError::invalid_argument(format!(
+    "chunk index {} is out of range for file with {} chunks",
+    chunk_idx, n_chunks,
+  ))
+}
+
+// `rest` is the bytes after the file's termination byte, which begin with
+// the checksum trailer if there is one. Returns the bytes after the trailer.
+pub(crate) fn verify_checksum_if_present<'a>(file: &[u8], rest: &'a [u8]) -> PcoResult<&'a [u8]> {
+  match checksum::parse_trailer(rest)? {
+    Some(expected) => {
+      let footer_start = file.len() - rest.len() - 1;
+      checksum::verify(&file[..footer_start], expected)?;
+      Ok(&rest[CHECKSUM_TRAILER_BYTES..])
+    }
+    None => Ok(rest),
+  }
+}
+
 #[cfg(test)]
 mod tests {
+  use crate::data_types::Latent;
+  use crate::errors::ErrorKind;
+  use crate::IntMultSpec;
+
   use super::*;
 
   #[test]
@@ -165,4 +849,473 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_equal_pages_fewer_numbers_than_pages() -> PcoResult<()> {
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPages(4));
+    for n in [0, 1, 3] {
+      let nums = (0..n).collect::<Vec<u32>>();
+      let compressed = simple_compress(&nums, &config)?;
+      assert_eq!(simple_decompress::<u32>(&compressed)?, nums);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_chunk() -> PcoResult<()> {
+    let nums = (0..1000).map(|x| x as u64 * 3).collect::<Vec<u64>>();
+    let src = simple_compress(
+      &nums,
+      &ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![300, 1, 699])),
+    )?;
+
+    assert_eq!(
+      decompress_chunk::<u64>(&src, 0)?,
+      &nums[..300]
+    );
+    assert_eq!(
+      decompress_chunk::<u64>(&src, 1)?,
+      &nums[300..301]
+    );
+    assert_eq!(
+      decompress_chunk::<u64>(&src, 2)?,
+      &nums[301..]
+    );
+    for chunk_idx in [3, 4] {
+      let err = decompress_chunk::<u64>(&src, chunk_idx).unwrap_err();
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+      assert!(err.to_string().contains("with 3 chunks"));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_recoverable() -> PcoResult<()> {
+    let nums = (0..3000).map(|x| x as f32).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![1000; 3]));
+    let src = simple_compress(&nums, &config)?;
+
+    let (recovered, err) = decompress_recoverable::<f32>(&src);
+    assert_eq!(recovered, nums);
+    assert!(err.is_none());
+
+    // cut off partway through the last chunk
+    let (recovered, err) = decompress_recoverable::<f32>(&src[..src.len() - 20]);
+    assert_eq!(recovered, &nums[..2000]);
+    assert!(matches!(
+      err.unwrap().kind,
+      ErrorKind::InsufficientData
+    ));
+
+    // cut off within the header
+    let (recovered, err) = decompress_recoverable::<f32>(&src[..3]);
+    assert!(recovered.is_empty());
+    assert!(err.is_some());
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_limited() -> PcoResult<()> {
+    let nums = (0..2500).map(|x| x * 3).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![1000, 1000, 500]));
+    let src = simple_compress(&nums, &config)?;
+    for limit in [0, 1, 300, 999, 1000, 1001, 2500, 10000] {
+      let decompressed = decompress_limited::<i64>(&src, limit)?;
+      assert_eq!(
+        decompressed,
+        &nums[..min(limit, nums.len())],
+        "limit={}",
+        limit
+      );
+    }
+
+    // later chunks are never read, so cutting them off doesn't matter
+    let (fd, rest) = FileDecompressor::new(src.as_slice())?;
+    let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<i64, _>(rest)? else {
+      panic!("expected a chunk");
+    };
+    cd.decompress_remaining_extend(&mut Vec::new())?;
+    let first_chunk_end = src.len() - cd.into_src().len();
+    let truncated = &src[..first_chunk_end];
+    assert_eq!(
+      decompress_limited::<i64>(truncated, 700)?,
+      &nums[..700]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_compress_with_overrides() -> PcoResult<()> {
+    let nums = (0..1000).map(|x| x * x).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(2));
+    let overrides = ChunkConfigOverrides::default().with_delta_encoding_order(Some(0));
+    let compressed = compress_with_overrides(&nums, &config, &overrides)?;
+    assert_eq!(
+      compressed,
+      simple_compress(
+        &nums,
+        &config.clone().with_delta_encoding_order(Some(0))
+      )?,
+    );
+    assert_eq!(simple_decompress::<i64>(&compressed)?, nums);
+    // the shared config is untouched
+    assert_eq!(config.delta_encoding_order, Some(2));
+
+    let overrides = ChunkConfigOverrides::default().with_compression_level(100);
+    let err = compress_with_overrides(&nums, &config, &overrides).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_as() -> PcoResult<()> {
+    let floats = (0..3000).map(|x| x as f64 * 0.1).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let src = simple_compress(&floats, &config)?;
+    assert_eq!(
+      decompress_as::<f32>(&src)?,
+      floats.iter().map(|&x| x as f32).collect::<Vec<_>>(),
+    );
+
+    let ints = (0..3000).map(|x| x * 20 - 1000).collect::<Vec<i64>>();
+    let src = simple_compress(&ints, &config)?;
+    assert_eq!(
+      decompress_as::<i32>(&src)?,
+      ints.iter().map(|&x| x as i32).collect::<Vec<_>>(),
+    );
+    assert_eq!(decompress_as::<f64>(&src)?[3], -940.0);
+    // the first number is negative and the last is too large
+    let err = decompress_as::<u16>(&src).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    let err = decompress_as::<i16>(&src).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    assert!(err.message.contains("index 1689"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_decompress_with_stats() -> PcoResult<()> {
+    // a chunk of two clusters, then a constant chunk
+    let mut nums = (0..2000)
+      .map(|x| (x % 2) * 1_000_000 + (x * 7) % 100)
+      .collect::<Vec<u32>>();
+    nums.extend([5; 500]);
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(0))
+      .with_paging_spec(PagingSpec::Exact(vec![2000, 500]));
+    let src = simple_compress(&nums, &config)?;
+
+    let (decompressed, stats) = simple_decompress_with_stats::<u32>(&src)?;
+    assert_eq!(decompressed, nums);
+    assert_eq!(stats.bin_counts_per_chunk.len(), 2);
+    assert_eq!(stats.bin_counts_per_chunk[1], vec![500]);
+
+    let (fd, rest) = FileDecompressor::new(src.as_slice())?;
+    let MaybeChunkDecompressor::Some(cd) = fd.chunk_decompressor::<u32, _>(rest)? else {
+      panic!("expected a chunk");
+    };
+    let bins = &cd.meta().per_latent_var[0].bins;
+    let bin_counts = &stats.bin_counts_per_chunk[0];
+    assert_eq!(bin_counts.len(), bins.len());
+    assert!(bins.len() >= 2);
+    assert_eq!(bin_counts.iter().sum::<usize>(), 2000);
+    let expected_offset_bits = bins
+      .iter()
+      .zip(bin_counts)
+      .map(|(bin, &count)| bin.offset_bits as usize * count)
+      .sum::<usize>();
+    assert_eq!(stats.offset_bits, expected_offset_bits);
+    Ok(())
+  }
+
+  #[test]
+  fn test_uniform_offset_batches_despite_mixed_bins() -> PcoResult<()> {
+    // a slowly drifting series: small jitter for a while, then large jitter
+    let nums = (0..4096_u32)
+      .map(|x| {
+        let jitter = (x * 7919) % 65536;
+        if x < 2048 {
+          jitter % 16
+        } else {
+          1_000_000 + jitter
+        }
+      })
+      .collect::<Vec<u32>>();
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(0))
+      .with_int_mult_spec(IntMultSpec::Disabled);
+    let src = simple_compress(&nums, &config)?;
+
+    let (fd, rest) = FileDecompressor::new(src.as_slice())?;
+    let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<u32, _>(rest)? else {
+      panic!("expected a chunk");
+    };
+    let bins = &cd.meta().per_latent_var[0].bins;
+    assert!(bins
+      .iter()
+      .any(|bin| bin.offset_bits != bins[0].offset_bits));
+    cd.enable_stats();
+    let mut decompressed = Vec::new();
+    cd.decompress_remaining_extend(&mut decompressed)?;
+    assert_eq!(decompressed, nums);
+    // each batch draws from only one bin, so every one takes the SIMD path
+    assert_eq!(
+      cd.stats()[0].uniform_offset_batches,
+      nums.len() / FULL_BATCH_N
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_compress_with_report() -> PcoResult<()> {
+    let nums = (0..3000)
+      .map(|x| x * 100 + (x * x * 7) % 1000)
+      .collect::<Vec<u32>>();
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(1))
+      .with_int_mult_spec(IntMultSpec::Disabled)
+      .with_paging_spec(PagingSpec::Exact(vec![1000, 2000]));
+    let (compressed, report) = compress_with_report(&nums, &config)?;
+    assert_eq!(compressed, simple_compress(&nums, &config)?);
+    assert_eq!(report.total_bits(), 8 * compressed.len());
+
+    // each of the 2 chunks stores one 32-bit delta moment
+    assert_eq!(report.delta_moment_bits, 2 * 32);
+    assert!(report.ans_bits > 0);
+    assert!(report.chunk_meta_bits > 0);
+    let (_, stats) = simple_decompress_with_stats::<u32>(&compressed)?;
+    assert_eq!(report.offset_bits, stats.offset_bits);
+
+    // metadata dominates a tiny chunk
+    let (compressed, report) = compress_with_report(&[1_u64, 2, 3], &ChunkConfig::default())?;
+    assert_eq!(report.total_bits(), 8 * compressed.len());
+    assert!(report.chunk_meta_bits > report.ans_bits + report.offset_bits);
+    Ok(())
+  }
+
+  #[cfg(feature = "bench")]
+  #[test]
+  fn test_compress_timed() -> PcoResult<()> {
+    use std::time::Duration;
+
+    let nums = (0..2000).map(|i| (i * i) % 1009).collect::<Vec<u32>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let (compressed, timings) = compress_timed(&nums, &config)?;
+    assert_eq!(compressed, simple_compress(&nums, &config)?);
+    assert!(timings.bin_building > Duration::ZERO);
+    assert!(timings.ans_encoding > Duration::ZERO);
+    assert!(timings.offset_writing > Duration::ZERO);
+    assert_eq!(
+      timings.total(),
+      timings.bin_building + timings.ans_encoding + timings.offset_writing + timings.other,
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_compress_with_report_trivial() -> PcoResult<()> {
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let (_, report) = compress_with_report(&vec![7_i32; 3000], &config)?;
+    assert_eq!(report.n_latent_vars, 3);
+    assert_eq!(report.n_trivial_latent_vars, 3);
+    assert!(report.is_all_trivial());
+    let err = report.ensure_nontrivial().unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+
+    let nums = (0..3000).map(|x| (x * 7919) % 1000).collect::<Vec<i32>>();
+    let (_, report) = compress_with_report(&nums, &config)?;
+    assert_eq!(report.n_trivial_latent_vars, 0);
+    report.ensure_nontrivial()?;
+
+    // no latent variables at all isn't suspicious
+    let (_, report) = compress_with_report::<i32>(&[], &config)?;
+    assert!(!report.is_all_trivial());
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_raw_latents() -> PcoResult<()> {
+    // without delta encoding, the latents are just the numbers
+    let nums = (0..700).map(|x| x * 7 % 1000).collect::<Vec<u32>>();
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(0))
+      .with_paging_spec(PagingSpec::Exact(vec![300, 400]));
+    let src = simple_compress(&nums, &config)?;
+    assert_eq!(
+      decompress_raw_latents::<u32>(&src)?,
+      vec![nums]
+    );
+
+    // with delta encoding, each chunk's first latent is held in its delta
+    // moments, and the rest are centered deltas
+    let nums = (0..700).map(|x| x * 3).collect::<Vec<u32>>();
+    let config = config
+      .with_delta_encoding_order(Some(1))
+      .with_int_mult_spec(IntMultSpec::Disabled);
+    let src = simple_compress(&nums, &config)?;
+    assert_eq!(
+      decompress_raw_latents::<u32>(&src)?,
+      vec![vec![u32::MID + 3; 698]]
+    );
+
+    // int mult mode has a latent variable for the multiplier and one for the
+    // adjustment
+    let nums = (0..700).map(|x| x * 10 + x % 3).collect::<Vec<u64>>();
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(Some(0))
+      .with_int_mult_spec(IntMultSpec::Provided(10));
+    let src = simple_compress(&nums, &config)?;
+    assert_eq!(
+      decompress_raw_latents::<u64>(&src)?,
+      vec![
+        (0..700).collect::<Vec<u64>>(),
+        (0..700).map(|x| x % 3).collect::<Vec<u64>>(),
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_simple_compress_with_progress() -> PcoResult<()> {
+    let nums = (0..1000).collect::<Vec<u32>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![300, 300, 400]));
+    let mut progress = Vec::new();
+    let compressed = simple_compress_with_progress(&nums, &config, |n| progress.push(n))?;
+    assert_eq!(progress, vec![300, 600, 1000]);
+    assert_eq!(compressed, simple_compress(&nums, &config)?);
+    Ok(())
+  }
+
+  #[test]
+  fn test_decompress_for_each() -> PcoResult<()> {
+    let nums = (0..3000).map(|i| (i * 37) % 1001).collect::<Vec<i32>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(700));
+    let compressed = simple_compress(&nums, &config)?;
+    let mut res = Vec::new();
+    decompress_for_each(&compressed, |x: i32| res.push(x))?;
+    assert_eq!(res, nums);
+
+    let mut sum = 0_i64;
+    decompress_for_each(&compressed, |x: i32| sum += x as i64)?;
+    assert_eq!(
+      sum,
+      nums.iter().map(|&x| x as i64).sum::<i64>()
+    );
+
+    let err = decompress_for_each(
+      &compressed[..compressed.len() - 1],
+      |_: i32| (),
+    )
+    .unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_compress_strided() -> PcoResult<()> {
+    // interleaved (x, y, z) records
+    let base = (0..3000_i64)
+      .map(|i| (i % 3) * 1000 + i / 3)
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(300));
+    for offset in 0..3 {
+      let field = base[offset..]
+        .iter()
+        .step_by(3)
+        .cloned()
+        .collect::<Vec<_>>();
+      let compressed = compress_strided(&base, 3, offset, 1000, &config)?;
+      assert_eq!(compressed, simple_compress(&field, &config)?);
+      assert_eq!(simple_decompress::<i64>(&compressed)?, field);
+    }
+    let compressed = compress_strided(&base, 3, 2, 0, &config)?;
+    assert!(simple_decompress::<i64>(&compressed)?.is_empty());
+
+    for (stride, offset, len) in [(0, 0, 1), (3, 3, 1000), (3, 3001, 0), (usize::MAX, 0, 3)] {
+      let err = compress_strided(&base, stride, offset, len, &config).unwrap_err();
+      assert!(matches!(
+        err.kind,
+        ErrorKind::InvalidArgument
+      ));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_compress_from_iter() -> PcoResult<()> {
+    // filtering hides the length of the iterator
+    let nums = (0..2500_u32).filter(|x| x % 7 != 0);
+    let expected = nums.clone().collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+    let compressed = compress_from_iter(nums, &config)?;
+    assert_eq!(
+      simple_decompress::<u32>(&compressed)?,
+      expected
+    );
+    assert_eq!(
+      validation::count_elements(&compressed)?,
+      expected.len()
+    );
+
+    let (fd, mut src) = FileDecompressor::new(compressed.as_slice())?;
+    let mut chunk_ns = Vec::new();
+    while let MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<u32, _>(src)? {
+      let mut dst = vec![0; 1000];
+      chunk_ns.push(cd.decompress(&mut dst)?.n_processed);
+      src = cd.into_src();
+    }
+    assert_eq!(
+      chunk_ns,
+      vec![1000, 1000, expected.len() - 2000]
+    );
+
+    let empty = compress_from_iter(std::iter::empty::<u32>(), &config)?;
+    assert!(simple_decompress::<u32>(&empty)?.is_empty());
+
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPages(2));
+    let err = compress_from_iter(0..10_u32, &config).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+    Ok(())
+  }
+
+  #[cfg(feature = "rayon")]
+  #[test]
+  fn test_par_compress_matches_simple_compress() -> PcoResult<()> {
+    let nums = (0..10_000)
+      .map(|x| ((x * 7919) % 1013) as f64 / 4.0)
+      .collect::<Vec<f64>>();
+    for paging_spec in [
+      PagingSpec::EqualPagesUpTo(1 << 16),
+      PagingSpec::EqualPages(7),
+      PagingSpec::Exact(vec![1, 4999, 5000]),
+    ] {
+      let config = ChunkConfig::default().with_paging_spec(paging_spec);
+      let expected = simple_compress(&nums, &config)?;
+      let compressed = par_compress(&nums, &config)?;
+      assert_eq!(compressed, expected);
+      assert_eq!(simple_decompress::<f64>(&compressed)?, nums);
+    }
+    Ok(())
+  }
 }
@@ -0,0 +1,138 @@
+use std::io;
+use std::io::Write;
+
+use crate::chunk_config::ChunkConfig;
+use crate::data_types::NumberLike;
+use crate::errors::PcoResult;
+use crate::standalone::checksum::Crc32c;
+use crate::standalone::compressor::FileCompressor;
+
+// passes bytes through to the inner `Write`, checksumming them if needed
+struct ChecksumWriter<W: Write> {
+  inner: W,
+  crc: Option<Crc32c>,
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let n = self.inner.write(buf)?;
+    if let Some(crc) = &mut self.crc {
+      crc.update(&buf[..n]);
+    }
+    Ok(n)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+/// Compresses a standalone .pco file incrementally into a `Write`, one chunk
+/// at a time.
+///
+/// Example:
+/// ```
+/// use pco::ChunkConfig;
+/// use pco::standalone::{simple_decompress, FileCompressor, StreamCompressor};
+/// # use pco::errors::PcoResult;
+///
+/// # fn main() -> PcoResult<()> {
+/// let mut compressor = StreamCompressor::new(
+///   FileCompressor::default(),
+///   ChunkConfig::default(),
+///   Vec::new(),
+/// )?;
+/// for batch in [vec![1, 2, 3], vec![4, 5]] {
+///   compressor.write_chunk::<i64>(&batch)?;
+/// }
+/// let compressed = compressor.finish()?;
+/// assert_eq!(simple_decompress::<i64>(&compressed)?, vec![1, 2, 3, 4, 5]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Each call to [`write_chunk`][Self::write_chunk] fully compresses and
+/// writes one chunk before returning, so the memory high-water mark is
+/// determined by the largest chunk rather than the whole file.
+/// While compressing a chunk, pco holds its latents and their encodings,
+/// which take a few times the chunk's uncompressed size; all of this is freed
+/// once the chunk is written.
+/// The output goes through small fixed-size buffers, so no compressed chunk
+/// is ever held in memory in full.
+pub struct StreamCompressor<W: Write> {
+  file_compressor: FileCompressor,
+  config: ChunkConfig,
+  dst: ChecksumWriter<W>,
+}
+
+impl<W: Write> StreamCompressor<W> {
+  /// Writes the header to the destination and returns a `StreamCompressor`
+  /// that will compress every chunk with the given config.
+  ///
+  /// The config's `PagingSpec` is ignored, since each chunk of a standalone
+  /// file has exactly one page.
+  /// If the file compressor has checksums enabled, a checksum of everything
+  /// written is included in the footer.
+  /// The file's format version is raised as needed for the config.
+  ///
+  /// Will return an error if the provided `Write` errors.
+  pub fn new(file_compressor: FileCompressor, config: ChunkConfig, dst: W) -> PcoResult<Self> {
+    let file_compressor = file_compressor.with_format_version_for(&config);
+    let crc = if file_compressor.has_checksum() {
+      Some(Crc32c::default())
+    } else {
+      None
+    };
+    let mut dst = ChecksumWriter { inner: dst, crc };
+    file_compressor.write_header(&mut dst)?;
+    Ok(Self {
+      file_compressor,
+      config,
+      dst,
+    })
+  }
+
+  /// Compresses the numbers and writes them to the destination as a
+  /// single chunk.
+  ///
+  /// Will return an error if the config is invalid, if there are no numbers
+  /// or too many for one chunk, or if the provided `Write` errors.
+  pub fn write_chunk<T: NumberLike>(&mut self, nums: &[T]) -> PcoResult<()> {
+    self.write_chunk_with_level(nums, self.config.compression_level)
+  }
+
+  /// Like [`write_chunk`][Self::write_chunk], but compresses this chunk with
+  /// the given compression level instead of the config's.
+  ///
+  /// Each chunk's metadata fully describes how it was compressed, so levels
+  /// can vary freely from chunk to chunk, e.g. to spend more effort only on
+  /// the chunks where it pays off.
+  ///
+  /// Will return an error if the level or config is invalid, if there are no
+  /// numbers or too many for one chunk, or if the provided `Write` errors.
+  pub fn write_chunk_with_level<T: NumberLike>(
+    &mut self,
+    nums: &[T],
+    compression_level: usize,
+  ) -> PcoResult<()> {
+    let config = ChunkConfig {
+      compression_level,
+      ..self.config.clone()
+    };
+    let chunk_compressor = self.file_compressor.chunk_compressor(nums, &config)?;
+    chunk_compressor.write_chunk(&mut self.dst)?;
+    Ok(())
+  }
+
+  /// Writes the footer to the destination, returning it.
+  ///
+  /// Will return an error if the provided `Write` errors.
+  pub fn finish(self) -> PcoResult<W> {
+    let ChecksumWriter { mut inner, crc } = self.dst;
+    self
+      .file_compressor
+      .write_footer_with_crc(crc.map(Crc32c::finish), &mut inner)?;
+    inner.flush()?;
+    Ok(inner)
+  }
+}
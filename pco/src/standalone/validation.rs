@@ -0,0 +1,294 @@
+use crate::data_types::{with_all_dtypes, Latent, NumberLike};
+use crate::errors::{PcoError, PcoResult};
+use crate::mode::Mode;
+use crate::standalone::columns;
+use crate::standalone::constants::MAGIC_TERMINATION_BYTE;
+use crate::standalone::decompressor::{FileDecompressor, MaybeChunkDecompressor};
+use crate::standalone::simple::verify_checksum_if_present;
+use crate::FULL_BATCH_N;
+
+/// A summary of a standalone file, as returned by [`validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FileStats<L: Latent> {
+  /// The total count of numbers in the file.
+  pub n: usize,
+  /// The count of numbers in each chunk, in order.
+  pub n_per_chunk: Vec<usize>,
+  /// The mode of each chunk, in order.
+  pub modes: Vec<Mode<L>>,
+}
+
+impl<L: Latent> FileStats<L> {
+  /// Returns the count of chunks in the file.
+  pub fn n_chunks(&self) -> usize {
+    self.n_per_chunk.len()
+  }
+}
+
+/// Checks that the compressed bytes are a complete, well-formed standalone
+/// file of numbers of type `T`, returning a summary of its contents.
+///
+/// This does everything [`simple_decompress`][crate::standalone::simple_decompress]
+/// does except keep the numbers: all metadata is parsed, every page is fully
+/// decoded, and the checksum is verified if present.
+/// Since pages are not length-prefixed, decoding them is the only way to find
+/// where each one ends and to check its entropy coding.
+/// But numbers are decoded one batch at a time into a reusable buffer, so
+/// memory usage does not grow with the size of the file.
+///
+/// Will return an error if there are any compatibility, corruption, or
+/// insufficient data issues, if the checksum does not match, or if there are
+/// any bytes after the end of the file other than a column index written by
+/// [`compress_columns`][crate::standalone::compress_columns] or
+/// [`compress_records`][crate::standalone::compress_records].
+pub fn validate<T: NumberLike>(file: &[u8]) -> PcoResult<FileStats<T::L>> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut batch = vec![T::default(); FULL_BATCH_N];
+  let mut stats = FileStats {
+    n: 0,
+    n_per_chunk: Vec::new(),
+    modes: Vec::new(),
+  };
+  loop {
+    let chunk_idx = stats.n_per_chunk.len();
+    let chunk_start = file.len() - src.len();
+    let locate = |err: PcoError| err.in_chunk(chunk_idx, chunk_start);
+    match file_decompressor
+      .chunk_decompressor::<T, _>(src)
+      .map_err(locate)?
+    {
+      MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+        stats.n += chunk_decompressor.n();
+        stats.n_per_chunk.push(chunk_decompressor.n());
+        stats.modes.push(chunk_decompressor.meta().mode);
+        while !chunk_decompressor
+          .decompress(&mut batch)
+          .map_err(locate)?
+          .finished
+        {}
+        src = chunk_decompressor.into_src();
+      }
+      MaybeChunkDecompressor::EndOfData(rest) => {
+        let rest = verify_checksum_if_present(file, rest)?;
+        if !rest.is_empty() && !columns::is_trailing_column_index(rest, stats.n) {
+          return Err(PcoError::corruption(format!(
+            "found {} unexpected bytes after the end of the file",
+            rest.len(),
+          )));
+        }
+        return Ok(stats);
+      }
+    }
+  }
+}
+
+fn skip_chunk_of_dtype<'a, T: NumberLike>(
+  file_decompressor: &FileDecompressor,
+  src: &'a [u8],
+) -> PcoResult<(usize, &'a [u8])> {
+  match file_decompressor.chunk_decompressor::<T, _>(src)? {
+    MaybeChunkDecompressor::Some(mut chunk_decompressor) => {
+      let mut batch = vec![T::default(); FULL_BATCH_N];
+      while !chunk_decompressor.decompress(&mut batch)?.finished {}
+      Ok((
+        chunk_decompressor.n(),
+        chunk_decompressor.into_src(),
+      ))
+    }
+    MaybeChunkDecompressor::EndOfData(_) => unreachable!("peeked a data type byte"),
+  }
+}
+
+// Decodes and discards the next chunk's numbers, whatever their data type,
+// returning its count of numbers and the rest of the source, or None if the
+// file has ended.
+pub(crate) fn skip_chunk<'a>(
+  file_decompressor: &FileDecompressor,
+  src: &'a [u8],
+) -> PcoResult<Option<(usize, &'a [u8])>> {
+  let byte = u8::from(file_decompressor.peek_dtype_or_termination(src)?);
+  macro_rules! skip_chunk_of_dtypes {
+    {$($(#[$attr:meta])* $name:literal => $t:ty,)+} => {
+      match byte {
+        $(
+          $(#[$attr])*
+          <$t>::DTYPE_BYTE => skip_chunk_of_dtype::<$t>(file_decompressor, src).map(Some),
+        )+
+        MAGIC_TERMINATION_BYTE => Ok(None),
+        _ => Err(PcoError::compatibility(format!(
+          "unrecognized data type byte {}",
+          byte,
+        ))),
+      }
+    };
+  }
+  with_all_dtypes!(skip_chunk_of_dtypes)
+}
+
+/// Returns the total count of numbers in a standalone file, without needing
+/// to know their data type.
+///
+/// This is useful for allocating exactly enough memory to decompress into.
+/// The count of numbers in each chunk is read from its metadata, but since
+/// pages are not length-prefixed, each page must still be decoded to find
+/// where the next chunk begins.
+/// As in [`validate`], numbers are decoded one batch at a time and
+/// discarded, so this is faster than decompressing and uses little memory.
+/// Even cheaper, but only a hint,
+/// [`FileDecompressor::n_hint`][crate::standalone::FileDecompressor::n_hint]
+/// is read from the file header alone.
+///
+/// Will return an error if there are any compatibility, corruption, or
+/// insufficient data issues, including chunks of unrecognized data types.
+/// Unlike `validate`, this does not check the checksum or trailing bytes.
+pub fn count_elements(file: &[u8]) -> PcoResult<usize> {
+  let (file_decompressor, mut src) = FileDecompressor::new(file)?;
+
+  let mut n = 0;
+  while let Some((chunk_n, rest)) = skip_chunk(&file_decompressor, src)? {
+    n += chunk_n;
+    src = rest;
+  }
+  Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+  use half::bf16;
+
+  use crate::constants::CURRENT_FORMAT_VERSION;
+  use crate::errors::ErrorKind;
+  use crate::format_version::FormatVersion;
+  use crate::standalone::{simple_compress, simple_decompress, FileCompressor, StreamCompressor};
+  use crate::{ChunkConfig, PagingSpec};
+
+  use super::*;
+
+  #[test]
+  fn test_validate() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| (i * 10) as i64).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![600, 400]));
+    let compressed = simple_compress(&nums, &config)?;
+
+    let stats = validate::<i64>(&compressed)?;
+    assert_eq!(stats.n, 1000);
+    assert_eq!(stats.n_chunks(), 2);
+    assert_eq!(stats.n_per_chunk, vec![600, 400]);
+    assert_eq!(stats.modes.len(), 2);
+
+    // truncated
+    let err = validate::<i64>(&compressed[..compressed.len() - 1]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+
+    // trailing garbage
+    let mut extended = compressed.clone();
+    extended.push(7);
+    let err = validate::<i64>(&extended).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+
+    // wrong data type
+    let err = validate::<u32>(&compressed).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    Ok(())
+  }
+
+  #[test]
+  fn test_count_elements() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i as f32).collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![600, 399, 1]));
+    let compressed = simple_compress(&nums, &config)?;
+    assert_eq!(count_elements(&compressed)?, 1000);
+
+    // the count doesn't rely on the header's hint
+    let fc = FileCompressor::default();
+    let mut compressed = Vec::new();
+    fc.write_header(&mut compressed)?;
+    fc.chunk_compressor(&[1_u16, 2, 3], &config)?
+      .write_chunk(&mut compressed)?;
+    fc.chunk_compressor(&[bf16::ONE; 5], &config)?
+      .write_chunk(&mut compressed)?;
+    let no_chunks_len = compressed.len();
+    fc.write_footer(&mut compressed)?;
+    assert_eq!(count_elements(&compressed)?, 8);
+
+    let err = count_elements(&compressed[..no_chunks_len]).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InsufficientData
+    ));
+
+    let mut empty = Vec::new();
+    fc.write_header(&mut empty)?;
+    fc.write_footer(&mut empty)?;
+    assert_eq!(count_elements(&empty)?, 0);
+    Ok(())
+  }
+
+  #[test]
+  fn test_validate_checksum() -> PcoResult<()> {
+    let nums = (0..300).map(|i| i as f32).collect::<Vec<_>>();
+    let mut compressor = StreamCompressor::new(
+      FileCompressor::default().with_checksum(true),
+      ChunkConfig::default(),
+      Vec::new(),
+    )?;
+    compressor.write_chunk(&nums)?;
+    let mut compressed = compressor.finish()?;
+    assert_eq!(validate::<f32>(&compressed)?.n, 300);
+
+    let last = compressed.len() - 1;
+    compressed[last] ^= 1;
+    let err = validate::<f32>(&compressed).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::ChecksumMismatch
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn test_corruption_names_chunk() -> PcoResult<()> {
+    let nums = (0..1000).map(|i| i * i).collect::<Vec<i64>>();
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(1));
+    // float xor mode needs the current format version
+    let file_compressor =
+      FileCompressor::default().with_format_version(FormatVersion(CURRENT_FORMAT_VERSION));
+    let mut compressed = file_compressor.write_header(Vec::new())?;
+    for chunk in nums.chunks(500) {
+      compressed = file_compressor
+        .chunk_compressor(chunk, &config)?
+        .write_chunk(compressed)?;
+    }
+    compressed = file_compressor.write_footer(compressed)?;
+    // the header and first chunk of a file are the same whatever follows
+    let chunk_1_start = file_compressor
+      .chunk_compressor(&nums[..500], &config)?
+      .write_chunk(file_compressor.write_header(Vec::new())?)?
+      .len();
+
+    // after the data type byte and count, the chunk metadata begins with its
+    // mode, which we change to float xor; that doesn't allow its delta order
+    let meta_start = chunk_1_start + 4;
+    compressed[meta_start] = (compressed[meta_start] & 0xf0) | 4;
+    let err = validate::<i64>(&compressed).unwrap_err();
+    assert!(matches!(err.kind, ErrorKind::Corruption));
+    assert!(
+      err.message.ends_with(&format!(
+        "at bit 4 (byte 0) of the chunk metadata (in chunk 1 starting at byte {} of the file)",
+        chunk_1_start,
+      )),
+      "{}",
+      err.message,
+    );
+    assert_eq!(
+      simple_decompress::<i64>(&compressed).unwrap_err(),
+      err
+    );
+    Ok(())
+  }
+}
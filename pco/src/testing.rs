@@ -0,0 +1,102 @@
+use crate::data_types::NumberLike;
+use crate::standalone::{simple_compress, simple_decompress};
+use crate::ChunkConfig;
+
+/// Compresses `nums` with `config`, decompresses them, and panics unless the
+/// result is bitwise identical.
+///
+/// Numbers are compared by their latents rather than by `==`, so NaNs and
+/// signed zeros must also round trip exactly.
+/// On a mismatch, the panic message gives the first divergent index along
+/// with both numbers.
+/// This also recompresses the decompressed numbers and panics unless the
+/// bytes are identical to the first compression, since compression should be
+/// deterministic.
+///
+/// Lossy configs, such as
+/// [`FloatMultSpec::Lossy`][crate::FloatMultSpec::Lossy], are not expected
+/// to round trip and will usually panic.
+/// Also panics if compression or decompression returns an error.
+pub fn assert_roundtrip<T: NumberLike>(nums: &[T], config: &ChunkConfig) {
+  let compressed = simple_compress(nums, config).unwrap_or_else(|e| {
+    panic!(
+      "compression of {} numbers failed: {}",
+      nums.len(),
+      e
+    )
+  });
+  let decompressed = simple_decompress::<T>(&compressed).unwrap_or_else(|e| {
+    panic!(
+      "decompression of {} compressed bytes failed: {}",
+      compressed.len(),
+      e
+    )
+  });
+
+  if let Some(i) = nums
+    .iter()
+    .zip(&decompressed)
+    .position(|(x, y)| x.to_latent_ordered() != y.to_latent_ordered())
+  {
+    panic!(
+      "decompressed numbers diverge at index {} of {}: expected {:?} but got {:?}",
+      i,
+      nums.len(),
+      nums[i],
+      decompressed[i],
+    );
+  }
+  assert_eq!(
+    decompressed.len(),
+    nums.len(),
+    "decompressed the wrong count of numbers",
+  );
+
+  let recompressed = simple_compress(&decompressed, config)
+    .unwrap_or_else(|e| panic!("recompression failed: {}", e));
+  if let Some(i) = compressed
+    .iter()
+    .zip(&recompressed)
+    .position(|(x, y)| x != y)
+  {
+    panic!(
+      "recompressed bytes diverge at byte {} of {}",
+      i,
+      compressed.len()
+    );
+  }
+  assert_eq!(
+    recompressed.len(),
+    compressed.len(),
+    "recompressed to a different number of bytes",
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{FloatMultSpec, PagingSpec};
+
+  use super::*;
+
+  #[test]
+  fn test_assert_roundtrip() {
+    let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(700));
+    let floats = (0..2000)
+      .map(|i| match i % 100 {
+        0 => f32::NAN,
+        1 => -0.0,
+        _ => i as f32 * 0.1,
+      })
+      .collect::<Vec<_>>();
+    assert_roundtrip(&floats, &config);
+    assert_roundtrip(&[] as &[u16], &config);
+    assert_roundtrip(&[i64::MIN, 0, i64::MAX], &config);
+  }
+
+  #[test]
+  #[should_panic(expected = "diverge at index 1 of 3")]
+  fn test_assert_roundtrip_lossy() {
+    let config = ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Lossy(0.5));
+    assert_roundtrip(&[0.0_f64, 1.1, 2.0], &config);
+  }
+}
@@ -0,0 +1,63 @@
+// Compressing the same numbers with the same config must always produce the
+// same bytes, even though mode and base selection sample the data and hash
+// their candidates.
+
+use crate::data_types::NumberLike;
+use crate::errors::PcoResult;
+use crate::standalone::simple_compress;
+use crate::{AutoLevelSpec, ChunkConfig, PagingSpec};
+
+// Each simple_compress call builds new HashMaps with fresh random hash keys,
+// so repeating it exercises different iteration orders.
+fn assert_deterministic<T: NumberLike>(nums: &[T], config: &ChunkConfig) -> PcoResult<()> {
+  let expected = simple_compress(nums, config)?;
+  for _ in 0..8 {
+    assert_eq!(simple_compress(nums, config)?, expected);
+  }
+  #[cfg(feature = "rayon")]
+  assert_eq!(
+    crate::standalone::par_compress(nums, config)?,
+    expected
+  );
+  Ok(())
+}
+
+#[test]
+fn test_deterministic_int_mult() -> PcoResult<()> {
+  // multiples of 6 and 10 give many competing candidate bases
+  let nums = (0..3000_u64)
+    .map(|i| {
+      let x = (i * 7919) % 1000;
+      if i % 2 == 0 {
+        x * 6
+      } else {
+        x * 10 + 1
+      }
+    })
+    .collect::<Vec<_>>();
+  assert_deterministic(&nums, &ChunkConfig::default())
+}
+
+#[test]
+fn test_deterministic_float_mult_and_dict() -> PcoResult<()> {
+  let floats = (0..3000)
+    .map(|i| ((i * 7919) % 1000) as f64 * 0.01)
+    .collect::<Vec<_>>();
+  assert_deterministic(&floats, &ChunkConfig::default())?;
+
+  let dict = (0..3000_i64)
+    .map(|i| [-7, 1_i64 << 40, 12345, 3][(i * i % 7) as usize % 4])
+    .collect::<Vec<_>>();
+  assert_deterministic(&dict, &ChunkConfig::default())
+}
+
+#[test]
+fn test_deterministic_auto_level() -> PcoResult<()> {
+  let nums = (0..5000_i32)
+    .map(|i| (i * 7919) % 2000 - i / 3)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_auto_level_spec(AutoLevelSpec::Enabled)
+    .with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+  assert_deterministic(&nums, &config)
+}
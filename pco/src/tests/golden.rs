@@ -0,0 +1,282 @@
+// Golden tests pin the exact bytes pco writes for a fixed set of inputs, so
+// any change to the wire format fails loudly, even a backward-compatible one
+// that the compatibility tests would miss.
+// CI also runs them on a big-endian target, so they catch any dependence on
+// the host's byte order.
+//
+// When a format change is intentional, regenerate the goldens with
+//   PCO_REGEN_GOLDENS=1 cargo test -p pco golden
+// and check in the updated files under assets/golden along with the change.
+
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use half::f16;
+
+use crate::data_types::NumberLike;
+use crate::errors::PcoResult;
+use crate::standalone::{self, FileDecompressor, MaybeChunkDecompressor};
+use crate::{
+  wrapped, ChunkConfig, DictSpec, FloatMultSpec, FloatQuantSpec, FloatXorSpec, IntMultSpec, Mode,
+  PagingSpec, RunLengthSpec,
+};
+
+const REGEN_ENV_VAR: &str = "PCO_REGEN_GOLDENS";
+
+fn get_golden_path(name: &str) -> PathBuf {
+  PathBuf::from_str(env!("CARGO_MANIFEST_DIR"))
+    .unwrap()
+    .join("assets")
+    .join("golden")
+    .join(format!("{}.pco", name))
+}
+
+fn assert_matches_golden(name: &str, compressed: &[u8]) -> PcoResult<()> {
+  let path = get_golden_path(name);
+  if std::env::var_os(REGEN_ENV_VAR).is_some() {
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(&path, compressed)?;
+    return Ok(());
+  }
+
+  let regen_hint = format!(
+    "if this format change is intentional, rerun with {}=1 to regenerate",
+    REGEN_ENV_VAR
+  );
+  let golden = fs::read(&path).unwrap_or_else(|e| {
+    panic!(
+      "could not read golden file {:?} ({}); {}",
+      path, e, regen_hint
+    )
+  });
+  if let Some(i) = golden.iter().zip(compressed).position(|(x, y)| x != y) {
+    panic!(
+      "{}: compressed bytes diverge from golden at byte {} ({} != {}); {}",
+      name, i, compressed[i], golden[i], regen_hint,
+    );
+  }
+  assert_eq!(
+    compressed.len(),
+    golden.len(),
+    "{}: compressed size differs from golden; {}",
+    name,
+    regen_hint,
+  );
+  Ok(())
+}
+
+fn first_chunk_mode<T: NumberLike>(compressed: &[u8]) -> PcoResult<Mode<T::L>> {
+  let (fd, src) = FileDecompressor::new(compressed)?;
+  match fd.chunk_decompressor::<T, _>(src)? {
+    MaybeChunkDecompressor::Some(cd) => Ok(cd.meta().mode),
+    MaybeChunkDecompressor::EndOfData(_) => panic!("expected at least one chunk"),
+  }
+}
+
+fn assert_standalone_golden<T: NumberLike>(
+  name: &str,
+  nums: &[T],
+  config: &ChunkConfig,
+  expected_mode: Mode<T::L>,
+) -> PcoResult<()> {
+  let compressed = standalone::simple_compress(nums, config)?;
+  assert_eq!(
+    first_chunk_mode::<T>(&compressed)?,
+    expected_mode,
+    "{}: golden input no longer exercises the intended mode",
+    name,
+  );
+  assert_matches_golden(name, &compressed)?;
+
+  // the goldens must also still decode to the same numbers
+  let decompressed = standalone::simple_decompress::<T>(&fs::read(get_golden_path(name))?)?;
+  assert_eq!(decompressed.len(), nums.len());
+  for (i, (x, y)) in decompressed.iter().zip(nums).enumerate() {
+    assert_eq!(
+      x.to_latent_ordered(),
+      y.to_latent_ordered(),
+      "{}: at {}",
+      name,
+      i
+    );
+  }
+  Ok(())
+}
+
+fn pseudorandom_u32s(n: usize) -> Vec<u32> {
+  let mut state = 1_u64;
+  (0..n)
+    .map(|_| {
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      (state >> 40) as u32
+    })
+    .collect()
+}
+
+#[test]
+fn golden_classic() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .map(|x| x % 10_000)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_paging_spec(PagingSpec::EqualPagesUpTo(400));
+  assert_standalone_golden("classic_u32", &nums, &config, Mode::Classic)
+}
+
+#[test]
+fn golden_delta() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .enumerate()
+    .map(|(i, x)| (i as i64 - 500).pow(2) + (x % 7) as i64)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default().with_delta_encoding_order(Some(2));
+  assert_standalone_golden("delta_i64", &nums, &config, Mode::Classic)
+}
+
+#[test]
+fn golden_f16() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .map(|x| f16::from_f32((x % 2000) as f32 * 0.37 - 300.0))
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Disabled);
+  assert_standalone_golden("classic_f16", &nums, &config, Mode::Classic)
+}
+
+#[test]
+fn golden_int_mult() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .map(|x| (x % 1000) as u64 * 1_000_000 + (x % 100 == 0) as u64)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_int_mult_spec(IntMultSpec::Provided(1_000_000));
+  assert_standalone_golden(
+    "int_mult_u64",
+    &nums,
+    &config,
+    Mode::IntMult(1_000_000),
+  )
+}
+
+#[test]
+fn golden_float_mult() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .map(|x| (x % 1000) as f64 * 0.01)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_float_mult_spec(FloatMultSpec::Provided(0.01));
+  assert_standalone_golden(
+    "float_mult_f64",
+    &nums,
+    &config,
+    Mode::float_mult(0.01_f64),
+  )
+}
+
+#[test]
+fn golden_float_quant() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .map(|x| f16::from_bits(x as u16 & 0x7bff).to_f32())
+    .collect::<Vec<_>>();
+  let k = f32::MANTISSA_DIGITS - f16::MANTISSA_DIGITS;
+  let config = ChunkConfig::default()
+    .with_float_mult_spec(FloatMultSpec::Disabled)
+    .with_float_quant_spec(FloatQuantSpec::Provided(k));
+  assert_standalone_golden(
+    "float_quant_f32",
+    &nums,
+    &config,
+    Mode::FloatQuant(k),
+  )
+}
+
+#[test]
+fn golden_float_xor() -> PcoResult<()> {
+  // a slowly changing magnitude with random signs, which XOR encodes with
+  // just the sign bit
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .scan(1.0_f64, |magnitude, x| {
+      if x % 16 == 0 {
+        *magnitude *= 1.001;
+      }
+      Some(if x % 2 == 0 { *magnitude } else { -*magnitude })
+    })
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_float_mult_spec(FloatMultSpec::Disabled)
+    .with_float_xor_spec(FloatXorSpec::Enabled)
+    .with_run_length_spec(RunLengthSpec::Disabled)
+    .with_dict_spec(DictSpec::Disabled);
+  assert_standalone_golden(
+    "float_xor_f64",
+    &nums,
+    &config,
+    Mode::FloatXor,
+  )
+}
+
+#[test]
+fn golden_run_length() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(100)
+    .into_iter()
+    .flat_map(|x| vec![x as i32 - (1 << 23); 10 + (x % 30) as usize])
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_run_length_spec(RunLengthSpec::Enabled);
+  assert_standalone_golden(
+    "run_length_i32",
+    &nums,
+    &config,
+    Mode::RunLength,
+  )
+}
+
+#[test]
+fn golden_dict() -> PcoResult<()> {
+  let values = pseudorandom_u32s(20)
+    .into_iter()
+    .map(|x| (x as u64) << 32 | x as u64)
+    .collect::<Vec<_>>();
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .map(|x| values[x as usize % values.len()])
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_dict_spec(DictSpec::Enabled);
+  assert_standalone_golden("dict_u64", &nums, &config, Mode::Dict)
+}
+
+#[test]
+fn golden_wrapped() -> PcoResult<()> {
+  let nums = pseudorandom_u32s(1000)
+    .into_iter()
+    .enumerate()
+    .map(|(i, x)| i as i32 * 3 + (x % 5) as i32)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(1))
+    .with_paging_spec(PagingSpec::Exact(vec![300, 700]));
+
+  let fc = wrapped::FileCompressor::default();
+  let mut compressed = fc.write_header(Vec::new())?;
+  let cc = fc.chunk_compressor(&nums, &config)?;
+  compressed = cc.write_chunk_meta(compressed)?;
+  for page_idx in 0..cc.n_per_page().len() {
+    compressed = cc.write_page(page_idx, compressed)?;
+  }
+  assert_matches_golden("wrapped_i32", &compressed)
+}
@@ -1,11 +1,13 @@
 use std::cmp::min;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Read, Write};
 
 use better_io::{BetterBufRead, BetterBufReader};
 
 use crate::chunk_config::ChunkConfig;
-use crate::errors::PcoResult;
+use crate::data_types::NumberLike;
+use crate::errors::{ErrorKind, PcoResult};
+use crate::standalone;
 use crate::wrapped::{FileCompressor, FileDecompressor, PageDecompressor};
 use crate::{PagingSpec, FULL_BATCH_N};
 
@@ -61,7 +63,10 @@ fn test_wrapped_decompress<R: BetterBufRead>(chunks: &[Chunk], src: R) -> PcoRes
     src = new_src;
 
     let mut page_start = 0;
-    let n_per_page = chunk.config.paging_spec.n_per_page(chunk.nums.len())?;
+    let n_per_page = chunk
+      .config
+      .paging_spec
+      .n_per_page::<u32>(chunk.nums.len())?;
     for &page_n in &n_per_page {
       let page_end = page_start + page_n;
 
@@ -127,3 +132,369 @@ fn test_low_level_wrapped() -> PcoResult<()> {
     },
   ])
 }
+
+#[test]
+fn test_standalone_decompress_remaining_into() -> PcoResult<()> {
+  let nums = (0..1000).collect::<Vec<u32>>();
+  let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![600, 400]));
+  let compressed = standalone::simple_compress(&nums, &config)?;
+
+  let (fd, mut src) = standalone::FileDecompressor::new(compressed.as_slice())?;
+  let mut buffer = vec![0; 600];
+  let mut decompressed = Vec::<u32>::new();
+  while let standalone::MaybeChunkDecompressor::Some(mut cd) =
+    fd.chunk_decompressor::<u32, _>(src)?
+  {
+    let n = cd.decompress_remaining_into(&mut buffer)?;
+    assert_eq!(n, cd.n());
+    decompressed.extend(&buffer[..n]);
+    src = cd.into_src();
+  }
+  assert_eq!(decompressed, nums);
+
+  let (fd, src) = standalone::FileDecompressor::new(compressed.as_slice())?;
+  let standalone::MaybeChunkDecompressor::Some(mut cd) = fd.chunk_decompressor::<u32, _>(src)?
+  else {
+    panic!("expected a chunk");
+  };
+  let err = cd
+    .decompress_remaining_into(&mut buffer[..599])
+    .unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InvalidArgument
+  ));
+  Ok(())
+}
+
+// a reader that only ever supplies a few bytes at a time, like a slow stream
+struct TrickleReader<'a> {
+  src: &'a [u8],
+}
+
+impl<'a> Read for TrickleReader<'a> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    let n = min(min(buf.len(), 7), self.src.len());
+    buf[..n].copy_from_slice(&self.src[..n]);
+    self.src = &self.src[n..];
+    Ok(n)
+  }
+}
+
+fn standalone_decompress_stream<R: Read>(src: R) -> PcoResult<Vec<u32>> {
+  let src = BetterBufReader::from_read_simple(src);
+  let (fd, mut src) = standalone::FileDecompressor::new(src)?;
+  let mut res = Vec::new();
+  let mut batch = vec![0; FULL_BATCH_N];
+  while let standalone::MaybeChunkDecompressor::Some(mut cd) =
+    fd.chunk_decompressor::<u32, _>(src)?
+  {
+    loop {
+      let progress = cd.decompress(&mut batch)?;
+      res.extend(&batch[..progress.n_processed]);
+      if progress.finished {
+        break;
+      }
+    }
+    src = cd.into_src();
+  }
+  Ok(res)
+}
+
+#[test]
+fn test_standalone_decompress_stream() -> PcoResult<()> {
+  let nums = (0..3000).map(|x| (x * 31) % 1000).collect::<Vec<u32>>();
+  let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(1000));
+  let compressed = standalone::simple_compress(&nums, &config)?;
+
+  let decompressed = standalone_decompress_stream(TrickleReader { src: &compressed })?;
+  assert_eq!(decompressed, nums);
+
+  // truncating anywhere, including mid-page, is insufficient data
+  for truncated_len in [3, compressed.len() / 2, compressed.len() - 2] {
+    let err = standalone_decompress_stream(TrickleReader {
+      src: &compressed[..truncated_len],
+    })
+    .unwrap_err();
+    assert!(
+      matches!(err.kind, ErrorKind::InsufficientData),
+      "len={} err={}",
+      truncated_len,
+      err,
+    );
+  }
+  Ok(())
+}
+
+#[test]
+fn test_standalone_checksum() -> PcoResult<()> {
+  let nums = (0..1000).collect::<Vec<u32>>();
+  let write_file = |checksum: bool| -> PcoResult<Vec<u8>> {
+    let fc = standalone::FileCompressor::default()
+      .with_n_hint(nums.len())
+      .with_checksum(checksum);
+    let mut compressed = fc.write_header(Vec::new())?;
+    for chunk in nums.chunks(600) {
+      let cc = fc.chunk_compressor(chunk, &ChunkConfig::default())?;
+      compressed = cc.write_chunk(compressed)?;
+    }
+    if checksum {
+      let footer = fc.write_checksummed_footer(&compressed, Vec::new())?;
+      compressed.extend(footer);
+      Ok(compressed)
+    } else {
+      fc.write_footer(compressed)
+    }
+  };
+
+  // without a checksum, files are unchanged
+  let unchecked = write_file(false)?;
+  assert_eq!(
+    unchecked,
+    standalone::simple_compress(
+      &nums,
+      &ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![600, 400]))
+    )?
+  );
+  let (fd, _) = standalone::FileDecompressor::new(unchecked.as_slice())?;
+  assert!(matches!(
+    fd.verify_checksum(&unchecked).unwrap_err().kind,
+    ErrorKind::InvalidArgument
+  ));
+
+  // the checksum only adds a trailer after the footer, where older
+  // decompressors stop reading
+  let mut compressed = write_file(true)?;
+  assert_eq!(compressed.len(), unchecked.len() + 8);
+  assert_eq!(
+    &compressed[..unchecked.len()],
+    unchecked.as_slice()
+  );
+  assert_eq!(
+    standalone::simple_decompress::<u32>(&compressed)?,
+    nums
+  );
+  let mut dst = vec![0; 1000];
+  let progress = standalone::simple_decompress_into::<u32>(&compressed, &mut dst)?;
+  assert!(progress.finished);
+  assert_eq!(dst, nums);
+
+  // the rest of the source begins with the trailer, and trailing data can
+  // follow it
+  compressed.extend([7, 8, 9]);
+  let (fd, mut src) = standalone::FileDecompressor::new(compressed.as_slice())?;
+  let rest = loop {
+    match fd.chunk_decompressor::<u32, _>(src)? {
+      standalone::MaybeChunkDecompressor::Some(cd) => src = cd.into_src(),
+      standalone::MaybeChunkDecompressor::EndOfData(rest) => break rest,
+    }
+  };
+  assert_eq!(rest, &compressed[unchecked.len()..]);
+  fd.verify_checksum(&compressed[..compressed.len() - 3])?;
+  compressed.truncate(compressed.len() - 3);
+
+  // a corrupted checksum is detected
+  let last_idx = compressed.len() - 1;
+  compressed[last_idx] ^= 1;
+  let err = standalone::simple_decompress::<u32>(&compressed).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::ChecksumMismatch
+  ));
+
+  // footers must match the checksum setting
+  let fc = standalone::FileCompressor::default().with_checksum(true);
+  assert!(matches!(
+    fc.write_footer(Vec::new()).unwrap_err().kind,
+    ErrorKind::InvalidArgument
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_standalone_checksum_detects_page_corruption() -> PcoResult<()> {
+  let nums = (0..1000_u32)
+    .map(|i| (i ^ (i << 7)).wrapping_mul(2654435761) >> 12)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default();
+  let unchecked = standalone::simple_compress(&nums, &config)?;
+  let fc = standalone::FileCompressor::default().with_checksum(true);
+  let mut compressed = fc.write_header(Vec::new())?;
+  compressed = fc
+    .chunk_compressor(&nums, &config)?
+    .write_chunk(compressed)?;
+  let footer = fc.write_checksummed_footer(&compressed, Vec::new())?;
+  compressed.extend(footer);
+
+  // a bit flip in the offsets at the end of the page still decompresses,
+  // just to the wrong numbers
+  let flip_idx = unchecked.len() - 10;
+  let mut corrupt = unchecked.clone();
+  corrupt[flip_idx] ^= 1;
+  let decompressed = standalone::simple_decompress::<u32>(&corrupt)?;
+  assert_eq!(decompressed.len(), nums.len());
+  assert_ne!(decompressed, nums);
+
+  // but the checksum catches it
+  compressed[flip_idx] ^= 1;
+  let err = standalone::simple_decompress::<u32>(&compressed).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::ChecksumMismatch
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_standalone_stream_compressor() -> PcoResult<()> {
+  let nums = (0..1000).map(|x| x % 77).collect::<Vec<u32>>();
+  let config = ChunkConfig::default();
+  let compress = |fc: standalone::FileCompressor| -> PcoResult<Vec<u8>> {
+    let mut compressor = standalone::StreamCompressor::new(fc, config.clone(), Vec::new())?;
+    for batch in nums.chunks(300) {
+      compressor.write_chunk(batch)?;
+    }
+    assert!(matches!(
+      compressor.write_chunk::<u32>(&[]).unwrap_err().kind,
+      ErrorKind::InvalidArgument
+    ));
+    compressor.finish()
+  };
+
+  let compressed = compress(standalone::FileCompressor::default().with_n_hint(nums.len()))?;
+  let expected = standalone::simple_compress(
+    &nums,
+    &config
+      .clone()
+      .with_paging_spec(PagingSpec::Exact(vec![300, 300, 300, 100])),
+  )?;
+  assert_eq!(compressed, expected);
+
+  let compressed = compress(standalone::FileCompressor::default().with_checksum(true))?;
+  let (fd, _) = standalone::FileDecompressor::new(compressed.as_slice())?;
+  fd.verify_checksum(&compressed)?;
+  assert_eq!(
+    standalone::simple_decompress::<u32>(&compressed)?,
+    nums
+  );
+  Ok(())
+}
+
+#[test]
+fn test_standalone_stream_compressor_per_chunk_levels() -> PcoResult<()> {
+  let nums = (0..1000).map(|x| (x * x) % 1013).collect::<Vec<u32>>();
+  let levels = [0, 12, 4];
+  let config = ChunkConfig::default();
+  let fc = standalone::FileCompressor::default();
+
+  let mut compressor = standalone::StreamCompressor::new(fc.clone(), config.clone(), Vec::new())?;
+  let mut expected = fc.write_header(Vec::new())?;
+  for (batch, &level) in nums.chunks(400).zip(&levels) {
+    compressor.write_chunk_with_level(batch, level)?;
+    expected = fc
+      .chunk_compressor(
+        batch,
+        &config.clone().with_compression_level(level),
+      )?
+      .write_chunk(expected)?;
+  }
+  assert!(matches!(
+    compressor
+      .write_chunk_with_level(&nums, 13)
+      .unwrap_err()
+      .kind,
+    ErrorKind::InvalidArgument
+  ));
+  let compressed = compressor.finish()?;
+  expected = fc.write_footer(expected)?;
+
+  assert_eq!(compressed, expected);
+  assert_eq!(
+    standalone::simple_decompress::<u32>(&compressed)?,
+    nums
+  );
+  Ok(())
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_wrapped_par_decompress_pages() -> PcoResult<()> {
+  let nums = (0..5000).map(|x| (x * x) % 7777).collect::<Vec<u32>>();
+  let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![1, 1000, 3999]));
+  let fc = FileCompressor::default();
+  let header = fc.write_header(Vec::new())?;
+  let cc = fc.chunk_compressor(&nums, &config)?;
+  let chunk_meta = cc.write_chunk_meta(Vec::new())?;
+  let n_per_page = cc.n_per_page();
+  let page_srcs = (0..n_per_page.len())
+    .map(|page_idx| cc.write_page(page_idx, Vec::new()))
+    .collect::<PcoResult<Vec<_>>>()?;
+
+  let (fd, _) = FileDecompressor::new(header.as_slice())?;
+  let (cd, _) = fd.chunk_decompressor::<u32, _>(chunk_meta.as_slice())?;
+  let pages = page_srcs
+    .iter()
+    .zip(&n_per_page)
+    .map(|(src, &n)| (src.as_slice(), n))
+    .collect::<Vec<_>>();
+  let mut dst = vec![0; nums.len()];
+  cd.par_decompress_pages(&pages, &mut dst)?;
+  assert_eq!(dst, nums);
+
+  // wrong dst length
+  let err = cd.par_decompress_pages(&pages, &mut dst[1..]).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InvalidArgument
+  ));
+
+  // truncated page
+  let mut truncated_pages = pages.clone();
+  let last_src = page_srcs[2].as_slice();
+  truncated_pages[2] = (&last_src[..last_src.len() / 2], 3999);
+  let err = cd
+    .par_decompress_pages(&truncated_pages, &mut dst)
+    .unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InsufficientData
+  ));
+  Ok(())
+}
+
+#[test]
+fn test_wrapped_parse_page_meta() -> PcoResult<()> {
+  let nums = (0..3000).map(|x| x * 3 + x % 7).collect::<Vec<i32>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(1))
+    .with_paging_spec(PagingSpec::Exact(vec![1000, 2000]));
+  let fc = FileCompressor::default();
+  let header = fc.write_header(Vec::new())?;
+  let cc = fc.chunk_compressor(&nums, &config)?;
+  let chunk_meta = cc.write_chunk_meta(Vec::new())?;
+  let page = cc.write_page(1, Vec::new())?;
+
+  let (fd, _) = FileDecompressor::new(header.as_slice())?;
+  let (cd, _) = fd.chunk_decompressor::<i32, _>(chunk_meta.as_slice())?;
+  let page_meta = cd.parse_page_meta(page.as_slice())?;
+  assert_eq!(page_meta.n_runs(), None);
+  assert_eq!(page_meta.per_latent_var().len(), 1);
+  let latent_var = &page_meta.per_latent_var()[0];
+  assert_eq!(
+    latent_var.delta_moments(),
+    &[nums[1000].to_latent_ordered()]
+  );
+  let ans_size_log = cd.meta().per_latent_var[0].ans_size_log;
+  assert_eq!(latent_var.ans_final_state_idxs().len(), 4);
+  assert!(latent_var
+    .ans_final_state_idxs()
+    .iter()
+    .all(|&idx| idx < 1 << ans_size_log));
+
+  let err = cd.parse_page_meta(&page[..2]).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InsufficientData
+  ));
+  Ok(())
+}
@@ -1,4 +1,6 @@
 mod compatibility;
+mod determinism;
+mod golden;
 mod low_level;
 mod recovery;
 mod stability;
@@ -1,20 +1,23 @@
-use half::f16;
+use half::{bf16, f16};
 use rand::Rng;
 use rand_xoshiro::rand_core::SeedableRng;
 
 use crate::chunk_config::ChunkConfig;
 use crate::constants::Bitlen;
-use crate::data_types::NumberLike;
-use crate::errors::PcoResult;
+use crate::data_types::{Latent, NumberLike};
+use crate::errors::{ErrorKind, PcoResult};
 use crate::standalone::{simple_compress, simple_decompress, FileCompressor};
-use crate::{ChunkMeta, FloatMultSpec, FloatQuantSpec, Mode};
+use crate::{
+  wrapped, AutoLevelSpec, ChunkMeta, DictSpec, FloatMultSpec, FloatQuantSpec, FloatXorSpec,
+  IntMultSpec, Mode, ModeKind, PagingSpec, RunLengthSpec, SparseSpec,
+};
 
 fn compress_w_meta<T: NumberLike>(
   nums: &[T],
   config: &ChunkConfig,
 ) -> PcoResult<(Vec<u8>, ChunkMeta<T::L>)> {
   let mut compressed = Vec::new();
-  let fc = FileCompressor::default();
+  let fc = FileCompressor::default().with_format_version_for(config);
   fc.write_header(&mut compressed)?;
   let cd = fc.chunk_compressor(nums, config)?;
   let meta = cd.meta().clone();
@@ -51,6 +54,8 @@ fn assert_recovers<T: NumberLike>(
   compression_level: usize,
   name: &str,
 ) -> PcoResult<()> {
+  // use a quantization that's valid even for the narrowest float types
+  let float_quant_k = (T::L::BITS / 2 - 2).min(14);
   for delta_encoding_order in [0, 1, 7] {
     for (fm, fq) in [
       (
@@ -59,7 +64,7 @@ fn assert_recovers<T: NumberLike>(
       ),
       (
         FloatMultSpec::Disabled,
-        FloatQuantSpec::Provided(14),
+        FloatQuantSpec::Provided(float_quant_k),
       ),
     ] {
       let config = ChunkConfig {
@@ -139,6 +144,44 @@ fn test_u16_codec() -> PcoResult<()> {
   assert_recovers(&[0_u16, u16::MAX, 2, 3, 4, 5], 1, "u16s")
 }
 
+#[test]
+fn test_u16_full_range() -> PcoResult<()> {
+  let nums = (u16::MIN..=u16::MAX).collect::<Vec<_>>();
+  assert_recovers(&nums, 4, "u16 full range")?;
+  let reversed = nums.into_iter().rev().collect::<Vec<_>>();
+  assert_recovers(&reversed, 4, "u16 full range reversed")
+}
+
+#[test]
+fn test_i16_full_range() -> PcoResult<()> {
+  let nums = (i16::MIN..=i16::MAX).collect::<Vec<_>>();
+  assert_recovers(&nums, 4, "i16 full range")?;
+  let extremes = [i16::MIN, i16::MAX].repeat(100);
+  assert_recovers(&extremes, 4, "i16 extremes")
+}
+
+#[test]
+fn test_i16_with_int_mult() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let mut nums = Vec::new();
+  for _ in 0..300 {
+    nums.push(rng.gen_range(-4000_i16..4000) * 8 + 3);
+  }
+  nums.push(i16::MIN);
+  nums.push(i16::MAX);
+  let (compressed, meta) = compress_w_meta(
+    &nums,
+    &ChunkConfig {
+      delta_encoding_order: Some(0),
+      ..Default::default()
+    },
+  )?;
+  assert_eq!(meta.mode, Mode::IntMult(8_u16));
+  let decompressed = simple_decompress(&compressed)?;
+  assert_nums_eq(&decompressed, &nums, "i16 w int mult")?;
+  assert_recovers(&nums, 4, "i16 int mult")
+}
+
 #[test]
 fn test_u32_codec() -> PcoResult<()> {
   assert_recovers(&[0_u32, u32::MAX, 3, 4, 5], 1, "u32s")
@@ -149,6 +192,15 @@ fn test_u64_codec() -> PcoResult<()> {
   assert_recovers(&[0_u64, u64::MAX, 3, 4, 5], 1, "u64s")
 }
 
+#[test]
+fn test_u128_codec() -> PcoResult<()> {
+  assert_recovers(
+    &[0_u128, u128::MAX, 3, 4, 5, 1 << 64, u64::MAX as u128],
+    1,
+    "u128s",
+  )
+}
+
 #[test]
 fn test_i16_codec() -> PcoResult<()> {
   assert_recovers(
@@ -176,6 +228,40 @@ fn test_i64_codec() -> PcoResult<()> {
   )
 }
 
+#[test]
+fn test_i128_codec() -> PcoResult<()> {
+  assert_recovers(
+    &[0_i128, -1, i128::MAX, i128::MIN, 7, -(1 << 100)],
+    1,
+    "i128s",
+  )
+}
+
+#[test]
+fn test_i128_timestamps() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  // nanosecond timestamps that don't fit in 64 bits
+  let mut t = 1_i128 << 70;
+  let mut nums = Vec::new();
+  for _ in 0..1000 {
+    t += rng.gen_range(999_000..1_001_000) * 1000;
+    nums.push(t);
+  }
+  let (compressed, meta) = compress_w_meta(
+    &nums,
+    &ChunkConfig {
+      delta_encoding_order: Some(1),
+      ..Default::default()
+    },
+  )?;
+  assert_eq!(meta.mode, Mode::IntMult(1000_u128));
+  // far less than the 16 bytes per number it takes uncompressed
+  assert!(compressed.len() < nums.len() * 2);
+  let decompressed = simple_decompress(&compressed)?;
+  assert_nums_eq(&decompressed, &nums, "i128 timestamps")?;
+  assert_recovers(&nums, 4, "i128 timestamps")
+}
+
 #[test]
 fn test_f16_codec() -> PcoResult<()> {
   assert_recovers(
@@ -194,6 +280,77 @@ fn test_f16_codec() -> PcoResult<()> {
   )
 }
 
+#[test]
+fn test_bf16_codec() -> PcoResult<()> {
+  assert_recovers(
+    &[
+      bf16::MAX,
+      bf16::MIN,
+      bf16::NAN,
+      bf16::NEG_INFINITY,
+      bf16::INFINITY,
+      bf16::from_f32(-0.0),
+      bf16::from_f32(0.0),
+      bf16::from_f32(77.7),
+    ],
+    1,
+    "bf16s",
+  )
+}
+
+#[test]
+fn test_16_bit_float_subnormals() -> PcoResult<()> {
+  let f16s = (0..1000_u16)
+    .map(|i| f16::from_bits((i % 0x0400) | ((i & 1) << 15)))
+    .collect::<Vec<_>>();
+  assert!(f16s[1..].iter().all(|x| !x.is_normal() && x.is_finite()));
+  assert_recovers(&f16s, 4, "f16 subnormals")?;
+
+  let bf16s = (0..1000_u16)
+    .map(|i| bf16::from_bits((i % 0x0080) | ((i & 1) << 15)))
+    .collect::<Vec<_>>();
+  assert!(bf16s[1..].iter().all(|x| !x.is_normal() && x.is_finite()));
+  assert_recovers(&bf16s, 4, "bf16 subnormals")
+}
+
+#[test]
+fn test_16_bit_float_mult() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let mut ints = Vec::new();
+  for _ in 0..1000 {
+    ints.push(rng.gen_range(-100..100) as f32);
+  }
+
+  let f16s = ints
+    .iter()
+    .map(|&x| f16::from_f32(x * 0.25))
+    .collect::<Vec<_>>();
+  let (_, meta) = compress_w_meta(&f16s, &ChunkConfig::default())?;
+  assert_eq!(
+    meta.mode,
+    Mode::float_mult(f16::from_f32(0.25))
+  );
+  assert_recovers(&f16s, 4, "f16 mult")?;
+
+  // bf16 has too few precision bits for FloatMult to beat Classic
+  // automatically, but a provided base should still work.
+  let bf16s = ints
+    .iter()
+    .map(|&x| bf16::from_f32(x * 3.0))
+    .collect::<Vec<_>>();
+  let config = ChunkConfig {
+    float_mult_spec: FloatMultSpec::Provided(3.0),
+    ..Default::default()
+  };
+  let (compressed, meta) = compress_w_meta(&bf16s, &config)?;
+  assert_eq!(
+    meta.mode,
+    Mode::float_mult(bf16::from_f32(3.0))
+  );
+  let decompressed = simple_decompress(&compressed)?;
+  assert_nums_eq(&decompressed, &bf16s, "bf16 mult")
+}
+
 #[test]
 fn test_f32_codec() -> PcoResult<()> {
   assert_recovers(
@@ -230,6 +387,75 @@ fn test_f64_codec() -> PcoResult<()> {
   )
 }
 
+#[test]
+fn test_non_finite_bit_patterns() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  // decimals, so that FloatMult is worth using, mixed with every kind of
+  // special float
+  let mut nums = (0..1000)
+    .map(|_| rng.gen_range(0..1000) as f64 * 0.01)
+    .collect::<Vec<_>>();
+  let specials = [
+    f64::NAN,
+    -f64::NAN,
+    f64::from_bits(0x7ff0_0000_0000_0001), // signaling
+    f64::from_bits(0x7ff8_0000_dead_beef),
+    f64::from_bits(0xfff4_0000_0000_0000),
+    f64::INFINITY,
+    f64::NEG_INFINITY,
+    -0.0,
+  ];
+  for (i, &special) in specials.iter().enumerate() {
+    nums[i * 111] = special;
+    nums[i * 111 + 1] = special;
+  }
+  for _ in 0..20 {
+    let payload = rng.gen_range(1..1_u64 << 52);
+    let idx = rng.gen_range(0..nums.len());
+    nums[idx] = f64::from_bits(0x7ff0_0000_0000_0000 | payload);
+  }
+
+  let (_, meta) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert_eq!(meta.mode, Mode::float_mult(0.01));
+
+  let configs = [
+    ChunkConfig::default(),
+    ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Disabled),
+    ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Provided(0.01)),
+    ChunkConfig::default()
+      .with_float_mult_spec(FloatMultSpec::Disabled)
+      .with_float_quant_spec(FloatQuantSpec::Provided(20)),
+    ChunkConfig::default().with_float_xor_spec(FloatXorSpec::Enabled),
+  ];
+  for (i, config) in configs.iter().enumerate() {
+    let (compressed, _) = compress_w_meta(&nums, config)?;
+    let decompressed = simple_decompress::<f64>(&compressed)?;
+    assert_eq!(decompressed.len(), nums.len());
+    for (&x, &y) in decompressed.iter().zip(&nums) {
+      assert_eq!(x.to_bits(), y.to_bits(), "config {}", i);
+    }
+  }
+
+  let f32s = nums.iter().map(|&x| x as f32).collect::<Vec<_>>();
+  assert_recovers(&f32s, 3, "f32 non-finite")?;
+  Ok(())
+}
+
+#[test]
+fn test_float_mult_signed_zeros() -> PcoResult<()> {
+  let config = ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Provided(0.1));
+  let mut nums = vec![-0.0_f64, 0.0];
+  // tiny numbers of either sign also have a quotient of 0
+  nums.extend([-0.01, 0.01, -0.0, 0.3, -0.3]);
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::float_mult(0.1));
+  let decompressed = simple_decompress::<f64>(&compressed)?;
+  for (&x, &y) in decompressed.iter().zip(&nums) {
+    assert_eq!(x.to_bits(), y.to_bits(), "{} vs {}", x, y);
+  }
+  Ok(())
+}
+
 #[test]
 fn test_multi_chunk() -> PcoResult<()> {
   let config = ChunkConfig::default();
@@ -254,6 +480,7 @@ fn recover_with_alternating_nums(offset_bits: Bitlen, name: &str) -> PcoResult<(
     &ChunkConfig {
       delta_encoding_order: Some(0),
       compression_level: 0,
+      dict_spec: DictSpec::Disabled,
       ..Default::default()
     },
   )?;
@@ -281,6 +508,32 @@ fn test_64_bit_offsets() -> PcoResult<()> {
   recover_with_alternating_nums(64, "64 bit offsets")
 }
 
+#[test]
+fn test_128_bit_offsets() -> PcoResult<()> {
+  for offset_bits in [64, 100, 113, 114, 128] {
+    let nums = [0_u128, 1 << (offset_bits - 1)].repeat(50);
+    let (compressed, meta) = compress_w_meta(
+      &nums,
+      &ChunkConfig {
+        delta_encoding_order: Some(0),
+        compression_level: 0,
+        dict_spec: DictSpec::Disabled,
+        ..Default::default()
+      },
+    )?;
+    let latent_var = &meta.per_latent_var[0];
+    assert_eq!(latent_var.bins.len(), 1);
+    assert_eq!(latent_var.bins[0].offset_bits, offset_bits);
+    let decompressed = simple_decompress(&compressed)?;
+    assert_nums_eq(
+      &decompressed,
+      &nums,
+      &format!("{} bit offsets", offset_bits),
+    )?;
+  }
+  Ok(())
+}
+
 #[test]
 fn test_with_int_mult() -> PcoResult<()> {
   let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
@@ -348,6 +601,7 @@ fn test_f16_mult() -> PcoResult<()> {
   let nums = [100.1, 299.9, 200.0].repeat(100);
   let config = ChunkConfig {
     float_mult_spec: FloatMultSpec::Provided(100.0),
+    dict_spec: DictSpec::Disabled,
     ..Default::default()
   };
   let (_, meta) = compress_w_meta(&nums, &config)?;
@@ -356,6 +610,46 @@ fn test_f16_mult() -> PcoResult<()> {
   assert_recovers(&nums, 1, "f16 mult mode")
 }
 
+#[test]
+fn test_lossy_float_mult() -> PcoResult<()> {
+  let tolerance = 1e-6;
+  let config = ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Lossy(tolerance));
+  let nums = (0..2000)
+    .map(|i| (i as f64 * 0.01).sin() * 1000.0)
+    .collect::<Vec<_>>();
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::float_mult(2.0 * tolerance));
+  // the adjustment latent variable is dropped entirely
+  assert!(meta.per_latent_var[1].is_trivial());
+  let (lossless, _) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert!(compressed.len() * 2 < lossless.len());
+  let decompressed = simple_decompress::<f64>(&compressed)?;
+  assert_eq!(decompressed.len(), nums.len());
+  for (&x, &y) in decompressed.iter().zip(&nums) {
+    assert!((x - y).abs() <= tolerance, "{} vs {}", x, y);
+  }
+
+  // floats that can't be brought within tolerance are kept exactly
+  let mut nums = nums;
+  nums[3] = f64::NAN;
+  nums[4] = f64::INFINITY;
+  nums[5] = f64::MAX;
+  let (compressed, _) = compress_w_meta(&nums, &config)?;
+  let decompressed = simple_decompress::<f64>(&compressed)?;
+  assert!(decompressed[3].is_nan());
+  assert_eq!(decompressed[4], f64::INFINITY);
+  assert_eq!(decompressed[5], f64::MAX);
+  for (&x, &y) in decompressed.iter().zip(&nums).skip(6) {
+    assert!((x - y).abs() <= tolerance, "{} vs {}", x, y);
+  }
+
+  for bad_tolerance in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+    let config = ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Lossy(bad_tolerance));
+    assert!(compress_w_meta(&nums, &config).is_err());
+  }
+  Ok(())
+}
+
 #[test]
 fn test_trivial_first_latent_var() -> PcoResult<()> {
   let mut nums = Vec::new();
@@ -369,3 +663,676 @@ fn test_trivial_first_latent_var() -> PcoResult<()> {
   assert_nums_eq(&decompressed, &nums, "trivial_first_latent")?;
   Ok(())
 }
+
+#[test]
+fn test_max_ans_size_log() -> PcoResult<()> {
+  let nums = (0..10000)
+    .map(|i: u32| ((i * 7919) % 10007) / (1 + i % 13))
+    .collect::<Vec<_>>();
+  for max_ans_size_log in [0, 3, 8] {
+    let config = ChunkConfig::default().with_max_ans_size_log(max_ans_size_log);
+    let (compressed, meta) = compress_w_meta(&nums, &config)?;
+    for latent_var_meta in &meta.per_latent_var {
+      assert!(latent_var_meta.ans_size_log <= max_ans_size_log);
+      assert!(latent_var_meta.bins.len() <= 1 << max_ans_size_log);
+    }
+    let decompressed = simple_decompress(&compressed)?;
+    assert_nums_eq(
+      &decompressed,
+      &nums,
+      &format!("max_ans_size_log={}", max_ans_size_log),
+    )?;
+  }
+
+  let config = ChunkConfig::default().with_max_ans_size_log(13);
+  assert!(compress_w_meta(&nums, &config).is_err());
+  Ok(())
+}
+
+#[test]
+fn test_high_delta_encoding_order() -> PcoResult<()> {
+  // a degree 9 polynomial only becomes constant after 9 deltas
+  let nums = (0..2000_u64)
+    .map(|i| i.wrapping_pow(9).wrapping_add(3 * i))
+    .collect::<Vec<_>>();
+  let config = |order| {
+    ChunkConfig::default()
+      .with_int_mult_spec(IntMultSpec::Disabled)
+      .with_delta_encoding_order(Some(order))
+  };
+
+  let (compressed, meta) = compress_w_meta(&nums, &config(9))?;
+  assert_eq!(meta.delta_encoding_order, 9);
+  let (low_order, _) = compress_w_meta(&nums, &config(7))?;
+  assert!(compressed.len() * 10 < low_order.len());
+  assert_nums_eq(
+    &simple_decompress::<u64>(&compressed)?,
+    &nums,
+    "delta order 9",
+  )?;
+
+  let (compressed, _) = compress_w_meta(&nums, &config(15))?;
+  assert_nums_eq(
+    &simple_decompress::<u64>(&compressed)?,
+    &nums,
+    "delta order 15",
+  )?;
+  assert!(compress_w_meta(&nums, &config(16)).is_err());
+  Ok(())
+}
+
+#[test]
+fn test_secondary_delta_encoding_order() -> PcoResult<()> {
+  // the multipliers are noisy, but the adjustments trend smoothly
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let nums = (0..3000_u64)
+    .map(|i| rng.gen_range(0..1000) * 1000 + i % 1000)
+    .collect::<Vec<_>>();
+  let config = ChunkConfig::default()
+    .with_int_mult_spec(IntMultSpec::Provided(1000))
+    .with_delta_encoding_order(Some(0));
+
+  let (without_secondary, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.secondary_delta_encoding_order, 0);
+  let config = config.with_secondary_delta_encoding_order(1);
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::IntMult(1000));
+  assert_eq!(meta.delta_encoding_order, 0);
+  assert_eq!(meta.secondary_delta_encoding_order, 1);
+  assert!(compressed.len() < without_secondary.len());
+  assert_nums_eq(
+    &simple_decompress::<u64>(&compressed)?,
+    &nums,
+    "secondary delta",
+  )?;
+
+  // it doesn't apply to classic mode
+  let classic_config = ChunkConfig::default()
+    .with_int_mult_spec(IntMultSpec::Disabled)
+    .with_secondary_delta_encoding_order(3);
+  let (_, meta) = compress_w_meta(&nums, &classic_config)?;
+  assert_eq!(meta.mode, Mode::Classic);
+  assert_eq!(meta.secondary_delta_encoding_order, 0);
+
+  let invalid_config = config.with_secondary_delta_encoding_order(16);
+  assert!(compress_w_meta(&nums, &invalid_config).is_err());
+  Ok(())
+}
+
+#[test]
+fn test_float_xor() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  // runs of a slowly changing magnitude with random signs, which XOR encodes
+  // with just the sign bit
+  let mut nums = Vec::new();
+  let mut magnitude = 1.0_f64;
+  while nums.len() < 3000 {
+    magnitude *= 1.0 + rng.gen_range(-0.01..0.01);
+    for _ in 0..rng.gen_range(1..30) {
+      nums.push(if rng.gen_bool(0.5) {
+        magnitude
+      } else {
+        -magnitude
+      });
+    }
+  }
+  nums.truncate(3000);
+  nums[777] = f64::NAN;
+
+  let config = ChunkConfig::default().with_float_xor_spec(FloatXorSpec::Enabled);
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::FloatXor);
+  assert_eq!(meta.delta_encoding_order, 0);
+  let (without_xor, _) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert!(compressed.len() * 4 < without_xor.len());
+  assert_nums_eq(
+    &simple_decompress::<f64>(&compressed)?,
+    &nums,
+    "float xor",
+  )?;
+
+  // each page restarts the XOR chain, so pages decompress independently
+  let paging_spec = PagingSpec::Exact(vec![1000, 1500, 500]);
+  let fc = wrapped::FileCompressor::default().with_format_version_for(&config);
+  let cc = fc.chunk_compressor(
+    &nums,
+    &config.clone().with_paging_spec(paging_spec),
+  )?;
+  assert_eq!(cc.meta().mode, Mode::FloatXor);
+  let mut page_1 = Vec::new();
+  cc.write_page(1, &mut page_1)?;
+  let chunk_meta = cc.write_chunk_meta(fc.write_header(Vec::new())?)?;
+  let (fd, rest) = wrapped::FileDecompressor::new(chunk_meta.as_slice())?;
+  let (cd, _) = fd.chunk_decompressor::<f64, _>(rest)?;
+  let mut pd = cd.page_decompressor(page_1.as_slice(), 1500)?;
+  let mut decompressed = vec![0.0; 1500];
+  pd.decompress(&mut decompressed)?;
+  assert_nums_eq(
+    &decompressed,
+    &nums[1000..2500],
+    "float xor page",
+  )?;
+
+  // a smooth series is better off with the usual modes
+  let smooth = (0..3000)
+    .map(|i| (i as f64 * 0.01).sin())
+    .collect::<Vec<_>>();
+  let (compressed, meta) = compress_w_meta(&smooth, &config)?;
+  assert_ne!(meta.mode, Mode::FloatXor);
+  assert_nums_eq(
+    &simple_decompress::<f64>(&compressed)?,
+    &smooth,
+    "float xor smooth",
+  )?;
+
+  // integers never use it
+  let (_, meta) = compress_w_meta(&[1_i32, 1, 1, -1, -1], &config)?;
+  assert_eq!(meta.mode, Mode::Classic);
+  Ok(())
+}
+
+#[test]
+fn test_run_length() -> PcoResult<()> {
+  // a step function with runs of random lengths
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let mut nums = Vec::new();
+  while nums.len() < 20000 {
+    let value = rng.gen_range(0.0..100.0_f64);
+    let run_length = rng.gen_range(1..200);
+    nums.extend(vec![value; run_length]);
+  }
+  nums.truncate(20000);
+
+  let run_length_config = ChunkConfig::default().with_run_length_spec(RunLengthSpec::Enabled);
+  let (compressed, meta) = compress_w_meta(&nums, &run_length_config)?;
+  assert_eq!(meta.mode, Mode::RunLength);
+  let (without_run_length, meta) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert_ne!(meta.mode, Mode::RunLength);
+  assert!(compressed.len() < without_run_length.len());
+  assert_nums_eq(
+    &simple_decompress::<f64>(&compressed)?,
+    &nums,
+    "run length",
+  )?;
+
+  // runs are split at page boundaries, and run lengths can be delta encoded
+  let config = run_length_config
+    .clone()
+    .with_paging_spec(PagingSpec::Exact(vec![1, 9999, 7000, 3000]))
+    .with_secondary_delta_encoding_order(1);
+  let compressed = simple_compress(&nums, &config)?;
+  assert_nums_eq(
+    &simple_decompress::<f64>(&compressed)?,
+    &nums,
+    "run length pages",
+  )?;
+
+  // a single gigantic run, longer than a u16 latent can count
+  let mut nums = vec![1_u16, 2];
+  nums.extend(vec![7; 200_000]);
+  nums.push(3);
+  let config = run_length_config
+    .clone()
+    .with_paging_spec(PagingSpec::EqualPagesUpTo(1 << 20));
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::RunLength);
+  assert!(compressed.len() < 100);
+  assert_nums_eq(
+    &simple_decompress::<u16>(&compressed)?,
+    &nums,
+    "run length gigantic run",
+  )?;
+
+  // short runs are better off with the usual modes
+  let nums = (0..3000).map(|i| i / 4).collect::<Vec<u32>>();
+  let (compressed, meta) = compress_w_meta(&nums, &run_length_config)?;
+  assert_ne!(meta.mode, Mode::RunLength);
+  assert_nums_eq(
+    &simple_decompress::<u32>(&compressed)?,
+    &nums,
+    "run length short runs",
+  )?;
+  Ok(())
+}
+
+#[test]
+fn test_sparse_mode() -> PcoResult<()> {
+  // a counter that only occasionally records a value
+  let nums = (0..20000)
+    .map(|i| if i % 20 == 0 { i } else { 0 })
+    .collect::<Vec<i64>>();
+  let sparse_config = ChunkConfig::default().with_sparse_spec(SparseSpec::Enabled);
+  let (compressed, meta) = compress_w_meta(&nums, &sparse_config)?;
+  assert_eq!(meta.mode, Mode::Sparse);
+  let (without_sparse, meta) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert_ne!(meta.mode, Mode::Sparse);
+  assert!(compressed.len() < without_sparse.len());
+  assert_nums_eq(
+    &simple_decompress::<i64>(&compressed)?,
+    &nums,
+    "sparse",
+  )?;
+
+  // entries are split at page boundaries, and the last page is all zeros
+  let mut nums = nums;
+  nums.extend(vec![0; 5000]);
+  let config = sparse_config
+    .clone()
+    .with_paging_spec(PagingSpec::Exact(vec![
+      1, 9999, 10007, 4993, 5000,
+    ]))
+    .with_secondary_delta_encoding_order(1);
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::Sparse);
+  assert_nums_eq(
+    &simple_decompress::<i64>(&compressed)?,
+    &nums,
+    "sparse pages",
+  )?;
+
+  // negative zero is not zero
+  let nums = (0..10000)
+    .map(|i| match i % 50 {
+      0 => -0.0,
+      25 => i as f32,
+      _ => 0.0,
+    })
+    .collect::<Vec<f32>>();
+  assert_nums_eq(
+    &simple_decompress::<f32>(&simple_compress(&nums, &sparse_config)?)?,
+    &nums,
+    "sparse negative zero",
+  )?;
+
+  // all zeros, including a run of zeros longer than a u16 latent can count
+  for n in [1, 1000, 200_000] {
+    let nums = vec![0_u16; n];
+    let config = sparse_config
+      .clone()
+      .with_paging_spec(PagingSpec::EqualPagesUpTo(1 << 20));
+    let compressed = simple_compress(&nums, &config)?;
+    assert!(compressed.len() < 100);
+    assert_nums_eq(
+      &simple_decompress::<u16>(&compressed)?,
+      &nums,
+      &format!("sparse {} zeros", n),
+    )?;
+  }
+  Ok(())
+}
+
+#[test]
+fn test_dict() -> PcoResult<()> {
+  // a few hundred hash values, each repeated many times
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let hashes = (0..300).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+  let nums = (0..30000)
+    .map(|_| hashes[rng.gen_range(0..hashes.len())])
+    .collect::<Vec<_>>();
+
+  let dict_config = ChunkConfig::default().with_dict_spec(DictSpec::Enabled);
+  let (compressed, meta) = compress_w_meta(&nums, &dict_config)?;
+  assert_eq!(meta.mode, Mode::Dict);
+  assert_eq!(meta.dict.len(), 300);
+  let (without_dict, meta) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert_ne!(meta.mode, Mode::Dict);
+  assert!(compressed.len() * 2 < without_dict.len());
+  assert_nums_eq(
+    &simple_decompress::<u64>(&compressed)?,
+    &nums,
+    "dict",
+  )?;
+
+  // each chunk of a file has its own dict, and delta encoding still works
+  let config = dict_config
+    .clone()
+    .with_paging_spec(PagingSpec::Exact(vec![1, 9999, 20000]))
+    .with_delta_encoding_order(Some(1));
+  let compressed = simple_compress(&nums, &config)?;
+  assert_nums_eq(
+    &simple_decompress::<u64>(&compressed)?,
+    &nums,
+    "dict pages",
+  )?;
+
+  // too many distinct numbers to try it
+  let nums = (0..30000)
+    .map(|_| rng.gen::<u64>() % 5000)
+    .collect::<Vec<_>>();
+  let (compressed, meta) = compress_w_meta(&nums, &dict_config)?;
+  assert_ne!(meta.mode, Mode::Dict);
+  assert!(meta.dict.is_empty());
+  assert_nums_eq(
+    &simple_decompress::<u64>(&compressed)?,
+    &nums,
+    "dict high cardinality",
+  )?;
+  Ok(())
+}
+
+#[test]
+fn test_auto_level() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  // a few far-apart clusters, which level 0's single bin handles badly
+  let clustered = (0..2000)
+    .map(|_| rng.gen_range(0..4) * 1_000_000 + rng.gen_range(0..16))
+    .collect::<Vec<u32>>();
+  let random = (0..2000).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+
+  for (nums, level, name) in [(&clustered, 0, "clustered"), (&random, 8, "random")] {
+    let config = ChunkConfig::default()
+      .with_compression_level(level)
+      .with_delta_encoding_order(Some(0))
+      .with_dict_spec(DictSpec::Disabled);
+    let (fixed, _) = compress_w_meta(nums, &config)?;
+    let (auto, _) = compress_w_meta(
+      nums,
+      &config.with_auto_level_spec(AutoLevelSpec::Enabled),
+    )?;
+    assert!(auto.len() <= fixed.len(), "{}", name);
+    if name == "clustered" {
+      assert!(auto.len() * 2 < fixed.len());
+    }
+    assert_nums_eq(&simple_decompress::<u32>(&auto)?, nums, name)?;
+  }
+  Ok(())
+}
+
+#[test]
+fn test_max_n_bins() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let nums = (0..10000)
+    .map(|_| rng.gen_range(0..100) * 1000 + rng.gen_range(0..10))
+    .collect::<Vec<u32>>();
+  let config = ChunkConfig::default()
+    .with_delta_encoding_order(Some(0))
+    .with_dict_spec(DictSpec::Disabled);
+  let (_, uncapped_meta) = compress_w_meta(&nums, &config)?;
+  assert!(uncapped_meta.per_latent_var[0].bins.len() > 16);
+
+  for (max_n_bins, expected_max) in [(1, 1), (16, 16), (20, 16)] {
+    let (compressed, meta) = compress_w_meta(
+      &nums,
+      &config.clone().with_max_n_bins(Some(max_n_bins)),
+    )?;
+    let latent_var = &meta.per_latent_var[0];
+    assert!(
+      latent_var.bins.len() <= expected_max,
+      "max_n_bins={}",
+      max_n_bins
+    );
+    assert!(1 << latent_var.ans_size_log >= latent_var.bins.len());
+    assert_nums_eq(
+      &simple_decompress::<u32>(&compressed)?,
+      &nums,
+      "max_n_bins",
+    )?;
+  }
+  Ok(())
+}
+
+#[test]
+fn test_explicit_bins() -> PcoResult<()> {
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  let nums = (0..10000)
+    .map(|_| rng.gen_range(0..1000))
+    .collect::<Vec<u32>>();
+  // the bin from 5000 to 6000 is empty, so it gets omitted
+  let config = ChunkConfig::default().with_explicit_bins(Some(vec![0, 100, 250, 5000, 6000]));
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::Classic);
+  assert_eq!(meta.delta_encoding_order, 0);
+  let bins = &meta.per_latent_var[0].bins;
+  assert_eq!(
+    bins.iter().map(|bin| bin.lower).collect::<Vec<_>>(),
+    vec![0, 100, 250],
+  );
+  assert_eq!(
+    bins.iter().map(|bin| bin.offset_bits).collect::<Vec<_>>(),
+    vec![7, 8, 13],
+  );
+  assert_nums_eq(
+    &simple_decompress::<u32>(&compressed)?,
+    &nums,
+    "explicit bins",
+  )?;
+
+  // boundaries for signed numbers are in terms of their ordered latents
+  let signeds = nums.iter().map(|&x| x as i32 - 500).collect::<Vec<_>>();
+  let boundaries = [-500_i32, 0, 499]
+    .map(|x| x.to_latent_ordered() as u64)
+    .to_vec();
+  let (compressed, meta) = compress_w_meta(
+    &signeds,
+    &ChunkConfig::default().with_explicit_bins(Some(boundaries)),
+  )?;
+  assert_eq!(meta.per_latent_var[0].bins.len(), 2);
+  assert_nums_eq(
+    &simple_decompress::<i32>(&compressed)?,
+    &signeds,
+    "signed explicit bins",
+  )?;
+
+  for (boundaries, delta_order) in [
+    (vec![0, 500], None),      // doesn't cover the data
+    (vec![0], None),           // too few boundaries
+    (vec![0, 500, 500], None), // not strictly increasing
+    (vec![0, 1 << 40], None),  // too large for u32
+    (vec![0, 1000], Some(1)),  // requires delta order 0
+  ] {
+    let config = ChunkConfig::default()
+      .with_delta_encoding_order(delta_order)
+      .with_explicit_bins(Some(boundaries.clone()));
+    let err = simple_compress(&nums, &config).unwrap_err();
+    assert!(
+      matches!(err.kind, ErrorKind::InvalidArgument),
+      "{:?}",
+      boundaries
+    );
+  }
+  Ok(())
+}
+
+#[test]
+fn test_constant() -> PcoResult<()> {
+  let n = 300_000;
+  let nums = vec![-7.5_f64; n];
+  let (compressed, meta) = compress_w_meta(&nums, &ChunkConfig::default())?;
+  assert_eq!(meta.mode, Mode::Classic);
+  assert!(meta.per_latent_var[0].is_trivial());
+  // only the header, chunk metadata, and footer
+  assert!(compressed.len() < 30);
+  assert_nums_eq(
+    &simple_decompress(&compressed)?,
+    &nums,
+    "constant",
+  )?;
+
+  // every page of a constant chunk is empty
+  let fc = wrapped::FileCompressor::default();
+  let config = ChunkConfig::default().with_paging_spec(PagingSpec::EqualPagesUpTo(100_000));
+  let cc = fc.chunk_compressor(&nums, &config)?;
+  for page_idx in 0..cc.n_per_page().len() {
+    assert!(cc.write_page(page_idx, Vec::new())?.is_empty());
+  }
+
+  // multiplier modes with constant latents decompress to the constant too
+  let nums = vec![3000_u32; n];
+  let config = ChunkConfig::default()
+    .with_int_mult_spec(IntMultSpec::Provided(1000))
+    .with_run_length_spec(RunLengthSpec::Disabled)
+    .with_dict_spec(DictSpec::Disabled);
+  let (compressed, meta) = compress_w_meta(&nums, &config)?;
+  assert_eq!(meta.mode, Mode::IntMult(1000));
+  assert_nums_eq(
+    &simple_decompress(&compressed)?,
+    &nums,
+    "constant int mult",
+  )?;
+
+  // a constant run in the middle of other chunks
+  let mut nums = (0..1000).collect::<Vec<u32>>();
+  nums.extend(vec![5; 2000]);
+  nums.extend(0..1000);
+  let config = ChunkConfig::default().with_paging_spec(PagingSpec::Exact(vec![1000, 2000, 1000]));
+  let compressed = simple_compress(&nums, &config)?;
+  assert_nums_eq(
+    &simple_decompress(&compressed)?,
+    &nums,
+    "constant middle chunk",
+  )?;
+  Ok(())
+}
+
+#[test]
+fn test_float_mult_wide_magnitudes() -> PcoResult<()> {
+  // multiples of 0.1 mixed with tiny numbers spanning many orders of
+  // magnitude, including denormals
+  let mut rng = rand_xoshiro::Xoroshiro128PlusPlus::seed_from_u64(0);
+  for frac_tiny in [0.0, 0.3, 0.7, 0.95] {
+    let mut nums32 = Vec::new();
+    let mut nums64 = Vec::new();
+    for _ in 0..20000 {
+      if rng.gen_bool(frac_tiny) {
+        let scale = rng.gen_range(1.0..10.0);
+        nums32.push(10_f32.powf(rng.gen_range(-44.0..-5.0)) * scale as f32);
+        nums64.push(10_f64.powf(rng.gen_range(-320.0..-5.0)) * scale);
+      } else {
+        let k = rng.gen_range(0..100000);
+        nums32.push(k as f32 * 0.1);
+        nums64.push(k as f64 * 0.1);
+      }
+    }
+
+    let classic_config = ChunkConfig::default().with_float_mult_spec(FloatMultSpec::Disabled);
+    let (compressed, _) = compress_w_meta(&nums32, &ChunkConfig::default())?;
+    let (classic, _) = compress_w_meta(&nums32, &classic_config)?;
+    assert!(
+      compressed.len() <= classic.len(),
+      "f32 {}",
+      frac_tiny
+    );
+    assert_nums_eq(
+      &simple_decompress::<f32>(&compressed)?,
+      &nums32,
+      "f32",
+    )?;
+
+    let (compressed, meta) = compress_w_meta(&nums64, &ChunkConfig::default())?;
+    let (classic, _) = compress_w_meta(&nums64, &classic_config)?;
+    assert!(
+      compressed.len() <= classic.len(),
+      "f64 {}",
+      frac_tiny
+    );
+    if frac_tiny <= 0.7 {
+      assert!(
+        matches!(meta.mode, Mode::FloatMult(_)),
+        "f64 {}",
+        frac_tiny
+      );
+    }
+    assert_nums_eq(
+      &simple_decompress::<f64>(&compressed)?,
+      &nums64,
+      "f64",
+    )?;
+  }
+  Ok(())
+}
+
+#[test]
+fn test_force_mode() -> PcoResult<()> {
+  // smooth data that auto selection would compress in classic mode
+  let ints = (0..5000).map(|i| (i * 7919) % 3000).collect::<Vec<i64>>();
+  let floats = ints.iter().map(|&i| i as f64 * 0.5).collect::<Vec<_>>();
+
+  for kind in [
+    ModeKind::Classic,
+    ModeKind::RunLength,
+    ModeKind::Sparse,
+    ModeKind::Dict,
+  ] {
+    let config = ChunkConfig::default().with_force_mode(Some(kind));
+    let (compressed, meta) = compress_w_meta(&ints, &config)?;
+    assert_eq!(meta.mode.kind(), kind);
+    assert_nums_eq(
+      &simple_decompress::<i64>(&compressed)?,
+      &ints,
+      &format!("forced {:?}", kind),
+    )?;
+  }
+
+  let config = ChunkConfig::default()
+    .with_force_mode(Some(ModeKind::IntMult))
+    .with_int_mult_spec(IntMultSpec::Provided(7));
+  let (compressed, meta) = compress_w_meta(&ints, &config)?;
+  assert_eq!(meta.mode, Mode::IntMult(7));
+  assert_nums_eq(
+    &simple_decompress::<i64>(&compressed)?,
+    &ints,
+    "forced int mult",
+  )?;
+
+  for (kind, float_mult_spec, float_quant_spec) in [
+    (
+      ModeKind::FloatMult,
+      FloatMultSpec::Provided(0.5),
+      FloatQuantSpec::Disabled,
+    ),
+    (
+      ModeKind::FloatQuant,
+      FloatMultSpec::Disabled,
+      FloatQuantSpec::Provided(10),
+    ),
+    (
+      ModeKind::FloatXor,
+      FloatMultSpec::default(),
+      FloatQuantSpec::default(),
+    ),
+  ] {
+    let config = ChunkConfig::default()
+      .with_force_mode(Some(kind))
+      .with_float_mult_spec(float_mult_spec)
+      .with_float_quant_spec(float_quant_spec);
+    let (compressed, meta) = compress_w_meta(&floats, &config)?;
+    assert_eq!(meta.mode.kind(), kind);
+    assert_nums_eq(
+      &simple_decompress::<f64>(&compressed)?,
+      &floats,
+      &format!("forced {:?}", kind),
+    )?;
+  }
+  Ok(())
+}
+
+#[test]
+fn test_force_mode_errors() {
+  let ints = (0..5000).map(|i| (i * 7919) % 3000).collect::<Vec<i64>>();
+  let floats = (0..5000).map(|i| i as f32).collect::<Vec<_>>();
+  let force = |kind| ChunkConfig::default().with_force_mode(Some(kind));
+
+  for err in [
+    // invalid for the data type
+    simple_compress(&ints, &force(ModeKind::FloatMult)).unwrap_err(),
+    simple_compress(&floats, &force(ModeKind::IntMult)).unwrap_err(),
+    // no base available
+    simple_compress(
+      &ints,
+      &force(ModeKind::IntMult).with_int_mult_spec(IntMultSpec::Disabled),
+    )
+    .unwrap_err(),
+    simple_compress(&floats, &force(ModeKind::FloatQuant)).unwrap_err(),
+    // too many distinct numbers
+    simple_compress(
+      &(0..5000).collect::<Vec<i64>>(),
+      &force(ModeKind::Dict),
+    )
+    .unwrap_err(),
+  ] {
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+  }
+}
@@ -3,13 +3,14 @@ use crate::chunk_meta::ChunkMeta;
 use crate::data_types::NumberLike;
 use crate::errors::{ErrorKind, PcoResult};
 use crate::standalone::{simple_decompress, FileCompressor};
-use crate::IntMultSpec;
+use crate::{IntMultSpec, RunLengthSpec};
 
 fn assert_panic_safe<T: NumberLike>(nums: Vec<T>) -> PcoResult<ChunkMeta<T::L>> {
   let fc = FileCompressor::default();
   let config = ChunkConfig {
     int_mult_spec: IntMultSpec::Disabled,
     delta_encoding_order: Some(0),
+    run_length_spec: RunLengthSpec::Disabled,
     ..Default::default()
   };
   let cc = fc.chunk_compressor(&nums, &config)?;
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+/// A breakdown of the time spent in each phase of compression, useful for
+/// benchmarking and profiling.
+///
+/// See [`compress_timed`][crate::standalone::compress_timed].
+/// Each phase is summed over all chunks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Timings {
+  /// The time spent planning each chunk: choosing its mode and delta
+  /// encoding order, then building and optimizing its bins and ANS tables.
+  pub bin_building: Duration,
+  /// The time spent assigning each latent to its bin and ANS encoding it,
+  /// which produces each page's ANS values and offsets.
+  pub ans_encoding: Duration,
+  /// The time spent writing each page's metadata and body, which mostly
+  /// consists of the ANS values and offsets.
+  pub offset_writing: Duration,
+  /// The time spent writing everything else: the header, footer, and each
+  /// chunk's preamble and metadata.
+  pub other: Duration,
+}
+
+impl Timings {
+  /// Returns the total time spent in all phases.
+  pub fn total(&self) -> Duration {
+    self.bin_building + self.ans_encoding + self.offset_writing + self.other
+  }
+}
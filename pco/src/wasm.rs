@@ -0,0 +1,55 @@
+//! Non-generic entry points for use from JavaScript via `wasm-bindgen`.
+//!
+//! Since `wasm-bindgen` cannot export generic functions, this module exposes
+//! a `compress_*` and `decompress_*` function for each number type that has
+//! a JS typed array counterpart.
+//! Each one wraps the corresponding function in [`standalone`][crate::standalone],
+//! converting any error into a JS `Error`.
+//!
+//! Example (JS):
+//! ```js
+//! const compressed = compress_f64(new Float64Array([1.5, 2.5]), 8);
+//! const recovered = decompress_f64(compressed);
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::errors::PcoError;
+use crate::standalone::{simple_compress, simple_decompress};
+use crate::ChunkConfig;
+
+fn to_js_error(err: PcoError) -> JsValue {
+  JsError::new(&err.to_string()).into()
+}
+
+macro_rules! impl_wasm_entry_points {
+  ($t: ty, $compress: ident, $decompress: ident) => {
+    /// Compresses the numbers into a standalone .pco file using the given
+    /// compression level and an otherwise default configuration.
+    ///
+    /// Will throw if the compression level is invalid.
+    #[wasm_bindgen]
+    pub fn $compress(nums: &[$t], compression_level: usize) -> Result<Vec<u8>, JsValue> {
+      let config = ChunkConfig::default().with_compression_level(compression_level);
+      simple_compress(nums, &config).map_err(to_js_error)
+    }
+
+    /// Decompresses a standalone .pco file into numbers.
+    ///
+    /// Will throw if the file is corrupt, truncated, or of a different
+    /// number type.
+    #[wasm_bindgen]
+    pub fn $decompress(bytes: &[u8]) -> Result<Vec<$t>, JsValue> {
+      simple_decompress::<$t>(bytes).map_err(to_js_error)
+    }
+  };
+}
+
+impl_wasm_entry_points!(u16, compress_u16, decompress_u16);
+impl_wasm_entry_points!(u32, compress_u32, decompress_u32);
+impl_wasm_entry_points!(u64, compress_u64, decompress_u64);
+impl_wasm_entry_points!(i16, compress_i16, decompress_i16);
+impl_wasm_entry_points!(i32, compress_i32, decompress_i32);
+impl_wasm_entry_points!(i64, compress_i64, decompress_i64);
+impl_wasm_entry_points!(f32, compress_f32, decompress_f32);
+impl_wasm_entry_points!(f64, compress_f64, decompress_f64);
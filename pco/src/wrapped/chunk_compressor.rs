@@ -1,25 +1,36 @@
 use std::cmp::min;
 use std::io::Write;
+use std::mem;
+#[cfg(feature = "bench")]
+use std::time::Instant;
 
+use crate::ans::Symbol;
 use crate::bin::BinCompressionInfo;
 use crate::bit_writer::BitWriter;
 use crate::compression_intermediates::{DissectedPage, DissectedPageVar, PageInfo};
+use crate::compression_report::CompressionReport;
 use crate::compression_table::CompressionTable;
 use crate::constants::{
-  Bitlen, Weight, ANS_INTERLEAVING, LIMITED_UNOPTIMIZED_BINS_LOG, MAX_COMPRESSION_LEVEL,
-  MAX_DELTA_ENCODING_ORDER, MAX_ENTRIES, OVERSHOOT_PADDING, PAGE_PADDING,
+  Bitlen, Weight, ANS_INTERLEAVING, LIMITED_UNOPTIMIZED_BINS_LOG, MAX_AUTO_DELTA_ENCODING_ORDER,
+  MAX_COMPRESSION_LEVEL, MAX_DICT_SIZE, MAX_ENTRIES, OVERSHOOT_PADDING, PAGE_PADDING,
+  RUN_LENGTH_MIN_AVG_RUN_LENGTH, SPARSE_MIN_AVG_ENTRY_LENGTH, TINY_CHUNK_MAX_N,
 };
 use crate::data_types::{Latent, NumberLike};
 use crate::delta::DeltaMoments;
 use crate::errors::{PcoError, PcoResult};
+use crate::format_version::FormatVersion;
 use crate::histograms::histogram;
 use crate::latent_batch_dissector::LatentBatchDissector;
 use crate::page_meta::{PageLatentVarMeta, PageMeta};
 use crate::read_write_uint::ReadWriteUint;
+#[cfg(feature = "bench")]
+use crate::timings::Timings;
 use crate::wrapped::guarantee;
 use crate::{
-  ans, bin_optimization, bit_reader, bit_writer, data_types, delta, read_write_uint, Bin,
-  ChunkConfig, ChunkLatentVarMeta, ChunkMeta, Mode, PagingSpec, FULL_BATCH_N,
+  ans, bin_optimization, bit_reader, bit_writer, bits, data_types, delta, dict_utils,
+  float_xor_utils, read_write_uint, run_length_utils, sparse_utils, AutoLevelSpec, Bin,
+  ChunkConfig, ChunkLatentVarMeta, ChunkMeta, DictSpec, FloatMultSpec, FloatXorSpec, Mode,
+  ModeKind, RunLengthSpec, SparseSpec, FULL_BATCH_N,
 };
 
 // if it looks like the average page of size n will use k bits, hint that it
@@ -27,6 +38,8 @@ use crate::{
 const PAGE_SIZE_OVERESTIMATION: f64 = 1.2;
 const N_PER_EXTRA_DELTA_GROUP: usize = 10000;
 const DELTA_GROUP_SIZE: usize = 200;
+// how far auto level strays from the configured level's unoptimized bins log
+const AUTO_LEVEL_BINS_LOG_STEP: Bitlen = 2;
 
 // returns table size log
 fn quantize_weights<L: Latent>(
@@ -53,11 +66,15 @@ struct TrainedBins<L: Latent> {
 fn train_infos<L: Latent>(
   mut latents: Vec<L>,
   unoptimized_bins_log: Bitlen,
+  max_ans_size_log: Bitlen,
 ) -> PcoResult<TrainedBins<L>> {
   if latents.is_empty() {
     return Ok(TrainedBins::default());
   }
 
+  // Having no more bins than the max table size guarantees that quantizing
+  // weights won't need to grow the table beyond it.
+  let unoptimized_bins_log = min(unoptimized_bins_log, max_ans_size_log);
   let n_latents = latents.len();
   let unoptimized_bins = histogram(&mut latents, unoptimized_bins_log as Bitlen);
 
@@ -66,15 +83,12 @@ fn train_infos<L: Latent>(
   } else {
     (n_latents - 1).ilog2() + 1
   };
-  // We cap the ANS table size so that it fits into L1 (or at least L2) cache
-  // and has predictably small bitlengths for fast decompression.
-  // Maybe in the future we could extend this to MAX_ANS_BITS (14) if the user
-  // enables something. We should definitely quantize more aggressively if we
-  // do that.
+  // Maybe in the future we could allow max_ans_size_log up to MAX_ANS_BITS
+  // (14). We should definitely quantize more aggressively if we do that.
   let estimated_ans_size_log = min(
     min(
       (unoptimized_bins_log + 2) as Bitlen,
-      MAX_COMPRESSION_LEVEL as Bitlen,
+      max_ans_size_log,
     ),
     n_log_ceil,
   );
@@ -99,6 +113,89 @@ fn train_infos<L: Latent>(
   })
 }
 
+// Like train_infos, but with bins dictated by the boundaries instead of
+// optimized. Only the weights are learned from the latents.
+fn train_explicit_infos<L: Latent>(
+  latents: Vec<L>,
+  boundaries: &[u64],
+  max_ans_size_log: Bitlen,
+) -> PcoResult<TrainedBins<L>> {
+  if latents.is_empty() {
+    return Ok(TrainedBins::default());
+  }
+
+  if let Some(&boundary) = boundaries.iter().find(|&&b| b > L::MAX.to_u64()) {
+    return Err(PcoError::invalid_argument(format!(
+      "explicit bin boundary {} exceeds the max latent {}",
+      boundary,
+      L::MAX,
+    )));
+  }
+  let boundaries = boundaries
+    .iter()
+    .map(|&b| L::from_u64(b))
+    .collect::<Vec<_>>();
+  let n_bins = boundaries.len() - 1;
+  let lowest = boundaries[0];
+  let highest = boundaries[n_bins];
+
+  let mut counts = vec![0; n_bins];
+  for &latent in &latents {
+    if latent < lowest || latent > highest {
+      return Err(PcoError::invalid_argument(format!(
+        "explicit bins cover {} to {}, but found latent {}",
+        lowest, highest, latent,
+      )));
+    }
+    let bin_idx = boundaries[1..n_bins].partition_point(|&b| b <= latent);
+    counts[bin_idx] += 1;
+  }
+
+  let mut infos = Vec::new();
+  for (bin_idx, &count) in counts.iter().enumerate() {
+    if count == 0 {
+      continue;
+    }
+    let lower = boundaries[bin_idx];
+    let upper = if bin_idx + 1 == n_bins {
+      highest
+    } else {
+      boundaries[bin_idx + 1] - L::ONE
+    };
+    infos.push(BinCompressionInfo {
+      weight: count,
+      lower,
+      upper,
+      offset_bits: bits::bits_to_encode_offset(upper - lower),
+      symbol: infos.len() as Symbol,
+    });
+  }
+  if infos.len() > 1 << max_ans_size_log {
+    return Err(PcoError::invalid_argument(format!(
+      "{} explicit bins are occupied, but the max ANS size log of {} allows at most {}",
+      infos.len(),
+      max_ans_size_log,
+      1 << max_ans_size_log,
+    )));
+  }
+
+  let n_latents = latents.len();
+  let bins_log_ceil = infos.len().next_power_of_two().ilog2();
+  let n_log_ceil = n_latents.next_power_of_two().ilog2();
+  let estimated_ans_size_log = min(
+    min(bins_log_ceil + 2, max_ans_size_log),
+    n_log_ceil,
+  );
+  let counts = infos.iter().map(|info| info.weight).collect::<Vec<_>>();
+  let ans_size_log = quantize_weights(&mut infos, n_latents, estimated_ans_size_log);
+
+  Ok(TrainedBins {
+    infos,
+    ans_size_log,
+    counts,
+  })
+}
+
 fn uninit_vec<T>(n: usize) -> Vec<T> {
   unsafe {
     let mut res = Vec::with_capacity(n);
@@ -234,34 +331,14 @@ pub struct ChunkCompressor<L: Latent> {
   deltas: Vec<Vec<L>>,
   // n_pages x n_latent_vars
   delta_moments: Vec<Vec<DeltaMoments<L>>>,
+  format_version: FormatVersion,
 }
 
 fn bins_from_compression_infos<L: Latent>(infos: &[BinCompressionInfo<L>]) -> Vec<Bin<L>> {
   infos.iter().cloned().map(Bin::from).collect()
 }
 
-fn validate_config(config: &ChunkConfig) -> PcoResult<()> {
-  let compression_level = config.compression_level;
-  if compression_level > MAX_COMPRESSION_LEVEL {
-    return Err(PcoError::invalid_argument(format!(
-      "compression level may not exceed {} (was {})",
-      MAX_COMPRESSION_LEVEL, compression_level,
-    )));
-  }
-
-  if let Some(order) = config.delta_encoding_order {
-    if order > MAX_DELTA_ENCODING_ORDER {
-      return Err(PcoError::invalid_argument(format!(
-        "delta encoding order may not exceed {} (was {})",
-        MAX_DELTA_ENCODING_ORDER, order,
-      )));
-    }
-  }
-
-  Ok(())
-}
-
-fn validate_chunk_size(n: usize) -> PcoResult<()> {
+pub(crate) fn validate_chunk_size(n: usize) -> PcoResult<()> {
   if n == 0 {
     return Err(PcoError::invalid_argument(
       "cannot compress empty chunk",
@@ -293,7 +370,9 @@ fn collect_contiguous_deltas<L: Latent>(
 fn build_page_infos_and_delta_moments<L: Latent>(
   mode: Mode<L>,
   delta_order: usize,
+  secondary_delta_order: usize,
   n_per_page: &[usize],
+  n_latents_per_page: &[usize],
   latents: &mut [Vec<L>],
 ) -> (Vec<PageInfo>, Vec<Vec<DeltaMoments<L>>>) {
   let n_pages = n_per_page.len();
@@ -302,44 +381,86 @@ fn build_page_infos_and_delta_moments<L: Latent>(
 
   // delta encoding
   let mut start_idx = 0;
-  for (&page_n, delta_moments) in n_per_page.iter().zip(delta_moments.iter_mut()) {
+  for ((&page_n, &n_latents), delta_moments) in n_per_page
+    .iter()
+    .zip(n_latents_per_page)
+    .zip(delta_moments.iter_mut())
+  {
     let mut end_idx_per_var = Vec::new();
     for (latent_var_idx, latents) in latents.iter_mut().enumerate() {
-      let var_delta_order = mode.delta_order_for_latent_var(latent_var_idx, delta_order);
+      let var_delta_order = mode.delta_order_for_latent_var(
+        latent_var_idx,
+        delta_order,
+        secondary_delta_order,
+      );
       delta_moments.push(delta::encode_in_place(
-        &mut latents[start_idx..start_idx + page_n],
+        &mut latents[start_idx..start_idx + n_latents],
         var_delta_order,
       ));
-      end_idx_per_var.push(start_idx + page_n.saturating_sub(var_delta_order));
+      end_idx_per_var.push(start_idx + n_latents.saturating_sub(var_delta_order));
     }
     page_infos.push(PageInfo {
       page_n,
+      n_latents,
       start_idx,
       end_idx_per_var,
     });
 
-    start_idx += page_n;
+    start_idx += n_latents;
   }
 
   (page_infos, delta_moments)
 }
 
-fn new_candidate_w_split_and_delta_order<L: Latent>(
-  mut latents: Vec<Vec<L>>, // start out plain, gets delta encoded in place
-  paging_spec: &PagingSpec,
-  mode: Mode<L>,
-  delta_order: usize,
-  unoptimized_bins_log: Bitlen,
-) -> PcoResult<(ChunkCompressor<L>, Vec<Vec<Weight>>)> {
-  let chunk_n = latents[0].len();
-  let n_per_page = paging_spec.n_per_page(chunk_n)?;
-  let n_latent_vars = mode.n_latent_vars();
+// builds everything needed to write a latent var from its trained bins,
+// returning its bin counts alongside
+fn finish_latent_var<L: Latent>(
+  trained: TrainedBins<L>,
+) -> PcoResult<(
+  ChunkLatentVarMeta<L>,
+  LatentVarPolicy<L>,
+  Vec<Weight>,
+)> {
+  let bins = bins_from_compression_infos(&trained.infos);
+  let needs_ans = bins.len() != 1;
+
+  let table = CompressionTable::from(trained.infos);
+  let encoder = ans::Encoder::from_bins(trained.ans_size_log, &bins)?;
+
+  let latent_meta = ChunkLatentVarMeta {
+    bins,
+    ans_size_log: trained.ans_size_log,
+  };
+  let max_bits_per_offset = latent_meta.max_bits_per_offset();
+  let avg_bits_per_delta = latent_meta.avg_bits_per_delta();
+  let is_trivial = latent_meta.is_trivial();
 
-  let (page_infos, delta_moments) =
-    build_page_infos_and_delta_moments(mode, delta_order, &n_per_page, &mut latents);
-  let deltas = latents;
+  let max_u64s_per_offset = read_write_uint::calc_max_u64s_for_writing(max_bits_per_offset);
 
-  // training bins
+  let policy = LatentVarPolicy {
+    table,
+    encoder,
+    avg_bits_per_delta,
+    is_trivial,
+    needs_ans,
+    max_u64s_per_offset,
+  };
+  Ok((latent_meta, policy, trained.counts))
+}
+
+struct TrainedLatentVars<L: Latent> {
+  var_metas: Vec<ChunkLatentVarMeta<L>>,
+  var_policies: Vec<LatentVarPolicy<L>>,
+  bin_counts: Vec<Vec<Weight>>,
+}
+
+fn train_latent_vars<L: Latent>(
+  deltas: &[Vec<L>],
+  page_infos: &[PageInfo],
+  unoptimized_bins_log: Bitlen,
+  max_ans_size_log: Bitlen,
+) -> PcoResult<TrainedLatentVars<L>> {
+  let n_latent_vars = deltas.len();
   let mut var_metas = Vec::with_capacity(n_latent_vars);
   let mut var_policies = Vec::with_capacity(n_latent_vars);
   let mut bin_counts = Vec::with_capacity(n_latent_vars);
@@ -354,49 +475,117 @@ fn new_candidate_w_split_and_delta_order<L: Latent>(
       )
     };
 
-    let contiguous_deltas = collect_contiguous_deltas(deltas, &page_infos, latent_idx);
+    let contiguous_deltas = collect_contiguous_deltas(deltas, page_infos, latent_idx);
 
-    let trained = train_infos(contiguous_deltas, unoptimized_bins_log)?;
-    let bins = bins_from_compression_infos(&trained.infos);
-    let needs_ans = bins.len() != 1;
-
-    let table = CompressionTable::from(trained.infos);
-    let encoder = ans::Encoder::from_bins(trained.ans_size_log, &bins)?;
+    let trained = train_infos(
+      contiguous_deltas,
+      unoptimized_bins_log,
+      max_ans_size_log,
+    )?;
+    let (latent_meta, policy, counts) = finish_latent_var(trained)?;
+    var_metas.push(latent_meta);
+    var_policies.push(policy);
+    bin_counts.push(counts);
+  }
 
-    let latent_meta = ChunkLatentVarMeta {
-      bins,
-      ans_size_log: trained.ans_size_log,
-    };
-    let max_bits_per_offset = latent_meta.max_bits_per_offset();
-    let avg_bits_per_delta = latent_meta.avg_bits_per_delta();
-    let is_trivial = latent_meta.is_trivial();
+  Ok(TrainedLatentVars {
+    var_metas,
+    var_policies,
+    bin_counts,
+  })
+}
 
-    let max_u64s_per_offset = read_write_uint::calc_max_u64s_for_writing(max_bits_per_offset);
+#[allow(clippy::too_many_arguments)]
+fn new_candidate_w_split_and_delta_order<L: Latent>(
+  mut latents: Vec<Vec<L>>, // start out plain, gets delta encoded in place
+  n_per_page: &[usize],
+  n_latents_per_page: &[usize],
+  mode: Mode<L>,
+  delta_order: usize,
+  secondary_delta_order: usize,
+  unoptimized_bins_log: Bitlen,
+  max_ans_size_log: Bitlen,
+) -> PcoResult<(ChunkCompressor<L>, Vec<Vec<Weight>>)> {
+  let n_latent_vars = mode.n_latent_vars();
 
-    var_metas.push(latent_meta);
-    var_policies.push(LatentVarPolicy {
-      table,
-      encoder,
-      avg_bits_per_delta,
-      is_trivial,
-      needs_ans,
-      max_u64s_per_offset,
-    });
-    bin_counts.push(trained.counts);
-  }
+  let (page_infos, delta_moments) = build_page_infos_and_delta_moments(
+    mode,
+    delta_order,
+    secondary_delta_order,
+    n_per_page,
+    n_latents_per_page,
+    &mut latents,
+  );
+  let deltas = latents;
+  let TrainedLatentVars {
+    var_metas,
+    var_policies,
+    bin_counts,
+  } = train_latent_vars(
+    &deltas,
+    &page_infos,
+    unoptimized_bins_log,
+    max_ans_size_log,
+  )?;
 
-  let meta = ChunkMeta::new(mode, delta_order, var_metas);
+  let secondary_delta_order = if n_latent_vars >= 2 {
+    secondary_delta_order
+  } else {
+    0
+  };
+  let meta = ChunkMeta::new(
+    mode,
+    delta_order,
+    secondary_delta_order,
+    var_metas,
+  );
   let chunk_compressor = ChunkCompressor {
     meta,
     latent_var_policies: var_policies,
     page_infos,
     deltas,
     delta_moments,
+    format_version: FormatVersion::default(),
   };
 
   Ok((chunk_compressor, bin_counts))
 }
 
+// Explicit bins are in terms of the numbers' ordered latents, so they only
+// apply in classic mode without delta encoding.
+fn new_candidate_w_explicit_bins<L: Latent>(
+  mut latents: Vec<Vec<L>>,
+  n_per_page: &[usize],
+  boundaries: &[u64],
+  max_ans_size_log: Bitlen,
+) -> PcoResult<(ChunkCompressor<L>, Vec<Vec<Weight>>)> {
+  let (page_infos, delta_moments) = build_page_infos_and_delta_moments(
+    Mode::Classic,
+    0,
+    0,
+    n_per_page,
+    n_per_page,
+    &mut latents,
+  );
+  let contiguous_latents = collect_contiguous_deltas(&latents[0], &page_infos, 0);
+  let trained = train_explicit_infos(
+    contiguous_latents,
+    boundaries,
+    max_ans_size_log,
+  )?;
+  let (latent_meta, policy, counts) = finish_latent_var(trained)?;
+
+  let chunk_compressor = ChunkCompressor {
+    meta: ChunkMeta::new(Mode::Classic, 0, 0, vec![latent_meta]),
+    latent_var_policies: vec![policy],
+    page_infos,
+    deltas: latents,
+    delta_moments,
+    format_version: FormatVersion::default(),
+  };
+  Ok((chunk_compressor, vec![counts]))
+}
+
 fn choose_delta_sample<L: Latent>(
   primary_latents: &[L],
   group_size: usize,
@@ -422,13 +611,14 @@ fn choose_delta_sample<L: Latent>(
   sample
 }
 
-// Right now this is entirely based on the primary latents since no existing
-// modes apply deltas to secondary latents. Might want to change this
-// eventually?
+// This automatic search only considers the primary latents. Deltas on
+// secondary latents are only ever applied as set by the user via
+// `ChunkConfig::secondary_delta_encoding_order`, which defaults to 0.
 #[inline(never)]
-fn choose_delta_encoding_order<L: Latent>(
+pub(crate) fn choose_delta_encoding_order<L: Latent>(
   primary_latents: &[L],
   unoptimized_bins_log: Bitlen,
+  max_ans_size_log: Bitlen,
 ) -> PcoResult<usize> {
   let sample = choose_delta_sample(
     primary_latents,
@@ -438,13 +628,16 @@ fn choose_delta_encoding_order<L: Latent>(
 
   let mut best_order = usize::MAX;
   let mut best_size = usize::MAX;
-  for delta_encoding_order in 0..MAX_DELTA_ENCODING_ORDER + 1 {
+  for delta_encoding_order in 0..MAX_AUTO_DELTA_ENCODING_ORDER + 1 {
     let (sample_cc, _) = new_candidate_w_split_and_delta_order(
       vec![sample.clone()],
-      &PagingSpec::Exact(vec![sample.len()]),
+      &[sample.len()],
+      &[sample.len()],
       Mode::Classic,
       delta_encoding_order,
+      0,
       unoptimized_bins_log,
+      max_ans_size_log,
     )?;
     let size_estimate = sample_cc.chunk_meta_size_hint() + sample_cc.page_size_hint_inner(0, 1.0);
     if size_estimate < best_size {
@@ -459,7 +652,7 @@ fn choose_delta_encoding_order<L: Latent>(
   Ok(best_order)
 }
 
-fn choose_unoptimized_bins_log(compression_level: usize, n: usize) -> Bitlen {
+pub(crate) fn choose_unoptimized_bins_log(compression_level: usize, n: usize) -> Bitlen {
   let compression_level = compression_level as Bitlen;
   let log_n = (n as f64).log2().floor() as Bitlen;
   let fast_unoptimized_bins_log = log_n.saturating_sub(4);
@@ -470,6 +663,19 @@ fn choose_unoptimized_bins_log(compression_level: usize, n: usize) -> Bitlen {
   }
 }
 
+// applies the config's bin cap, if any, to the level-derived bins log
+fn choose_config_unoptimized_bins_log(
+  compression_level: usize,
+  config: &ChunkConfig,
+  n: usize,
+) -> Bitlen {
+  let unoptimized_bins_log = choose_unoptimized_bins_log(compression_level, n);
+  match config.max_n_bins {
+    Some(max_n_bins) => min(unoptimized_bins_log, max_n_bins.ilog2()),
+    None => unoptimized_bins_log,
+  }
+}
+
 // We pull this stuff out of `new` because it only depends on the latent type
 // and we don't need a specialization for each full dtype.
 // Returns a chunk compressor and the counts (per latent var) of numbers in
@@ -477,33 +683,102 @@ fn choose_unoptimized_bins_log(compression_level: usize, n: usize) -> Bitlen {
 fn new_candidate_w_split<L: Latent>(
   mode: Mode<L>,
   latents: Vec<Vec<L>>,
+  n_per_page: &[usize],
+  n_latents_per_page: &[usize],
   config: &ChunkConfig,
 ) -> PcoResult<(ChunkCompressor<L>, Vec<Vec<Weight>>)> {
-  let unoptimized_bins_log =
-    choose_unoptimized_bins_log(config.compression_level, latents[0].len());
-  let delta_order = if let Some(delta_order) = config.delta_encoding_order {
-    delta_order
+  let unoptimized_bins_log = choose_config_unoptimized_bins_log(
+    config.compression_level,
+    config,
+    latents[0].len(),
+  );
+  let (delta_order, secondary_delta_order) = if matches!(mode, Mode::FloatXor) {
+    (0, 0)
+  } else if let Some(delta_order) = config.delta_encoding_order {
+    (
+      delta_order,
+      config.secondary_delta_encoding_order,
+    )
   } else {
-    choose_delta_encoding_order(&latents[0], unoptimized_bins_log)?
+    let delta_order = choose_delta_encoding_order(
+      &latents[0],
+      unoptimized_bins_log,
+      config.max_ans_size_log,
+    )?;
+    (
+      delta_order,
+      config.secondary_delta_encoding_order,
+    )
   };
 
   new_candidate_w_split_and_delta_order(
     latents,
-    &config.paging_spec,
+    n_per_page,
+    n_latents_per_page,
     mode,
     delta_order,
+    secondary_delta_order,
     unoptimized_bins_log,
+    config.max_ans_size_log,
   )
 }
 
+// Retrains the candidate's bins with fewer and more unoptimized bins, keeping
+// whichever has the smallest size estimate. The deltas are reused, so each
+// attempt costs only one round of bin training.
+fn auto_tune_bins<L: Latent>(
+  candidate: &mut ChunkCompressor<L>,
+  bin_counts: &mut Vec<Vec<Weight>>,
+  config: &ChunkConfig,
+) -> PcoResult<()> {
+  let n_latents = candidate.deltas[0].len();
+  let configured = choose_config_unoptimized_bins_log(config.compression_level, config, n_latents);
+  let max = choose_config_unoptimized_bins_log(MAX_COMPRESSION_LEVEL, config, n_latents);
+  let mut best_size = candidate.size_estimate();
+  for unoptimized_bins_log in [
+    configured.saturating_sub(AUTO_LEVEL_BINS_LOG_STEP),
+    min(configured + AUTO_LEVEL_BINS_LOG_STEP, max),
+  ] {
+    if unoptimized_bins_log == configured {
+      continue;
+    }
+
+    let trained = train_latent_vars(
+      &candidate.deltas,
+      &candidate.page_infos,
+      unoptimized_bins_log,
+      config.max_ans_size_log,
+    )?;
+    let prev_var_metas = mem::replace(
+      &mut candidate.meta.per_latent_var,
+      trained.var_metas,
+    );
+    let prev_var_policies = mem::replace(
+      &mut candidate.latent_var_policies,
+      trained.var_policies,
+    );
+    let size = candidate.size_estimate();
+    if size < best_size {
+      best_size = size;
+      *bin_counts = trained.bin_counts;
+    } else {
+      candidate.meta.per_latent_var = prev_var_metas;
+      candidate.latent_var_policies = prev_var_policies;
+    }
+  }
+  Ok(())
+}
+
 fn should_fallback<L: Latent>(
   n: usize,
   candidate: &ChunkCompressor<L>,
   bin_counts_per_latent_var: Vec<Vec<Weight>>,
+  has_explicit_bins: bool,
 ) -> bool {
   let meta = &candidate.meta;
-  if meta.delta_encoding_order == 0 && matches!(meta.mode, Mode::Classic) {
-    // we already have a size guarantee in this case
+  if meta.delta_encoding_order == 0 && matches!(meta.mode, Mode::Classic) && !has_explicit_bins {
+    // we already have a size guarantee in this case, since optimized bins
+    // can't be worse than the trivial one
     return false;
   }
 
@@ -522,7 +797,7 @@ fn should_fallback<L: Latent>(
     }
   }
 
-  let worst_case_size = meta.exact_size()
+  let worst_case_size = meta.exact_size(&FormatVersion::default())
     + n_pages * meta.exact_page_meta_size()
     + worst_case_body_bit_size.div_ceil(8);
   let baseline_size = guarantee::chunk_size::<L>(n);
@@ -533,9 +808,15 @@ fn fallback_chunk_compressor<L: Latent>(
   mut latents: Vec<Vec<L>>,
   config: &ChunkConfig,
 ) -> PcoResult<ChunkCompressor<L>> {
-  let n_per_page = config.paging_spec.n_per_page(latents[0].len())?;
-  let (page_infos, delta_moments) =
-    build_page_infos_and_delta_moments(Mode::Classic, 0, &n_per_page, &mut latents);
+  let n_per_page = config.paging_spec.n_per_page::<L>(latents[0].len())?;
+  let (page_infos, delta_moments) = build_page_infos_and_delta_moments(
+    Mode::Classic,
+    0,
+    0,
+    &n_per_page,
+    &n_per_page,
+    &mut latents,
+  );
   let infos = vec![BinCompressionInfo::<L> {
     weight: 1,
     symbol: 0,
@@ -562,6 +843,269 @@ fn fallback_chunk_compressor<L: Latent>(
     page_infos,
     deltas: latents,
     delta_moments,
+    format_version: FormatVersion::default(),
+  })
+}
+
+// For tiny chunks, metadata dominates and there is too little data for mode
+// detection, delta encoding, or bin optimization to pay off, so we store
+// each latent as an offset from the minimum in a single bin. With one bin,
+// there are no ANS bits to write or read. The exception is a lone number,
+// which is cheapest as a delta moment with no latents or bins at all.
+fn tiny_chunk_compressor<L: Latent>(
+  mut latents: Vec<Vec<L>>,
+  n_per_page: &[usize],
+  delta_order: usize,
+) -> PcoResult<ChunkCompressor<L>> {
+  let (page_infos, delta_moments) = build_page_infos_and_delta_moments(
+    Mode::Classic,
+    delta_order,
+    0,
+    n_per_page,
+    n_per_page,
+    &mut latents,
+  );
+  let deltas = page_infos
+    .iter()
+    .flat_map(|page| &latents[0][page.start_idx..page.end_idx_per_var[0]])
+    .collect::<Vec<_>>();
+  let trained = match (deltas.iter().min(), deltas.iter().max()) {
+    (Some(&&lower), Some(&&upper)) => TrainedBins {
+      infos: vec![BinCompressionInfo {
+        weight: 1,
+        lower,
+        upper,
+        offset_bits: bits::bits_to_encode_offset(upper - lower),
+        symbol: 0,
+      }],
+      ans_size_log: 0,
+      counts: vec![deltas.len() as Weight],
+    },
+    _ => TrainedBins::default(),
+  };
+  let (latent_meta, policy, _) = finish_latent_var(trained)?;
+  Ok(ChunkCompressor {
+    meta: ChunkMeta::new(
+      Mode::Classic,
+      delta_order,
+      0,
+      vec![latent_meta],
+    ),
+    latent_var_policies: vec![policy],
+    page_infos,
+    deltas: latents,
+    delta_moments,
+    format_version: FormatVersion::default(),
+  })
+}
+
+// Compresses in the forced kind of mode without considering any others or
+// falling back.
+fn new_forced<T: NumberLike>(
+  nums: &[T],
+  n_per_page: &[usize],
+  kind: ModeKind,
+  config: &ChunkConfig,
+) -> PcoResult<ChunkCompressor<T::L>> {
+  if !T::mode_is_valid(kind.example_mode()) {
+    return Err(PcoError::invalid_argument(format!(
+      "forced mode {:?} is not valid for {}",
+      kind,
+      std::any::type_name::<T>(),
+    )));
+  }
+
+  let (mut candidate, mut bin_counts) = match kind {
+    ModeKind::Classic => new_candidate_w_split(
+      Mode::Classic,
+      data_types::split_latents_classic(nums),
+      n_per_page,
+      n_per_page,
+      config,
+    )?,
+    ModeKind::IntMult | ModeKind::FloatMult | ModeKind::FloatQuant => {
+      // these modes get their base from the corresponding spec
+      let (mode, latents) = T::choose_mode_and_split_latents(nums, config)?;
+      if mode.kind() != kind {
+        return Err(PcoError::invalid_argument(format!(
+          "forced mode {:?} has no base; enable or provide one via its spec",
+          kind,
+        )));
+      }
+      new_candidate_w_split(mode, latents, n_per_page, n_per_page, config)?
+    }
+    ModeKind::FloatXor => new_candidate_w_split(
+      Mode::FloatXor,
+      float_xor_utils::split_latents(nums, n_per_page),
+      n_per_page,
+      n_per_page,
+      config,
+    )?,
+    ModeKind::RunLength => {
+      let (latents, n_runs_per_page) = run_length_utils::split_latents(nums, n_per_page);
+      new_candidate_w_split(
+        Mode::RunLength,
+        latents,
+        n_per_page,
+        &n_runs_per_page,
+        config,
+      )?
+    }
+    ModeKind::Sparse => {
+      let (latents, n_entries_per_page) = sparse_utils::split_latents(nums, n_per_page);
+      new_candidate_w_split(
+        Mode::Sparse,
+        latents,
+        n_per_page,
+        &n_entries_per_page,
+        config,
+      )?
+    }
+    ModeKind::Dict => {
+      let dict = dict_utils::build_dict(nums).ok_or_else(|| {
+        PcoError::invalid_argument(format!(
+          "forced mode Dict supports at most {} distinct numbers",
+          MAX_DICT_SIZE,
+        ))
+      })?;
+      let latents = dict_utils::split_latents(nums, &dict);
+      let (mut candidate, bin_counts) = new_candidate_w_split(
+        Mode::Dict,
+        latents,
+        n_per_page,
+        n_per_page,
+        config,
+      )?;
+      candidate.meta.dict = dict;
+      (candidate, bin_counts)
+    }
+  };
+  if config.auto_level_spec == AutoLevelSpec::Enabled {
+    auto_tune_bins(&mut candidate, &mut bin_counts, config)?;
+  }
+  Ok(candidate)
+}
+
+// Converts existing bins back into compression infos, checking that they
+// cover every delta.
+fn infos_covering_deltas<L: Latent>(
+  var_meta: &ChunkLatentVarMeta<L>,
+  deltas: &[L],
+  latent_var_idx: usize,
+) -> PcoResult<Vec<BinCompressionInfo<L>>> {
+  let infos = var_meta
+    .bins
+    .iter()
+    .enumerate()
+    .map(|(symbol, bin)| {
+      let max_offset = if bin.offset_bits >= L::BITS {
+        L::MAX
+      } else {
+        (L::ONE << bin.offset_bits) - L::ONE
+      };
+      let upper = if L::MAX - bin.lower < max_offset {
+        L::MAX
+      } else {
+        bin.lower + max_offset
+      };
+      BinCompressionInfo {
+        weight: bin.weight,
+        lower: bin.lower,
+        upper,
+        offset_bits: bin.offset_bits,
+        symbol: symbol as Symbol,
+      }
+    })
+    .collect::<Vec<_>>();
+
+  let mut sorted_infos = infos.clone();
+  sorted_infos.sort_unstable_by_key(|info| info.lower);
+  for &delta in deltas {
+    let n_lower_or_equal = sorted_infos.partition_point(|info| info.lower <= delta);
+    if n_lower_or_equal == 0 || delta > sorted_infos[n_lower_or_equal - 1].upper {
+      return Err(PcoError::invalid_argument(format!(
+        "latent {} of latent variable {} is not covered by the chunk's bins",
+        delta, latent_var_idx,
+      )));
+    }
+  }
+  Ok(infos)
+}
+
+// Builds a chunk with a single page that reuses the given chunk metadata
+// exactly, without choosing a mode or training bins.
+// Returns an InvalidArgument error if the numbers can't be encoded with it.
+pub(crate) fn new_w_meta<T: NumberLike>(
+  nums: &[T],
+  meta: &ChunkMeta<T::L>,
+) -> PcoResult<ChunkCompressor<T::L>> {
+  let n = nums.len();
+  validate_chunk_size(n)?;
+  let mode = meta.mode;
+  if !T::mode_is_valid(mode) {
+    return Err(PcoError::invalid_argument(format!(
+      "mode {} is not valid for {}",
+      mode,
+      std::any::type_name::<T>(),
+    )));
+  }
+
+  let n_per_page = vec![n];
+  let (mut latents, n_latents_per_page) = match mode {
+    Mode::Classic | Mode::IntMult(_) | Mode::FloatMult(_) | Mode::FloatQuant(_) => (
+      T::split_latents_for_mode(nums, mode)?,
+      n_per_page.clone(),
+    ),
+    Mode::FloatXor => (
+      float_xor_utils::split_latents(nums, &n_per_page),
+      n_per_page.clone(),
+    ),
+    Mode::RunLength => run_length_utils::split_latents(nums, &n_per_page),
+    Mode::Sparse => sparse_utils::split_latents(nums, &n_per_page),
+    Mode::Dict => {
+      if let Some(&num) = nums
+        .iter()
+        .find(|num| meta.dict.binary_search(&num.to_latent_ordered()).is_err())
+      {
+        return Err(PcoError::invalid_argument(format!(
+          "{} is not in the chunk's dict",
+          num,
+        )));
+      }
+      (
+        dict_utils::split_latents(nums, &meta.dict),
+        n_per_page.clone(),
+      )
+    }
+  };
+
+  let (page_infos, delta_moments) = build_page_infos_and_delta_moments(
+    mode,
+    meta.delta_encoding_order,
+    meta.secondary_delta_encoding_order,
+    &n_per_page,
+    &n_latents_per_page,
+    &mut latents,
+  );
+  let mut latent_var_policies = Vec::with_capacity(meta.per_latent_var.len());
+  for (latent_var_idx, (var_meta, deltas)) in meta.per_latent_var.iter().zip(&latents).enumerate() {
+    let contiguous_deltas = collect_contiguous_deltas(deltas, &page_infos, latent_var_idx);
+    let trained = TrainedBins {
+      infos: infos_covering_deltas(var_meta, &contiguous_deltas, latent_var_idx)?,
+      ans_size_log: var_meta.ans_size_log,
+      counts: Vec::new(),
+    };
+    let (_, policy, _) = finish_latent_var(trained)?;
+    latent_var_policies.push(policy);
+  }
+
+  Ok(ChunkCompressor {
+    meta: meta.clone(),
+    latent_var_policies,
+    page_infos,
+    deltas: latents,
+    delta_moments,
+    format_version: FormatVersion::default(),
   })
 }
 
@@ -570,14 +1114,135 @@ pub(crate) fn new<T: NumberLike>(
   nums: &[T],
   config: &ChunkConfig,
 ) -> PcoResult<ChunkCompressor<T::L>> {
-  validate_config(config)?;
+  config.validate()?;
   let n = nums.len();
   validate_chunk_size(n)?;
 
-  let (mode, latents) = T::choose_mode_and_split_latents(nums, config);
+  let n_per_page = config.paging_spec.n_per_page::<T::L>(n)?;
+  if let Some(boundaries) = &config.explicit_bins {
+    let (candidate, bin_counts) = new_candidate_w_explicit_bins(
+      data_types::split_latents_classic(nums),
+      &n_per_page,
+      boundaries,
+      config.max_ans_size_log,
+    )?;
+    if should_fallback(n, &candidate, bin_counts, true) {
+      let latents = data_types::split_latents_classic(nums);
+      return fallback_chunk_compressor(latents, config);
+    }
+    return Ok(candidate);
+  }
+
+  if let Some(kind) = config.force_mode {
+    return new_forced(nums, &n_per_page, kind, config);
+  }
+
+  if n <= TINY_CHUNK_MAX_N
+    && config.delta_encoding_order.unwrap_or(0) == 0
+    && !matches!(
+      config.float_mult_spec,
+      FloatMultSpec::Lossy(_)
+    )
+  {
+    let delta_order = usize::from(n == 1 && config.delta_encoding_order.is_none());
+    return tiny_chunk_compressor(
+      T::split_latents_for_mode(nums, Mode::Classic)?,
+      &n_per_page,
+      delta_order,
+    );
+  }
+
+  let (mode, latents) = T::choose_mode_and_split_latents(nums, config)?;
 
-  let (candidate, bin_counts) = new_candidate_w_split(mode, latents, config)?;
-  if should_fallback(n, &candidate, bin_counts) {
+  let (mut candidate, mut bin_counts) = new_candidate_w_split(
+    mode,
+    latents,
+    &n_per_page,
+    &n_per_page,
+    config,
+  )?;
+  if matches!(mode, Mode::FloatMult(_)) && config.float_mult_spec == FloatMultSpec::Enabled {
+    // float mult's base detection is heuristic, so make sure the detected
+    // base actually beats classic
+    let (classic_candidate, classic_bin_counts) = new_candidate_w_split(
+      Mode::Classic,
+      data_types::split_latents_classic(nums),
+      &n_per_page,
+      &n_per_page,
+      config,
+    )?;
+    if classic_candidate.size_estimate() < candidate.size_estimate() {
+      candidate = classic_candidate;
+      bin_counts = classic_bin_counts;
+    }
+  }
+  if config.float_xor_spec == FloatXorSpec::Enabled && T::mode_is_valid(Mode::FloatXor) {
+    let latents = float_xor_utils::split_latents(nums, &n_per_page);
+    let (xor_candidate, xor_bin_counts) = new_candidate_w_split(
+      Mode::FloatXor,
+      latents,
+      &n_per_page,
+      &n_per_page,
+      config,
+    )?;
+    if xor_candidate.size_estimate() < candidate.size_estimate() {
+      candidate = xor_candidate;
+      bin_counts = xor_bin_counts;
+    }
+  }
+  if config.run_length_spec == RunLengthSpec::Enabled
+    && run_length_utils::count_runs(nums) * RUN_LENGTH_MIN_AVG_RUN_LENGTH <= n
+  {
+    let (latents, n_runs_per_page) = run_length_utils::split_latents(nums, &n_per_page);
+    let (run_length_candidate, run_length_bin_counts) = new_candidate_w_split(
+      Mode::RunLength,
+      latents,
+      &n_per_page,
+      &n_runs_per_page,
+      config,
+    )?;
+    if run_length_candidate.size_estimate() < candidate.size_estimate() {
+      candidate = run_length_candidate;
+      bin_counts = run_length_bin_counts;
+    }
+  }
+  if config.sparse_spec == SparseSpec::Enabled
+    && sparse_utils::count_entries(nums) * SPARSE_MIN_AVG_ENTRY_LENGTH <= n
+  {
+    let (latents, n_entries_per_page) = sparse_utils::split_latents(nums, &n_per_page);
+    let (sparse_candidate, sparse_bin_counts) = new_candidate_w_split(
+      Mode::Sparse,
+      latents,
+      &n_per_page,
+      &n_entries_per_page,
+      config,
+    )?;
+    if sparse_candidate.size_estimate() < candidate.size_estimate() {
+      candidate = sparse_candidate;
+      bin_counts = sparse_bin_counts;
+    }
+  }
+  if config.dict_spec == DictSpec::Enabled {
+    if let Some(dict) = dict_utils::build_dict(nums) {
+      let latents = dict_utils::split_latents(nums, &dict);
+      let (mut dict_candidate, dict_bin_counts) = new_candidate_w_split(
+        Mode::Dict,
+        latents,
+        &n_per_page,
+        &n_per_page,
+        config,
+      )?;
+      dict_candidate.meta.dict = dict;
+      if dict_candidate.size_estimate() < candidate.size_estimate() {
+        candidate = dict_candidate;
+        bin_counts = dict_bin_counts;
+      }
+    }
+  }
+  if config.auto_level_spec == AutoLevelSpec::Enabled {
+    auto_tune_bins(&mut candidate, &mut bin_counts, config)?;
+  }
+  if should_fallback(n, &candidate, bin_counts, false) {
     let latents = data_types::split_latents_classic(nums);
     return fallback_chunk_compressor(latents, config);
   }
@@ -586,6 +1251,20 @@ pub(crate) fn new<T: NumberLike>(
 }
 
 impl<L: Latent> ChunkCompressor<L> {
+  // the format version of the file this chunk will be written into
+  pub(crate) fn with_format_version(mut self, format_version: FormatVersion) -> PcoResult<Self> {
+    let min_format_version = self.meta.min_format_version();
+    if min_format_version > format_version.0 {
+      return Err(PcoError::invalid_argument(format!(
+        "chunk needs format version {} but the file was started with version {}; \
+        choose the file's format version with `with_format_version_for`",
+        min_format_version, format_version.0,
+      )));
+    }
+    self.format_version = format_version;
+    Ok(self)
+  }
+
   fn page_moments(&self, page_idx: usize, latent_var_idx: usize) -> &DeltaMoments<L> {
     &self.delta_moments[page_idx][latent_var_idx]
   }
@@ -605,7 +1284,7 @@ impl<L: Latent> ChunkCompressor<L> {
   /// This can be useful when building the file as a `Vec<u8>` in memory;
   /// you can `.reserve()` ahead of time.
   pub fn chunk_meta_size_hint(&self) -> usize {
-    self.meta.exact_size()
+    self.meta.exact_size(&self.format_version)
   }
 
   /// Writes the chunk metadata to the destination.
@@ -614,9 +1293,9 @@ impl<L: Latent> ChunkCompressor<L> {
   pub fn write_chunk_meta<W: Write>(&self, dst: W) -> PcoResult<W> {
     let mut writer = BitWriter::new(
       dst,
-      self.meta.exact_size() + OVERSHOOT_PADDING,
+      self.meta.exact_size(&self.format_version) + OVERSHOOT_PADDING,
     );
-    unsafe { self.meta.write_to(&mut writer)? };
+    unsafe { self.meta.write_to(&self.format_version, &mut writer)? };
     Ok(writer.into_inner())
   }
 
@@ -670,7 +1349,7 @@ impl<L: Latent> ChunkCompressor<L> {
     }
 
     Ok(DissectedPage {
-      page_n: page_info.page_n,
+      n_latents: page_info.n_latents,
       per_var,
     })
   }
@@ -683,6 +1362,14 @@ impl<L: Latent> ChunkCompressor<L> {
     self.page_size_hint_inner(page_idx, PAGE_SIZE_OVERESTIMATION)
   }
 
+  // an unpadded estimate of the chunk meta and all its pages
+  pub(crate) fn size_estimate(&self) -> usize {
+    self.chunk_meta_size_hint()
+      + (0..self.page_infos.len())
+        .map(|page_idx| self.page_size_hint_inner(page_idx, 1.0))
+        .sum::<usize>()
+  }
+
   fn page_size_hint_inner(&self, page_idx: usize, page_size_overestimation: f64) -> usize {
     let page_info = &self.page_infos[page_idx];
     let mut body_bit_size = 0;
@@ -705,10 +1392,10 @@ impl<L: Latent> ChunkCompressor<L> {
     writer: &mut BitWriter<W>,
   ) -> PcoResult<()> {
     let mut batch_start = 0;
-    while batch_start < dissected_page.page_n {
+    while batch_start < dissected_page.n_latents {
       let batch_end = min(
         batch_start + FULL_BATCH_N,
-        dissected_page.page_n,
+        dissected_page.n_latents,
       );
       for (dissected_page_var, policy) in
         dissected_page.per_var.iter().zip(&self.latent_var_policies)
@@ -729,6 +1416,44 @@ impl<L: Latent> ChunkCompressor<L> {
   ///
   /// Will return an error if the provided `Write` errors.
   pub fn write_page<W: Write>(&self, page_idx: usize, dst: W) -> PcoResult<W> {
+    self.write_page_inner(page_idx, dst, None)
+  }
+
+  // Like write_page, but tallies the page's bits into the report.
+  pub(crate) fn write_page_w_report(
+    &self,
+    page_idx: usize,
+    dst: Vec<u8>,
+    report: &mut CompressionReport,
+  ) -> PcoResult<Vec<u8>> {
+    let start = dst.len();
+    let tallied_before = report.total_bits();
+    let dst = self.write_page_inner(page_idx, dst, Some(report))?;
+    let tallied = report.total_bits() - tallied_before;
+    report.other_bits += 8 * (dst.len() - start) - tallied;
+    Ok(dst)
+  }
+
+  // Like write_page, but adds the time spent encoding and writing the page
+  // into the timings.
+  #[cfg(feature = "bench")]
+  pub(crate) fn write_page_timed<W: Write>(
+    &self,
+    page_idx: usize,
+    dst: W,
+    timings: &mut Timings,
+  ) -> PcoResult<W> {
+    self.validate_page_idx(page_idx)?;
+    let start = Instant::now();
+    let dissected_page = self.dissect_page(page_idx)?;
+    timings.ans_encoding += start.elapsed();
+    let start = Instant::now();
+    let dst = self.write_dissected_page_w_meta(page_idx, dissected_page, dst, None)?;
+    timings.offset_writing += start.elapsed();
+    Ok(dst)
+  }
+
+  fn validate_page_idx(&self, page_idx: usize) -> PcoResult<()> {
     let n_pages = self.page_infos.len();
     if page_idx >= n_pages {
       return Err(PcoError::invalid_argument(format!(
@@ -736,10 +1461,28 @@ impl<L: Latent> ChunkCompressor<L> {
         page_idx, n_pages,
       )));
     }
+    Ok(())
+  }
 
-    let mut writer = BitWriter::new(dst, PAGE_PADDING);
-
+  fn write_page_inner<W: Write>(
+    &self,
+    page_idx: usize,
+    dst: W,
+    report: Option<&mut CompressionReport>,
+  ) -> PcoResult<W> {
+    self.validate_page_idx(page_idx)?;
     let dissected_page = self.dissect_page(page_idx)?;
+    self.write_dissected_page_w_meta(page_idx, dissected_page, dst, report)
+  }
+
+  fn write_dissected_page_w_meta<W: Write>(
+    &self,
+    page_idx: usize,
+    dissected_page: DissectedPage<L>,
+    dst: W,
+    report: Option<&mut CompressionReport>,
+  ) -> PcoResult<W> {
+    let mut writer = BitWriter::new(dst, PAGE_PADDING);
 
     let n_latents = self.meta.mode.n_latent_vars();
     let mut latent_metas = Vec::with_capacity(n_latents);
@@ -757,7 +1500,13 @@ impl<L: Latent> ChunkCompressor<L> {
         ans_final_state_idxs,
       });
     }
+    let n_runs = if self.meta.mode.has_runs() {
+      Some(self.page_infos[page_idx].n_latents)
+    } else {
+      None
+    };
     let page_meta = PageMeta {
+      n_runs,
       per_var: latent_metas,
     };
     let ans_size_logs = self
@@ -765,6 +1514,30 @@ impl<L: Latent> ChunkCompressor<L> {
       .iter()
       .map(|config| config.encoder.size_log());
 
+    if let Some(report) = report {
+      for ((latent_meta, policy), dissected) in page_meta
+        .per_var
+        .iter()
+        .zip(&self.latent_var_policies)
+        .zip(&dissected_page.per_var)
+      {
+        report.delta_moment_bits += latent_meta.delta_moments.moments.len() * L::BITS as usize;
+        report.ans_state_bits += ANS_INTERLEAVING * policy.encoder.size_log() as usize;
+        if policy.needs_ans {
+          report.ans_bits += dissected
+            .ans_bits
+            .iter()
+            .map(|&bits| bits as usize)
+            .sum::<usize>();
+        }
+        report.offset_bits += dissected
+          .offset_bits
+          .iter()
+          .map(|&bits| bits as usize)
+          .sum::<usize>();
+      }
+    }
+
     unsafe { page_meta.write_to(ans_size_logs, &mut writer) };
 
     self.write_dissected_page(dissected_page, &mut writer)?;
@@ -777,6 +1550,10 @@ impl<L: Latent> ChunkCompressor<L> {
 
 #[cfg(test)]
 mod tests {
+  use rand::Rng;
+  use rand_xoshiro::rand_core::SeedableRng;
+  use rand_xoshiro::Xoroshiro128PlusPlus;
+
   use super::*;
 
   #[test]
@@ -803,4 +1580,95 @@ mod tests {
       vec![0, 1, 3, 4, 6, 7]
     );
   }
+
+  #[test]
+  fn test_tiny_chunks() -> PcoResult<()> {
+    let config = ChunkConfig::default();
+    let cc = new(&[1.5_f32], &config)?;
+    assert_eq!(cc.meta.delta_encoding_order, 1);
+    assert!(cc.meta.per_latent_var[0].bins.is_empty());
+
+    let nums = [7_i64, -3, 1000, 7];
+    for (n, offset_bits) in [(2, 4), (3, 10), (4, 10)] {
+      let cc = new(&nums[..n], &config)?;
+      assert!(matches!(cc.meta.mode, Mode::Classic));
+      assert_eq!(cc.meta.delta_encoding_order, 0);
+      let var_meta = &cc.meta.per_latent_var[0];
+      assert_eq!(var_meta.ans_size_log, 0);
+      assert_eq!(var_meta.bins.len(), 1);
+      assert_eq!(
+        var_meta.bins[0].lower,
+        (-3_i64).to_latent_ordered()
+      );
+      assert_eq!(var_meta.bins[0].offset_bits, offset_bits);
+    }
+
+    // an explicit delta order still applies
+    let cc = new(
+      &nums,
+      &config.with_delta_encoding_order(Some(2)),
+    )?;
+    assert_eq!(cc.meta.delta_encoding_order, 2);
+    Ok(())
+  }
+
+  #[test]
+  fn test_heavy_tailed_outliers_get_their_own_bins() -> PcoResult<()> {
+    // mostly small numbers with 1% huge outliers; since each bin has its own
+    // offset bits, the outliers shouldn't widen the bins of the small numbers
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    let nums = (0..100_000)
+      .map(|_| {
+        if rng.gen_range(0..100) == 0 {
+          rng.gen::<u64>() >> 1
+        } else {
+          rng.gen_range(0..16)
+        }
+      })
+      .collect::<Vec<u64>>();
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(0));
+    let cc = new(&nums, &config)?;
+    assert!(matches!(cc.meta.mode, Mode::Classic));
+    let bins = &cc.meta.per_latent_var[0].bins;
+    assert!(bins
+      .iter()
+      .filter(|bin| bin.lower < 16)
+      .all(|bin| bin.offset_bits <= 4));
+
+    let compressed = crate::standalone::simple_compress(&nums, &config)?;
+    let bits_per_num = 8.0 * compressed.len() as f64 / nums.len() as f64;
+    // 4 bits for small numbers plus 1% of 63 bits for outliers
+    assert!(
+      bits_per_num < 4.0 + 0.01 * 63.0 + 0.1,
+      "{}",
+      bits_per_num
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_zero_centered_deltas_get_tight_bins() -> PcoResult<()> {
+    // deltas are toggled to be centered at L::MID before binning, so small
+    // negative and positive deltas are adjacent, as with a zigzag transform
+    let mut rng = Xoroshiro128PlusPlus::seed_from_u64(0);
+    let mut x = 0_i64;
+    let nums = (0..100_000)
+      .map(|_| {
+        x += rng.gen_range(-8..=8);
+        x
+      })
+      .collect::<Vec<_>>();
+    let config = ChunkConfig::default().with_delta_encoding_order(Some(1));
+    let cc = new(&nums, &config)?;
+    let bins = &cc.meta.per_latent_var[0].bins;
+    let lowest = bins.iter().map(|bin| bin.lower).min().unwrap();
+    assert_eq!(lowest, u64::MID - 8);
+    assert!(bins.iter().all(|bin| bin.offset_bits <= 4));
+
+    let compressed = crate::standalone::simple_compress(&nums, &config)?;
+    let bits_per_num = 8.0 * compressed.len() as f64 / nums.len() as f64;
+    // the entropy of 17 equally likely deltas is about 4.09 bits
+    assert!(bits_per_num < 4.2, "{}", bits_per_num);
+    Ok(())
+  }
 }
@@ -1,9 +1,13 @@
 use better_io::BetterBufRead;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
+use crate::bit_reader::BitReaderBuilder;
+use crate::constants::PAGE_PADDING;
 use crate::data_types::NumberLike;
 use crate::errors::{PcoError, PcoResult};
-use crate::wrapped::PageDecompressor;
-use crate::ChunkMeta;
+use crate::wrapped::{PageBuffers, PageDecompressor};
+use crate::{bit_reader, ChunkMeta, PageMeta};
 
 /// Holds metadata about a chunk and can produce page decompressors.
 #[derive(Clone, Debug)]
@@ -39,4 +43,67 @@ impl<T: NumberLike> ChunkDecompressor<T> {
   ) -> PcoResult<PageDecompressor<T, R>> {
     PageDecompressor::new(src, &self.meta, n)
   }
+
+  /// Reads only the metadata at the start of a page, without preparing to
+  /// decode its body.
+  ///
+  /// The page metadata holds the delta moments and final ANS state indices
+  /// each latent variable starts decoding from, which can be useful for
+  /// building an external index of decoding state at page boundaries.
+  ///
+  /// Will return an error if corruptions or insufficient data are found.
+  pub fn parse_page_meta<R: BetterBufRead>(&self, mut src: R) -> PcoResult<PageMeta<T::L>> {
+    bit_reader::ensure_buf_read_capacity(&mut src, PAGE_PADDING);
+    let mut reader_builder = BitReaderBuilder::new(src, PAGE_PADDING, 0);
+    reader_builder.with_reader(|reader| unsafe { PageMeta::parse_from(reader, &self.meta) })
+  }
+
+  pub(crate) fn page_decompressor_reusing<R: BetterBufRead>(
+    &self,
+    src: R,
+    n: usize,
+    buffers: PageBuffers<T::L>,
+  ) -> PcoResult<PageDecompressor<T, R>> {
+    PageDecompressor::new_reusing(src, &self.meta, n, buffers)
+  }
+
+  /// Decompresses multiple pages of this chunk in parallel using rayon,
+  /// writing their numbers contiguously into `dst` in the order given.
+  ///
+  /// Each page is provided as its compressed bytes and its count of numbers,
+  /// as tracked by the wrapping format.
+  /// Pages are independent given the chunk metadata, so this is equivalent to
+  /// decompressing each one with [`page_decompressor`][Self::page_decompressor].
+  ///
+  /// Will return an InvalidArgument error if `dst`'s length differs from the
+  /// total count of numbers, or an error if any page has corruptions or
+  /// insufficient data.
+  #[cfg(feature = "rayon")]
+  pub fn par_decompress_pages(&self, pages: &[(&[u8], usize)], dst: &mut [T]) -> PcoResult<()> {
+    let total_n: usize = pages.iter().map(|&(_, n)| n).sum();
+    if dst.len() != total_n {
+      return Err(PcoError::invalid_argument(format!(
+        "dst length must equal the total count of numbers in the pages ({} != {})",
+        dst.len(),
+        total_n,
+      )));
+    }
+
+    let mut page_dsts = Vec::with_capacity(pages.len());
+    let mut remaining_dst = dst;
+    for &(_, n) in pages {
+      let (page_dst, rest) = remaining_dst.split_at_mut(n);
+      page_dsts.push(page_dst);
+      remaining_dst = rest;
+    }
+
+    pages
+      .par_iter()
+      .zip(page_dsts.into_par_iter())
+      .try_for_each(|(&(src, n), page_dst)| {
+        let mut pd = self.page_decompressor(src, n)?;
+        pd.decompress(page_dst)?;
+        Ok(())
+      })
+  }
 }
@@ -1,7 +1,7 @@
 use std::io::Write;
 
 use crate::bit_writer::BitWriter;
-use crate::constants::HEADER_PADDING;
+use crate::constants::{COMPATIBLE_FORMAT_VERSION, HEADER_PADDING};
 use crate::data_types::NumberLike;
 use crate::errors::PcoResult;
 use crate::format_version::FormatVersion;
@@ -43,12 +43,43 @@ use crate::ChunkConfig;
 /// The one requirement for a wrapping format is that it saves the count of
 /// numbers in each page; this will be needed for decompression.
 /// Otherwise, you may write anything else you like in your wrapping file!
-#[derive(Clone, Debug, Default)]
+///
+/// By default, files get a format version that older versions of pco can
+/// also decompress.
+/// If any chunk's config opts into features that need a newer format version,
+/// choose it with [`with_format_version_for`][Self::with_format_version_for]
+/// before writing the header.
+#[derive(Clone, Debug)]
 pub struct FileCompressor {
   format_version: FormatVersion,
 }
 
+impl Default for FileCompressor {
+  fn default() -> Self {
+    Self {
+      format_version: FormatVersion(COMPATIBLE_FORMAT_VERSION),
+    }
+  }
+}
+
 impl FileCompressor {
+  /// Raises the file's format version, if necessary, so that it can hold
+  /// chunks compressed with the given config.
+  ///
+  /// Call this for each distinct config you plan to use.
+  pub fn with_format_version_for(mut self, config: &ChunkConfig) -> Self {
+    let version = self.format_version.0.max(config.min_format_version());
+    self.format_version = FormatVersion(version);
+    self
+  }
+
+  // only for writing a file whose chunks are copied from files of an older
+  // format version
+  pub(crate) fn with_format_version(mut self, format_version: FormatVersion) -> Self {
+    self.format_version = format_version;
+    self
+  }
+
   /// Writes a short header to the destination.
   ///
   /// Will return an error if the provided `Write` errors.
@@ -62,7 +93,8 @@ impl FileCompressor {
   /// Creates a `ChunkCompressor` that can be used to write chunk metadata
   /// and create page compressors.
   ///
-  /// Will return an error if any arguments provided are invalid.
+  /// Will return an error if any arguments provided are invalid, including
+  /// configs that need a newer format version than this file's.
   ///
   /// Although this doesn't write anything yet, it does the bulk of
   /// compute necessary for the compression.
@@ -71,6 +103,10 @@ impl FileCompressor {
     nums: &[T],
     config: &ChunkConfig,
   ) -> PcoResult<ChunkCompressor<T::L>> {
-    chunk_compressor::new(nums, config)
+    chunk_compressor::new(nums, config)?.with_format_version(self.format_version)
+  }
+
+  pub(crate) fn format_version(&self) -> FormatVersion {
+    self.format_version
   }
 }
@@ -1,4 +1,5 @@
 use crate::data_types::Latent;
+use crate::format_version::FormatVersion;
 use crate::{Bin, ChunkLatentVarMeta, ChunkMeta, Mode};
 
 /// Returns the maximum possible byte size of a wrapped header.
@@ -10,6 +11,7 @@ pub(crate) fn baseline_chunk_meta<L: Latent>() -> ChunkMeta<L> {
   ChunkMeta {
     mode: Mode::Classic,
     delta_encoding_order: 0,
+    secondary_delta_encoding_order: 0,
     per_latent_var: vec![ChunkLatentVarMeta {
       ans_size_log: 0,
       bins: vec![Bin {
@@ -18,6 +20,7 @@ pub(crate) fn baseline_chunk_meta<L: Latent>() -> ChunkMeta<L> {
         offset_bits: L::BITS,
       }],
     }],
+    dict: vec![],
   }
 }
 
@@ -26,12 +29,14 @@ pub(crate) fn baseline_chunk_meta<L: Latent>() -> ChunkMeta<L> {
 pub fn chunk_size<L: Latent>(n: usize) -> usize {
   // TODO if we ever add NumberLikes that are smaller than their Latents, we
   // may want to make this more generic
-  baseline_chunk_meta::<L>().exact_size() + n * L::BITS.div_ceil(8) as usize
+  baseline_chunk_meta::<L>().exact_size(&FormatVersion::default())
+    + n * L::BITS.div_ceil(8) as usize
 }
 
 #[cfg(test)]
 mod tests {
   use rand::Rng;
+
   use rand_xoshiro::rand_core::SeedableRng;
   use rand_xoshiro::Xoroshiro128PlusPlus;
 
@@ -53,7 +58,7 @@ mod tests {
 
   fn check_chunk_guarantee<T: NumberLike>(nums: &[T], config: &ChunkConfig) -> PcoResult<()> {
     let n = nums.len();
-    let n_pages = config.paging_spec.n_per_page(n)?.len();
+    let n_pages = config.paging_spec.n_per_page::<T::L>(n)?.len();
     let mut dst = Vec::new();
     let fc = FileCompressor::default();
     let cc = fc.chunk_compressor(nums, config)?;
@@ -2,9 +2,10 @@ pub use chunk_compressor::ChunkCompressor;
 pub use chunk_decompressor::ChunkDecompressor;
 pub use file_compressor::FileCompressor;
 pub use file_decompressor::FileDecompressor;
+pub(crate) use page_decompressor::PageBuffers;
 pub use page_decompressor::PageDecompressor;
 
-mod chunk_compressor;
+pub(crate) mod chunk_compressor;
 mod chunk_decompressor;
 mod file_compressor;
 mod file_decompressor;
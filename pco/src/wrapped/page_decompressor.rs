@@ -9,11 +9,13 @@ use crate::constants::{FULL_BATCH_N, PAGE_PADDING};
 use crate::data_types::{Latent, NumberLike};
 use crate::delta;
 use crate::delta::DeltaMoments;
-use crate::errors::{PcoError, PcoResult};
-use crate::latent_batch_decompressor::LatentBatchDecompressor;
+use crate::errors::{ErrorKind, PcoError, PcoResult};
+use crate::latent_batch_decompressor::{
+  LatentBatchBuffers, LatentBatchDecompressor, LatentVarStats,
+};
 use crate::page_meta::PageMeta;
 use crate::progress::Progress;
-use crate::{bit_reader, ChunkMeta, Mode};
+use crate::{bit_reader, dict_utils, float_xor_utils, sparse_utils, ChunkMeta, Mode};
 
 const PERFORMANT_BUF_READ_CAPACITY: usize = 8192;
 
@@ -22,16 +24,38 @@ pub struct State<L: Latent> {
   n_processed: usize,
   latent_batch_decompressors: Vec<LatentBatchDecompressor<L>>,
   delta_momentss: Vec<DeltaMoments<L>>, // one per latent variable
+  xor_prev_latent: L,                   // only used in float xor mode
   primary_latents: [L; FULL_BATCH_N],
   secondary_latents: [L; FULL_BATCH_N],
+  // only used in run length mode, where the latent buffers hold a batch of
+  // runs that get expanded into numbers
+  n_runs_decoded: usize,
+  run_batch_len: usize,
+  run_idx: usize,
+  run_value: L,
+  run_remaining: usize,
+}
+
+// heap allocations that can be recycled from one PageDecompressor to the next
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PageBuffers<L: Latent> {
+  latent_vars: Vec<LatentBatchBuffers<L>>,
+  dict: Vec<L>,
 }
 
 /// Holds metadata about a page and supports decompression.
 pub struct PageDecompressor<T: NumberLike, R: BetterBufRead> {
   // immutable
   n: usize,
+  // the count of latents per var before delta encoding, which is n except in
+  // run length mode
+  n_latents: usize,
   mode: Mode<T::L>,
+  dict: Vec<T::L>, // only used in dict mode
   maybe_constant_secondary: Option<T::L>,
+  // set when every number in the page is the same, in which case there are
+  // no body bits to decode
+  maybe_constant_num: Option<T>,
   phantom: PhantomData<T>,
 
   // mutable
@@ -76,7 +100,16 @@ fn convert_from_latents_nontransmutable<T: NumberLike>(primary: &[T::L], dst: &m
 }
 
 impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
-  pub(crate) fn new(mut src: R, chunk_meta: &ChunkMeta<T::L>, n: usize) -> PcoResult<Self> {
+  pub(crate) fn new(src: R, chunk_meta: &ChunkMeta<T::L>, n: usize) -> PcoResult<Self> {
+    Self::new_reusing(src, chunk_meta, n, PageBuffers::default())
+  }
+
+  pub(crate) fn new_reusing(
+    mut src: R,
+    chunk_meta: &ChunkMeta<T::L>,
+    n: usize,
+    buffers: PageBuffers<T::L>,
+  ) -> PcoResult<Self> {
     bit_reader::ensure_buf_read_capacity(&mut src, PERFORMANT_BUF_READ_CAPACITY);
     let mut reader_builder = BitReaderBuilder::new(src, PAGE_PADDING, 0);
 
@@ -84,25 +117,42 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
       .with_reader(|reader| unsafe { PageMeta::<T::L>::parse_from(reader, chunk_meta) })?;
 
     let mode = chunk_meta.mode;
+    let n_latents = match page_meta.n_runs {
+      Some(n_runs) if n_runs > n || (n_runs == 0 && n > 0) => {
+        return Err(PcoError::corruption(format!(
+          "page of {} numbers cannot have {} runs",
+          n, n_runs,
+        )));
+      }
+      Some(n_runs) => n_runs,
+      None => n,
+    };
     let delta_momentss = page_meta
       .per_var
       .iter()
       .map(|latent| latent.delta_moments.clone())
       .collect::<Vec<_>>();
 
+    let PageBuffers {
+      latent_vars: latent_var_buffers,
+      mut dict,
+    } = buffers;
+    let mut latent_var_buffers = latent_var_buffers.into_iter();
     let mut latent_batch_decompressors = Vec::new();
     for latent_idx in 0..mode.n_latent_vars() {
       let chunk_latent_meta = &chunk_meta.per_latent_var[latent_idx];
-      if chunk_latent_meta.bins.is_empty() && n > chunk_meta.delta_encoding_order {
+      let delta_order = chunk_meta.delta_order_for_latent_var(latent_idx);
+      if chunk_latent_meta.bins.is_empty() && n_latents > delta_order {
         return Err(PcoError::corruption(format!(
           "unable to decompress chunk with no bins and {} deltas",
-          n - chunk_meta.delta_encoding_order,
+          n_latents - delta_order,
         )));
       }
 
       latent_batch_decompressors.push(LatentBatchDecompressor::new(
         chunk_latent_meta,
         &page_meta.per_var[latent_idx],
+        latent_var_buffers.next().unwrap_or_default(),
       )?);
     }
 
@@ -113,20 +163,73 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
         None
       };
 
+    let all_latents_constant = latent_batch_decompressors
+      .iter()
+      .zip(&delta_momentss)
+      .all(|(lbd, delta_moments)| lbd.maybe_constant_value.is_some() && delta_moments.order() == 0);
+    let maybe_constant_num = if !all_latents_constant {
+      None
+    } else if matches!(mode, Mode::RunLength) {
+      let run_length = maybe_constant_secondary.unwrap_or_default().to_u64() + 1;
+      if (n_latents as u64).checked_mul(run_length) != Some(n as u64) {
+        return Err(PcoError::corruption(format!(
+          "{} runs of length {} do not add up to the page's {} numbers",
+          n_latents, run_length, n,
+        )));
+      }
+      let value = latent_batch_decompressors[0].maybe_constant_value.unwrap();
+      Some(T::from_latent_ordered(value))
+    } else if matches!(mode, Mode::Sparse) {
+      let n_zeros = maybe_constant_secondary.unwrap_or_default().to_u64();
+      if (n_latents as u64).checked_mul(n_zeros + 1) != Some(n as u64) {
+        return Err(PcoError::corruption(format!(
+          "{} entries with {} zeros each do not add up to the page's {} numbers",
+          n_latents, n_zeros, n,
+        )));
+      }
+      // the page is only constant if the value is zero or has no zeros after
+      // it
+      let value = latent_batch_decompressors[0].maybe_constant_value.unwrap();
+      (n_zeros == 0 || value == sparse_utils::zero_latent::<T>())
+        .then(|| T::from_latent_ordered(value))
+    } else if matches!(mode, Mode::Dict) {
+      let mut primary = [latent_batch_decompressors[0].maybe_constant_value.unwrap()];
+      dict_utils::join_latents(&chunk_meta.dict, &mut primary)?;
+      Some(T::from_latent_ordered(primary[0]))
+    } else if !matches!(mode, Mode::FloatXor) {
+      let mut primary = [latent_batch_decompressors[0].maybe_constant_value.unwrap()];
+      let secondary = [maybe_constant_secondary.unwrap_or_default()];
+      T::join_latents(mode, &mut primary, &secondary);
+      Some(T::from_latent_ordered(primary[0]))
+    } else {
+      None
+    };
+
     // we don't store the whole ChunkMeta because it can get large due to bins
     let secondary_default = maybe_constant_secondary.unwrap_or(T::L::default());
+    dict.clear();
+    dict.extend_from_slice(&chunk_meta.dict);
     Ok(Self {
       n,
+      n_latents,
       mode,
+      dict,
       maybe_constant_secondary,
+      maybe_constant_num,
       phantom: PhantomData,
       reader_builder,
       state: State {
         n_processed: 0,
         latent_batch_decompressors,
         delta_momentss,
+        xor_prev_latent: T::L::ZERO,
         primary_latents: [T::L::default(); FULL_BATCH_N],
         secondary_latents: [secondary_default; FULL_BATCH_N],
+        n_runs_decoded: 0,
+        run_batch_len: 0,
+        run_idx: 0,
+        run_value: T::L::ZERO,
+        run_remaining: 0,
       },
     })
   }
@@ -138,6 +241,7 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
     let State {
       latent_batch_decompressors,
       delta_momentss,
+      xor_prev_latent,
       primary_latents,
       secondary_latents,
       n_processed,
@@ -177,22 +281,140 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
     }
 
     if T::TRANSMUTABLE_TO_LATENT {
-      T::join_latents(
-        mode,
-        T::transmute_to_latents(dst),
-        secondary_latents,
-      );
+      let primary = T::transmute_to_latents(dst);
+      if matches!(mode, Mode::Dict) {
+        dict_utils::join_latents(&self.dict, primary)?;
+      } else {
+        T::join_latents(mode, primary, secondary_latents);
+      }
+      if matches!(mode, Mode::FloatXor) {
+        float_xor_utils::decode_in_place(xor_prev_latent, primary);
+      }
       convert_from_latents_transmutable(dst);
     } else {
       let primary = &mut primary_latents[..batch_n];
-      T::join_latents(mode, primary, secondary_latents);
+      if matches!(mode, Mode::Dict) {
+        dict_utils::join_latents(&self.dict, primary)?;
+      } else {
+        T::join_latents(mode, primary, secondary_latents);
+      }
+      if matches!(mode, Mode::FloatXor) {
+        float_xor_utils::decode_in_place(xor_prev_latent, primary);
+      }
       convert_from_latents_nontransmutable(primary, dst);
     }
 
     *n_processed += batch_n;
     if *n_processed == n {
       self.reader_builder.with_reader(|reader| {
-        reader.drain_empty_byte("expected trailing bits at end of page to be empty")
+        reader.drain_empty_byte(
+          "expected trailing bits at end of page to be empty",
+          "page",
+        )
+      })?;
+    }
+
+    Ok(())
+  }
+
+  // decodes the next batch of runs into the latent buffers
+  fn decompress_run_batch(&mut self) -> PcoResult<()> {
+    let n_runs = self.n_latents;
+    let State {
+      latent_batch_decompressors,
+      delta_momentss,
+      primary_latents,
+      secondary_latents,
+      n_runs_decoded,
+      run_batch_len,
+      run_idx,
+      ..
+    } = &mut self.state;
+
+    if *n_runs_decoded == n_runs {
+      return Err(PcoError::corruption(format!(
+        "the page's {} runs add up to fewer than its {} numbers",
+        n_runs, self.n,
+      )));
+    }
+    let batch_n = min(FULL_BATCH_N, n_runs - *n_runs_decoded);
+    self.reader_builder.with_reader(|reader| unsafe {
+      decompress_latents_w_delta(
+        reader,
+        &mut delta_momentss[0],
+        &mut latent_batch_decompressors[0],
+        &mut primary_latents[..batch_n],
+        n_runs - *n_runs_decoded,
+      )
+    })?;
+    if self.maybe_constant_secondary.is_none() {
+      self.reader_builder.with_reader(|reader| unsafe {
+        decompress_latents_w_delta(
+          reader,
+          &mut delta_momentss[1],
+          &mut latent_batch_decompressors[1],
+          &mut secondary_latents[..batch_n],
+          n_runs - *n_runs_decoded,
+        )
+      })?;
+    }
+
+    *n_runs_decoded += batch_n;
+    *run_batch_len = batch_n;
+    *run_idx = 0;
+    Ok(())
+  }
+
+  fn decompress_run_length_batch(&mut self, dst: &mut [T]) -> PcoResult<()> {
+    let mut dst_idx = 0;
+    while dst_idx < dst.len() {
+      if self.state.run_remaining == 0 {
+        if self.state.run_idx == self.state.run_batch_len {
+          self.decompress_run_batch()?;
+        }
+        let State {
+          primary_latents,
+          secondary_latents,
+          run_idx,
+          run_value,
+          run_remaining,
+          ..
+        } = &mut self.state;
+        if matches!(self.mode, Mode::Sparse) {
+          // the nonzero value is followed by a run of zeros
+          dst[dst_idx] = T::from_latent_ordered(primary_latents[*run_idx]);
+          dst_idx += 1;
+          *run_value = sparse_utils::zero_latent::<T>();
+          *run_remaining = secondary_latents[*run_idx].to_u64() as usize;
+        } else {
+          *run_value = primary_latents[*run_idx];
+          *run_remaining = secondary_latents[*run_idx].to_u64() as usize + 1;
+        }
+        *run_idx += 1;
+      }
+      let n_fill = min(self.state.run_remaining, dst.len() - dst_idx);
+      dst[dst_idx..dst_idx + n_fill].fill(T::from_latent_ordered(self.state.run_value));
+      self.state.run_remaining -= n_fill;
+      dst_idx += n_fill;
+    }
+
+    self.state.n_processed += dst.len();
+    if self.state.n_processed == self.n {
+      let state = &self.state;
+      if state.run_remaining > 0
+        || state.run_idx < state.run_batch_len
+        || state.n_runs_decoded < self.n_latents
+      {
+        return Err(PcoError::corruption(format!(
+          "the page's {} runs add up to more than its {} numbers",
+          self.n_latents, self.n,
+        )));
+      }
+      self.reader_builder.with_reader(|reader| {
+        reader.drain_empty_byte(
+          "expected trailing bits at end of page to be empty",
+          "page",
+        )
       })?;
     }
 
@@ -219,10 +441,39 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
 
     let n_to_process = min(num_dst.len(), self.n_remaining());
 
+    if let Some(num) = self.maybe_constant_num {
+      num_dst[..n_to_process].fill(num);
+      self.state.n_processed += n_to_process;
+      if n_to_process > 0 && self.n_remaining() == 0 {
+        self.reader_builder.with_reader(|reader| {
+          reader.drain_empty_byte(
+            "expected trailing bits at end of page to be empty",
+            "page",
+          )
+        })?;
+      }
+      return Ok(Progress {
+        n_processed: n_to_process,
+        finished: self.n_remaining() == 0,
+      });
+    }
+
     let mut n_processed = 0;
     while n_processed < n_to_process {
       let dst_batch_end = min(n_processed + FULL_BATCH_N, n_to_process);
-      self.decompress_batch(&mut num_dst[n_processed..dst_batch_end])?;
+      let dst_batch = &mut num_dst[n_processed..dst_batch_end];
+      if self.mode.has_runs() {
+        self.decompress_run_length_batch(dst_batch)
+      } else {
+        self.decompress_batch(dst_batch)
+      }
+      .map_err(|err| match err.kind {
+        ErrorKind::InsufficientData => PcoError::insufficient_data(format!(
+          "page data ended after decompressing {} of its {} numbers: {}",
+          self.state.n_processed, self.n, err.message,
+        )),
+        _ => err,
+      })?;
       n_processed = dst_batch_end;
     }
 
@@ -232,6 +483,76 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
     })
   }
 
+  // Decodes the rest of the page's latents as stored, appending them to the
+  // destination for each latent variable, without undoing delta encoding or
+  // joining them into numbers. Each latent variable yields its count of
+  // latents minus its delta order, since the rest is held in the page's delta
+  // moments.
+  pub(crate) fn decompress_raw_latents_extend(&mut self, dst: &mut [Vec<T::L>]) -> PcoResult<()> {
+    if self.state.n_processed > 0 {
+      return Err(PcoError::invalid_argument(
+        "raw latents can only be decompressed from the start of a page",
+      ));
+    }
+
+    let n_latents = self.n_latents;
+    let State {
+      latent_batch_decompressors,
+      delta_momentss,
+      ..
+    } = &mut self.state;
+    // the latent variables are interleaved batch by batch
+    let mut batch_start = 0;
+    while batch_start < n_latents {
+      let batch_end = min(batch_start + FULL_BATCH_N, n_latents);
+      for (latent_var_idx, lbd) in latent_batch_decompressors.iter_mut().enumerate() {
+        let n_pre_delta = n_latents.saturating_sub(delta_momentss[latent_var_idx].order());
+        let batch_n = min(batch_end, n_pre_delta).saturating_sub(batch_start);
+        let var_dst = &mut dst[latent_var_idx];
+        let initial_len = var_dst.len();
+        var_dst.resize(initial_len + batch_n, T::L::ZERO);
+        self.reader_builder.with_reader(|reader| unsafe {
+          lbd.decompress_latent_batch(reader, &mut var_dst[initial_len..])
+        })?;
+      }
+      batch_start = batch_end;
+    }
+
+    self.state.n_processed = self.n;
+    if self.n > 0 {
+      self.reader_builder.with_reader(|reader| {
+        reader.drain_empty_byte(
+          "expected trailing bits at end of page to be empty",
+          "page",
+        )
+      })?;
+    }
+    Ok(())
+  }
+
+  // Starts counting what each latent variable decodes from here on. Pages
+  // whose numbers are all the same decode nothing, so in that case we count
+  // all the primary latents up front, which come from the only bin.
+  pub(crate) fn enable_stats(&mut self) {
+    for lbd in &mut self.state.latent_batch_decompressors {
+      lbd.enable_stats();
+    }
+    if self.maybe_constant_num.is_some() {
+      if let Some(stats) = &mut self.state.latent_batch_decompressors[0].stats {
+        stats.bin_counts[0] += self.n_latents;
+      }
+    }
+  }
+
+  pub(crate) fn stats(&self) -> Vec<LatentVarStats> {
+    self
+      .state
+      .latent_batch_decompressors
+      .iter()
+      .map(|lbd| lbd.stats.clone().unwrap_or_default())
+      .collect()
+  }
+
   fn n_remaining(&self) -> usize {
     self.n - self.state.n_processed
   }
@@ -240,4 +561,17 @@ impl<T: NumberLike, R: BetterBufRead> PageDecompressor<T, R> {
   pub fn into_src(self) -> R {
     self.reader_builder.into_inner()
   }
+
+  pub(crate) fn into_src_and_buffers(self) -> (R, PageBuffers<T::L>) {
+    let buffers = PageBuffers {
+      latent_vars: self
+        .state
+        .latent_batch_decompressors
+        .into_iter()
+        .map(LatentBatchDecompressor::into_buffers)
+        .collect(),
+      dict: self.dict,
+    };
+    (self.reader_builder.into_inner(), buffers)
+  }
 }
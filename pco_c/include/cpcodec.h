@@ -2,6 +2,9 @@
 extern "C" {
 #endif
 
+#include <stddef.h>
+#include <stdint.h>
+
 #include "cpcodec_generated.h"
 
 // cbindgen can only handle literal constants, which isn't helpful when these
@@ -3,6 +3,12 @@ typedef enum PcoError {
   PcoInvalidType,
   PcoCompressionError,
   PcoDecompressionError,
+  PcoChecksumMismatch,
+  PcoCompatibilityError,
+  PcoCorruptionError,
+  PcoInsufficientDataError,
+  PcoInvalidArgumentError,
+  PcoIoError,
 } PcoError;
 
 typedef struct PcoFfiVec {
@@ -23,3 +29,316 @@ enum PcoError pco_simple_decompress(const void *compressed,
                                     struct PcoFfiVec *dst);
 
 enum PcoError pco_free_pcovec(struct PcoFfiVec *ffi_vec);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_u16(const uint16_t *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_u16(const uint8_t *src,
+                                 size_t len,
+                                 uint16_t **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_u16(uint16_t *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_u32(const uint32_t *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_u32(const uint8_t *src,
+                                 size_t len,
+                                 uint32_t **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_u32(uint32_t *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_u64(const uint64_t *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_u64(const uint8_t *src,
+                                 size_t len,
+                                 uint64_t **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_u64(uint64_t *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_i16(const int16_t *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_i16(const uint8_t *src,
+                                 size_t len,
+                                 int16_t **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_i16(int16_t *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_i32(const int32_t *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_i32(const uint8_t *src,
+                                 size_t len,
+                                 int32_t **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_i32(int32_t *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_i64(const int64_t *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_i64(const uint8_t *src,
+                                 size_t len,
+                                 int64_t **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_i64(int64_t *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_f32(const float *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_f32(const uint8_t *src,
+                                 size_t len,
+                                 float **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_f32(float *ptr, size_t len);
+
+/**
+ * Compresses `len` numbers starting at `nums` into a standalone .pco
+ * file with the given compression level.
+ *
+ * On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+ * bytes owned by the caller, which must be released with exactly one
+ * call to `pco_free_bytes(*dst, *dst_len)`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_compress_f64(const double *nums,
+                               size_t len,
+                               int level,
+                               uint8_t **dst,
+                               size_t *dst_len);
+
+/**
+ * Decompresses the `len` bytes of a standalone .pco file starting at
+ * `src`.
+ *
+ * On success, `*dst` is set to a newly allocated array of `*dst_len`
+ * numbers owned by the caller, which must be released with exactly
+ * one call to the matching free function, passing `*dst` and
+ * `*dst_len`.
+ * On failure, `*dst` and `*dst_len` are left untouched.
+ */
+enum PcoError pco_decompress_f64(const uint8_t *src,
+                                 size_t len,
+                                 double **dst,
+                                 size_t *dst_len);
+
+/**
+ * Frees an array returned by the matching decompress function.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_f64(double *ptr, size_t len);
+
+/**
+ * Frees a buffer of compressed bytes returned by one of the typed compress
+ * functions.
+ *
+ * `len` must be the length returned along with it.
+ * Passing a null pointer does nothing.
+ */
+void pco_free_bytes(uint8_t *ptr, size_t len);
@@ -2,9 +2,10 @@
 
 use std::ptr;
 
-use libc::{c_uchar, c_uint, c_void};
+use libc::{c_int, c_uchar, c_uint, c_void, size_t};
 
 use pco::data_types::{CoreDataType, NumberLike};
+use pco::errors::ErrorKind;
 
 use crate::PcoError::PcoInvalidType;
 
@@ -12,9 +13,29 @@ use crate::PcoError::PcoInvalidType;
 pub enum PcoError {
   PcoSuccess,
   PcoInvalidType,
-  // TODO split this into the actual error kinds
   PcoCompressionError,
   PcoDecompressionError,
+  // The typed functions below return these more specific codes, one per
+  // pco error kind, instead of the generic compression/decompression codes.
+  PcoChecksumMismatch,
+  PcoCompatibilityError,
+  PcoCorruptionError,
+  PcoInsufficientDataError,
+  PcoInvalidArgumentError,
+  PcoIoError,
+}
+
+impl From<pco::errors::PcoError> for PcoError {
+  fn from(err: pco::errors::PcoError) -> Self {
+    match err.kind {
+      ErrorKind::ChecksumMismatch => PcoError::PcoChecksumMismatch,
+      ErrorKind::Compatibility => PcoError::PcoCompatibilityError,
+      ErrorKind::Corruption => PcoError::PcoCorruptionError,
+      ErrorKind::InsufficientData => PcoError::PcoInsufficientDataError,
+      ErrorKind::InvalidArgument => PcoError::PcoInvalidArgumentError,
+      _ => PcoError::PcoIoError,
+    }
+  }
 }
 
 macro_rules! impl_dtypes {
@@ -153,3 +174,125 @@ pub unsafe extern "C" fn pco_free_pcovec(ffi_vec: *mut PcoFfiVec) -> PcoError {
   unsafe { (*ffi_vec).free() };
   PcoError::PcoSuccess
 }
+
+// Hands ownership of the vector's buffer to the caller, who must later
+// return it to the matching `pco_free_*` function along with its length.
+unsafe fn write_owned<T>(v: Vec<T>, dst: *mut *mut T, dst_len: *mut size_t) {
+  let len = v.len();
+  *dst = Box::into_raw(v.into_boxed_slice()) as *mut T;
+  *dst_len = len;
+}
+
+unsafe fn free_owned<T>(ptr: *mut T, len: size_t) {
+  if !ptr.is_null() {
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+      ptr, len,
+    )));
+  }
+}
+
+unsafe fn slice_or_empty<'a, T>(ptr: *const T, len: size_t) -> Option<&'a [T]> {
+  if len == 0 {
+    Some(&[])
+  } else if ptr.is_null() {
+    None
+  } else {
+    Some(std::slice::from_raw_parts(ptr, len))
+  }
+}
+
+macro_rules! impl_typed_fns {
+  {$($t:ty => $compress:ident, $decompress:ident, $free:ident;)+} => {
+    $(
+      /// Compresses `len` numbers starting at `nums` into a standalone .pco
+      /// file with the given compression level.
+      ///
+      /// On success, `*dst` is set to a newly allocated buffer of `*dst_len`
+      /// bytes owned by the caller, which must be released with exactly one
+      /// call to `pco_free_bytes(*dst, *dst_len)`.
+      /// On failure, `*dst` and `*dst_len` are left untouched.
+      #[no_mangle]
+      pub unsafe extern "C" fn $compress(
+        nums: *const $t,
+        len: size_t,
+        level: c_int,
+        dst: *mut *mut u8,
+        dst_len: *mut size_t,
+      ) -> PcoError {
+        let (Some(nums), Ok(level)) = (slice_or_empty(nums, len), usize::try_from(level)) else {
+          return PcoError::PcoInvalidArgumentError;
+        };
+        if dst.is_null() || dst_len.is_null() {
+          return PcoError::PcoInvalidArgumentError;
+        }
+        match pco::standalone::simpler_compress(nums, level) {
+          Ok(compressed) => {
+            write_owned(compressed, dst, dst_len);
+            PcoError::PcoSuccess
+          }
+          Err(e) => e.into(),
+        }
+      }
+
+      /// Decompresses the `len` bytes of a standalone .pco file starting at
+      /// `src`.
+      ///
+      /// On success, `*dst` is set to a newly allocated array of `*dst_len`
+      /// numbers owned by the caller, which must be released with exactly
+      /// one call to the matching free function, passing `*dst` and
+      /// `*dst_len`.
+      /// On failure, `*dst` and `*dst_len` are left untouched.
+      #[no_mangle]
+      pub unsafe extern "C" fn $decompress(
+        src: *const u8,
+        len: size_t,
+        dst: *mut *mut $t,
+        dst_len: *mut size_t,
+      ) -> PcoError {
+        let Some(src) = slice_or_empty(src, len) else {
+          return PcoError::PcoInvalidArgumentError;
+        };
+        if dst.is_null() || dst_len.is_null() {
+          return PcoError::PcoInvalidArgumentError;
+        }
+        match pco::standalone::simple_decompress::<$t>(src) {
+          Ok(nums) => {
+            write_owned(nums, dst, dst_len);
+            PcoError::PcoSuccess
+          }
+          Err(e) => e.into(),
+        }
+      }
+
+      /// Frees an array returned by the matching decompress function.
+      ///
+      /// `len` must be the length returned along with it.
+      /// Passing a null pointer does nothing.
+      #[no_mangle]
+      pub unsafe extern "C" fn $free(ptr: *mut $t, len: size_t) {
+        free_owned(ptr, len)
+      }
+    )+
+  }
+}
+
+impl_typed_fns! {
+  u16 => pco_compress_u16, pco_decompress_u16, pco_free_u16;
+  u32 => pco_compress_u32, pco_decompress_u32, pco_free_u32;
+  u64 => pco_compress_u64, pco_decompress_u64, pco_free_u64;
+  i16 => pco_compress_i16, pco_decompress_i16, pco_free_i16;
+  i32 => pco_compress_i32, pco_decompress_i32, pco_free_i32;
+  i64 => pco_compress_i64, pco_decompress_i64, pco_free_i64;
+  f32 => pco_compress_f32, pco_decompress_f32, pco_free_f32;
+  f64 => pco_compress_f64, pco_decompress_f64, pco_free_f64;
+}
+
+/// Frees a buffer of compressed bytes returned by one of the typed compress
+/// functions.
+///
+/// `len` must be the length returned along with it.
+/// Passing a null pointer does nothing.
+#[no_mangle]
+pub unsafe extern "C" fn pco_free_bytes(ptr: *mut u8, len: size_t) {
+  free_owned(ptr, len)
+}
@@ -5,13 +5,13 @@ int is_empty(struct PcoFfiVec *vec) {
   return vec->len == 0 && vec->ptr == NULL && vec->raw_box == NULL;
 }
 
-int main() {
+int test_dynamic_typed() {
   float input[] = {1.1f, 2.2f, 3.3f, 4.4f};
   int num_elems = sizeof(input) / sizeof(input[0]);
   int retcode = 0;
 
   struct PcoFfiVec cvec;
-  enum PcoError res = pco_auto_compress(&input, num_elems, PCO_TYPE_F32, 8, &cvec);
+  enum PcoError res = pco_simpler_compress(&input, num_elems, PCO_TYPE_F32, 8, &cvec);
   if (res != PcoSuccess) {
     printf("Error compressing: %d\n", res);
     retcode = 1;
@@ -20,7 +20,7 @@ int main() {
   printf("Compressed %d floats to %d bytes\n", num_elems, cvec.len);
 
   struct PcoFfiVec dvec;
-  res = pco_auto_decompress(cvec.ptr, cvec.len, PCO_TYPE_F32, &dvec);
+  res = pco_simple_decompress(cvec.ptr, cvec.len, PCO_TYPE_F32, &dvec);
   if (res != PcoSuccess) {
     printf("Error decompressing: %d\n", res);
     pco_free_pcovec(&cvec);
@@ -57,4 +57,61 @@ cleanup_cvec:
   }
 cleanup_none:
   return retcode;
-}
\ No newline at end of file
+}
+int test_typed() {
+  double input[] = {1.5, -2.25, 3.0, 1e100};
+  size_t num_elems = sizeof(input) / sizeof(input[0]);
+  int retcode = 0;
+
+  uint8_t *compressed = NULL;
+  size_t compressed_len = 0;
+  enum PcoError res = pco_compress_f64(input, num_elems, 8, &compressed, &compressed_len);
+  if (res != PcoSuccess) {
+    printf("Error compressing: %d\n", res);
+    return 1;
+  }
+  printf("Compressed %zu doubles to %zu bytes\n", num_elems, compressed_len);
+
+  double *decompressed = NULL;
+  size_t decompressed_len = 0;
+  res = pco_decompress_f64(compressed, compressed_len, &decompressed, &decompressed_len);
+  if (res != PcoSuccess) {
+    printf("Error decompressing: %d\n", res);
+    retcode = 1;
+    goto cleanup_compressed;
+  }
+  if (decompressed_len != num_elems) {
+    printf("Sizes do not match!!!\n");
+    retcode = 1;
+    goto cleanup_all;
+  }
+  for (size_t i = 0; i < num_elems; i++) {
+    if (input[i] != decompressed[i]) {
+      printf("Values do not match!!!\n");
+      retcode = 1;
+      goto cleanup_all;
+    }
+  }
+  printf("Values match\n");
+
+  res = pco_compress_f64(input, num_elems, 13, &compressed, &compressed_len);
+  if (res != PcoInvalidArgumentError) {
+    printf("Expected invalid argument for bad level, got %d\n", res);
+    retcode = 1;
+  }
+  res = pco_decompress_f64(compressed, compressed_len / 2, &decompressed, &decompressed_len);
+  if (res != PcoInsufficientDataError && res != PcoCorruptionError) {
+    printf("Expected error for truncated data, got %d\n", res);
+    retcode = 1;
+  }
+
+cleanup_all:
+  pco_free_f64(decompressed, decompressed_len);
+cleanup_compressed:
+  pco_free_bytes(compressed, compressed_len);
+  return retcode;
+}
+
+int main() {
+  return test_dynamic_typed() || test_typed();
+}
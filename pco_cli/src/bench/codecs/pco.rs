@@ -1,9 +1,20 @@
-use pco::{ChunkConfig, FloatMultSpec, FloatQuantSpec, IntMultSpec};
+use pco::{
+  AutoLevelSpec, ChunkConfig, DictSpec, FloatMultSpec, FloatQuantSpec, FloatXorSpec, IntMultSpec,
+  RunLengthSpec, SparseSpec,
+};
 
 use crate::bench::codecs::CodecInternal;
 use crate::chunk_config_opt::ChunkConfigOpt;
 use crate::dtypes::PcoNumberLike;
 
+fn unparse_auto_level(spec: &AutoLevelSpec) -> String {
+  use AutoLevelSpec::*;
+  match spec {
+    Disabled => "Disabled".to_string(),
+    Enabled => "Enabled".to_string(),
+  }
+}
+
 fn unparse_int_mult(spec: &IntMultSpec) -> String {
   use IntMultSpec::*;
   match spec {
@@ -19,6 +30,7 @@ fn unparse_float_mult(spec: &FloatMultSpec) -> String {
     Disabled => "Disabled".to_string(),
     Enabled => "Enabled".to_string(),
     Provided(base) => base.to_string(),
+    Lossy(tolerance) => format!("Lossy({})", tolerance),
   }
 }
 
@@ -30,6 +42,38 @@ fn unparse_float_quant(spec: &FloatQuantSpec) -> String {
   }
 }
 
+fn unparse_float_xor(spec: &FloatXorSpec) -> String {
+  use FloatXorSpec::*;
+  match spec {
+    Disabled => "Disabled".to_string(),
+    Enabled => "Enabled".to_string(),
+  }
+}
+
+fn unparse_run_length(spec: &RunLengthSpec) -> String {
+  use RunLengthSpec::*;
+  match spec {
+    Disabled => "Disabled".to_string(),
+    Enabled => "Enabled".to_string(),
+  }
+}
+
+fn unparse_dict(spec: &DictSpec) -> String {
+  use DictSpec::*;
+  match spec {
+    Disabled => "Disabled".to_string(),
+    Enabled => "Enabled".to_string(),
+  }
+}
+
+fn unparse_sparse(spec: &SparseSpec) -> String {
+  use SparseSpec::*;
+  match spec {
+    Disabled => "Disabled".to_string(),
+    Enabled => "Enabled".to_string(),
+  }
+}
+
 impl CodecInternal for ChunkConfigOpt {
   fn name(&self) -> &'static str {
     "pco"
@@ -38,6 +82,10 @@ impl CodecInternal for ChunkConfigOpt {
   fn get_confs(&self) -> Vec<(&'static str, String)> {
     vec![
       ("level", self.level.to_string()),
+      (
+        "auto-level",
+        unparse_auto_level(&self.auto_level),
+      ),
       (
         "delta-order",
         self
@@ -45,6 +93,10 @@ impl CodecInternal for ChunkConfigOpt {
           .map(|order| order.to_string())
           .unwrap_or("Auto".to_string()),
       ),
+      (
+        "secondary-delta-order",
+        self.secondary_delta_encoding_order.to_string(),
+      ),
       ("int-mult", unparse_int_mult(&self.int_mult)),
       (
         "float-mult",
@@ -54,6 +106,16 @@ impl CodecInternal for ChunkConfigOpt {
         "float-quant",
         unparse_float_quant(&self.float_quant),
       ),
+      (
+        "float-xor",
+        unparse_float_xor(&self.float_xor),
+      ),
+      (
+        "run-length",
+        unparse_run_length(&self.run_length),
+      ),
+      ("dict", unparse_dict(&self.dict)),
+      ("sparse", unparse_sparse(&self.sparse)),
       ("chunk-n", self.chunk_n.to_string()),
     ]
   }
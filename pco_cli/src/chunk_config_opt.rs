@@ -1,6 +1,9 @@
 use clap::Parser;
 
-use pco::{ChunkConfig, FloatMultSpec, FloatQuantSpec, IntMultSpec, PagingSpec};
+use pco::{
+  AutoLevelSpec, ChunkConfig, DictSpec, FloatMultSpec, FloatQuantSpec, FloatXorSpec, IntMultSpec,
+  PagingSpec, RunLengthSpec, SparseSpec,
+};
 
 use crate::parse;
 
@@ -9,6 +12,11 @@ pub struct ChunkConfigOpt {
   /// Compression level.
   #[arg(long, default_value = "8")]
   pub level: usize,
+  /// Can be "Enabled" or "Disabled". When enabled, each chunk may use more
+  /// or fewer bins than the compression level implies if it is estimated to
+  /// compress better.
+  #[arg(long, default_value = "Disabled", value_parser = parse::auto_level)]
+  pub auto_level: AutoLevelSpec,
   // We fully quality `Option` to use a value parser that returns Option<usize>
   // instead of just usize. See
   // https://github.com/clap-rs/clap/issues/5536#issuecomment-2179646989
@@ -16,6 +24,10 @@ pub struct ChunkConfigOpt {
   /// which tries to automatically detect the best delta encoding order.
   #[arg(long = "delta-order", default_value = "Auto", value_parser = parse::delta_encoding_order)]
   pub delta_encoding_order: std::option::Option<usize>,
+  /// How many times to apply delta encoding to the secondary latent variable
+  /// in modes that have one, such as int mult.
+  #[arg(long = "secondary-delta-order", default_value = "0")]
+  pub secondary_delta_encoding_order: usize,
   /// Can be "Enabled", "Disabled", or a fixed integer to use as the base in
   /// int mult mode.
   #[arg(long, default_value = "Enabled", value_parser = parse::int_mult)]
@@ -28,6 +40,18 @@ pub struct ChunkConfigOpt {
   /// Can be "Disabled", or a fixed integer to use as the parameter `k` in float quant mode.
   #[arg(long, default_value = "Disabled", value_parser = parse::float_quant)]
   pub float_quant: FloatQuantSpec,
+  /// Can be "Enabled" or "Disabled".
+  #[arg(long, default_value = "Disabled", value_parser = parse::float_xor)]
+  pub float_xor: FloatXorSpec,
+  /// Can be "Enabled" or "Disabled".
+  #[arg(long, default_value = "Disabled", value_parser = parse::run_length)]
+  pub run_length: RunLengthSpec,
+  /// Can be "Enabled" or "Disabled".
+  #[arg(long, default_value = "Disabled", value_parser = parse::dict)]
+  pub dict: DictSpec,
+  /// Can be "Enabled" or "Disabled".
+  #[arg(long, default_value = "Disabled", value_parser = parse::sparse)]
+  pub sparse: SparseSpec,
   #[arg(long, default_value_t = pco::DEFAULT_MAX_PAGE_N)]
   pub chunk_n: usize,
 }
@@ -36,10 +60,16 @@ impl From<&ChunkConfigOpt> for ChunkConfig {
   fn from(opt: &ChunkConfigOpt) -> Self {
     ChunkConfig::default()
       .with_compression_level(opt.level)
+      .with_auto_level_spec(opt.auto_level)
       .with_delta_encoding_order(opt.delta_encoding_order)
+      .with_secondary_delta_encoding_order(opt.secondary_delta_encoding_order)
       .with_int_mult_spec(opt.int_mult)
       .with_float_mult_spec(opt.float_mult)
       .with_float_quant_spec(opt.float_quant)
+      .with_float_xor_spec(opt.float_xor)
+      .with_run_length_spec(opt.run_length)
+      .with_dict_spec(opt.dict)
+      .with_sparse_spec(opt.sparse)
       .with_paging_spec(PagingSpec::EqualPagesUpTo(opt.chunk_n))
   }
 }
@@ -30,7 +30,7 @@ impl<P: ArrowNumberLike> CompressHandler for ArrowHandlerImpl<P> {
 
     let config = ChunkConfig::from(&opt.chunk_config);
     let chunk_size = opt.chunk_config.chunk_n;
-    let fc = FileCompressor::default();
+    let fc = FileCompressor::default().with_format_version_for(&config);
     fc.write_header(&file)?;
 
     let col_idx = utils::find_col_idx(
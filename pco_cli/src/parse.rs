@@ -3,7 +3,10 @@ use std::str::FromStr;
 use anyhow::anyhow;
 use arrow::datatypes::{DataType, TimeUnit};
 
-use pco::{FloatMultSpec, FloatQuantSpec, IntMultSpec};
+use pco::{
+  AutoLevelSpec, DictSpec, FloatMultSpec, FloatQuantSpec, FloatXorSpec, IntMultSpec, RunLengthSpec,
+  SparseSpec,
+};
 
 pub fn delta_encoding_order(s: &str) -> anyhow::Result<Option<usize>> {
   match s.to_lowercase().as_str() {
@@ -60,6 +63,56 @@ pub fn float_quant(s: &str) -> anyhow::Result<FloatQuantSpec> {
   Ok(spec)
 }
 
+pub fn float_xor(s: &str) -> anyhow::Result<FloatXorSpec> {
+  let lowercase = s.to_lowercase();
+  let spec = match lowercase.as_str() {
+    "enabled" => FloatXorSpec::Enabled,
+    "disabled" => FloatXorSpec::Disabled,
+    other => return Err(anyhow!("cannot parse float xor: {}", other)),
+  };
+  Ok(spec)
+}
+
+pub fn run_length(s: &str) -> anyhow::Result<RunLengthSpec> {
+  let lowercase = s.to_lowercase();
+  let spec = match lowercase.as_str() {
+    "enabled" => RunLengthSpec::Enabled,
+    "disabled" => RunLengthSpec::Disabled,
+    other => return Err(anyhow!("cannot parse run length: {}", other)),
+  };
+  Ok(spec)
+}
+
+pub fn auto_level(s: &str) -> anyhow::Result<AutoLevelSpec> {
+  let lowercase = s.to_lowercase();
+  let spec = match lowercase.as_str() {
+    "enabled" => AutoLevelSpec::Enabled,
+    "disabled" => AutoLevelSpec::Disabled,
+    other => return Err(anyhow!("cannot parse auto level: {}", other)),
+  };
+  Ok(spec)
+}
+
+pub fn dict(s: &str) -> anyhow::Result<DictSpec> {
+  let lowercase = s.to_lowercase();
+  let spec = match lowercase.as_str() {
+    "enabled" => DictSpec::Enabled,
+    "disabled" => DictSpec::Disabled,
+    other => return Err(anyhow!("cannot parse dict: {}", other)),
+  };
+  Ok(spec)
+}
+
+pub fn sparse(s: &str) -> anyhow::Result<SparseSpec> {
+  let lowercase = s.to_lowercase();
+  let spec = match lowercase.as_str() {
+    "enabled" => SparseSpec::Enabled,
+    "disabled" => SparseSpec::Disabled,
+    other => return Err(anyhow!("cannot parse sparse: {}", other)),
+  };
+  Ok(spec)
+}
+
 pub fn arrow_dtype(s: &str) -> anyhow::Result<DataType> {
   let name_pairs = [
     ("f16", DataType::Float16),
@@ -1,7 +1,10 @@
 use half::f16;
 use numpy::PyArrayDyn;
 use pco::data_types::CoreDataType;
-use pco::{ChunkConfig, FloatMultSpec, FloatQuantSpec, IntMultSpec, PagingSpec, Progress};
+use pco::{
+  AutoLevelSpec, ChunkConfig, DictSpec, FloatMultSpec, FloatQuantSpec, FloatXorSpec, IntMultSpec,
+  PagingSpec, Progress, RunLengthSpec, SparseSpec,
+};
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::{pymodule, FromPyObject, PyModule, PyResult, Python};
 use pyo3::{py_run, pyclass, pymethods, PyErr};
@@ -105,6 +108,13 @@ impl PyFloatMultSpec {
   fn provided(base: f64) -> Self {
     Self(FloatMultSpec::Provided(base))
   }
+
+  /// :returns: a FloatMultSpec that lossily compresses floats to within the
+  /// given absolute `tolerance`.
+  #[staticmethod]
+  fn lossy(tolerance: f64) -> Self {
+    Self(FloatMultSpec::Lossy(tolerance))
+  }
 }
 
 #[pyclass(name = "FloatQuantSpec")]
@@ -129,6 +139,120 @@ impl PyFloatQuantSpec {
   }
 }
 
+#[pyclass(name = "FloatXorSpec")]
+#[derive(Clone, Default)]
+pub struct PyFloatXorSpec(FloatXorSpec);
+
+/// Specifies if pcodec should consider XORing consecutive floats to compress
+/// data.
+#[pymethods]
+impl PyFloatXorSpec {
+  /// :returns: a FloatXorSpec disabling float XOR mode.
+  #[staticmethod]
+  fn disabled() -> Self {
+    Self(FloatXorSpec::Disabled)
+  }
+
+  /// :returns: a FloatXorSpec enabling float XOR mode.
+  /// Pcodec will use it when it is estimated to compress better.
+  #[staticmethod]
+  fn enabled() -> Self {
+    Self(FloatXorSpec::Enabled)
+  }
+}
+
+#[pyclass(name = "RunLengthSpec")]
+#[derive(Clone, Default)]
+pub struct PyRunLengthSpec(RunLengthSpec);
+
+/// Specifies if pcodec should consider encoding runs of identical numbers as
+/// (value, length) pairs.
+#[pymethods]
+impl PyRunLengthSpec {
+  /// :returns: a RunLengthSpec disabling run length mode.
+  #[staticmethod]
+  fn disabled() -> Self {
+    Self(RunLengthSpec::Disabled)
+  }
+
+  /// :returns: a RunLengthSpec enabling run length mode.
+  /// Pcodec will use it when runs are long and it is estimated to compress
+  /// better.
+  #[staticmethod]
+  fn enabled() -> Self {
+    Self(RunLengthSpec::Enabled)
+  }
+}
+
+#[pyclass(name = "DictSpec")]
+#[derive(Clone, Default)]
+pub struct PyDictSpec(DictSpec);
+
+/// Specifies if pcodec should consider encoding numbers as indices into a
+/// dictionary of each chunk's distinct numbers.
+#[pymethods]
+impl PyDictSpec {
+  /// :returns: a DictSpec disabling dict mode.
+  #[staticmethod]
+  fn disabled() -> Self {
+    Self(DictSpec::Disabled)
+  }
+
+  /// :returns: a DictSpec enabling dict mode.
+  /// Pcodec will use it when there are few distinct numbers and it is
+  /// estimated to compress better.
+  #[staticmethod]
+  fn enabled() -> Self {
+    Self(DictSpec::Enabled)
+  }
+}
+
+#[pyclass(name = "SparseSpec")]
+#[derive(Clone, Default)]
+pub struct PySparseSpec(SparseSpec);
+
+/// Specifies if pcodec should consider encoding only the nonzero numbers
+/// and the counts of zeros between them.
+#[pymethods]
+impl PySparseSpec {
+  /// :returns: a SparseSpec disabling sparse mode.
+  #[staticmethod]
+  fn disabled() -> Self {
+    Self(SparseSpec::Disabled)
+  }
+
+  /// :returns: a SparseSpec enabling sparse mode.
+  /// Pcodec will use it when most numbers are zero and it is estimated to
+  /// compress better.
+  #[staticmethod]
+  fn enabled() -> Self {
+    Self(SparseSpec::Enabled)
+  }
+}
+
+#[pyclass(name = "AutoLevelSpec")]
+#[derive(Clone, Default)]
+pub struct PyAutoLevelSpec(AutoLevelSpec);
+
+/// Specifies if each chunk may tune its own bin count instead of using
+/// the one implied by the compression level.
+#[pymethods]
+impl PyAutoLevelSpec {
+  /// :returns: an AutoLevelSpec that uses the compression level as-is.
+  #[staticmethod]
+  fn disabled() -> Self {
+    Self(AutoLevelSpec::Disabled)
+  }
+
+  /// :returns: an AutoLevelSpec enabling auto level.
+  /// Pcodec will also try fewer and more bins for each chunk, keeping
+  /// whichever is estimated to compress best.
+  #[staticmethod]
+  fn enabled() -> Self {
+    Self(AutoLevelSpec::Enabled)
+  }
+}
+
 #[pyclass(name = "PagingSpec")]
 #[derive(Clone, Default)]
 pub struct PyPagingSpec(PagingSpec);
@@ -157,9 +281,15 @@ impl PyPagingSpec {
 pub struct PyChunkConfig {
   compression_level: usize,
   delta_encoding_order: Option<usize>,
+  secondary_delta_encoding_order: usize,
   int_mult_spec: PyIntMultSpec,
   float_mult_spec: PyFloatMultSpec,
   float_quant_spec: PyFloatQuantSpec,
+  float_xor_spec: PyFloatXorSpec,
+  run_length_spec: PyRunLengthSpec,
+  dict_spec: PyDictSpec,
+  sparse_spec: PySparseSpec,
+  auto_level_spec: PyAutoLevelSpec,
   paging_spec: PyPagingSpec,
 }
 
@@ -170,10 +300,14 @@ impl PyChunkConfig {
   /// :param compression_level: a compression level from 0-12, where 12 takes
   /// the longest and compresses the most.
   ///
-  /// :param delta_encoding_order: either a delta encoding level from 0-7 or
+  /// :param delta_encoding_order: either a delta encoding level from 0-15 or
   /// None. If set to None, pcodec will try to infer the optimal delta encoding
   /// order.
   ///
+  /// :param secondary_delta_encoding_order: a delta encoding level from 0-15
+  /// for the secondary latent variable, in modes that have one, such as
+  /// integer multiplier mode. This is 0 by default and never inferred.
+  ///
   /// :param int_mult_spec: a IntMultSpec that configures whether integer
   /// multiplier detection is enabled.
   ///
@@ -207,6 +341,37 @@ impl PyChunkConfig {
   /// * float-valued data stored in a type that is unnecessarily wide (e.g.
   /// stored as `f64`s where only a `f32` worth of precision is used)
   ///
+  /// :param float_xor_spec: a FloatXorSpec that configures whether float XOR
+  /// mode is considered.
+  ///
+  /// Examples where this helps:
+  /// * time series where many consecutive values are bit-identical
+  ///
+  /// When enabled, this can roughly double compression time.
+  ///
+  /// :param run_length_spec: a RunLengthSpec that configures whether run
+  /// length mode is considered.
+  ///
+  /// Examples where this helps:
+  /// * step functions and other data with long runs of identical values
+  ///
+  /// :param dict_spec: a DictSpec that configures whether dict mode is
+  /// considered.
+  ///
+  /// Examples where this helps:
+  /// * a few hash values or IDs, each repeated many times
+  ///
+  /// :param sparse_spec: a SparseSpec that configures whether sparse mode is
+  /// considered.
+  ///
+  /// Examples where this helps:
+  /// * counts of rare events, which are zero most of the time
+  ///
+  /// :param auto_level_spec: an AutoLevelSpec that configures whether each
+  /// chunk may use more or fewer bins than the compression level implies.
+  ///
+  /// When enabled, this costs two extra rounds of bin training per chunk.
+  ///
   /// :param paging_spec: a PagingSpec describing how many numbers should
   /// go into each page.
   ///
@@ -215,25 +380,44 @@ impl PyChunkConfig {
   #[pyo3(signature = (
     compression_level=pco::DEFAULT_COMPRESSION_LEVEL,
     delta_encoding_order=None,
+    secondary_delta_encoding_order=0,
     int_mult_spec=PyIntMultSpec::default(),
     float_mult_spec=PyFloatMultSpec::default(),
     float_quant_spec=PyFloatQuantSpec::default(),
+    float_xor_spec=PyFloatXorSpec::default(),
+    run_length_spec=PyRunLengthSpec::default(),
+    dict_spec=PyDictSpec::default(),
+    sparse_spec=PySparseSpec::default(),
+    auto_level_spec=PyAutoLevelSpec::default(),
     paging_spec=PyPagingSpec::default(),
   ))]
+  #[allow(clippy::too_many_arguments)]
   fn new(
     compression_level: usize,
     delta_encoding_order: Option<usize>,
+    secondary_delta_encoding_order: usize,
     int_mult_spec: PyIntMultSpec,
     float_mult_spec: PyFloatMultSpec,
     float_quant_spec: PyFloatQuantSpec,
+    float_xor_spec: PyFloatXorSpec,
+    run_length_spec: PyRunLengthSpec,
+    dict_spec: PyDictSpec,
+    sparse_spec: PySparseSpec,
+    auto_level_spec: PyAutoLevelSpec,
     paging_spec: PyPagingSpec,
   ) -> Self {
     Self {
       compression_level,
       delta_encoding_order,
+      secondary_delta_encoding_order,
       int_mult_spec,
       float_mult_spec,
       float_quant_spec,
+      float_xor_spec,
+      run_length_spec,
+      dict_spec,
+      sparse_spec,
+      auto_level_spec,
       paging_spec,
     }
   }
@@ -246,9 +430,15 @@ impl TryFrom<&PyChunkConfig> for ChunkConfig {
     let res = ChunkConfig::default()
       .with_compression_level(py_config.compression_level)
       .with_delta_encoding_order(py_config.delta_encoding_order)
+      .with_secondary_delta_encoding_order(py_config.secondary_delta_encoding_order)
       .with_int_mult_spec(py_config.int_mult_spec.0)
       .with_float_mult_spec(py_config.float_mult_spec.0)
       .with_float_quant_spec(py_config.float_quant_spec.0)
+      .with_float_xor_spec(py_config.float_xor_spec.0)
+      .with_run_length_spec(py_config.run_length_spec.0)
+      .with_dict_spec(py_config.dict_spec.0)
+      .with_sparse_spec(py_config.sparse_spec.0)
+      .with_auto_level_spec(py_config.auto_level_spec.0)
       .with_paging_spec(py_config.paging_spec.0.clone());
     Ok(res)
   }
@@ -278,6 +468,11 @@ fn pcodec(py: Python, m: &PyModule) -> PyResult<()> {
   m.add_class::<PyIntMultSpec>()?;
   m.add_class::<PyFloatMultSpec>()?;
   m.add_class::<PyFloatQuantSpec>()?;
+  m.add_class::<PyFloatXorSpec>()?;
+  m.add_class::<PyRunLengthSpec>()?;
+  m.add_class::<PyDictSpec>()?;
+  m.add_class::<PySparseSpec>()?;
+  m.add_class::<PyAutoLevelSpec>()?;
   m.add_class::<PyPagingSpec>()?;
   m.add_class::<PyChunkConfig>()?;
   m.add(
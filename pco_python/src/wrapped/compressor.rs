@@ -51,6 +51,21 @@ impl PyFc {
     }
   }
 
+  /// Raises the file's format version, if necessary, so that it can hold
+  /// chunks compressed with the provided config.
+  ///
+  /// By default, files get a format version that older versions of pcodec
+  /// can also decompress. This must be called before writing the header.
+  ///
+  /// :param config: a ChunkConfig object that will be used for some chunks.
+  ///
+  /// :raises: TypeError, RuntimeError
+  fn set_format_version_for(&mut self, config: &PyChunkConfig) -> PyResult<()> {
+    let config: ChunkConfig = config.try_into()?;
+    self.inner = self.inner.clone().with_format_version_for(&config);
+    Ok(())
+  }
+
   /// :returns: a bytes object containing the encoded header
   ///
   /// :raises: TypeError, RuntimeError
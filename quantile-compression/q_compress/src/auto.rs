@@ -41,18 +41,34 @@ pub fn auto_decompress<T: NumberLike>(bytes: &[u8]) -> QCompressResult<Vec<T>> {
 ///
 /// This has some compute cost by trying different configurations on a subset
 /// of the numbers to determine the most likely one to do well.
+/// If `nums` is sorted, this sets
+/// [`assume_sorted`][CompressorConfig::assume_sorted] and only considers
+/// delta encoding orders of at least 1.
 /// See [`CompressorConfig`] for information about compression levels.
 pub fn auto_compressor_config<T: NumberLike>(
   nums: &[T],
   compression_level: usize,
 ) -> CompressorConfig {
-  let delta_encoding_order = auto_delta_encoding_order(nums, compression_level);
+  let assume_sorted = is_sorted(nums);
+  let delta_encoding_order = auto_delta_encoding_order(nums, compression_level, assume_sorted);
   CompressorConfig::default()
     .with_compression_level(compression_level)
     .with_delta_encoding_order(delta_encoding_order)
+    .with_assume_sorted(assume_sorted)
 }
 
-fn auto_delta_encoding_order<T: NumberLike>(nums: &[T], compression_level: usize) -> usize {
+// checks for ascending order, using the same total order as prefixes
+fn is_sorted<T: NumberLike>(nums: &[T]) -> bool {
+  nums
+    .windows(2)
+    .all(|pair| pair[0].to_unsigned() <= pair[1].to_unsigned())
+}
+
+fn auto_delta_encoding_order<T: NumberLike>(
+  nums: &[T],
+  compression_level: usize,
+  assume_sorted: bool,
+) -> usize {
   let mut sampled_nums;
   let head_nums = if nums.len() < AUTO_DELTA_LIMIT {
     nums
@@ -72,9 +88,11 @@ fn auto_delta_encoding_order<T: NumberLike>(nums: &[T], compression_level: usize
     &sampled_nums
   };
 
+  // sorted numbers are always better off with delta encoding
+  let min_order = if assume_sorted { 1 } else { 0 };
   let mut best_order = usize::MAX;
   let mut best_size = usize::MAX;
-  for delta_encoding_order in 0..8 {
+  for delta_encoding_order in min_order..8 {
     // Taking deltas of a large dataset won't change the GCD,
     // so we don't need to waste compute here inferring GCD's just to
     // determine the best delta order.
@@ -118,13 +136,16 @@ mod tests {
       linear_trend.push(i);
       quadratic_trend.push(i * i);
     }
-    assert_eq!(auto_delta_encoding_order(&no_trend, 3), 0);
     assert_eq!(
-      auto_delta_encoding_order(&linear_trend, 3),
+      auto_delta_encoding_order(&no_trend, 3, false),
+      0
+    );
+    assert_eq!(
+      auto_delta_encoding_order(&linear_trend, 3, false),
       1
     );
     assert_eq!(
-      auto_delta_encoding_order(&quadratic_trend, 3),
+      auto_delta_encoding_order(&quadratic_trend, 3, false),
       2
     );
   }
@@ -138,6 +159,33 @@ mod tests {
     for _ in 1000..2000 {
       nums.push(78);
     }
-    assert_eq!(auto_delta_encoding_order(&nums, 3), 1);
+    assert_eq!(auto_delta_encoding_order(&nums, 3, false), 1);
+  }
+
+  #[test]
+  fn test_auto_sorted() {
+    let sorted = (0..2000_i64)
+      .map(|i| i * 1000 + (i * 7919) % 1000)
+      .collect::<Vec<_>>();
+    let config = auto_compressor_config(&sorted, 6);
+    assert!(config.assume_sorted);
+    assert!(config.delta_encoding_order >= 1);
+
+    let mut shuffled = sorted.clone();
+    shuffled.swap(3, 1500);
+    assert!(!auto_compressor_config(&shuffled, 6).assume_sorted);
+  }
+
+  #[test]
+  fn test_assume_sorted_unsorted_data() {
+    let nums = (0..1000_u32).map(|i| (i * 7919) % 1000).collect::<Vec<_>>();
+    let config = CompressorConfig::default().with_assume_sorted(true);
+    let mut compressor = Compressor::<u32>::from_config(config);
+    let compressed = compressor.simple_compress(&nums);
+    assert_eq!(compressor.flags().delta_encoding_order, 1);
+    assert_eq!(
+      auto_decompress::<u32>(&compressed).unwrap(),
+      nums
+    );
   }
 }
@@ -1,5 +1,6 @@
 use std::cmp::{max, min};
 use std::fmt::Debug;
+use std::io::Write;
 
 use crate::bit_writer::BitWriter;
 use crate::chunk_metadata::{ChunkMetadata, PrefixMetadata};
@@ -13,7 +14,7 @@ use crate::errors::{QCompressError, QCompressResult};
 use crate::gcd_utils::{GcdOperator, GeneralGcdOp, TrivialGcdOp};
 use crate::prefix::{Prefix, PrefixCompressionInfo};
 use crate::prefix_optimization;
-use crate::{gcd_utils, huffman_encoding, Flags};
+use crate::{gcd_utils, huffman_encoding, ColumnMetadata, Flags};
 
 /// All configurations available for a compressor.
 ///
@@ -71,6 +72,37 @@ pub struct CompressorConfig {
   /// When this is helpful and in rare cases when it isn't, compression speed
   /// is slightly reduced.
   pub use_gcds: bool,
+  /// `assume_sorted` hints that the numbers are sorted in ascending order,
+  /// like indexes or sorted keys (default false).
+  ///
+  /// Sorted numbers compress far better with delta encoding, so when this is
+  /// set and `delta_encoding_order` is 0, the compressor uses 1st order
+  /// delta encoding instead.
+  /// [`auto_compressor_config()`][crate::auto_compressor_config] sets this
+  /// when it detects that the numbers it samples are sorted.
+  ///
+  /// This is only a hint: if the numbers aren't actually sorted, they still
+  /// round-trip exactly, but compression ratio may suffer.
+  pub assume_sorted: bool,
+  /// `use_chunk_index` appends an index of each chunk's byte offset after
+  /// the footer (default false).
+  ///
+  /// This costs 8 bytes per chunk plus 9 bytes, and lets
+  /// [`ParsedFile`][crate::standalone::ParsedFile] find every chunk from the
+  /// index instead of scanning the whole file, which helps for large files
+  /// with many chunks when only a few are needed.
+  /// It has no effect in wrapped mode.
+  pub use_chunk_index: bool,
+  /// `column_metadata` stores a column name and optional units in a trailer
+  /// after the footer (default None).
+  ///
+  /// This costs 6 bytes plus the length of the strings, and readers can
+  /// inspect it via
+  /// [`ParsedFile::column_name`][crate::standalone::ParsedFile::column_name].
+  /// Compression will return an InvalidArgument error when writing the
+  /// header if either string exceeds 255 bytes or the units are empty.
+  /// It has no effect in wrapped mode.
+  pub column_metadata: Option<ColumnMetadata>,
 }
 
 impl Default for CompressorConfig {
@@ -79,6 +111,9 @@ impl Default for CompressorConfig {
       compression_level: DEFAULT_COMPRESSION_LEVEL,
       delta_encoding_order: 0,
       use_gcds: true,
+      assume_sorted: false,
+      use_chunk_index: false,
+      column_metadata: None,
     }
   }
 }
@@ -101,6 +136,24 @@ impl CompressorConfig {
     self.use_gcds = use_gcds;
     self
   }
+
+  /// Sets [`assume_sorted`][CompressorConfig::assume_sorted].
+  pub fn with_assume_sorted(mut self, assume_sorted: bool) -> Self {
+    self.assume_sorted = assume_sorted;
+    self
+  }
+
+  /// Sets [`use_chunk_index`][CompressorConfig::use_chunk_index].
+  pub fn with_use_chunk_index(mut self, use_chunk_index: bool) -> Self {
+    self.use_chunk_index = use_chunk_index;
+    self
+  }
+
+  /// Sets [`column_metadata`][CompressorConfig::column_metadata].
+  pub fn with_column_metadata(mut self, column_metadata: ColumnMetadata) -> Self {
+    self.column_metadata = Some(column_metadata);
+    self
+  }
 }
 
 // InternalCompressorConfig captures all settings that don't belong in flags
@@ -286,26 +339,34 @@ fn train_prefixes<T: NumberLike>(
   Ok(optimized_prefs)
 }
 
+// Compresses unsigneds from start_idx onward, stopping once it reaches
+// stop_idx (or passes it, if a run crosses it), and returns the index it
+// stopped at. The body is only finished once every unsigned is written.
 fn trained_compress_body<U: UnsignedLike>(
   table: &CompressionTable<U>,
   use_gcd: bool,
   unsigneds: &[U],
+  start_idx: usize,
+  stop_idx: usize,
   writer: &mut BitWriter,
-) -> QCompressResult<()> {
+) -> QCompressResult<usize> {
   if use_gcd {
-    compress_data_page::<U, GeneralGcdOp>(table, unsigneds, writer)
+    compress_data_page::<U, GeneralGcdOp>(table, unsigneds, start_idx, stop_idx, writer)
   } else {
-    compress_data_page::<U, TrivialGcdOp>(table, unsigneds, writer)
+    compress_data_page::<U, TrivialGcdOp>(table, unsigneds, start_idx, stop_idx, writer)
   }
 }
 
 fn compress_data_page<U: UnsignedLike, GcdOp: GcdOperator<U>>(
   table: &CompressionTable<U>,
   unsigneds: &[U],
+  start_idx: usize,
+  stop_idx: usize,
   writer: &mut BitWriter,
-) -> QCompressResult<()> {
-  let mut i = 0;
-  while i < unsigneds.len() {
+) -> QCompressResult<usize> {
+  let end_idx = min(stop_idx, unsigneds.len());
+  let mut i = start_idx;
+  while i < end_idx {
     let unsigned = unsigneds[i];
     let p = table.search(unsigned)?;
     writer.write_usize(p.code, p.code_len);
@@ -335,8 +396,10 @@ fn compress_data_page<U: UnsignedLike, GcdOp: GcdOperator<U>>(
       }
     }
   }
-  writer.finish_byte();
-  Ok(())
+  if i >= unsigneds.len() {
+    writer.finish_byte();
+  }
+  Ok(i)
 }
 
 fn compress_offset<U: UnsignedLike, GcdOp: GcdOperator<U>>(
@@ -409,6 +472,13 @@ pub struct BaseCompressor<T: NumberLike> {
   pub flags: Flags,
   pub writer: BitWriter,
   pub state: State<T>,
+  // the byte offset of each chunk written so far and the total byte size of
+  // the file so far, including drained bytes; only the standalone compressor
+  // tracks these, for its chunk index
+  pub chunk_offsets: Vec<usize>,
+  pub byte_idx: usize,
+  // written by the standalone compressor after the footer
+  pub column_metadata: Option<ColumnMetadata>,
 }
 
 impl<T: NumberLike> BaseCompressor<T> {
@@ -418,6 +488,13 @@ impl<T: NumberLike> BaseCompressor<T> {
       flags: Flags::from_config(&config, use_wrapped_mode),
       writer: BitWriter::default(),
       state: State::default(),
+      chunk_offsets: Vec::new(),
+      byte_idx: 0,
+      column_metadata: if use_wrapped_mode {
+        None
+      } else {
+        config.column_metadata
+      },
     }
   }
 
@@ -425,10 +502,14 @@ impl<T: NumberLike> BaseCompressor<T> {
     if !matches!(self.state, State::PreHeader) {
       return Err(self.state.wrong_step_err("header"));
     }
+    if let Some(column_metadata) = &self.column_metadata {
+      column_metadata.validate()?;
+    }
 
     self.writer.write_aligned_bytes(&MAGIC_HEADER)?;
     self.writer.write_aligned_byte(T::HEADER_BYTE)?;
     self.flags.write(&mut self.writer)?;
+    self.byte_idx = self.writer.byte_size();
     self.state = State::StartOfChunk;
     Ok(())
   }
@@ -507,6 +588,28 @@ impl<T: NumberLike> BaseCompressor<T> {
   }
 
   pub fn data_page_internal(&mut self) -> QCompressResult<()> {
+    self.data_page_internal_with_flush(usize::MAX, |_| Ok(()))
+  }
+
+  // Like data_page_internal, but moves complete words of the body from the
+  // writer to dst every STREAMING_FLUSH_N numbers, so the writer never holds
+  // the whole body.
+  pub fn data_page_internal_streaming<W: Write>(&mut self, dst: &mut W) -> QCompressResult<()> {
+    self.data_page_internal_with_flush(STREAMING_FLUSH_N, |writer| {
+      dst
+        .write_all(&writer.drain_complete_words())
+        .map_err(QCompressError::io)
+    })
+  }
+
+  fn data_page_internal_with_flush<F>(
+    &mut self,
+    flush_every: usize,
+    mut flush: F,
+  ) -> QCompressResult<()>
+  where
+    F: FnMut(&mut BitWriter) -> QCompressResult<()>,
+  {
     let has_pages_remaining = {
       let info = match &mut self.state {
         State::MidChunk(info) => Ok(info),
@@ -524,12 +627,21 @@ impl<T: NumberLike> BaseCompressor<T> {
       } else {
         &[]
       };
-      trained_compress_body(
-        &info.table,
-        info.use_gcd,
-        slice,
-        &mut self.writer,
-      )?;
+      let mut i = 0;
+      loop {
+        i = trained_compress_body(
+          &info.table,
+          info.use_gcd,
+          slice,
+          i,
+          i.saturating_add(flush_every),
+          &mut self.writer,
+        )?;
+        if i >= slice.len() {
+          break;
+        }
+        flush(&mut self.writer)?;
+      }
 
       info.idx += data_page_n;
       info.page_idx += 1;
@@ -187,6 +187,16 @@ impl BitWriter {
     }
   }
 
+  /// Returns the bytes of all words completed so far, leaving the incomplete
+  /// final word in the writer.
+  ///
+  /// Afterward, sizes and bit indices are relative to the remaining word.
+  pub fn drain_complete_words(&mut self) -> Vec<u8> {
+    let res = bits::words_to_bytes(&self.words);
+    self.words.clear();
+    res
+  }
+
   pub fn drain_bytes(&mut self) -> Vec<u8> {
     let byte_size = self.byte_size();
     self.words.push(self.word);
@@ -257,4 +267,18 @@ mod tests {
     let bytes = writer.drain_bytes();
     assert_eq!(bytes, vec![0, 32, 64],);
   }
+
+  #[test]
+  fn test_long_diffs_are_msb_first() {
+    let mut writer = BitWriter::default();
+    writer.write_usize(0xa, 4);
+    writer.write_diff(0x0123_4567_89ab_cdef_u64, 64);
+    writer.write_diff(1_u128 << 127 | 0xff, 128);
+    writer.write_usize(0x5, 4);
+
+    let mut expected = vec![0xa0, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf8];
+    expected.extend([0; 14]);
+    expected.extend([0x0f, 0xf5]);
+    assert_eq!(writer.drain_bytes(), expected);
+  }
 }
@@ -38,6 +38,13 @@ impl<T: NumberLike> PrefixMetadata<T> {
       PrefixMetadata::Delta { prefixes } => gcd_utils::use_gcd_arithmetic(prefixes),
     }
   }
+
+  pub(crate) fn primary_gcd(&self) -> Option<T::Unsigned> {
+    match self {
+      PrefixMetadata::Simple { prefixes } => gcd_utils::primary_gcd(prefixes),
+      PrefixMetadata::Delta { prefixes } => gcd_utils::primary_gcd(prefixes),
+    }
+  }
 }
 
 /// The metadata of a Quantile-compressed chunk.
@@ -181,6 +188,20 @@ impl<T: NumberLike> ChunkMetadata<T> {
     }
   }
 
+  /// Returns the greatest common divisor the chunk's numbers (or deltas, if
+  /// delta encoding was used) were compressed with, if GCD arithmetic is
+  /// active and all prefixes with nontrivial ranges share the same one.
+  ///
+  /// The GCD is expressed in the data type's unsigned representation, so
+  /// e.g. it is a multiple of machine epsilon rather than a float quotient
+  /// for floats.
+  /// Returns `None` if no prefix uses GCD arithmetic, or if prefixes use
+  /// different GCDs, in which case they can be found in each
+  /// [`Prefix`]'s `gcd` field.
+  pub fn primary_gcd(&self) -> Option<T::Unsigned> {
+    self.prefix_metadata.primary_gcd()
+  }
+
   // TODO in 1.0 make this private
   pub fn parse_from(reader: &mut BitReader, flags: &Flags) -> QCompressResult<Self> {
     let (n, compressed_body_size, delta_moments) = if flags.use_wrapped_mode {
@@ -0,0 +1,118 @@
+use crate::bit_writer::BitWriter;
+use crate::constants::{COLUMN_METADATA_MAGIC, MAX_COLUMN_METADATA_STR_BYTES};
+use crate::errors::{QCompressError, QCompressResult};
+
+// the 2 string lengths and the magic
+const TRAILER_FIXED_BYTES: usize = 2 + COLUMN_METADATA_MAGIC.len();
+
+/// A column's name and optional units, stored in a trailer at the very end
+/// of a standalone file.
+///
+/// The trailer goes after the footer (and chunk index, if any), where
+/// decompressors stop reading, so files with column metadata decompress the
+/// same with versions of q_compress that predate it.
+/// It consists of the name's UTF-8 bytes, the units' UTF-8 bytes, each
+/// one's length as a byte, and a 4-byte magic for "qcom" in ascii.
+/// So neither string may exceed 255 bytes.
+/// Units are stored with a length of 0 when absent, so they may not be
+/// empty.
+/// Wrapped files have no footer, so they have no column metadata.
+/// ```
+/// use q_compress::{ColumnMetadata, CompressorConfig};
+///
+/// let config = CompressorConfig::default()
+///   .with_column_metadata(ColumnMetadata::new("temperature").with_units("°C"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ColumnMetadata {
+  /// The column's name.
+  pub name: String,
+  /// The column's units, if any, e.g. `"ms"` or `"°C"`.
+  pub units: Option<String>,
+}
+
+impl ColumnMetadata {
+  /// Creates column metadata with the given name and no units.
+  pub fn new<S: Into<String>>(name: S) -> Self {
+    Self {
+      name: name.into(),
+      units: None,
+    }
+  }
+
+  /// Sets [`units`][ColumnMetadata::units].
+  pub fn with_units<S: Into<String>>(mut self, units: S) -> Self {
+    self.units = Some(units.into());
+    self
+  }
+
+  pub(crate) fn validate(&self) -> QCompressResult<()> {
+    if matches!(&self.units, Some(units) if units.is_empty()) {
+      return Err(QCompressError::invalid_argument(
+        "column units may not be empty; use None instead",
+      ));
+    }
+    for (description, s) in [("name", self.name.as_str()), ("units", self.units_str())] {
+      if s.len() > MAX_COLUMN_METADATA_STR_BYTES {
+        return Err(QCompressError::invalid_argument(format!(
+          "column {} may not exceed {} bytes (was {})",
+          description,
+          MAX_COLUMN_METADATA_STR_BYTES,
+          s.len(),
+        )));
+      }
+    }
+    Ok(())
+  }
+
+  fn units_str(&self) -> &str {
+    self.units.as_deref().unwrap_or("")
+  }
+
+  pub(crate) fn write_trailer(&self, writer: &mut BitWriter) -> QCompressResult<()> {
+    let units = self.units_str();
+    writer.write_aligned_bytes(self.name.as_bytes())?;
+    writer.write_aligned_bytes(units.as_bytes())?;
+    writer.write_aligned_byte(self.name.len() as u8)?;
+    writer.write_aligned_byte(units.len() as u8)?;
+    writer.write_aligned_bytes(&COLUMN_METADATA_MAGIC)
+  }
+
+  // Returns the column metadata in the trailer at the end of the file, if
+  // any, along with the trailer's size in bytes.
+  pub(crate) fn parse_trailer(file: &[u8]) -> QCompressResult<Option<(Self, usize)>> {
+    if !file.ends_with(&COLUMN_METADATA_MAGIC) || file.len() < TRAILER_FIXED_BYTES {
+      return Ok(None);
+    }
+
+    let lens_start = file.len() - TRAILER_FIXED_BYTES;
+    let name_len = file[lens_start] as usize;
+    let units_len = file[lens_start + 1] as usize;
+    let trailer_size = name_len + units_len + TRAILER_FIXED_BYTES;
+    let name_start = file.len().checked_sub(trailer_size).ok_or_else(|| {
+      QCompressError::corruption(format!(
+        "column metadata trailer of {} bytes does not fit in file of {} bytes",
+        trailer_size,
+        file.len(),
+      ))
+    })?;
+    let units_start = name_start + name_len;
+    let name = parse_str(&file[name_start..units_start], "name")?;
+    let units = parse_str(&file[units_start..lens_start], "units")?;
+    let meta = Self {
+      name,
+      units: if units.is_empty() { None } else { Some(units) },
+    };
+    Ok(Some((meta, trailer_size)))
+  }
+}
+
+fn parse_str(bytes: &[u8], description: &str) -> QCompressResult<String> {
+  String::from_utf8(bytes.to_vec()).map_err(|e| {
+    QCompressError::corruption(format!(
+      "column {} is not valid UTF-8: {}",
+      description, e,
+    ))
+  })
+}
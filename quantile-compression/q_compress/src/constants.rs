@@ -1,6 +1,7 @@
 pub const MAGIC_HEADER: [u8; 4] = [113, 99, 111, 33]; // ascii for qco!
 pub const MAGIC_CHUNK_BYTE: u8 = 44; // ,
 pub const MAGIC_TERMINATION_BYTE: u8 = 46; // .
+pub const COLUMN_METADATA_MAGIC: [u8; 4] = [113, 99, 111, 109]; // ascii for qcom
 
 pub const MAX_DELTA_ENCODING_ORDER: usize = 7;
 pub const BITS_TO_ENCODE_DELTA_ENCODING_ORDER: usize = 3;
@@ -10,6 +11,8 @@ pub const BITS_TO_ENCODE_N_PREFIXES: usize = 15;
 pub const MAX_JUMPSTART: usize = BITS_TO_ENCODE_N_ENTRIES;
 pub const BITS_TO_ENCODE_JUMPSTART: usize = 5;
 pub const BITS_TO_ENCODE_COMPRESSED_BODY_SIZE: usize = 32;
+pub const CHUNK_INDEX_ENTRY_BYTES: usize = 8;
+pub const MAX_COLUMN_METADATA_STR_BYTES: usize = u8::MAX as usize;
 
 // MAX_PREFIX_TABLE_SIZE_LOG is a performance tuning parameter
 // Too high, and we use excessive memory and in some cases hurt performance.
@@ -24,6 +27,10 @@ pub const MAX_COMPRESSION_LEVEL: usize = 12;
 pub const MIN_N_TO_USE_RUN_LEN: usize = 1001;
 pub const MIN_FREQUENCY_TO_USE_RUN_LEN: f64 = 0.8;
 
+// when compressing to a seekable writer, how many numbers of a chunk body to
+// compress before passing the finished bytes along
+pub const STREAMING_FLUSH_N: usize = 1 << 16;
+
 pub const AUTO_DELTA_LIMIT: usize = 1100;
 pub const MAX_AUTO_DELTA_COMPRESSION_LEVEL: usize = 6;
 
@@ -59,6 +66,11 @@ mod tests {
     assert_can_encode(BITS_TO_ENCODE_JUMPSTART, MAX_JUMPSTART);
   }
 
+  #[test]
+  fn test_column_metadata_len_fits_in_byte() {
+    assert!(MAX_COLUMN_METADATA_STR_BYTES <= u8::MAX as usize);
+  }
+
   #[test]
   fn test_prefix_table_size_fits_in_word() {
     assert!(MAX_PREFIX_TABLE_SIZE_LOG > 0);
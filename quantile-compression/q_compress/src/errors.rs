@@ -1,6 +1,7 @@
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::io;
 
 // TODO in 1.0 make this #[non_exhaustive]
 /// The different kinds of errors for `q_compress`.
@@ -64,6 +65,15 @@ impl QCompressError {
   pub(crate) fn invalid_argument<S: AsRef<str>>(message: S) -> Self {
     Self::new(ErrorKind::InvalidArgument, message)
   }
+
+  // There is no error kind for IO, so a failure of the destination passed in
+  // counts as an invalid argument.
+  pub(crate) fn io(err: io::Error) -> Self {
+    Self::invalid_argument(format!(
+      "failed to write to destination: {}",
+      err
+    ))
+  }
 }
 
 impl Display for QCompressError {
@@ -2,7 +2,7 @@
 // of the compressed data.
 // New flags may be added in over time in a backward-compatible way.
 
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::convert::{TryFrom, TryInto};
 
 use crate::bit_reader::BitReader;
@@ -66,6 +66,14 @@ pub struct Flags {
   ///
   /// Introduced in 0.11.2.
   pub use_wrapped_mode: bool,
+  /// Whether the file ends with an index of each chunk's byte offset, after
+  /// the termination footer.
+  /// This lets readers jump directly to any chunk without parsing the
+  /// metadata of the chunks before it.
+  /// It is never used in wrapped mode, since wrapped files have no footer.
+  ///
+  /// Introduced in 0.11.8.
+  pub use_chunk_index: bool,
 }
 
 impl TryFrom<Vec<bool>> for Flags {
@@ -78,6 +86,7 @@ impl TryFrom<Vec<bool>> for Flags {
       use_min_count_encoding: false,
       use_gcds: false,
       use_wrapped_mode: false,
+      use_chunk_index: false,
     };
 
     let mut bit_iter = bools.iter();
@@ -95,7 +104,9 @@ impl TryFrom<Vec<bool>> for Flags {
 
     flags.use_wrapped_mode = bit_iter.next() == Some(&true);
 
-    // if we ever add another bit flag, it will increase file size by 1 byte when on
+    // this is the first flag in the second byte, so it increases file size by
+    // 1 byte when on
+    flags.use_chunk_index = bit_iter.next() == Some(&true);
 
     for &bit in bit_iter {
       if bit {
@@ -133,6 +144,8 @@ impl TryInto<Vec<bool>> for &Flags {
 
     res.push(self.use_wrapped_mode);
 
+    res.push(self.use_chunk_index);
+
     let necessary_len = res
       .iter()
       .rposition(|&bit| bit)
@@ -206,12 +219,18 @@ impl Flags {
   }
 
   pub(crate) fn from_config(config: &CompressorConfig, use_wrapped_mode: bool) -> Self {
+    let delta_encoding_order = if config.assume_sorted {
+      max(config.delta_encoding_order, 1)
+    } else {
+      config.delta_encoding_order
+    };
     Flags {
       use_5_bit_code_len: true,
-      delta_encoding_order: config.delta_encoding_order,
+      delta_encoding_order,
       use_min_count_encoding: true,
       use_gcds: config.use_gcds,
       use_wrapped_mode,
+      use_chunk_index: config.use_chunk_index && !use_wrapped_mode,
     }
   }
 }
@@ -94,6 +94,16 @@ pub fn use_gcd_arithmetic<T: NumberLike>(prefixes: &[Prefix<T>]) -> bool {
     .any(|p| p.gcd > T::Unsigned::ONE && p.upper != p.lower)
 }
 
+// Returns the GCD decompression multiplies offsets by, if every prefix that
+// uses GCD arithmetic shares it.
+pub fn primary_gcd<T: NumberLike>(prefixes: &[Prefix<T>]) -> Option<T::Unsigned> {
+  if use_gcd_arithmetic(prefixes) {
+    common_gcd_for_chunk_meta(prefixes)
+  } else {
+    None
+  }
+}
+
 pub fn gcd_bits_required<U: UnsignedLike>(range: U) -> usize {
   range.to_f64().log2().ceil() as usize
 }
@@ -179,6 +189,7 @@ impl<U: UnsignedLike> GcdOperator<U> for GeneralGcdOp {
 #[cfg(test)]
 mod tests {
   use crate::gcd_utils::*;
+  use crate::{Compressor, CompressorConfig};
 
   #[test]
   fn test_pair_gcd() {
@@ -193,6 +204,48 @@ mod tests {
     assert_eq!(pair_gcd(7, (1_u64 << 63) - 1), 7);
   }
 
+  #[test]
+  fn test_primary_gcd() {
+    let prefix = |lower: u32, upper: u32, gcd: u32| Prefix {
+      count: 1,
+      code: vec![],
+      lower,
+      upper,
+      run_len_jumpstart: None,
+      gcd,
+    };
+    assert_eq!(primary_gcd::<u32>(&[]), None);
+    assert_eq!(
+      primary_gcd(&[prefix(0, 0, 1), prefix(3, 3, 1)]),
+      None
+    );
+    assert_eq!(
+      primary_gcd(&[prefix(0, 30, 3), prefix(40, 40, 1), prefix(43, 49, 3)]),
+      Some(3)
+    );
+    assert_eq!(
+      primary_gcd(&[prefix(0, 30, 3), prefix(40, 50, 5)]),
+      None
+    );
+  }
+
+  #[test]
+  fn test_chunk_primary_gcd() {
+    let mut compressor = Compressor::from_config(CompressorConfig::default());
+    compressor.header().unwrap();
+    let nums = (0..1000_u64).map(|x| x * x * 7).collect::<Vec<_>>();
+    assert_eq!(
+      compressor.chunk(&nums).unwrap().primary_gcd(),
+      Some(7)
+    );
+
+    let nums = (0..1000_u64).map(|x| x * x + 1).collect::<Vec<_>>();
+    assert_eq!(
+      compressor.chunk(&nums).unwrap().primary_gcd(),
+      None
+    );
+  }
+
   #[test]
   fn test_gcd() {
     assert_eq!(gcd(&[0_u32, 4, 6, 8, 10]), 2);
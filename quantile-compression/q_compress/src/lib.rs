@@ -12,6 +12,7 @@ pub use auto::{auto_compress, auto_compressor_config, auto_decompress};
 pub use base_compressor::CompressorConfig;
 pub use base_decompressor::DecompressorConfig;
 pub use chunk_metadata::{ChunkMetadata, PrefixMetadata};
+pub use column_metadata::ColumnMetadata;
 pub use constants::DEFAULT_COMPRESSION_LEVEL;
 // TODO in 1.0 remove these standalone things from top level
 pub use flags::Flags;
@@ -33,6 +34,7 @@ mod bits;
 mod body_decompressor;
 mod chunk_metadata;
 mod chunk_spec;
+mod column_metadata;
 mod compression_table;
 mod constants;
 mod delta_encoding;
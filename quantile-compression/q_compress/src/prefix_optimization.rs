@@ -146,6 +146,7 @@ mod tests {
       use_5_bit_code_len: true,
       delta_encoding_order: 0,
       use_wrapped_mode: false,
+      use_chunk_index: false,
     }
   }
 
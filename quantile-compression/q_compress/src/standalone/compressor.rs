@@ -1,8 +1,13 @@
+use std::io::{Seek, SeekFrom, Write};
+
 use crate::base_compressor::{BaseCompressor, State};
+use crate::bit_writer::BitWriter;
 use crate::chunk_spec::ChunkSpec;
-use crate::constants::MAGIC_TERMINATION_BYTE;
+use crate::constants::{
+  BITS_TO_ENCODE_COMPRESSED_BODY_SIZE, BITS_TO_ENCODE_N_ENTRIES, MAGIC_TERMINATION_BYTE,
+};
 use crate::data_types::NumberLike;
-use crate::errors::QCompressResult;
+use crate::errors::{QCompressError, QCompressResult};
 use crate::{bits, ChunkMetadata, CompressorConfig, Flags};
 
 /// Converts vectors of numbers into compressed bytes in
@@ -42,6 +47,58 @@ impl<T: NumberLike> Default for Compressor<T> {
 
 const DEFAULT_CHUNK_SIZE: usize = 1_000_000;
 
+fn default_chunks<T>(nums: &[T]) -> impl Iterator<Item = &[T]> {
+  let n_chunks = bits::ceil_div(nums.len(), DEFAULT_CHUNK_SIZE);
+  let n_per_chunk = bits::ceil_div(nums.len(), n_chunks.max(1));
+  nums.chunks(n_per_chunk.max(1))
+}
+
+/// Compresses the numbers into .qco format like
+/// [`Compressor::simple_compress`], writing to `dst` as it goes.
+///
+/// Each chunk's metadata contains the byte size of the chunk body that
+/// follows it, which normally forces the compressor to hold the whole body
+/// in memory until it is done.
+/// Instead, this writes the metadata with a placeholder size, streams the
+/// body directly to `dst`, and then seeks back to fill in the size, so only
+/// a small part of each body is held in memory at once.
+/// The resulting bytes are identical to those of `simple_compress`.
+///
+/// If `dst` turns out not to support seeking (e.g. a file handle for a
+/// pipe), this falls back to buffering each chunk in memory before writing
+/// it.
+///
+/// Will return an InvalidArgument error if writing to or seeking `dst`
+/// fails.
+pub fn compress_streaming<T: NumberLike, W: Write + Seek>(
+  dst: &mut W,
+  nums: &[T],
+  config: CompressorConfig,
+) -> QCompressResult<()> {
+  let mut compressor = Compressor::<T>::from_config(config);
+  compressor.header()?;
+  dst
+    .write_all(&compressor.drain_bytes())
+    .map_err(QCompressError::io)?;
+
+  let seekable = dst.stream_position().is_ok();
+  for chunk in default_chunks(nums) {
+    if seekable {
+      compressor.chunk_streaming(chunk, dst)?;
+    } else {
+      compressor.chunk(chunk)?;
+      dst
+        .write_all(&compressor.drain_bytes())
+        .map_err(QCompressError::io)?;
+    }
+  }
+
+  compressor.footer()?;
+  dst
+    .write_all(&compressor.drain_bytes())
+    .map_err(QCompressError::io)
+}
+
 impl<T: NumberLike> Compressor<T> {
   /// Creates a new compressor, given a [`CompressorConfig`].
   /// Internally, the compressor builds [`Flags`] as well as an internal
@@ -77,6 +134,7 @@ impl<T: NumberLike> Compressor<T> {
   /// The chunk body encodes the numbers passed in here.
   pub fn chunk(&mut self, nums: &[T]) -> QCompressResult<ChunkMetadata<T>> {
     let pre_meta_bit_idx = self.0.writer.bit_size();
+    let pre_meta_byte_idx = self.0.writer.byte_size();
     let mut meta = self
       .0
       .chunk_metadata_internal(nums, &ChunkSpec::default())?;
@@ -86,18 +144,83 @@ impl<T: NumberLike> Compressor<T> {
 
     meta.compressed_body_size = self.0.writer.byte_size() - post_meta_byte_idx;
     meta.update_write_compressed_body_size(&mut self.0.writer, pre_meta_bit_idx);
+    self.record_chunk(self.0.writer.byte_size() - pre_meta_byte_idx);
     Ok(meta)
   }
 
+  fn record_chunk(&mut self, chunk_byte_size: usize) {
+    self.0.chunk_offsets.push(self.0.byte_idx);
+    self.0.byte_idx += chunk_byte_size;
+  }
+
+  // Like chunk, but writes the chunk to dst as it goes, starting with any
+  // bytes not yet drained.
+  fn chunk_streaming<W: Write + Seek>(&mut self, nums: &[T], dst: &mut W) -> QCompressResult<()> {
+    let pre_meta_byte_idx =
+      dst.stream_position().map_err(QCompressError::io)? + self.0.writer.byte_size() as u64;
+    self
+      .0
+      .chunk_metadata_internal(nums, &ChunkSpec::default())?;
+    dst
+      .write_all(&self.drain_bytes())
+      .map_err(QCompressError::io)?;
+    let body_start = dst.stream_position().map_err(QCompressError::io)?;
+
+    self.0.data_page_internal_streaming(dst)?;
+    dst
+      .write_all(&self.drain_bytes())
+      .map_err(QCompressError::io)?;
+    let body_end = dst.stream_position().map_err(QCompressError::io)?;
+
+    // the body size comes right after the magic chunk byte and n, both of
+    // which are byte-aligned
+    let mut size_writer = BitWriter::default();
+    size_writer.write_usize(
+      (body_end - body_start) as usize,
+      BITS_TO_ENCODE_COMPRESSED_BODY_SIZE,
+    );
+    let size_byte_idx = pre_meta_byte_idx + 1 + (BITS_TO_ENCODE_N_ENTRIES / 8) as u64;
+    dst
+      .seek(SeekFrom::Start(size_byte_idx))
+      .map_err(QCompressError::io)?;
+    dst
+      .write_all(&size_writer.drain_bytes())
+      .map_err(QCompressError::io)?;
+    dst
+      .seek(SeekFrom::Start(body_end))
+      .map_err(QCompressError::io)?;
+    self.record_chunk((body_end - pre_meta_byte_idx) as usize);
+    Ok(())
+  }
+
   /// Writes out a single footer byte indicating that the .qco file has ended.
   /// Will return an error if the compressor has not yet written the header
   /// or already written the footer.
+  ///
+  /// If [`use_chunk_index`][CompressorConfig::use_chunk_index] is enabled,
+  /// this also writes the chunk index after the footer byte: each chunk's
+  /// byte offset and then the count of chunks, each as a little-endian `u64`.
+  /// Any [`column_metadata`][CompressorConfig::column_metadata] comes last,
+  /// as described in [`ColumnMetadata`][crate::ColumnMetadata].
   pub fn footer(&mut self) -> QCompressResult<()> {
     if !matches!(self.0.state, State::StartOfChunk) {
       return Err(self.0.state.wrong_step_err("footer"));
     }
 
     self.0.writer.write_aligned_byte(MAGIC_TERMINATION_BYTE)?;
+    if self.0.flags.use_chunk_index {
+      for &offset in &self.0.chunk_offsets {
+        self
+          .0
+          .writer
+          .write_aligned_bytes(&(offset as u64).to_le_bytes())?;
+      }
+      let n_chunks = self.0.chunk_offsets.len() as u64;
+      self.0.writer.write_aligned_bytes(&n_chunks.to_le_bytes())?;
+    }
+    if let Some(column_metadata) = &self.0.column_metadata {
+      column_metadata.write_trailer(&mut self.0.writer)?;
+    }
     self.0.state = State::Terminated;
     Ok(())
   }
@@ -113,12 +236,8 @@ impl<T: NumberLike> Compressor<T> {
 
     self.header().unwrap();
 
-    if !nums.is_empty() {
-      let n_chunks = bits::ceil_div(nums.len(), DEFAULT_CHUNK_SIZE);
-      let n_per_chunk = bits::ceil_div(nums.len(), n_chunks);
-      nums.chunks(n_per_chunk).for_each(|chunk| {
-        self.chunk(chunk).unwrap();
-      });
+    for chunk in default_chunks(nums) {
+      self.chunk(chunk).unwrap();
     }
 
     self.footer().unwrap();
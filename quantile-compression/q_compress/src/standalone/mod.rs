@@ -1,6 +1,8 @@
-pub use compressor::Compressor;
+pub use compressor::{compress_streaming, Compressor};
 pub use decompressor::DecompressedItem;
 pub use decompressor::Decompressor;
+pub use parsed_file::ParsedFile;
 
 mod compressor;
 mod decompressor;
+mod parsed_file;
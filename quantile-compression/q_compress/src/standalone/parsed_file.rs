@@ -0,0 +1,276 @@
+use std::convert::TryFrom;
+use std::io::Write;
+
+use crate::constants::{CHUNK_INDEX_ENTRY_BYTES, MAGIC_TERMINATION_BYTE};
+use crate::data_types::NumberLike;
+use crate::errors::{ErrorKind, QCompressError, QCompressResult};
+use crate::standalone::Decompressor;
+use crate::{ChunkMetadata, ColumnMetadata, Flags};
+
+// The header is only a few bytes, so we feed it to the decompressor a little
+// at a time rather than copying the whole file into it.
+const HEADER_READ_INCREMENT: usize = 8;
+
+fn read_u64_le(bytes: &[u8]) -> usize {
+  let mut word = [0; CHUNK_INDEX_ENTRY_BYTES];
+  word.copy_from_slice(bytes);
+  u64::from_le_bytes(word) as usize
+}
+
+/// A handle on a complete .qco file, holding its [`Flags`], every
+/// chunk's [`ChunkMetadata`], and every chunk's byte offset for random
+/// access.
+///
+/// Building one via `TryFrom<&[u8]>` parses the header and all chunk
+/// metadata up front, but none of the chunk bodies.
+/// If the file was compressed with
+/// [`use_chunk_index`][crate::CompressorConfig::use_chunk_index], the chunk
+/// offsets come from the chunk index at the end of the file, and each
+/// chunk's metadata is read directly at its offset.
+/// Otherwise, the chunks are scanned once, skipping over their bodies.
+/// Either way, the bodies are only decompressed on demand, via
+/// [`decompress_chunk`][ParsedFile::decompress_chunk], which jumps straight
+/// to the chunk, or [`decompress_all`][ParsedFile::decompress_all].
+/// ```
+/// use std::convert::TryFrom;
+/// use q_compress::standalone::ParsedFile;
+///
+/// let my_bytes = q_compress::auto_compress(&[1_i32, 2, 3], 6);
+/// let parsed = ParsedFile::<i32>::try_from(my_bytes.as_slice()).expect("parse");
+/// assert_eq!(parsed.total_count(), 3);
+/// let nums = parsed.decompress_all().expect("decompress");
+/// assert_eq!(parsed.decompress_chunk(0).expect("chunk 0"), nums);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParsedFile<'a, T: NumberLike> {
+  bytes: &'a [u8],
+  flags: Flags,
+  header_size: usize,
+  // the byte offset of each chunk, followed by that of the footer
+  offsets: Vec<usize>,
+  chunk_metas: Vec<ChunkMetadata<T>>,
+  column_metadata: Option<ColumnMetadata>,
+}
+
+impl<'a, T: NumberLike> TryFrom<&'a [u8]> for ParsedFile<'a, T> {
+  type Error = QCompressError;
+
+  /// Will return an error if the bytes run out before the footer,
+  /// contain flags from a newer, incompatible version of q_compress,
+  /// or contain any corruptions in the header, chunk index, chunk metadata,
+  /// or column metadata.
+  fn try_from(bytes: &'a [u8]) -> QCompressResult<Self> {
+    let column_metadata = ColumnMetadata::parse_trailer(bytes)?;
+    let (flags, header_size) = parse_header::<T>(bytes)?;
+    let mut res = Self {
+      bytes,
+      flags,
+      header_size,
+      offsets: Vec::new(),
+      chunk_metas: Vec::new(),
+      column_metadata: None,
+    };
+
+    if res.flags.use_chunk_index {
+      // the column metadata trailer, if any, comes after the chunk index
+      let trailer_size = column_metadata.as_ref().map_or(0, |&(_, size)| size);
+      res.offsets = read_chunk_index(
+        &bytes[..bytes.len() - trailer_size],
+        header_size,
+      )?;
+      for chunk_idx in 0..res.chunk_count() {
+        let meta = res
+          .chunk_decompressor(chunk_idx)?
+          .chunk_metadata()?
+          .ok_or_else(|| missing_chunk_err(chunk_idx))?;
+        res.chunk_metas.push(meta);
+      }
+    } else {
+      let mut decompressor = Self::decompressor(bytes);
+      decompressor.header()?;
+      loop {
+        res.offsets.push(decompressor.bit_idx() / 8);
+        match decompressor.chunk_metadata()? {
+          Some(meta) => res.chunk_metas.push(meta),
+          None => break,
+        }
+        decompressor.skip_chunk_body()?;
+      }
+    }
+
+    res.column_metadata = column_metadata.map(|(meta, _)| meta);
+    Ok(res)
+  }
+}
+
+fn parse_header<T: NumberLike>(bytes: &[u8]) -> QCompressResult<(Flags, usize)> {
+  let mut decompressor = Decompressor::<T>::default();
+  let mut n_written = 0;
+  loop {
+    let end = (n_written + HEADER_READ_INCREMENT).min(bytes.len());
+    decompressor.write_all(&bytes[n_written..end]).unwrap(); // writing to a decompressor can't fail
+    n_written = end;
+    match decompressor.header() {
+      Ok(flags) => return Ok((flags, decompressor.bit_idx() / 8)),
+      Err(e) if matches!(e.kind, ErrorKind::InsufficientData) && n_written < bytes.len() => (),
+      Err(e) => return Err(e),
+    }
+  }
+}
+
+fn read_chunk_index(bytes: &[u8], header_size: usize) -> QCompressResult<Vec<usize>> {
+  let insufficient = || {
+    QCompressError::insufficient_data(format!(
+      "{} bytes is too short for a file with a chunk index",
+      bytes.len()
+    ))
+  };
+  let count_start = bytes
+    .len()
+    .checked_sub(CHUNK_INDEX_ENTRY_BYTES)
+    .ok_or_else(insufficient)?;
+  let n_chunks = read_u64_le(&bytes[count_start..]);
+  let index_start = n_chunks
+    .checked_mul(CHUNK_INDEX_ENTRY_BYTES)
+    .and_then(|index_size| count_start.checked_sub(index_size))
+    .filter(|&index_start| index_start > header_size)
+    .ok_or_else(insufficient)?;
+
+  let footer_offset = index_start - 1;
+  if bytes[footer_offset] != MAGIC_TERMINATION_BYTE {
+    return Err(QCompressError::corruption(format!(
+      "expected footer byte {} before chunk index but found {}",
+      MAGIC_TERMINATION_BYTE, bytes[footer_offset],
+    )));
+  }
+
+  let mut offsets = bytes[index_start..count_start]
+    .chunks_exact(CHUNK_INDEX_ENTRY_BYTES)
+    .map(read_u64_le)
+    .collect::<Vec<_>>();
+  offsets.push(footer_offset);
+  if offsets[0] != header_size || offsets.windows(2).any(|pair| pair[0] >= pair[1]) {
+    return Err(QCompressError::corruption(
+      "chunk index offsets must start after the header and increase strictly up to the footer",
+    ));
+  }
+  Ok(offsets)
+}
+
+impl<'a, T: NumberLike> ParsedFile<'a, T> {
+  fn decompressor(bytes: &[u8]) -> Decompressor<T> {
+    let mut decompressor = Decompressor::<T>::default();
+    decompressor.write_all(bytes).unwrap(); // writing to a decompressor can't fail
+    decompressor
+  }
+
+  /// Returns the bytes the file was parsed from.
+  pub fn bytes(&self) -> &'a [u8] {
+    self.bytes
+  }
+
+  /// Returns the file's [`Flags`].
+  pub fn flags(&self) -> &Flags {
+    &self.flags
+  }
+
+  /// Returns the file's [`ColumnMetadata`], if any.
+  pub fn column_metadata(&self) -> Option<&ColumnMetadata> {
+    self.column_metadata.as_ref()
+  }
+
+  /// Returns the column name stored in the file, if any.
+  pub fn column_name(&self) -> Option<&str> {
+    self.column_metadata.as_ref().map(|meta| meta.name.as_str())
+  }
+
+  /// Returns the column units stored in the file, if any.
+  pub fn units(&self) -> Option<&str> {
+    self
+      .column_metadata
+      .as_ref()
+      .and_then(|meta| meta.units.as_deref())
+  }
+
+  /// Returns the [`ChunkMetadata`] of every chunk, in order.
+  pub fn chunk_metas(&self) -> &[ChunkMetadata<T>] {
+    &self.chunk_metas
+  }
+
+  /// Returns the count of chunks in the file.
+  pub fn chunk_count(&self) -> usize {
+    self.offsets.len() - 1
+  }
+
+  /// Returns whether the file has a chunk index, as opposed to its chunk
+  /// offsets having been found by scanning.
+  pub fn has_chunk_index(&self) -> bool {
+    self.flags.use_chunk_index
+  }
+
+  /// Returns the byte offset of each chunk in the file.
+  pub fn chunk_offsets(&self) -> &[usize] {
+    &self.offsets[..self.chunk_count()]
+  }
+
+  /// Returns the count of numbers across all chunks, without decompressing
+  /// any chunk bodies.
+  pub fn total_count(&self) -> usize {
+    self.chunk_metas.iter().map(|meta| meta.n).sum()
+  }
+
+  // Returns a decompressor that has read the header and is positioned at the
+  // start of the chunk, holding only the header and that chunk's bytes.
+  fn chunk_decompressor(&self, chunk_idx: usize) -> QCompressResult<Decompressor<T>> {
+    if chunk_idx >= self.chunk_count() {
+      return Err(QCompressError::invalid_argument(format!(
+        "chunk index {} out of bounds for file with {} chunks",
+        chunk_idx,
+        self.chunk_count(),
+      )));
+    }
+
+    let mut decompressor = Decompressor::<T>::default();
+    decompressor
+      .write_all(&self.bytes[..self.header_size])
+      .unwrap(); // writing to a decompressor can't fail
+    decompressor.header()?;
+    decompressor
+      .write_all(&self.bytes[self.offsets[chunk_idx]..self.offsets[chunk_idx + 1]])
+      .unwrap();
+    Ok(decompressor)
+  }
+
+  /// Decompresses the chunk at index `chunk_idx`, without touching any other
+  /// chunk.
+  ///
+  /// Will return an error if `chunk_idx` is out of bounds or the chunk body
+  /// contains any corruptions.
+  pub fn decompress_chunk(&self, chunk_idx: usize) -> QCompressResult<Vec<T>> {
+    let mut decompressor = self.chunk_decompressor(chunk_idx)?;
+    if decompressor.chunk_metadata()?.is_none() {
+      return Err(missing_chunk_err(chunk_idx));
+    }
+    decompressor.chunk_body()
+  }
+
+  /// Decompresses every chunk, returning all the numbers in the file.
+  ///
+  /// Will return an error if any chunk body contains any corruptions.
+  pub fn decompress_all(&self) -> QCompressResult<Vec<T>> {
+    let mut res = Vec::with_capacity(self.total_count());
+    let mut decompressor = Self::decompressor(self.bytes);
+    decompressor.header()?;
+    while decompressor.chunk_metadata()?.is_some() {
+      res.extend(decompressor.chunk_body()?);
+    }
+    Ok(res)
+  }
+}
+
+fn missing_chunk_err(chunk_idx: usize) -> QCompressError {
+  QCompressError::corruption(format!(
+    "found footer instead of chunk {}",
+    chunk_idx
+  ))
+}
@@ -0,0 +1,36 @@
+// Pins the byte layout of the standalone header and chunk metadata fields for
+// readers in other languages. Every multi-bit field is written most
+// significant bit first, so fields that span whole bytes come out big-endian
+// on every platform.
+use crate::constants::{MAGIC_CHUNK_BYTE, MAGIC_HEADER, MAGIC_TERMINATION_BYTE};
+use crate::data_types::NumberLike;
+use crate::errors::QCompressResult;
+use crate::{Compressor, CompressorConfig};
+
+#[test]
+fn test_standalone_field_byte_order() -> QCompressResult<()> {
+  let config = CompressorConfig::default().with_delta_encoding_order(1);
+  let mut compressor = Compressor::<i32>::from_config(config);
+  compressor.header()?;
+  let header = compressor.drain_bytes();
+  assert_eq!(&header[..4], &MAGIC_HEADER);
+  assert_eq!(header[4], i32::HEADER_BYTE);
+
+  let nums = (0..300).map(|i| 1000 + i * i).collect::<Vec<i32>>();
+  let meta = compressor.chunk(&nums)?;
+  compressor.footer()?;
+  let chunk = compressor.drain_bytes();
+
+  assert_eq!(chunk[0], MAGIC_CHUNK_BYTE);
+  // n, 24 bits
+  assert_eq!(&chunk[1..4], &[0, 1, 44]);
+  // compressed body size, 32 bits
+  let body_size = (meta.compressed_body_size as u32).to_be_bytes();
+  assert_eq!(&chunk[4..8], &body_size);
+  let footer_idx = chunk.len() - 1;
+  assert_eq!(chunk[footer_idx], MAGIC_TERMINATION_BYTE);
+  assert!(meta.compressed_body_size < footer_idx);
+  // the first delta moment, as the number's own big-endian bytes
+  assert_eq!(&chunk[8..12], &1000_i32.to_be_bytes());
+  Ok(())
+}
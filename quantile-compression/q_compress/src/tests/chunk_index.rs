@@ -0,0 +1,116 @@
+use std::convert::TryFrom;
+
+use crate::errors::ErrorKind;
+use crate::standalone::ParsedFile;
+use crate::{auto_decompress, Compressor, CompressorConfig};
+
+fn compress_chunks(chunks: &[Vec<i64>], use_chunk_index: bool) -> Vec<u8> {
+  let config = CompressorConfig::default()
+    .with_delta_encoding_order(1)
+    .with_use_chunk_index(use_chunk_index);
+  let mut compressor = Compressor::<i64>::from_config(config);
+  compressor.header().unwrap();
+  // drain partway through to make sure offsets account for drained bytes
+  let mut bytes = compressor.drain_bytes();
+  for chunk in chunks {
+    compressor.chunk(chunk).unwrap();
+    bytes.extend(compressor.drain_bytes());
+  }
+  compressor.footer().unwrap();
+  bytes.extend(compressor.drain_bytes());
+  bytes
+}
+
+fn example_chunks() -> Vec<Vec<i64>> {
+  (0..20)
+    .map(|chunk_idx| (0..50 + chunk_idx * 10).map(|i| i * chunk_idx).collect())
+    .collect()
+}
+
+#[test]
+fn test_chunk_index_matches_scan() {
+  let chunks = example_chunks();
+  let indexed_bytes = compress_chunks(&chunks, true);
+  let scanned_bytes = compress_chunks(&chunks, false);
+  // 1 flag byte, plus 8 bytes for each offset and the count
+  assert_eq!(
+    indexed_bytes.len(),
+    scanned_bytes.len() + 1 + 8 * 21
+  );
+
+  let indexed = ParsedFile::<i64>::try_from(indexed_bytes.as_slice()).unwrap();
+  let scanned = ParsedFile::<i64>::try_from(scanned_bytes.as_slice()).unwrap();
+  assert!(indexed.has_chunk_index());
+  assert!(!scanned.has_chunk_index());
+  assert_eq!(indexed.chunk_count(), 20);
+  assert_eq!(scanned.chunk_count(), 20);
+  // the index makes every offset 1 byte later, due to the extra flag byte
+  for (&indexed_offset, &scanned_offset) in
+    indexed.chunk_offsets().iter().zip(scanned.chunk_offsets())
+  {
+    assert_eq!(indexed_offset, scanned_offset + 1);
+  }
+
+  for file in [&indexed, &scanned] {
+    for chunk_idx in [19, 0, 7] {
+      assert_eq!(
+        file.chunk_metas()[chunk_idx].n,
+        chunks[chunk_idx].len()
+      );
+      assert_eq!(
+        file.decompress_chunk(chunk_idx).unwrap(),
+        chunks[chunk_idx]
+      );
+    }
+    let err = file.decompress_chunk(20).unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+  }
+
+  assert_eq!(indexed.chunk_metas(), scanned.chunk_metas());
+  assert_eq!(indexed.total_count(), chunks.concat().len());
+
+  // readers that ignore the index still work
+  assert_eq!(
+    auto_decompress::<i64>(&indexed_bytes).unwrap(),
+    chunks.concat()
+  );
+}
+
+#[test]
+fn test_chunk_index_empty() {
+  let bytes = compress_chunks(&[], true);
+  let indexed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+  assert_eq!(indexed.chunk_count(), 0);
+  assert_eq!(
+    auto_decompress::<i64>(&bytes).unwrap(),
+    Vec::<i64>::new()
+  );
+}
+
+#[test]
+fn test_corrupt_chunk_index() {
+  let bytes = compress_chunks(&example_chunks(), true);
+
+  let err = ParsedFile::<i64>::try_from(&bytes[..bytes.len() - 1]).unwrap_err();
+  assert!(!matches!(err.kind, ErrorKind::Compatibility));
+
+  // swap the first two offsets
+  let mut corrupt = bytes.clone();
+  let index_start = bytes.len() - 8 * 21;
+  corrupt[index_start..index_start + 16].rotate_left(8);
+  let err = ParsedFile::<i64>::try_from(corrupt.as_slice()).unwrap_err();
+  assert!(matches!(err.kind, ErrorKind::Corruption));
+
+  // claim more chunks than fit in the file
+  let mut corrupt = bytes;
+  let count_start = corrupt.len() - 8;
+  corrupt[count_start..].copy_from_slice(&u64::MAX.to_le_bytes());
+  let err = ParsedFile::<i64>::try_from(corrupt.as_slice()).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InsufficientData
+  ));
+}
@@ -1,6 +1,9 @@
 mod atomicity;
 mod backward_compatibility;
+mod byte_layout;
+mod chunk_index;
 mod low_level;
+mod parsed_file;
 mod recovery;
 mod stability;
 mod streaming;
@@ -0,0 +1,162 @@
+use std::convert::TryFrom;
+
+use crate::errors::ErrorKind;
+use crate::standalone::ParsedFile;
+use crate::{ColumnMetadata, Compressor, CompressorConfig};
+
+fn compress_chunks(chunks: &[Vec<i64>]) -> Vec<u8> {
+  let mut compressor =
+    Compressor::<i64>::from_config(CompressorConfig::default().with_delta_encoding_order(1));
+  compressor.header().unwrap();
+  for chunk in chunks {
+    compressor.chunk(chunk).unwrap();
+  }
+  compressor.footer().unwrap();
+  compressor.drain_bytes()
+}
+
+#[test]
+fn test_parsed_file() {
+  let chunks = vec![
+    (0..100).collect::<Vec<i64>>(),
+    vec![-5],
+    (0..1000).map(|i| i * i).collect::<Vec<i64>>(),
+  ];
+  let bytes = compress_chunks(&chunks);
+  let parsed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+
+  assert_eq!(parsed.flags().delta_encoding_order, 1);
+  assert_eq!(parsed.chunk_count(), 3);
+  assert_eq!(parsed.total_count(), 1101);
+  assert_eq!(parsed.chunk_metas()[1].n, 1);
+  for (chunk_idx, chunk) in chunks.iter().enumerate() {
+    assert_eq!(
+      &parsed.decompress_chunk(chunk_idx).unwrap(),
+      chunk
+    );
+  }
+  assert_eq!(
+    parsed.decompress_all().unwrap(),
+    chunks.concat()
+  );
+
+  let err = parsed.decompress_chunk(3).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InvalidArgument
+  ));
+}
+
+#[test]
+fn test_parsed_file_empty_and_truncated() {
+  let bytes = compress_chunks(&[]);
+  let parsed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+  assert_eq!(parsed.chunk_count(), 0);
+  assert_eq!(
+    parsed.decompress_all().unwrap(),
+    Vec::<i64>::new()
+  );
+
+  let bytes = compress_chunks(&[(0..100).collect()]);
+  let err = ParsedFile::<i64>::try_from(&bytes[..bytes.len() - 2]).unwrap_err();
+  assert!(matches!(
+    err.kind,
+    ErrorKind::InsufficientData
+  ));
+}
+
+fn compress_w_config(config: CompressorConfig) -> Vec<u8> {
+  let mut compressor = Compressor::<i64>::from_config(config);
+  compressor.header().unwrap();
+  compressor.chunk(&[1, 2, 3]).unwrap();
+  compressor.chunk(&[4, 5]).unwrap();
+  compressor.footer().unwrap();
+  compressor.drain_bytes()
+}
+
+fn compress_w_column_metadata(column_metadata: ColumnMetadata) -> Vec<u8> {
+  compress_w_config(CompressorConfig::default().with_column_metadata(column_metadata))
+}
+
+#[test]
+fn test_parsed_file_column_metadata() {
+  let bytes = compress_chunks(&[vec![1, 2, 3]]);
+  let parsed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+  assert_eq!(parsed.column_name(), None);
+  assert_eq!(parsed.units(), None);
+
+  let name = "temperature";
+  let bytes = compress_w_column_metadata(ColumnMetadata::new(name).with_units("°C"));
+  let parsed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+  assert_eq!(parsed.column_name(), Some(name));
+  assert_eq!(parsed.units(), Some("°C"));
+  assert_eq!(
+    parsed.decompress_all().unwrap(),
+    vec![1, 2, 3, 4, 5]
+  );
+  assert_eq!(
+    parsed.decompress_chunk(0).unwrap(),
+    vec![1, 2, 3]
+  );
+
+  // the trailer comes after the footer, so the rest of the file is
+  // unchanged and readers that don't know about it still decompress it
+  let plain = compress_w_config(CompressorConfig::default());
+  assert!(bytes.starts_with(&plain));
+  assert_eq!(
+    crate::auto_decompress::<i64>(&bytes).unwrap(),
+    vec![1, 2, 3, 4, 5]
+  );
+
+  // the chunk index comes before the trailer
+  let config = CompressorConfig::default()
+    .with_use_chunk_index(true)
+    .with_column_metadata(ColumnMetadata::new(name));
+  let bytes = compress_w_config(config);
+  let parsed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+  assert_eq!(parsed.column_name(), Some(name));
+  assert!(parsed.has_chunk_index());
+  assert_eq!(
+    parsed.decompress_chunk(1).unwrap(),
+    vec![4, 5]
+  );
+
+  let bytes = compress_w_column_metadata(ColumnMetadata::new(""));
+  let parsed = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap();
+  assert_eq!(parsed.column_name(), Some(""));
+  assert_eq!(parsed.units(), None);
+  assert_eq!(
+    parsed.decompress_all().unwrap(),
+    vec![1, 2, 3, 4, 5]
+  );
+}
+
+#[test]
+fn test_column_metadata_errors() {
+  for column_metadata in [
+    ColumnMetadata::new("x".repeat(256)),
+    ColumnMetadata::new("x").with_units("y".repeat(256)),
+    ColumnMetadata::new("x").with_units(""),
+  ] {
+    let config = CompressorConfig::default().with_column_metadata(column_metadata);
+    let err = Compressor::<i64>::from_config(config).header().unwrap_err();
+    assert!(matches!(
+      err.kind,
+      ErrorKind::InvalidArgument
+    ));
+  }
+
+  // the trailer is the name, the units, their lengths, and the magic
+  let mut bytes = compress_w_column_metadata(ColumnMetadata::new("name"));
+  let trailer_start = bytes.len() - 10;
+  assert_eq!(&bytes[trailer_start..], b"name\x04\x00qcom");
+  bytes[trailer_start] = 0xff;
+  let err = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap_err();
+  assert!(matches!(err.kind, ErrorKind::Corruption));
+
+  let mut bytes = compress_w_column_metadata(ColumnMetadata::new("name"));
+  let name_len_idx = bytes.len() - 6;
+  bytes[name_len_idx] = 0xff;
+  let err = ParsedFile::<i64>::try_from(bytes.as_slice()).unwrap_err();
+  assert!(matches!(err.kind, ErrorKind::Corruption));
+}
@@ -1,11 +1,16 @@
-use std::io::Write;
+use std::io;
+use std::io::{Cursor, Seek, SeekFrom, Write};
 
 use futures::{StreamExt, TryStreamExt};
 use rand::Rng;
 
 use crate::data_types::NumberLike;
-use crate::errors::QCompressResult;
-use crate::{DecompressedItem, Decompressor, DecompressorConfig, DEFAULT_COMPRESSION_LEVEL};
+use crate::errors::{ErrorKind, QCompressResult};
+use crate::standalone::compress_streaming;
+use crate::{
+  Compressor, CompressorConfig, DecompressedItem, Decompressor, DecompressorConfig,
+  DEFAULT_COMPRESSION_LEVEL,
+};
 
 struct State<T: NumberLike> {
   decompressor: Decompressor<T>,
@@ -86,3 +91,82 @@ async fn check_streaming_recovery<T: NumberLike>(
   assert_eq!(nums, true_nums);
   Ok(())
 }
+
+// a writer that, like a pipe, cannot seek
+#[derive(Default)]
+struct UnseekableWriter(Vec<u8>);
+
+impl Write for UnseekableWriter {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+impl Seek for UnseekableWriter {
+  fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "cannot seek",
+    ))
+  }
+}
+
+fn check_streaming_compress<T: NumberLike>(nums: &[T], config: CompressorConfig) {
+  let expected = Compressor::<T>::from_config(config.clone()).simple_compress(nums);
+
+  // writing after some existing bytes to make sure offsets are relative to
+  // where the file starts
+  let mut cursor = Cursor::new(vec![7_u8; 3]);
+  cursor.seek(SeekFrom::End(0)).unwrap();
+  compress_streaming(&mut cursor, nums, config.clone()).unwrap();
+  assert_eq!(&cursor.into_inner()[3..], expected);
+
+  let mut unseekable = UnseekableWriter::default();
+  compress_streaming(&mut unseekable, nums, config).unwrap();
+  assert_eq!(unseekable.0, expected);
+}
+
+#[test]
+fn test_streaming_compress() {
+  let mut rng = rand::thread_rng();
+  // long enough to flush many times, and to span 2 chunks
+  let nums = (0..1_200_000)
+    .map(|_| rng.gen_range(0..1000))
+    .collect::<Vec<i32>>();
+  check_streaming_compress(&nums, CompressorConfig::default());
+  check_streaming_compress(
+    &nums,
+    CompressorConfig::default().with_use_chunk_index(true),
+  );
+
+  // runs crossing flush boundaries
+  let nums = (0..300_000).map(|i| i % 70_000 == 0).collect::<Vec<bool>>();
+  check_streaming_compress(&nums, CompressorConfig::default());
+
+  let nums = (0..100_000_i64).map(|i| i * i).collect::<Vec<_>>();
+  check_streaming_compress(
+    &nums,
+    CompressorConfig::default().with_delta_encoding_order(2),
+  );
+  check_streaming_compress::<u32>(&[], CompressorConfig::default());
+}
+
+#[test]
+fn test_streaming_compress_write_failure() {
+  // a cursor over a fixed buffer too small for the whole file
+  let mut buf = [0_u8; 16];
+  let mut cursor = Cursor::new(&mut buf[..]);
+  let nums = (0..1000).collect::<Vec<i32>>();
+  let err = compress_streaming(
+    &mut cursor,
+    &nums,
+    CompressorConfig::default(),
+  )
+  .unwrap_err();
+  assert_eq!(err.kind, ErrorKind::InvalidArgument);
+  assert!(err.message.contains("failed to write to destination"));
+}